@@ -78,4 +78,19 @@ ARB_BUILDER_SUBCLASS &with_arg(const T &data) {
   return *this;
 }
 
+/**
+ * Pushes a (binary) string to the back of the shared memory argument list
+ * (builder pattern).
+ *
+ * \param data The data for the new argument, represented as a (binary)
+ * string.
+ * \returns `&self`, to continue building.
+ * \throws std::runtime_error When the current handle is invalid or the
+ * shared memory segment cannot be allocated.
+ */
+ARB_BUILDER_SUBCLASS &with_shm_string(const std::string &data) {
+  push_arb_shm_string(data);
+  return *this;
+}
+
 #undef ARB_BUILDER_SUBCLASS
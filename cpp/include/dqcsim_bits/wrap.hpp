@@ -784,7 +784,28 @@ namespace wrap {
      *  - exactly one measurement result is reported upstream for exactly the
      *    qubits in the measures set.
      */
-    Custom = 4
+    Custom = 4,
+
+    /**
+     * Pauli measurement gates have one or more target qubits and a Hermitian
+     * unitary matrix sized for the number of target qubits, representing the
+     * measured observable. This is usually a product of Pauli matrices, such
+     * as ZZ or XXXX. The first target qubit is also the only measured qubit.
+     *
+     * The semantics are:
+     *
+     *  - the qubits are projected onto the +1 or -1 eigenspace of the
+     *    observable, with the probabilities dictated by the current state;
+     *  - a single measurement result is propagated upstream for the first
+     *    target qubit, being zero for the +1 eigenspace and one for the -1
+     *    eigenspace.
+     *
+     * The data field may add pragma-like hints to the gate, for instance to
+     * represent the line number in the source file that generated the gate,
+     * error modelling information, and so on. This data may be silently
+     * ignored.
+     */
+    PauliMeasurement = 5
 
   };
 
@@ -800,6 +821,7 @@ namespace wrap {
       case GateType::Measurement: return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT;
       case GateType::Prep:        return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP;
       case GateType::Custom:      return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM;
+      case GateType::PauliMeasurement: return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT;
     }
     std::cerr << "unknown gate type" << std::endl;
     std::terminate();
@@ -820,6 +842,7 @@ namespace wrap {
       case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT: return GateType::Measurement;
       case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP:        return GateType::Prep;
       case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM:      return GateType::Custom;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT: return GateType::PauliMeasurement;
       case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_INVALID:     throw std::runtime_error(raw::dqcs_error_get());
     }
     throw std::invalid_argument("unknown gate type");
@@ -2161,6 +2184,114 @@ namespace wrap {
       check(raw::dqcs_arb_clear(handle));
     }
 
+    /**
+     * Returns the shared memory argument at the given index as a (binary)
+     * string. Negative indices are relative to the back of the list, as in
+     * Python.
+     *
+     * \note This copies the data out of the shared memory segment. Use
+     * `get_arb_shm_ptr()` to access large arguments without copying.
+     *
+     * \param index The index of the argument to retrieve.
+     * \returns A copy of the argument data as a (binary) string.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * argument index is out of range.
+     */
+    std::string get_arb_shm_string(ssize_t index) const {
+      size_t size = get_arb_shm_size(index);
+      std::string data;
+      data.resize(size);
+      if (size) {
+        check(raw::dqcs_arb_shm_get(handle, index, &data.front(), size));
+      }
+      return data;
+    }
+
+    /**
+     * Returns a pointer to the contents of the shared memory argument at the
+     * given index, without copying. Negative indices are relative to the back
+     * of the list, as in Python.
+     *
+     * The pointer is only valid as long as this object exists and its shared
+     * memory arguments are not modified. The memory must not be written to.
+     * Use `get_arb_shm_size()` to get the size of the buffer.
+     *
+     * \param index The index of the argument to access.
+     * \returns A pointer to the argument data, or `nullptr` if the argument
+     * is empty.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * argument index is out of range.
+     */
+    const void *get_arb_shm_ptr(ssize_t index) const {
+      const void *ptr = raw::dqcs_arb_shm_ptr(handle, index);
+      if (ptr == nullptr) {
+        // Distinguish between empty arguments and errors.
+        get_arb_shm_size(index);
+      }
+      return ptr;
+    }
+
+    /**
+     * Returns the size in bytes of the shared memory argument at the given
+     * index. Negative indices are relative to the back of the list, as in
+     * Python.
+     *
+     * \param index The index of the argument to query.
+     * \returns The size of the argument in bytes.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * argument index is out of range.
+     */
+    size_t get_arb_shm_size(ssize_t index) const {
+      return check(raw::dqcs_arb_shm_size(handle, index));
+    }
+
+    /**
+     * Pushes a (binary) string to the back of the shared memory argument
+     * list. The data is copied into a new shared memory segment, after which
+     * only a handle to the segment is sent between plugins.
+     *
+     * \param data The data for the new argument, represented as a (binary)
+     * string.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * shared memory segment cannot be allocated.
+     */
+    void push_arb_shm_string(const std::string &data) {
+      push_arb_shm(data.data(), data.size());
+    }
+
+    /**
+     * Pushes a buffer to the back of the shared memory argument list. The
+     * data is copied into a new shared memory segment, after which only a
+     * handle to the segment is sent between plugins.
+     *
+     * \param data Pointer to the data for the new argument.
+     * \param size The size of the data in bytes.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * shared memory segment cannot be allocated.
+     */
+    void push_arb_shm(const void *data, size_t size) {
+      check(raw::dqcs_arb_shm_push(handle, data, size));
+    }
+
+    /**
+     * Returns the number of shared memory arguments.
+     *
+     * \returns The number of shared memory arguments.
+     * \throws std::runtime_error When the current handle is invalid.
+     */
+    size_t get_arb_shm_count() const {
+      return check(raw::dqcs_arb_shm_len(handle));
+    }
+
+    /**
+     * Clears the shared memory argument list.
+     *
+     * \throws std::runtime_error When the current handle is invalid.
+     */
+    void clear_arb_shm() {
+      check(raw::dqcs_arb_shm_clear(handle));
+    }
+
     /**
      * Assigns all arb data from the given arb to this one.
      *
@@ -3106,6 +3237,20 @@ namespace wrap {
       : Handle(check(raw::dqcs_mat_basis(to_raw(basis)))) {
     }
 
+    /**
+     * Constructs a matrix representing a product of Pauli matrices, for
+     * instance for use as the observable of a Pauli measurement gate.
+     *
+     * \param paulis A string consisting of the characters `I`, `X`, `Y`, and
+     * `Z` (in either case), one for each qubit, such as `"ZZ"` or `"XXXX"`.
+     * The first character corresponds to the first qubit.
+     * \returns A new matrix containing the desired data.
+     * \throws std::runtime_error When constructing the matrix failed.
+     */
+    static Matrix pauli(const std::string &paulis) {
+      return Matrix(check(raw::dqcs_mat_pauli(paulis.c_str())));
+    }
+
     /**
      * Copy-constructs a matrix.
      *
@@ -3669,6 +3814,42 @@ namespace wrap {
       return measure(QubitSet(measures), Matrix(basis));
     }
 
+    /**
+     * Constructs a new Pauli measurement gate.
+     *
+     * \param targets A qubit reference set with the qubits that the
+     * observable applies to. The single measurement result is returned for
+     * the first qubit in the set.
+     * \param observable The measured observable, a Hermitian unitary matrix
+     * sized for the number of target qubits, usually constructed using
+     * `Matrix::pauli()`. The qubits are projected onto its +1 (zero) or -1
+     * (one) eigenspace.
+     * \returns The requested Pauli measurement gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate pauli_measure(QubitSet &&targets, Matrix &&observable) {
+      return Gate(check(raw::dqcs_gate_new_pauli_measurement(targets.get_handle(), observable.get_handle())));
+    }
+
+    /**
+     * Constructs a new Pauli measurement gate.
+     *
+     * \param targets A qubit reference set with the qubits that the
+     * observable applies to, passed by copy. The single measurement result is
+     * returned for the first qubit in the set.
+     * \param observable The measured observable, a Hermitian unitary matrix
+     * sized for the number of target qubits, usually constructed using
+     * `Matrix::pauli()`. The qubits are projected onto its +1 (zero) or -1
+     * (one) eigenspace.
+     * \returns The requested Pauli measurement gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate pauli_measure(const QubitSet &targets, const Matrix &observable) {
+      return pauli_measure(QubitSet(targets), Matrix(observable));
+    }
+
     /**
      * Constructs a new Z-axis prep gate, putting the qubits in the |0> state.
      *
@@ -4240,6 +4421,75 @@ namespace wrap {
       return check(raw::dqcs_gate_has_name(handle));
     }
 
+    /**
+     * Makes this gate conditional on the measurement register of a qubit.
+     *
+     * The gate is only executed if the measurement register of `qubit` equals
+     * `value` at the time the gate is executed. The condition is evaluated by
+     * the backend. Any previous condition is replaced. Gates that measure
+     * qubits cannot be made conditional.
+     *
+     * \param qubit The qubit whose measurement register is tested.
+     * \param value The value that the measurement register must have for the
+     * gate to be executed.
+     * \throws std::runtime_error When the current handle is invalid, the
+     * value is invalid, or the gate measures qubits.
+     */
+    void set_condition(const QubitRef &qubit, MeasurementValue value) {
+      check(raw::dqcs_gate_set_condition(handle, qubit.get_index(), to_raw(value)));
+    }
+
+    /**
+     * Makes this gate conditional on the measurement register of a qubit
+     * (builder pattern).
+     *
+     * \param qubit The qubit whose measurement register is tested.
+     * \param value The value that the measurement register must have for the
+     * gate to be executed.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the current handle is invalid, the
+     * value is invalid, or the gate measures qubits.
+     */
+    Gate &&with_condition(const QubitRef &qubit, MeasurementValue value) {
+      set_condition(qubit, value);
+      return std::move(*this);
+    }
+
+    /**
+     * Returns whether this gate is conditional.
+     *
+     * \returns Whether this gate is conditional.
+     * \throws std::runtime_error When the current handle is invalid.
+     */
+    bool has_condition() const {
+      return check(raw::dqcs_gate_has_condition(handle));
+    }
+
+    /**
+     * Returns the qubit whose measurement register controls the execution of
+     * this conditional gate.
+     *
+     * \returns The qubit whose measurement register is tested.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * gate is not conditional.
+     */
+    QubitRef get_condition_qubit() const {
+      return QubitRef(check(raw::dqcs_gate_condition_qubit(handle)));
+    }
+
+    /**
+     * Returns the measurement register value for which this conditional gate
+     * is executed.
+     *
+     * \returns The value that the measurement register must have for the
+     * gate to be executed.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * gate is not conditional.
+     */
+    MeasurementValue get_condition_value() const {
+      return check(raw::dqcs_gate_condition_value(handle));
+    }
+
     // Include `ArbData` builder pattern functions.
     /**
      * Helper macro to prevent code repetition; not visible outside of the header.
@@ -4595,6 +4845,11 @@ namespace wrap {
      * matrices. Defaults to 1 ppm.
      * \param ignore_global_phase Whether global phase should be ignored when
      * detecting incoming gate matrices.
+     * \param record_parameters Whether the exact parameters of parameterized
+     * gates constructed by this mapping are recorded in the gate data, such
+     * that they are passed along the gatestream without floating-point loss.
+     * Refer to `dqcs_gm_add_predef_unitary_exact()` for more information.
+     * Defaults to false.
      * \returns `&self`, to continue building.
      * \throws std::runtime_error When the gate map handle is invalid.
      * \warning If the key is equal to a the key for a previously added
@@ -4607,9 +4862,12 @@ namespace wrap {
       PredefinedGate gate,
       int num_controls = -1,
       double epsilon = 0.000001,
-      bool ignore_global_phase = true
+      bool ignore_global_phase = true,
+      bool record_parameters = false
     ) {
-      check(raw::dqcs_gm_add_predef_unitary(
+      check((record_parameters
+        ? raw::dqcs_gm_add_predef_unitary_exact
+        : raw::dqcs_gm_add_predef_unitary)(
         handle,
         unbound_delete,
         new Unbound(std::move(key)),
@@ -4636,6 +4894,11 @@ namespace wrap {
      * matrices. Defaults to 1 ppm.
      * \param ignore_global_phase Whether global phase should be ignored when
      * detecting incoming gate matrices.
+     * \param record_parameters Whether the exact parameters of parameterized
+     * gates constructed by this mapping are recorded in the gate data, such
+     * that they are passed along the gatestream without floating-point loss.
+     * Refer to `dqcs_gm_add_predef_unitary_exact()` for more information.
+     * Defaults to false.
      * \returns `&self`, to continue building.
      * \throws std::runtime_error When the gate map handle is invalid.
      * \warning If the key is equal to a the key for a previously added
@@ -4648,9 +4911,12 @@ namespace wrap {
       PredefinedGate gate,
       int num_controls = -1,
       double epsilon = 0.000001,
-      bool ignore_global_phase = true
+      bool ignore_global_phase = true,
+      bool record_parameters = false
     ) {
-      check(raw::dqcs_gm_add_predef_unitary(
+      check((record_parameters
+        ? raw::dqcs_gm_add_predef_unitary_exact
+        : raw::dqcs_gm_add_predef_unitary)(
         handle,
         unbound_delete,
         new Unbound(key),
@@ -4948,6 +5214,70 @@ namespace wrap {
       return std::move(*this);
     }
 
+    /**
+     * Adds a Pauli measurement gate mapping.
+     *
+     * \param key The `Unbound` object that refers to this type of gate in your
+     * representation.
+     * \param observable The measured observable, usually constructed using
+     * `Matrix::pauli()`. The number of qubits of the gate is implied by its
+     * size.
+     * \param epsilon Maximum RMS deviation when detecting the above
+     * observable.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the gate map handle is invalid.
+     * \warning If the key is equal to a the key for a previously added
+     * converter, the previous converter is silently overwritten.
+     * \note If you get template errors, ensure that your `Unbound` type has a
+     * move constructor.
+     */
+    GateMap &&with_pauli_measure(
+      Unbound &&key,
+      Matrix &&observable,
+      double epsilon = 0.000001
+    ) {
+      check(raw::dqcs_gm_add_pauli_measure(
+        handle,
+        unbound_delete,
+        new Unbound(std::move(key)),
+        observable.get_handle(),
+        epsilon
+      ));
+      return std::move(*this);
+    }
+
+    /**
+     * Adds a Pauli measurement gate mapping.
+     *
+     * \param key The `Unbound` object that refers to this type of gate in your
+     * representation.
+     * \param observable The measured observable, usually constructed using
+     * `Matrix::pauli()`. The number of qubits of the gate is implied by its
+     * size.
+     * \param epsilon Maximum RMS deviation when detecting the above
+     * observable.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the gate map handle is invalid.
+     * \warning If the key is equal to a the key for a previously added
+     * converter, the previous converter is silently overwritten.
+     * \note If you get template errors, ensure that your `Unbound` type has a
+     * copy constructor.
+     */
+    GateMap &&with_pauli_measure(
+      const Unbound &key,
+      const Matrix &observable,
+      double epsilon = 0.000001
+    ) {
+      check(raw::dqcs_gm_add_pauli_measure(
+        handle,
+        unbound_delete,
+        new Unbound(key),
+        Matrix(observable).get_handle(),
+        epsilon
+      ));
+      return std::move(*this);
+    }
+
     /**
      * Adds a prep gate mapping.
      *
@@ -5984,6 +6314,58 @@ namespace wrap {
       free(QubitSet().with(qubit));
     }
 
+    /**
+     * Resets the given downstream qubits to the |0> state.
+     *
+     * Besides resetting the quantum state, this clears the measurement
+     * registers of the qubits, such that `get_measurement()` and the
+     * cycles-since-measure functions behave as if the qubits were freshly
+     * allocated.
+     *
+     * \param qubits The list of qubits to reset, passed by move.
+     * \throws std::runtime_error When the qubit set handle is invalid, an
+     * asynchronous exception is received, or this is called by a backend
+     * plugin.
+     *
+     * \note This function is implemented asynchronously for multiprocessing
+     * performance reasons. Therefore, any exception thrown by the downstream
+     * plugin will not be (immediately) visible.
+     */
+    void reset(QubitSet &&qubits) {
+      check(raw::dqcs_plugin_reset(state, qubits.get_handle()));
+    }
+
+    /**
+     * Resets the given downstream qubits to the |0> state.
+     *
+     * \param qubits The list of qubits to reset, passed by copy.
+     * \throws std::runtime_error When the qubit set handle is invalid, an
+     * asynchronous exception is received, or this is called by a backend
+     * plugin.
+     *
+     * \note This function is implemented asynchronously for multiprocessing
+     * performance reasons. Therefore, any exception thrown by the downstream
+     * plugin will not be (immediately) visible.
+     */
+    void reset(const QubitSet &qubits) {
+      reset(QubitSet(qubits));
+    }
+
+    /**
+     * Resets the given downstream qubit to the |0> state.
+     *
+     * \param qubit The qubit to reset.
+     * \throws std::runtime_error When an asynchronous exception is received
+     * or this is called by a backend plugin.
+     *
+     * \note This function is implemented asynchronously for multiprocessing
+     * performance reasons. Therefore, any exception thrown by the downstream
+     * plugin will not be (immediately) visible.
+     */
+    void reset(const QubitRef &qubit) {
+      reset(QubitSet().with(qubit));
+    }
+
     /**
      * Sends a gate to the downstream plugin.
      *
@@ -6266,6 +6648,21 @@ namespace wrap {
       return check(raw::dqcs_plugin_advance(state, cycles));
     }
 
+    /**
+     * Sends the queued allocate, free, gate, and reset requests downstream.
+     *
+     * These requests are queued until the next synchronization point, such
+     * as a measurement query, `arb()`, or `advance()`, such that many of them
+     * can be sent in a single message. Calling this function is never
+     * required.
+     *
+     * \throws std::runtime_error When an asynchronous exception is received,
+     * or this is called by a backend plugin.
+     */
+    void flush() {
+      check(raw::dqcs_plugin_flush(state));
+    }
+
     /**
      * Sends an arbitrary command downstream.
      *
@@ -6601,6 +6998,11 @@ namespace wrap {
      */
     typedef Callback<void, PluginState&, QubitSet&&> Free;
 
+    /**
+     * Callback wrapper specialized for the `reset` callback.
+     */
+    typedef Callback<void, PluginState&, QubitSet&&> Reset;
+
     /**
      * Callback wrapper specialized for the `gate` callback.
      */
@@ -6803,6 +7205,31 @@ namespace wrap {
       return raw::dqcs_return_t::DQCS_FAILURE;
     }
 
+    /**
+     * Entry point for the `reset` callback.
+     */
+    static raw::dqcs_return_t reset(
+      void *user_data,
+      raw::dqcs_plugin_state_t state,
+      raw::dqcs_handle_t qubits
+    ) noexcept {
+
+      // Wrap inputs.
+      callback::Reset *cb_wrapper = reinterpret_cast<callback::Reset*>(user_data);
+      PluginState state_wrapper(state);
+      QubitSet qubits_wrapper(qubits);
+
+      // Catch exceptions thrown in the user function to convert them to
+      // DQCsim's error reporting protocol.
+      try {
+        (*(cb_wrapper->cb))(state_wrapper, std::move(qubits_wrapper));
+        return raw::dqcs_return_t::DQCS_SUCCESS;
+      } catch (const std::exception &e) {
+        raw::dqcs_error_set(e.what());
+      }
+      return raw::dqcs_return_t::DQCS_FAILURE;
+    }
+
     /**
      * Entry point for the `gate` callback.
      */
@@ -7185,6 +7612,17 @@ namespace wrap {
    * the backend without modification. For backends it is no-op. Frontends do
    * not support this callback.
    *
+   * # Reset
+   *
+   * The reset callback is called when the upstream plugin requests that a
+   * number of qubits be reset to the |0> state. The measurement registers of
+   * the qubits are cleared automatically.
+   *
+   * The default behavior for operator plugins is to pass the command on to
+   * the backend without modification. For backends, the gate callback is
+   * called with a prep gate in the Z basis for the qubits. Frontends do not
+   * support this callback.
+   *
    * # Gate
    *
    * The gate callback is called when the upstream plugin requests that a gate
@@ -7424,6 +7862,53 @@ namespace wrap {
       return str;
     }
 
+    /**
+     * Registers an `ArbCmd` operation supported by the plugin.
+     *
+     * The registered operations are advertised to the host and the upstream
+     * plugin during initialization. As long as no operations are registered,
+     * any `ArbCmd` is accepted. Once at least one operation is registered,
+     * `ArbCmd`s for other operations are rejected, both before they are sent
+     * and by the plugin itself.
+     *
+     * \param iface The interface identifier of the operation.
+     * \param oper The operation identifier of the operation.
+     * \param schema An optional JSON schema for the JSON/CBOR payload of the
+     * operation, in its JSON string representation. An empty string
+     * registers the operation without a schema.
+     * \throws std::runtime_error When the identifiers or the schema are
+     * invalid, or when the plugin definition handle is invalid.
+     */
+    void add_arb_op(
+      const std::string &iface,
+      const std::string &oper,
+      const std::string &schema = ""
+    ) {
+      check(raw::dqcs_pdef_add_arb_op(
+        handle, iface.c_str(), oper.c_str(), schema.c_str()));
+    }
+
+    /**
+     * Registers an `ArbCmd` operation supported by the plugin (builder
+     * pattern). See `add_arb_op()` for more information.
+     *
+     * \param iface The interface identifier of the operation.
+     * \param oper The operation identifier of the operation.
+     * \param schema An optional JSON schema for the JSON/CBOR payload of the
+     * operation, in its JSON string representation.
+     * \returns This plugin definition object, to continue building.
+     * \throws std::runtime_error When the identifiers or the schema are
+     * invalid, or when the plugin definition handle is invalid.
+     */
+    Plugin &&with_arb_op(
+      const std::string &iface,
+      const std::string &oper,
+      const std::string &schema = ""
+    ) {
+      add_arb_op(iface, oper, schema);
+      return std::move(*this);
+    }
+
     // Code below is generated using the following Python script:
     // print('    // Code below is generated using the following Python script:')
     // with open(__file__, 'r') as f:
@@ -7501,6 +7986,7 @@ namespace wrap {
     //     ('run',                 'run',                  'Run'),
     //     ('allocate',            'allocate',             'Allocate'),
     //     ('free',                'free',                 'Free'),
+    //     ('reset',               'reset',                'Reset'),
     //     ('gate',                'gate',                 'Gate'),
     //     ('modify-measurement',  'modify_measurement',   'ModifyMeasurement'),
     //     ('advance',             'advance',              'Advance'),
@@ -7830,6 +8316,70 @@ namespace wrap {
       return std::move(*this);
     }
 
+  private:
+
+    /**
+     * Assigns the reset callback function from a `new`-initialized
+     * raw pointer to a `callback::Reset` object. Callee will ensure that
+     * `delete` is called.
+     */
+    void set_reset(callback::Reset *cb) {
+      try {
+        check(raw::dqcs_pdef_set_reset_cb(
+          handle,
+          CallbackEntryPoints::reset,
+          CallbackEntryPoints::user_free<callback::Reset>,
+          cb));
+      } catch (...) {
+        delete cb;
+        throw;
+      }
+    }
+
+  public:
+
+    /**
+     * Assigns the reset callback function from a pre-existing
+     * `callback::Reset` object by copy.
+     *
+     * \param cb The callback object.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the current handle is invalid or of an
+     * unsupported plugin type, or when the callback object is invalid.
+     */
+    Plugin &&with_reset(const callback::Reset &cb) {
+      set_reset(new callback::Reset(cb));
+      return std::move(*this);
+    }
+
+    /**
+     * Assigns the reset callback function from a pre-existing
+     * `callback::Reset` object by move.
+     *
+     * \param cb The callback object.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the current handle is invalid or of an
+     * unsupported plugin type, or when the callback object is invalid.
+     */
+    Plugin &&with_reset(callback::Reset &&cb) {
+      set_reset(new callback::Reset(std::move(cb)));
+      return std::move(*this);
+    }
+
+    /**
+     * Assigns the reset callback function by constructing the
+     * callback object implicitly.
+     *
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the current handle is invalid or of an
+     * unsupported plugin type, or when the callback object is invalid.
+     */
+    template<typename... Args>
+    Plugin &&with_reset(Args... args) {
+      set_reset(new callback::Reset(args...));
+      return std::move(*this);
+    }
+
   private:
 
     /**
@@ -8310,6 +8860,33 @@ namespace wrap {
      */
     virtual void log_tee(Loglevel verbosity, const std::string &filename) = 0;
 
+    /**
+     * Configures a plugin to also output its log messages to a file, with
+     * optional appending and size-based rotation.
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    virtual void log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) = 0;
+
   };
 
   /**
@@ -8582,6 +9159,68 @@ namespace wrap {
       return std::move(*this);
     }
 
+    /**
+     * Configures a plugin process to also output its log messages to a file, with
+     * optional appending and size-based rotation.
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    void log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) override {
+      check(raw::dqcs_pcfg_tee_rotate(
+        handle, to_raw(verbosity), filename.c_str(),
+        append, max_size, keep, compress));
+    }
+
+    /**
+     * Configures a plugin process to also output its log messages to a file, with
+     * optional appending and size-based rotation (builder pattern).
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    PluginProcessConfiguration &&with_log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) {
+      log_tee_rotate(verbosity, filename, append, max_size, keep, compress);
+      return std::move(*this);
+    }
+
     /**
      * Configures the capture mode for the stdout stream of the specified
      * plugin process.
@@ -8911,6 +9550,68 @@ namespace wrap {
       return std::move(*this);
     }
 
+    /**
+     * Configures a plugin thread to also output its log messages to a file, with
+     * optional appending and size-based rotation.
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    void log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) override {
+      check(raw::dqcs_tcfg_tee_rotate(
+        handle, to_raw(verbosity), filename.c_str(),
+        append, max_size, keep, compress));
+    }
+
+    /**
+     * Configures a plugin thread to also output its log messages to a file, with
+     * optional appending and size-based rotation (builder pattern).
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    PluginThreadConfiguration &&with_log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) {
+      log_tee_rotate(verbosity, filename, append, max_size, keep, compress);
+      return std::move(*this);
+    }
+
   };
 
   /**
@@ -9525,6 +10226,58 @@ namespace wrap {
       return str;
     }
 
+    /**
+     * Queries whether a plugin, referenced by instance name, supports the
+     * given `ArbCmd` operation.
+     *
+     * Plugins that did not register any supported operations are assumed to
+     * accept any `ArbCmd`, so this always returns true for them.
+     *
+     * \param name The instance name of the plugin to query.
+     * \param iface The interface identifier of the operation.
+     * \param oper The operation identifier of the operation.
+     * \returns Whether the plugin supports the operation.
+     * \throws std::runtime_error When the given name does not identify a
+     * plugin, when the identifiers are invalid, or when the simulation is in
+     * an invalid state.
+     */
+    bool supports_arb(
+      const std::string &name,
+      const std::string &iface,
+      const std::string &oper
+    ) {
+      return check(raw::dqcs_sim_supports_arb(
+        handle, name.c_str(), iface.c_str(), oper.c_str()));
+    }
+
+    /**
+     * Queries whether a plugin, referenced by index, supports the given
+     * `ArbCmd` operation.
+     *
+     * Plugins that did not register any supported operations are assumed to
+     * accept any `ArbCmd`, so this always returns true for them.
+     *
+     * \param index The index of the plugin to query. The frontend always has
+     * index 0. 1 through N are used for the operators in front to back order
+     * (where N is the number of operators). The backend is at index N+1.
+     * Python-style negative indices are also supported. That is, -1 can be
+     * used to refer to the backend, -2 to the last operator, and so on.
+     * \param iface The interface identifier of the operation.
+     * \param oper The operation identifier of the operation.
+     * \returns Whether the plugin supports the operation.
+     * \throws std::runtime_error When the given index is out of range, when
+     * the identifiers are invalid, or when the simulation is in an invalid
+     * state.
+     */
+    bool supports_arb(
+      ssize_t index,
+      const std::string &iface,
+      const std::string &oper
+    ) {
+      return check(raw::dqcs_sim_supports_arb_idx(
+        handle, index, iface.c_str(), oper.c_str()));
+    }
+
     /**
      * Writes a reproduction file for the simulation so far.
      *
@@ -9840,6 +10593,70 @@ namespace wrap {
       return std::move(*this);
     }
 
+    /**
+     * Configures DQCsim to also output its log messages to a file, with
+     * optional appending and size-based rotation.
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \throws std::runtime_error When the simulation configuration handle is
+     * invalid for some reason.
+     */
+    void log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) {
+      check(raw::dqcs_scfg_tee_rotate(
+        handle, to_raw(verbosity), filename.c_str(),
+        append, max_size, keep, compress));
+    }
+
+    /**
+     * Configures DQCsim to also output its log messages to a file, with
+     * optional appending and size-based rotation (builder pattern).
+     *
+     * \param verbosity Configures the verbosity level for the tee'd output
+     * file only.
+     * \param filename The path to the file to tee log messages to.
+     * \param append Whether to append to the file if it already exists,
+     * instead of truncating it.
+     * \param max_size The size in bytes at which the file is rotated, or 0 to
+     * disable rotation. Rotated files are renamed to `<filename>.1`, and
+     * previously rotated files are shifted up to `<filename>.<keep>`; older
+     * files are deleted.
+     * \param keep The number of rotated files to keep.
+     * \param compress Whether to compress rotated files with gzip, adding a
+     * `.gz` extension.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the simulation configuration handle is
+     * invalid for some reason.
+     */
+    SimulationConfiguration &&with_log_tee_rotate(
+      Loglevel verbosity,
+      const std::string &filename,
+      bool append,
+      size_t max_size,
+      size_t keep = 5,
+      bool compress = false
+    ) {
+      log_tee_rotate(verbosity, filename, append, max_size, keep, compress);
+      return std::move(*this);
+    }
+
   private:
 
     /**
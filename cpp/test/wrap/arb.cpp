@@ -93,3 +93,28 @@ TEST(arb, arg_value) {
   EXPECT_EQ(data.pop_arb_arg_as<Numbers>().x[5], numbers.x[5]);
   EXPECT_EQ(data.pop_arb_arg_as<int>(), 33);
 }
+
+// Tests the shared memory argument list accessors.
+TEST(arb, shm) {
+  wrap::ArbData data;
+  EXPECT_ERROR(data.get_arb_shm_string(0), "Invalid argument: index out of range: 0");
+  EXPECT_ERROR(data.get_arb_shm_ptr(0), "Invalid argument: index out of range: 0");
+  data.push_arb_shm_string("hello");
+  data.push_arb_shm_string("");
+  const Numbers numbers = {{4, 8, 15, 16, 23, 42}};
+  data.push_arb_shm(&numbers, sizeof(numbers));
+  EXPECT_EQ(data.get_arb_shm_count(), 3);
+  EXPECT_EQ(data.get_arb_shm_string(0), "hello");
+  EXPECT_EQ(data.get_arb_shm_string(1), "");
+  EXPECT_EQ(data.get_arb_shm_ptr(1), nullptr);
+  EXPECT_EQ(data.get_arb_shm_size(-1), sizeof(numbers));
+  EXPECT_EQ(static_cast<const Numbers*>(data.get_arb_shm_ptr(-1))->x[5], 42);
+
+  wrap::ArbData data2 = wrap::ArbData().with_shm_string("world");
+  data = data2;
+  EXPECT_EQ(data.get_arb_shm_count(), 1);
+  EXPECT_EQ(data.get_arb_shm_string(-1), "world");
+  data.clear_arb_shm();
+  EXPECT_EQ(data.get_arb_shm_count(), 0);
+  EXPECT_EQ(data2.get_arb_shm_count(), 1);
+}
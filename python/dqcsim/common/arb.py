@@ -23,6 +23,12 @@ class ArbData(object):
     string list entries implement Python's buffer protocol (bytes, bytearray,
     etc.) and that the JSON/CBOR entries are serializable as such using the
     cbor library.
+
+    In addition, ArbData objects can carry a list of shared memory arguments,
+    accessible through `shared`, `append_shared()`, and `with_shared()`. These
+    are binary strings as well, but they are copied into a shared memory
+    segment once, after which only a handle to the segment is passed between
+    plugins. This makes them suitable for large payloads.
     """
 
     def __init__(self, *args, **kwargs):
@@ -42,16 +48,18 @@ class ArbData(object):
         if len(args) == 1 and not kwargs and isinstance(args[0], ArbData):
             self._args = copy.deepcopy(args[0]._args)
             self._json = copy.deepcopy(args[0]._json)
+            self._shared = copy.deepcopy(args[0]._shared)
         else:
             for arg in args:
                 memoryview(arg)
             self._args = list(args)
             _check_json(kwargs)
             self._json = kwargs
+            self._shared = []
 
     def __bool__(self):
         """Returns whether there is non-default data in this ArbData object."""
-        return bool(self._args) or bool(self._json)
+        return bool(self._args) or bool(self._json) or bool(self._shared)
 
     def __len__(self):
         """Returns the number of binary string arguments."""
@@ -115,6 +123,26 @@ class ArbData(object):
         for value in it:
             self.append(value)
 
+    @property
+    def shared(self):
+        """The shared memory arguments, as a tuple of binary strings."""
+        return tuple(self._shared)
+
+    def append_shared(self, value):
+        """Appends a binary string to the shared memory argument list."""
+        memoryview(value)
+        self._shared.append(value)
+
+    def with_shared(self, *values):
+        """Appends the given binary strings to the shared memory argument list
+        and returns self, to allow for in-line construction. For example:
+
+            ArbData(b"header", answer=42).with_shared(payload)
+        """
+        for value in values:
+            self.append_shared(value)
+        return self
+
     def keys(self):
         """Iterates over the JSON object entry keys."""
         return self._json.keys()
@@ -135,14 +163,22 @@ class ArbData(object):
         """Clears the JSON data."""
         self._json = {}
 
+    def clear_shared(self):
+        """Clears the shared memory argument list."""
+        self._shared = []
+
     def clear(self):
         """Resets the ArbData object."""
         self.clear_args()
         self.clear_json()
+        self.clear_shared()
 
     def __eq__(self, other):
         if isinstance(other, ArbData):
-            return self._args == other._args and self._json == other._json
+            return ( #@
+                self._args == other._args
+                and self._json == other._json
+                and self._shared == other._shared)
         return False
 
     @classmethod
@@ -167,7 +203,15 @@ class ArbData(object):
                     raw.dqcs_arb_get_raw(hndl, i, arg)
                 args.append(bytes(arg[:argl]))
 
-        return ArbData(*args, **kwargs)
+            # Load shared memory arguments. These can be large, so query the
+            # size first instead of guessing.
+            shared = []
+            for i in range(raw.dqcs_arb_shm_len(hndl)):
+                arg = bytearray(raw.dqcs_arb_shm_size(hndl, i))
+                raw.dqcs_arb_shm_get(hndl, i, arg)
+                shared.append(bytes(arg))
+
+        return ArbData(*args, **kwargs).with_shared(*shared)
 
     def _to_raw(self, handle=None):
         """Makes an API handle for this ArbData object."""
@@ -175,10 +219,13 @@ class ArbData(object):
             handle = Handle(raw.dqcs_arb_new())
         else:
             raw.dqcs_arb_clear(int(handle))
+            raw.dqcs_arb_shm_clear(int(handle))
         with handle as hndl:
             raw.dqcs_arb_cbor_set(hndl, cbor.dumps(self._json))
             for arg in self._args:
                 raw.dqcs_arb_push_raw(hndl, arg)
+            for arg in self._shared:
+                raw.dqcs_arb_shm_push(hndl, arg)
         return handle
 
     def __repr__(self):
@@ -187,7 +234,15 @@ class ArbData(object):
             e.append(repr(arg))
         for key, value in sorted(self._json.items()):
             e.append("{!s}={!r}".format(key, value))
-        return "ArbData({})".format(', '.join(e))
+        return "ArbData({})".format(', '.join(e)) + self._repr_shared()
+
+    def _repr_shared(self):
+        """Returns the suffix for `__repr__()` representing the shared memory
+        arguments. These tend to be large, so only their sizes are listed."""
+        if not self._shared:
+            return ''
+        return ".with_shared({})".format(', '.join(
+            "<{} bytes>".format(memoryview(arg).nbytes) for arg in self._shared))
 
     __str__ = __repr__
 
@@ -75,6 +75,7 @@ class ArbCmd(ArbData):
             cmd = ArbCmd(raw.dqcs_cmd_iface_get(hndl), raw.dqcs_cmd_oper_get(hndl))
         cmd._args = arg._args
         cmd._json = arg._json
+        cmd._shared = arg._shared
         return cmd
 
     def _to_raw(self):
@@ -89,7 +90,7 @@ class ArbCmd(ArbData):
             e.append(repr(arg))
         for key, value in sorted(self._json.items()):
             e.append("{!s}={!r}".format(key, value))
-        return "ArbCmd({})".format(', '.join(e))
+        return "ArbCmd({})".format(', '.join(e)) + self._repr_shared()
 
     __str__ = __repr__
 
@@ -74,6 +74,7 @@ class Measurement(ArbData):
             meas = Measurement(raw.dqcs_meas_qubit_get(hndl), value)
         meas._args = arg._args
         meas._json = arg._json
+        meas._shared = arg._shared
         return meas
 
     def _to_raw(self):
@@ -97,7 +98,7 @@ class Measurement(ArbData):
             e.append(repr(arg))
         for key, value in sorted(self._json.items()):
             e.append("{!s}={!r}".format(key, value))
-        return "Measurement({})".format(', '.join(e))
+        return "Measurement({})".format(', '.join(e)) + self._repr_shared()
 
     __str__ = __repr__
 
@@ -7,6 +7,65 @@ import sys
 import os
 import zlib
 
+class TeeFile(object):
+    """Configures appending and size-based rotation for a tee file.
+
+    `TeeFile` objects can be used instead of a plain `Loglevel` as the values
+    of the `tee` dictionaries accepted by `Simulator` and `with_frontend()`
+    and friends."""
+
+    def __init__(self, verbosity, append=False, max_size=0, keep=5, compress=False):
+        """Constructs a tee file configuration.
+
+        `verbosity` is the loglevel filter for the file. If `append` is set,
+        messages are appended to the file if it already exists instead of
+        truncating it. If `max_size` is nonzero, the file is rotated when it
+        would exceed this many bytes: it is renamed to `<filename>.1`,
+        previously rotated files are shifted up to `<filename>.<keep>`, and
+        older files are deleted. If `compress` is set, rotated files are
+        compressed with gzip and get a `.gz` extension."""
+        super().__init__()
+        if not isinstance(verbosity, Loglevel):
+            raise TypeError("verbosity must be a Loglevel")
+        self.verbosity = verbosity
+        self.append = bool(append)
+        self.max_size = int(max_size)
+        if self.max_size < 0:
+            raise ValueError("max_size cannot be negative")
+        self.keep = int(keep)
+        if self.keep < 0:
+            raise ValueError("keep cannot be negative")
+        self.compress = bool(compress)
+
+    def __repr__(self):
+        return "TeeFile({!r}, append={!r}, max_size={!r}, keep={!r}, compress={!r})".format(
+            self.verbosity, self.append, self.max_size, self.keep, self.compress)
+
+    __str__ = __repr__
+
+def _check_tee(tee):
+    """Checks the `tee` dictionary passed to `Simulator` and friends and
+    returns a copy of it."""
+    tee = dict(tee)
+    for key, value in tee.items():
+        if not isinstance(key, str):
+            raise TypeError("tee file key must be a string")
+        if not isinstance(value, (Loglevel, TeeFile)):
+            raise TypeError("tee file value must be a Loglevel or a TeeFile")
+    return tee
+
+def _apply_tee(handle, tee, tee_fn, tee_rotate_fn):
+    """Configures the tee files in the given dictionary for the given scfg,
+    pcfg, or tcfg handle, using the corresponding `dqcs_*_tee()` and
+    `dqcs_*_tee_rotate()` functions."""
+    for key, value in tee.items():
+        if isinstance(value, TeeFile):
+            tee_rotate_fn(
+                handle, int(value.verbosity), key, value.append,
+                value.max_size, value.keep, value.compress)
+        else:
+            tee_fn(handle, int(value), key)
+
 class Simulator(object):
     """Represents a DQCsim simulator managed by Python.
 
@@ -105,13 +164,15 @@ class Simulator(object):
             sink specified by `log_capture` (if any). This defaults to
             `Loglevel.TRACE` to effectively disable the filter.
 
-          - `tee = {str: Loglevel}` (default: `{}`)
+          - `tee = {str: Loglevel or TeeFile}` (default: `{}`)
 
             Each entry in the dictionary consists of a log output filename as
             key and a loglevel filter as the value. It causes log messages
             generated by this plugin (though not including any captured
             streams) that pass the specified filter to be logged to the given
-            log file, in addition to their normal handling.
+            log file, in addition to their normal handling. A `TeeFile` can be
+            specified instead of a loglevel filter to configure appending and
+            size-based rotation.
         """
         super().__init__()
         self._sim_handle = None
@@ -182,12 +243,7 @@ class Simulator(object):
         if not isinstance(self._log_capture_verbosity, Loglevel):
             raise TypeError("log_capture_verbosity must be a Loglevel")
 
-        self._tee = dict(kwargs.pop('tee', {}))
-        for key, value in self._tee.items():
-            if not isinstance(key, str):
-                raise TypeError("tee file key must be a string")
-            if not isinstance(value, Loglevel):
-                raise TypeError("tee file value must be a Loglevel")
+        self._tee = _check_tee(kwargs.pop('tee', {}))
 
         if kwargs:
             raise TypeError("unexpected keyword argument {!r}".format(next(iter(kwargs.keys()))))
@@ -273,12 +329,7 @@ class Simulator(object):
         if not isinstance(verbosity, Loglevel):
             raise TypeError("verbosity must be a Loglevel")
 
-        tee = dict(kwargs.pop('tee', {}))
-        for key, value in tee.items():
-            if not isinstance(key, str):
-                raise TypeError("tee file key must be a string")
-            if not isinstance(value, Loglevel):
-                raise TypeError("tee file value must be a Loglevel")
+        tee = _check_tee(kwargs.pop('tee', {}))
 
         if arg_mode == 1 or arg_mode == 2:
             # Pop kwargs that are only available for processes.
@@ -327,8 +378,7 @@ class Simulator(object):
 
                     # Logging.
                     raw.dqcs_pcfg_verbosity_set(p, int(verbosity))
-                    for key, value in tee.items():
-                        raw.dqcs_pcfg_tee(p, int(value), key)
+                    _apply_tee(p, tee, raw.dqcs_pcfg_tee, raw.dqcs_pcfg_tee_rotate)
 
                     # Process environment.
                     if work is not None:
@@ -376,8 +426,7 @@ class Simulator(object):
 
                     # Logging.
                     raw.dqcs_tcfg_verbosity_set(t, int(verbosity))
-                    for key, value in tee.items():
-                        raw.dqcs_tcfg_tee(t, int(value), key)
+                    _apply_tee(t, tee, raw.dqcs_tcfg_tee, raw.dqcs_tcfg_tee_rotate)
 
                 # Return the handle.
                 return tcfg
@@ -477,13 +526,15 @@ class Simulator(object):
             forwarded to the simulator process. This defaults to
             `Loglevel.TRACE` to effectively disable the filter.
 
-          - `tee = {str: Loglevel}` (default: `{}`)
+          - `tee = {str: Loglevel or TeeFile}` (default: `{}`)
 
             Each entry in the dictionary consists of a log output filename as
             key and a loglevel filter as the value. It causes log messages
             generated by this plugin (though not including any captured
             streams) that pass the specified filter to be logged to the given
-            log file, in addition to their normal handling.
+            log file, in addition to their normal handling. A `TeeFile` can be
+            specified instead of a loglevel filter to configure appending and
+            size-based rotation.
 
           - `stderr = Loglevel or None` (default: `Loglevel.INFO`)
 
@@ -617,8 +668,7 @@ class Simulator(object):
             raw.dqcs_scfg_stderr_verbosity_set(scfg, int(self._stderr_verbosity))
             if self._log_capture is not None:
                 raw.dqcs_scfg_log_callback_pyfun(scfg, int(self._log_capture_verbosity), self._log_capture)
-            for key, value in self._tee.items():
-                raw.dqcs_scfg_tee(scfg, int(value), key)
+            _apply_tee(scfg, self._tee, raw.dqcs_scfg_tee, raw.dqcs_scfg_tee_rotate)
 
             # Push the plugins.
             with self._front() as xcfg:
@@ -752,6 +802,24 @@ class Simulator(object):
                 else:
                     return ArbData._from_raw(Handle(raw.dqcs_sim_arb(sim, str(target), cmd)))
 
+    def supports_arb(self, target, iface, oper):
+        """Returns whether one of the plugins in the pipeline supports the
+        given `ArbCmd` operation.
+
+        The `target` parameter works the same as the one in `arb()`. Plugins
+        that did not register any supported operations are assumed to accept
+        any `ArbCmd`, so this always returns `True` for them. This function
+        only works while a simulation is running, since the plugins advertise
+        their operations during initialization.
+        """
+        if self._sim_handle is None:
+            raise RuntimeError("No simulation is currently running")
+        with self._sim_handle as sim:
+            if isinstance(target, int):
+                return bool(raw.dqcs_sim_supports_arb_idx(sim, int(target), str(iface), str(oper)))
+            else:
+                return bool(raw.dqcs_sim_supports_arb(sim, str(target), str(iface), str(oper)))
+
     def get_meta(self, target):
         """Returns metadata information for one of the plugins in the
         pipeline.
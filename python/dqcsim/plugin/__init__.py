@@ -57,6 +57,8 @@ import sys
 import inspect
 import traceback
 import math, cmath
+import contextlib
+import json
 
 class JoinHandle(object):
     """Returned by `Plugin.start()` to allow waiting for completion."""
@@ -165,6 +167,34 @@ class Plugin(object):
                 ifaces.add(s[2])
             self._arb_interfaces[source] = ifaces
 
+        # The ArbCmd operations advertised through `add_arb_op()`.
+        self._arb_ops = []
+
+    def add_arb_op(self, iface, oper, schema=None):
+        """Registers an `ArbCmd` operation supported by this plugin.
+
+        The registered operations are advertised to the host and the upstream
+        plugin when the plugin is initialized. As long as no operations are
+        registered, any `ArbCmd` is accepted, and commands for unsupported
+        interfaces are silently ignored. Once at least one operation is
+        registered, `ArbCmd`s for other operations are rejected with an error,
+        both before they are sent and when they are received.
+
+        `schema` optionally specifies a JSON schema for the JSON payload of the
+        operation, either as a string or as a Python object that is converted
+        to JSON. Commands with a payload that does not match the schema are
+        rejected as well.
+
+        This must be called before the plugin is started, usually from
+        `__init__()`."""
+        if not hasattr(self, '_arb_ops'):
+            raise RuntimeError("It looks like you've overridden __init__ and forgot to call super().__init__(). Please fix!")
+        if self._started:
+            raise RuntimeError("Cannot register ArbCmd operations after the plugin has been started")
+        if schema is not None and not isinstance(schema, str):
+            schema = json.dumps(schema)
+        self._arb_ops.append((str(iface), str(oper), schema))
+
     def _check_run(self, simulator):
         """Checks that the plugin is ready to be started and figures out the
         simulator address."""
@@ -412,11 +442,17 @@ class Plugin(object):
             raw.dqcs_pdef_set_initialize_cb_pyfun(pd, self._cbent('initialize'))
             raw.dqcs_pdef_set_drop_cb_pyfun(pd, self._cbent('drop'))
             raw.dqcs_pdef_set_host_arb_cb_pyfun(pd, self._cbent('host_arb'))
+            for iface, oper, schema in self._arb_ops:
+                raw.dqcs_pdef_add_arb_op(pd, iface, oper, schema)
         return pdef
 
 class GateStreamSource(Plugin):
     """Adds gatestream source functions."""
 
+    # The (qubit, value) condition applied to the gates sent downstream, or
+    # None if they are unconditional. See `conditional()`.
+    _condition = None
+
     #==========================================================================
     # API functions operating on plugin state
     #==========================================================================
@@ -447,6 +483,16 @@ class GateStreamSource(Plugin):
         with QubitSet._to_raw(*qubits) as qubits:
             self._pc(raw.dqcs_plugin_free, qubits)
 
+    def reset(self, *qubits):
+        """Instructs the downstream plugin to reset the given qubits to |0>.
+
+        Besides resetting the quantum state, this clears the measurement
+        registers of the qubits, such that `get_measurement()` and the
+        cycles-since-measure functions behave as if the qubits were freshly
+        allocated."""
+        with QubitSet._to_raw(*qubits) as qubits:
+            self._pc(raw.dqcs_plugin_reset, qubits)
+
     def unitary(self, targets, matrix, controls=[], arb=None):
         """Instructs the downstream plugin to execute a unitary quantum gate.
 
@@ -469,7 +515,48 @@ class GateStreamSource(Plugin):
                             raise TypeError('arb must be None or an instance of ArbData')
                         arb._to_raw(gate)
                     with gate as gate_raw:
-                        self._pc(raw.dqcs_plugin_gate, gate_raw)
+                        self._gate(gate_raw)
+
+    def _gate(self, gate):
+        """Sends the given raw gate handle downstream, after making it
+        conditional if a condition is active."""
+        if self._condition is not None:
+            qubit, value = self._condition
+            if value is None:
+                value = raw.DQCS_MEAS_UNDEFINED
+            elif value:
+                value = raw.DQCS_MEAS_ONE
+            else:
+                value = raw.DQCS_MEAS_ZERO
+            raw.dqcs_gate_set_condition(gate, qubit, value)
+        self._pc(raw.dqcs_plugin_gate, gate)
+
+    @contextlib.contextmanager
+    def _condition_scope(self, condition):
+        """Context manager that sets the condition for the gates sent
+        downstream within its scope, restoring the previous condition
+        afterwards."""
+        previous = self._condition
+        self._condition = condition
+        try:
+            yield
+        finally:
+            self._condition = previous
+
+    def conditional(self, qubit, value):
+        """Returns a context manager that makes the gates sent downstream
+        within its scope conditional on the measurement register of `qubit`.
+
+        The gates are only executed if the measurement register equals
+        `value` (0, 1, or None for undefined) at the time they are executed.
+        The condition is evaluated by the backend, so there is no need to wait
+        for the measurement result using `get_measurement()`. Gates that
+        measure qubits cannot be made conditional. For example:
+
+            with self.conditional(a, 1):
+                self.x_gate(b)
+        """
+        return self._condition_scope((qubit, value))
 
     def i_gate(self, target, arb=None):
         """Instructs the downstream plugin to execute an I gate.
@@ -675,7 +762,7 @@ class GateStreamSource(Plugin):
                         raise TypeError('arb must be None or an instance of ArbData')
                     arb._to_raw(gate)
                 with gate as gate_raw:
-                    self._pc(raw.dqcs_plugin_gate, gate_raw)
+                    self._gate(gate_raw)
 
     def measure_x(self, *qubits, arb=None):
         """Instructs the downstream plugin to measure the given qubits in the
@@ -704,6 +791,33 @@ class GateStreamSource(Plugin):
         """
         self.measure(*qubits, basis='Z', arb=arb)
 
+    def pauli_measure(self, targets, observable, arb=None):
+        """Instructs the downstream plugin to measure an observable, usually a
+        product of Pauli matrices, over the given qubits.
+
+        `targets` must be a non-empty iterable of qubits. `observable` is
+        either a string such as `'ZZ'` consisting of the characters `I`, `X`,
+        `Y`, and `Z`, one for each target qubit, or a Hermitian unitary matrix
+        appropriately sized for the number of target qubits, specified as a
+        row-major one-dimensional list of Python complex numbers. The qubits
+        are projected onto the +1 or -1 eigenspace of the observable, and a
+        single measurement result (0 or 1 respectively) is returned for the
+        first target qubit.
+        """
+        if isinstance(observable, str):
+            observable = Handle(raw.dqcs_mat_pauli(observable))
+        else:
+            observable = Handle(raw.dqcs_mat_new(observable))
+        with QubitSet._to_raw(targets) as targets:
+            with observable as mat:
+                gate = Handle(raw.dqcs_gate_new_pauli_measurement(targets, mat))
+                if arb is not None:
+                    if not isinstance(arb, ArbData):
+                        raise TypeError('arb must be None or an instance of ArbData')
+                    arb._to_raw(gate)
+                with gate as gate_raw:
+                    self._gate(gate_raw)
+
     def prepare(self, *qubits, basis='Z', arb=None):
         """Instructs the downstream plugin to force the given qubits into the
         base state for the given basis.
@@ -736,7 +850,7 @@ class GateStreamSource(Plugin):
                         raise TypeError('arb must be None or an instance of ArbData')
                     arb._to_raw(gate)
                 with gate as gate_raw:
-                    self._pc(raw.dqcs_plugin_gate, gate_raw)
+                    self._gate(gate_raw)
 
     def prepare_x(self, *qubits, arb=None):
         """Instructs the downstream plugin to force the given qubits into the
@@ -793,7 +907,7 @@ class GateStreamSource(Plugin):
                         gate = Handle(raw.dqcs_gate_new_custom(name, targets, controls, measures, 0))
                     ArbData(*args, **kwargs)._to_raw(gate)
                     with gate as gate:
-                        self._pc(raw.dqcs_plugin_gate, gate)
+                        self._gate(gate)
 
     def get_measurement(self, qubit):
         """Returns the `Measurement` representing the latest measurement result
@@ -818,6 +932,16 @@ class GateStreamSource(Plugin):
         advancement is returned."""
         return self._pc(raw.dqcs_plugin_advance, int(cycles))
 
+    def flush(self):
+        """Sends the queued allocate, free, gate, and reset requests to the
+        downstream plugin.
+
+        These requests are queued until the next synchronization point, such
+        as a measurement query, `arb()`, or `advance()`, such that many of
+        them can be sent in a single message. Calling this function is never
+        required."""
+        self._pc(raw.dqcs_plugin_flush)
+
     def get_cycle(self):
         """Returns the current simulation time for the downstream plugin."""
         return self._pc(raw.dqcs_plugin_get_cycle)
@@ -987,6 +1111,15 @@ class Operator(GateStreamSource):
         qubit list. If the handler is not specified, the deallocation is
         forwarded automatically.
 
+     - `handle_reset(qubits: [Qubit]) -> None`
+
+        Called when the upstream plugin wants to reset the specified qubits to
+        |0>. The measurement registers of the qubits are cleared automatically.
+
+        In almost all cases, this handler must call `reset()` to forward the
+        reset downstream, possibly modifying the qubit list. If the handler is
+        not specified, the reset is forwarded automatically.
+
      - `handle_unitary_gate(
             targets: [Qubit],
             matrix: [complex],
@@ -1057,6 +1190,20 @@ class Operator(GateStreamSource):
          - initialize each qubit to |0>
          - rotate each qubit by the given matrix
 
+     - `handle_pauli_measurement_gate(
+            targets: [Qubit],
+            observable: [complex],
+            arb: ArbData
+        ) -> [Measurement]`
+
+        Called when the upstream plugin wants to measure an observable, usually
+        a product of Pauli matrices, over the given qubits. The observable is a
+        Hermitian unitary matrix sized for the number of target qubits. A
+        single measurement result is returned upstream for the first target
+        qubit, with the same semantics as for `handle_measurement_gate()`. The
+        gate is normally forwarded downstream using `pauli_measure()`. If this
+        handler is not defined, this is done automatically.
+
      -  `handle_<name>_gate(
             targets: [Qubit],
             controls: [Qubit],
@@ -1109,6 +1256,11 @@ class Operator(GateStreamSource):
         command is forwarded downstream. If there is at least one, it is NOT
         forwarded downstream, even if the requested operation does not have a
         handler (an error will be reported instead).
+
+    Gates received from upstream can be conditional on the measurement register
+    of a qubit (see `conditional()`). The gates sent downstream by the gate
+    handlers, or by the default forwarding behavior, inherit this condition.
+    Handlers of conditional gates can therefore not measure qubits.
     """
 
     #==========================================================================
@@ -1125,6 +1277,11 @@ class Operator(GateStreamSource):
         qubits = QubitSet._from_raw(Handle(qubits_handle))
         self._cb(state_handle, 'handle_free', qubits)
 
+    def _route_reset(self, state_handle, qubits_handle):
+        """Routes the reset callback to user code."""
+        qubits = QubitSet._from_raw(Handle(qubits_handle))
+        self._cb(state_handle, 'handle_reset', qubits)
+
     def _forward_unitary_gate(self, targets, controls, matrix, arb):
         self.unitary(targets, matrix, controls, arb)
 
@@ -1134,6 +1291,9 @@ class Operator(GateStreamSource):
     def _forward_prepare_gate(self, qubits, basis, arb):
         self.prepare(qubits, basis=basis, arb=arb)
 
+    def _forward_pauli_measurement_gate(self, targets, observable, arb):
+        self.pauli_measure(targets, observable, arb=arb)
+
     def _route_gate(self, state_handle, gate_handle):
         """Routes the gate callback to user code."""
 
@@ -1155,12 +1315,32 @@ class Operator(GateStreamSource):
             fast_forward = fast_forward and not hasattr(self, 'handle_measurement_gate')
         elif typ == raw.DQCS_GATE_TYPE_PREP:
             fast_forward = fast_forward and not hasattr(self, 'handle_prepare_gate')
+        elif typ == raw.DQCS_GATE_TYPE_PAULI_MEASUREMENT:
+            fast_forward = not hasattr(self, 'handle_pauli_measurement_gate')
         elif typ == raw.DQCS_GATE_TYPE_CUSTOM:
             fast_forward = not hasattr(self, 'handle_{}_gate'.format(name))
         if fast_forward:
             raw.dqcs_plugin_gate(state_handle, gate_handle)
             return MeasurementSet._to_raw([]).take()
 
+        # Gates sent downstream in response to a conditional gate inherit its
+        # condition.
+        condition = None
+        if raw.dqcs_gate_has_condition(gate_handle):
+            condition = (
+                raw.dqcs_gate_condition_qubit(gate_handle),
+                {
+                    raw.DQCS_MEAS_ZERO: 0,
+                    raw.DQCS_MEAS_ONE: 1,
+                    raw.DQCS_MEAS_UNDEFINED: None,
+                }[raw.dqcs_gate_condition_value(gate_handle)]
+            ) #@
+        with self._condition_scope(condition):
+            return self._route_gate_to_handler(state_handle, gate_handle, typ, name)
+
+    def _route_gate_to_handler(self, state_handle, gate_handle, typ, name):
+        """Routes a gate that was not fast-forwarded to user code."""
+
         # Convert from Rust domain to Python domain.
         targets = QubitSet._from_raw(Handle(raw.dqcs_gate_targets(gate_handle)))
         controls = QubitSet._from_raw(Handle(raw.dqcs_gate_controls(gate_handle)))
@@ -1193,6 +1373,11 @@ class Operator(GateStreamSource):
                 self._cb(state_handle, 'handle_prepare_gate', targets, matrix, data)
             except NotImplementedError:
                 self._cb(state_handle, '_forward_prepare_gate', targets, matrix, data)
+        elif typ == raw.DQCS_GATE_TYPE_PAULI_MEASUREMENT:
+            try:
+                measurements = self._cb(state_handle, 'handle_pauli_measurement_gate', targets, matrix, data)
+            except NotImplementedError:
+                self._cb(state_handle, '_forward_pauli_measurement_gate', targets, matrix, data)
         elif typ == raw.DQCS_GATE_TYPE_CUSTOM:
             # Note that `handle_<name>_gate` must exist at this point,
             # otherwise it would have been forwarded earlier.
@@ -1249,6 +1434,8 @@ class Operator(GateStreamSource):
                 raw.dqcs_pdef_set_allocate_cb_pyfun(pd, self._cbent('allocate'))
             if 'handle_free' in handlers:
                 raw.dqcs_pdef_set_free_cb_pyfun(pd, self._cbent('free'))
+            if 'handle_reset' in handlers:
+                raw.dqcs_pdef_set_reset_cb_pyfun(pd, self._cbent('reset'))
             if any(map(lambda name: name.endswith('_gate'), handlers)):
                 raw.dqcs_pdef_set_gate_cb_pyfun(pd, self._cbent('gate'))
             if 'handle_measurement' in handlers:
@@ -1345,6 +1532,13 @@ class Backend(Plugin):
         Called when the upstream plugin doesn't need the specified qubits
         anymore.
 
+     - `handle_reset(qubits: [Qubit]) -> None`
+
+        Called when the upstream plugin wants to reset the specified qubits to
+        |0>. The measurement registers of the qubits are cleared automatically.
+        If this function is not specified, `handle_prepare_gate()` is called
+        with the Z basis instead.
+
      - `handle_controlled_gate(
             targets: [Qubit],
             controls: [Qubit],
@@ -1377,6 +1571,20 @@ class Backend(Plugin):
         of which the binary string list is passed to `*args`, and the JSON
         object is passed to `**kwargs`.
 
+     - `handle_pauli_measurement_gate(
+            targets: [Qubit],
+            observable: [complex],
+            arb: ArbData
+        ) -> [Measurement]`
+
+        Called when an observable, usually a product of Pauli matrices, must be
+        measured over the given qubits. The observable is a Hermitian unitary
+        matrix sized for the number of target qubits. The qubits must be
+        projected onto its +1 or -1 eigenspace, and the returned list must
+        contain a single measurement for the first target qubit, with value 0
+        or 1 respectively. If this function is not specified, Pauli measurement
+        gates result in an error.
+
      - `handle_advance(cycles: int) -> None`
 
         Called to advance simulation time.
@@ -1413,6 +1621,11 @@ class Backend(Plugin):
         except NotImplementedError:
             pass
 
+    def _route_reset(self, state_handle, qubits_handle):
+        """Routes the reset callback to user code."""
+        qubits = QubitSet._from_raw(Handle(qubits_handle))
+        self._cb(state_handle, 'handle_reset', qubits)
+
     def _route_gate(self, state_handle, gate_handle):
         """Routes the gate callback to user code."""
         typ = raw.dqcs_gate_type(gate_handle)
@@ -1467,6 +1680,11 @@ class Backend(Plugin):
             measurements = self._cb(state_handle, 'handle_measurement_gate', measures, matrix, data)
         elif typ == raw.DQCS_GATE_TYPE_PREP:
             self._cb(state_handle, 'handle_prepare_gate', targets, matrix, data)
+        elif typ == raw.DQCS_GATE_TYPE_PAULI_MEASUREMENT:
+            try:
+                measurements = self._cb(state_handle, 'handle_pauli_measurement_gate', targets, matrix, data)
+            except NotImplementedError:
+                raise NotImplementedError("Pauli measurement gates are not implemented by this plugin")
         elif typ == raw.DQCS_GATE_TYPE_CUSTOM:
             try:
                 measurements = self._cb(state_handle,
@@ -1499,6 +1717,9 @@ class Backend(Plugin):
         with pdef as pd:
             raw.dqcs_pdef_set_allocate_cb_pyfun(pd, self._cbent('allocate'))
             raw.dqcs_pdef_set_free_cb_pyfun(pd, self._cbent('free'))
+            if hasattr(self, 'handle_reset'):
+                # Without a handler, DQCsim falls back to a prep gate.
+                raw.dqcs_pdef_set_reset_cb_pyfun(pd, self._cbent('reset'))
             raw.dqcs_pdef_set_gate_cb_pyfun(pd, self._cbent('gate'))
             raw.dqcs_pdef_set_advance_cb_pyfun(pd, self._cbent('advance'))
             raw.dqcs_pdef_set_upstream_arb_cb_pyfun(pd, self._cbent('upstream_arb'))
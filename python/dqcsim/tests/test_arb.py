@@ -137,10 +137,28 @@ class Operations(unittest.TestCase):
         a.clear_json()
         self.assertEqual(a, ArbData(b'a', b'b', b'c'))
 
-        a = ArbData(b'a', b'b', b'c', b=3, c=4, d=5)
+        a = ArbData(b'a', b'b', b'c', b=3, c=4, d=5).with_shared(b'x')
+        a.clear_shared()
+        self.assertEqual(a, ArbData(b'a', b'b', b'c', b=3, c=4, d=5))
+
+        a = ArbData(b'a', b'b', b'c', b=3, c=4, d=5).with_shared(b'x')
         a.clear()
         self.assertEqual(a, ArbData())
 
+    def test_shared(self):
+        a = ArbData(b'a', b=3)
+        self.assertEqual(a.shared, ())
+        a.append_shared(b'x')
+        with self.assertRaises(TypeError):
+            a.append_shared('y')
+        self.assertIs(a.with_shared(b'yz', bytearray(b'w')), a)
+        self.assertEqual(a.shared, (b'x', b'yz', bytearray(b'w')))
+        self.assertEqual(len(a), 1)
+        self.assertTrue(bool(ArbData().with_shared(b'')))
+        self.assertNotEqual(a, ArbData(b'a', b=3))
+        self.assertEqual(a, ArbData(a))
+        self.assertEqual(repr(a), "ArbData(b'a', b=3).with_shared(<1 bytes>, <2 bytes>, <1 bytes>)")
+
     def test_handles(self):
         a = ArbData(b'a', b'b', b'c', b=3, c=4, d=5)._to_raw()
         self.maxDiff = None
@@ -217,6 +235,13 @@ class Operations(unittest.TestCase):
 
         self.assertEqual(ArbData._from_raw(a), ArbData(b'c', b'd', b'e', b=6, c=7, d=8))
 
+    def test_shared_handles(self):
+        a = ArbData(b'a', b=3).with_shared(b'x', bytes(range(256)) * 4096, b'')
+        h = a._to_raw()
+        self.assertEqual(ArbData._from_raw(h), a)
+        ArbData(b'c')._to_raw(h)
+        self.assertEqual(ArbData._from_raw(h), ArbData(b'c'))
+
     def test_long_data(self):
         data = list(range(256))
         bdata = bytes(data) * 4
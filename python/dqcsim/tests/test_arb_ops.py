@@ -0,0 +1,69 @@
+import unittest, logging, os, sys, tempfile, re, math, cmath, pickle
+from dqcsim.common import *
+from dqcsim.host import *
+from dqcsim.plugin import *
+
+def catch_errors(fn, *args, **kwargs):
+    try:
+        return fn(*args, **kwargs)
+    except Exception as e:
+        return str(e)
+
+@plugin("Test frontend plugin", "Test", "0.1")
+class TestFrontend(Frontend):
+    def handle_run(self, *args, **kwargs):
+        return ArbData(pickle.dumps((
+            catch_errors(self.arb, 'a', 'b', shots=3)[0],
+            catch_errors(self.arb, 'a', 'c'),
+            catch_errors(self.arb, 'a', 'b', shots='3'),
+        )))
+
+@plugin("Test backend plugin", "Test", "0.1")
+class TestBackend(Backend):
+    def __init__(self):
+        super().__init__()
+        self.add_arb_op('a', 'b', {'properties': {'shots': {'type': 'integer'}}})
+        self.add_arb_op('x', 'y')
+
+    def handle_unitary_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_measurement_gate(self, measures, matrix, arb):
+        return [Measurement(qubit, 0) for qubit in measures]
+
+    def handle_prepare_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_upstream_a_b(self, shots=None):
+        return ArbData(b'ok')
+
+    def handle_upstream_a_c(self):
+        return ArbData(b'not ok')
+
+    def handle_host_x_y(self):
+        return ArbData(b'ok')
+
+class Tests(unittest.TestCase):
+    def test_arb_ops(self):
+        sim = Simulator(
+            TestFrontend(), TestBackend(),
+            repro=None, stderr_verbosity=Loglevel.ERROR
+        )
+        sim.simulate()
+        self.assertTrue(sim.supports_arb('back', 'a', 'b'))
+        self.assertTrue(sim.supports_arb(-1, 'x', 'y'))
+        self.assertFalse(sim.supports_arb(-1, 'x', 'z'))
+        self.assertTrue(sim.supports_arb('front', 'x', 'z'))
+        self.assertEqual(sim.arb('back', 'x', 'y')[0], b'ok')
+        with self.assertRaises(RuntimeError):
+            sim.arb('back', 'x', 'z')
+        sim.start()
+        self.assertEqual(pickle.loads(sim.wait()[0]), (
+            b'ok',
+            'Invalid argument: downstream plugin does not support ArbCmd a.c',
+            'Invalid argument: invalid payload for ArbCmd a.b: $.shots: expected integer, found string',
+        ))
+        sim.stop()
+
+if __name__ == '__main__':
+    unittest.main()
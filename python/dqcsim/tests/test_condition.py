@@ -0,0 +1,75 @@
+import unittest, logging, os, sys, tempfile, re, math, cmath, pickle
+from dqcsim.common import *
+from dqcsim.host import *
+from dqcsim.plugin import *
+
+@plugin("Test frontend plugin", "Test", "0.1")
+class TestFrontend(Frontend):
+    def handle_run(self, *args, **kwargs):
+        self.allocate(3)
+        self.measure(1)
+        with self.conditional(1, 1):
+            self.x_gate(2)
+        with self.conditional(1, 0):
+            self.z_gate(2)
+        self.h_gate(2)
+
+@plugin("Test backend plugin", "Test", "0.1")
+class TestBackend(Backend):
+    def __init__(self):
+        super().__init__()
+        self.call_log = []
+
+    def handle_unitary_gate(self, targets, matrix, arb):
+        self.call_log.append({
+            'targets': targets,
+            'matrix': pickle.dumps(matrix),
+        })
+
+    def handle_measurement_gate(self, measures, matrix, arb):
+        return [Measurement(qubit, qubit % 2) for qubit in measures]
+
+    def handle_prepare_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_host_get_log(self):
+        log = self.call_log
+        self.call_log = []
+        return ArbData(log=log)
+
+@plugin("Null operator plugin", "Test", "0.1")
+class NullOperator(Operator):
+    pass
+
+@plugin("Test operator", "Test", "0.1")
+class ShiftOperator(Operator):
+    def handle_unitary_gate(self, targets, matrix, arb):
+        self.unitary([q+1 for q in targets], matrix)
+
+class Tests(unittest.TestCase):
+    def check_with_operator(self, operator_cls, shift):
+        sim = Simulator(
+            TestFrontend(), operator_cls(), TestBackend(),
+            repro=None, stderr_verbosity=Loglevel.ERROR
+        )
+        sim.simulate()
+        sim.start()
+        sim.wait()
+        log = sim.arb('back', 'get', 'log')['log']
+        sim.stop()
+
+        # The Z gate is skipped, because qubit 1 was measured as one.
+        self.assertEqual(len(log), 2)
+        self.assertEqual(log[0]['targets'], [2+shift])
+        self.assertTrue(abs(pickle.loads(log[0]['matrix'])[1] - 1.0) < 0.01)
+        self.assertEqual(log[1]['targets'], [2+shift])
+        self.assertTrue(abs(pickle.loads(log[1]['matrix'])[0] - math.sqrt(0.5)) < 0.01)
+
+    def test_condition_with_null_operator(self):
+        self.check_with_operator(NullOperator, 0)
+
+    def test_condition_with_operator(self):
+        self.check_with_operator(ShiftOperator, 1)
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,116 @@
+import unittest, logging, os, sys, tempfile, re, math, cmath, pickle
+from dqcsim.common import *
+from dqcsim.host import *
+from dqcsim.plugin import *
+
+ZZ = [
+    1.0, 0.0, 0.0, 0.0,
+    0.0, -1.0, 0.0, 0.0,
+    0.0, 0.0, -1.0, 0.0,
+    0.0, 0.0, 0.0, 1.0,
+]
+
+ZI = [
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, -1.0, 0.0,
+    0.0, 0.0, 0.0, -1.0,
+]
+
+@plugin("Test frontend plugin", "Test", "0.1")
+class TestFrontend(Frontend):
+    def handle_run(self, *args, **kwargs):
+        self.allocate(3)
+        self.pauli_measure([2, 1], 'ZZ')
+        self.pauli_measure([3, 2], ZI)
+        return ArbData(
+            a=self.get_measurement(2).value,
+            b=self.get_measurement(3).value)
+
+@plugin("Test backend plugin", "Test", "0.1")
+class TestBackend(Backend):
+    def __init__(self):
+        super().__init__()
+        self.call_log = []
+
+    def handle_unitary_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_measurement_gate(self, measures, matrix, arb):
+        return [Measurement(qubit, 0) for qubit in measures]
+
+    def handle_prepare_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_pauli_measurement_gate(self, targets, observable, arb):
+        self.call_log.append({
+            'targets': targets,
+            'observable': pickle.dumps(observable),
+        })
+        return [Measurement(targets[0], targets[0] == 3)]
+
+    def handle_host_get_log(self):
+        log = self.call_log
+        self.call_log = []
+        return ArbData(log=log)
+
+@plugin("Invalid backend plugin", "Test", "0.1")
+class InvalidBackend(Backend):
+    def handle_unitary_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_measurement_gate(self, measures, matrix, arb):
+        return [Measurement(qubit, 0) for qubit in measures]
+
+    def handle_prepare_gate(self, targets, matrix, arb):
+        pass
+
+@plugin("Null operator plugin", "Test", "0.1")
+class NullOperator(Operator):
+    pass
+
+@plugin("Test operator", "Test", "0.1")
+class ForwardingOperator(Operator):
+    def handle_pauli_measurement_gate(self, targets, observable, arb):
+        self.pauli_measure(targets, observable, arb=arb)
+
+class Tests(unittest.TestCase):
+    def assertEqualMatrix(self, pickled, reference):
+        received = pickle.loads(pickled)
+        self.assertEqual(len(received), len(reference))
+        for rec, ref in zip(received, reference):
+            self.assertTrue(abs(rec - ref) < 0.01)
+
+    def check_with_operator(self, operator_cls):
+        sim = Simulator(
+            TestFrontend(), operator_cls(), TestBackend(),
+            repro=None, stderr_verbosity=Loglevel.ERROR
+        )
+        sim.simulate()
+        sim.start()
+        self.assertEqual(sim.wait(), ArbData(a=0, b=1))
+        log = sim.arb('back', 'get', 'log')['log']
+        sim.stop()
+
+        self.assertEqual(len(log), 2)
+        self.assertEqual(log[0]['targets'], [2, 1])
+        self.assertEqualMatrix(log[0]['observable'], ZZ)
+        self.assertEqual(log[1]['targets'], [3, 2])
+        self.assertEqualMatrix(log[1]['observable'], ZI)
+
+    def test_pauli_measurement_with_null_operator(self):
+        self.check_with_operator(NullOperator)
+
+    def test_pauli_measurement_with_operator(self):
+        self.check_with_operator(ForwardingOperator)
+
+    def test_invalid_backend(self):
+        sim = Simulator(
+            TestFrontend(), InvalidBackend(),
+            repro=None, stderr_verbosity=Loglevel.OFF
+        )
+        with self.assertRaises(RuntimeError):
+            sim.run()
+
+if __name__ == '__main__':
+    unittest.main()
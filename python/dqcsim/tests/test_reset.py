@@ -0,0 +1,83 @@
+import unittest, logging, os, sys, tempfile, re, math, cmath, pickle
+from dqcsim.common import *
+from dqcsim.host import *
+from dqcsim.plugin import *
+
+def catch_errors(fn, *args, **kwargs):
+    try:
+        return fn(*args, **kwargs)
+    except Exception as e:
+        return str(e)
+
+@plugin("Test frontend plugin", "Test", "0.1")
+class TestFrontend(Frontend):
+    def handle_run(self, *args, **kwargs):
+        self.allocate(3)
+        self.measure(1, 2)
+        self.get_measurement(1)
+        self.reset(1, 3)
+        return ArbData(pickle.dumps((
+            catch_errors(self.get_measurement, 1),
+            catch_errors(self.get_measurement, 2),
+        )))
+
+@plugin("Test backend plugin", "Test", "0.1")
+class TestBackend(Backend):
+    def __init__(self):
+        super().__init__()
+        self.call_log = []
+
+    def handle_unitary_gate(self, targets, matrix, arb):
+        pass
+
+    def handle_measurement_gate(self, measures, matrix, arb):
+        return [Measurement(qubit, 1) for qubit in measures]
+
+    def handle_prepare_gate(self, targets, matrix, arb):
+        self.call_log.append(('prepare', targets))
+
+    def handle_host_get_log(self):
+        log = self.call_log
+        self.call_log = []
+        return ArbData(pickle.dumps(log))
+
+@plugin("Test backend plugin", "Test", "0.2")
+class TestResetBackend(TestBackend):
+    def handle_reset(self, qubits):
+        self.call_log.append(('reset', qubits))
+
+@plugin("Null operator plugin", "Test", "0.1")
+class NullOperator(Operator):
+    pass
+
+@plugin("Test operator", "Test", "0.1")
+class TestOperator(Operator):
+    def handle_reset(self, qubits):
+        self.reset([q for q in qubits if q != 3])
+
+class Tests(unittest.TestCase):
+    def check(self, operator_cls, backend_cls, expected_log):
+        sim = Simulator(
+            TestFrontend(), operator_cls(), backend_cls(),
+            repro=None, stderr_verbosity=Loglevel.ERROR
+        )
+        sim.simulate()
+        sim.start()
+        self.assertEqual(pickle.loads(sim.wait()[0]), (
+            'Invalid argument: qubit 1 has not been measured yet',
+            Measurement(2, 1),
+        ))
+        self.assertEqual(pickle.loads(sim.arb('back', 'get', 'log')[0]), expected_log)
+        sim.stop()
+
+    def test_reset_as_prep(self):
+        self.check(NullOperator, TestBackend, [('prepare', [1, 3])])
+
+    def test_reset_callback(self):
+        self.check(NullOperator, TestResetBackend, [('reset', [1, 3])])
+
+    def test_reset_with_operator(self):
+        self.check(TestOperator, TestResetBackend, [('reset', [1])])
+
+if __name__ == '__main__':
+    unittest.main()
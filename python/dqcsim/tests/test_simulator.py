@@ -300,6 +300,35 @@ class Tests(unittest.TestCase):
                 f = f.read()
                 self.assertTrue('Trace' in f)
 
+    def test_tee_rotate(self):
+        with tempfile.TemporaryDirectory() as base:
+            for _ in range(2):
+                sim = Simulator(
+                    (NullFrontend(), {'tee': {
+                        base+'/front.log': TeeFile(Loglevel.TRACE, max_size=64, keep=2, compress=True),
+                    }}),
+                    NullBackend(),
+                    tee={base+'/sim.log': TeeFile(Loglevel.TRACE, append=True)},
+                    repro=None, stderr_verbosity=Loglevel.OFF
+                )
+                sim.simulate(33)
+                sim.stop()
+
+            # Each message exceeds the maximum size, so each ends up in its
+            # own file.
+            self.assertTrue(os.path.exists(base+'/front.log.1.gz'))
+            self.assertTrue(os.path.exists(base+'/front.log.2.gz'))
+            self.assertFalse(os.path.exists(base+'/front.log.3.gz'))
+
+            with open(base+'/sim.log', 'r') as f:
+                f = f.read()
+                self.assertEqual(f.count('seed: 33\n'), 2)
+
+        with self.assertRaises(TypeError):
+            Simulator(NullFrontend(), NullBackend(), tee={'x': 'trace'})
+        with self.assertRaises(ValueError):
+            TeeFile(Loglevel.TRACE, max_size=-1)
+
     def test_seed(self):
         with tempfile.TemporaryDirectory() as base:
             sim = Simulator(
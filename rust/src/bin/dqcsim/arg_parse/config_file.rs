@@ -0,0 +1,604 @@
+use crate::{
+    arg_parse::opts::{DQCsimStructOpt, PluginStructOpt},
+    output::OutputFormat,
+};
+use dqcsim::{
+    common::{
+        log::{module_filter::LogDirectives, tee_file::TeeFileConfiguration, *},
+        util::friendly_enum_parse,
+    },
+    host::{configuration::*, reproduction::HostCall},
+};
+use failure::{format_err, Error};
+use serde::Deserialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Scalar value in a configuration file that is interpreted as a string, so
+/// numbers can be written without quotes.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Integer(u64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scalar::Integer(x) => write!(f, "{}", x),
+            Scalar::Float(x) => write!(f, "{}", x),
+            Scalar::String(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+/// The plugin description in a configuration file, before its values are
+/// parsed.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct RawPlugin {
+    spec: String,
+    name: Option<String>,
+    #[serde(default)]
+    init: Vec<String>,
+    #[serde(default)]
+    env: Vec<String>,
+    work: Option<PathBuf>,
+    level: Option<String>,
+    #[serde(default)]
+    tee: Vec<String>,
+    stdout: Option<String>,
+    stderr: Option<String>,
+    accept_timeout: Option<Scalar>,
+    shutdown_timeout: Option<Scalar>,
+}
+
+/// The contents of a configuration file, before its values are parsed.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct RawConfigFile {
+    plugin_path: Option<Vec<PathBuf>>,
+    interpreter: Option<Vec<String>>,
+    seed: Option<Scalar>,
+    level: Option<String>,
+    log_format: Option<String>,
+    tee: Option<Vec<String>>,
+    dqcsim_level: Option<String>,
+    plugin_level: Option<String>,
+    host_stdout: Option<bool>,
+    output: Option<String>,
+    repro_out: Option<PathBuf>,
+    repro_paths: Option<String>,
+    trace_out: Option<PathBuf>,
+    profile: Option<bool>,
+    call: Option<Vec<String>>,
+    #[serde(default)]
+    plugins: Vec<RawPlugin>,
+}
+
+/// Prefixes the error of a failed conversion with the configuration file key
+/// it belongs to.
+fn context<T>(key: &str, result: Result<T, impl fmt::Display>) -> Result<T, Error> {
+    result.map_err(|e| format_err!("{}: {}", key, e))
+}
+
+/// Parses a configuration file value using the same syntax as the
+/// corresponding command line option.
+fn parse<T>(key: &str, value: impl fmt::Display) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    context(key, value.to_string().parse())
+}
+
+/// Replaces `target` with `value` if the latter is specified and the option
+/// was not given on the command line.
+fn set<T>(given: bool, target: &mut T, value: Option<T>) {
+    if let (false, Some(value)) = (given, value) {
+        *target = value;
+    }
+}
+
+/// Parses all the values of a list-valued key.
+fn parse_all<T>(key: &str, values: &[impl fmt::Display]) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| parse(&format!("{}[{}]", key, index), value))
+        .collect()
+}
+
+/// A simulation configuration loaded from a YAML or TOML file with
+/// `--config`.
+///
+/// The keys in the file are named after the corresponding long command line
+/// options and their values use the same syntax. Options that are not
+/// specified in the file are `None`, such that the command line defaults
+/// apply.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigFile {
+    /// The extra plugin search directories (`plugin-path`).
+    pub plugin_path: Option<Vec<PathBuf>>,
+
+    /// The interpreter commands for script plugins (`interpreter`).
+    pub interpreters: Option<Vec<InterpreterEntry>>,
+
+    /// The random seed (`seed`).
+    pub seed: Option<Seed>,
+
+    /// The minimum loglevel for messages written to stderr (`level`).
+    pub stderr_level: Option<LoglevelFilter>,
+
+    /// The format for messages written to stderr (`log-format`).
+    pub stderr_format: Option<LogFormat>,
+
+    /// The tee files for DQCsim as a whole (`tee`).
+    pub tee_files: Option<Vec<TeeFileConfiguration>>,
+
+    /// The logging verbosity for DQCsim itself (`dqcsim-level`).
+    pub dqcsim_level: Option<LoglevelFilter>,
+
+    /// The default logging verbosity for the plugins (`plugin-level`).
+    pub plugin_level: Option<LogDirectives>,
+
+    /// Whether to print host call return values to stdout (`host-stdout`).
+    pub host_stdout: Option<bool>,
+
+    /// The format of the output written to stdout (`output`).
+    pub output_format: Option<OutputFormat>,
+
+    /// The reproduction output filename (`repro-out`).
+    pub repro_out: Option<PathBuf>,
+
+    /// The path style for the reproduction file (`repro-paths`).
+    pub repro_path_style: Option<ReproductionPathStyle>,
+
+    /// The gatestream trace output filename (`trace-out`).
+    pub trace_out: Option<PathBuf>,
+
+    /// Whether to log the plugin performance profiles (`profile`).
+    pub profile: Option<bool>,
+
+    /// The host call sequence (`call`).
+    pub host_calls: Option<Vec<HostCall>>,
+
+    /// The sugared plugin specifications (`spec`) and the plugin options
+    /// (`plugins`), from front to back.
+    pub plugins: Vec<(String, PluginStructOpt)>,
+}
+
+impl ConfigFile {
+    /// Loads a configuration file. Files with a `.toml` extension are parsed
+    /// as TOML, anything else as YAML (which includes JSON). Relative paths
+    /// in the file are relative to the directory containing it.
+    pub fn from_file(file: impl AsRef<Path>) -> Result<ConfigFile, Error> {
+        let file = file.as_ref();
+        let contents = std::fs::read_to_string(file)?;
+        let config = if file.extension().is_some_and(|ext| ext == "toml") {
+            ConfigFile::from_toml(&contents)
+        } else {
+            ConfigFile::from_yaml(&contents)
+        }?;
+        Ok(config.relative_to(file.parent().unwrap_or_else(|| Path::new(""))))
+    }
+
+    /// Parses a configuration file in YAML format.
+    pub fn from_yaml(contents: &str) -> Result<ConfigFile, Error> {
+        ConfigFile::from_raw(serde_yaml::from_str(contents)?)
+    }
+
+    /// Parses a configuration file in TOML format.
+    pub fn from_toml(contents: &str) -> Result<ConfigFile, Error> {
+        ConfigFile::from_raw(toml::from_str(contents)?)
+    }
+
+    /// Applies the options in this file to the options parsed from the
+    /// command line, except for those that were given on the command line,
+    /// including their `--no-` forms. Returns the plugin definitions, which
+    /// are handled separately.
+    pub fn apply(
+        self,
+        opts: &mut DQCsimStructOpt,
+        matches: &clap::ArgMatches,
+    ) -> Vec<(String, PluginStructOpt)> {
+        let given = |arg: &str| matches.occurrences_of(arg) > 0;
+        set(
+            given("plugin-path"),
+            &mut opts.plugin_path,
+            self.plugin_path,
+        );
+        set(
+            given("interpreters"),
+            &mut opts.interpreters,
+            self.interpreters,
+        );
+        set(given("seed"), &mut opts.seed, self.seed.map(Some));
+        set(
+            given("stderr-level"),
+            &mut opts.stderr_level,
+            self.stderr_level,
+        );
+        set(
+            given("stderr-format"),
+            &mut opts.stderr_format,
+            self.stderr_format,
+        );
+        set(given("tee-files"), &mut opts.tee_files, self.tee_files);
+        set(
+            given("dqcsim-level"),
+            &mut opts.dqcsim_level,
+            self.dqcsim_level,
+        );
+        set(
+            given("plugin-level"),
+            &mut opts.plugin_level,
+            self.plugin_level,
+        );
+        set(
+            given("host-stdout") || given("no-host-stdout"),
+            &mut opts.host_stdout,
+            self.host_stdout,
+        );
+        set(
+            given("repro-out") || given("no-repro-out"),
+            &mut opts.repro_out,
+            self.repro_out.map(Some),
+        );
+        set(
+            given("repro-path-style"),
+            &mut opts.repro_path_style,
+            self.repro_path_style,
+        );
+        set(
+            given("trace-out") || given("no-trace-out"),
+            &mut opts.trace_out,
+            self.trace_out.map(Some),
+        );
+        set(
+            given("profile") || given("no-profile"),
+            &mut opts.profile,
+            self.profile,
+        );
+        set(given("host-calls"), &mut opts.host_calls, self.host_calls);
+        set(
+            given("output-format"),
+            &mut opts.output_format,
+            self.output_format,
+        );
+        self.plugins
+    }
+
+    /// Resolves the relative paths in this file relative to the given
+    /// directory, which should be the directory containing the file.
+    fn relative_to(mut self, dir: &Path) -> ConfigFile {
+        if let Some(plugin_path) = self.plugin_path.as_mut() {
+            for path in plugin_path.iter_mut() {
+                *path = dir.join(&path);
+            }
+        }
+        for tee_file in self.tee_files.iter_mut().flatten() {
+            tee_file.file = dir.join(&tee_file.file);
+        }
+        self.repro_out = self.repro_out.map(|path| dir.join(path));
+        self.trace_out = self.trace_out.map(|path| dir.join(path));
+        for (spec, plugin) in self.plugins.iter_mut() {
+            // Specifications without a slash are only paths if the file
+            // exists; otherwise they are plugin names, to be looked up in
+            // the plugin search path.
+            let path = dir.join(&spec);
+            if spec.contains('/') || path.exists() {
+                *spec = path.to_string_lossy().into_owned();
+            }
+            plugin.work = plugin.work.take().map(|path| dir.join(path));
+            for tee_file in plugin.tee_files.iter_mut() {
+                tee_file.file = dir.join(&tee_file.file);
+            }
+        }
+        self
+    }
+
+    fn from_raw(raw: RawConfigFile) -> Result<ConfigFile, Error> {
+        Ok(ConfigFile {
+            plugin_path: raw.plugin_path,
+            interpreters: raw
+                .interpreter
+                .map(|x| parse_all("interpreter", &x))
+                .transpose()?,
+            seed: raw.seed.map(|seed| Seed::from(&seed.to_string()[..])),
+            stderr_level: raw
+                .level
+                .map(|x| context("level", friendly_enum_parse(&x)))
+                .transpose()?,
+            stderr_format: raw
+                .log_format
+                .map(|x| context("log-format", friendly_enum_parse(&x)))
+                .transpose()?,
+            tee_files: raw.tee.map(|x| parse_all("tee", &x)).transpose()?,
+            dqcsim_level: raw
+                .dqcsim_level
+                .map(|x| context("dqcsim-level", friendly_enum_parse(&x)))
+                .transpose()?,
+            plugin_level: raw
+                .plugin_level
+                .map(|x| parse("plugin-level", x))
+                .transpose()?,
+            host_stdout: raw.host_stdout,
+            output_format: raw
+                .output
+                .map(|x| context("output", friendly_enum_parse(&x)))
+                .transpose()?,
+            repro_out: raw.repro_out,
+            repro_path_style: raw
+                .repro_paths
+                .map(|x| context("repro-paths", friendly_enum_parse(&x)))
+                .transpose()?,
+            trace_out: raw.trace_out,
+            profile: raw.profile,
+            host_calls: raw.call.map(|x| parse_all("call", &x)).transpose()?,
+            plugins: raw
+                .plugins
+                .into_iter()
+                .enumerate()
+                .map(|(index, plugin)| {
+                    let key = |name: &str| format!("plugins[{}].{}", index, name);
+                    Ok((
+                        plugin.spec,
+                        PluginStructOpt {
+                            name: plugin.name,
+                            init: parse_all(&key("init"), &plugin.init)?,
+                            env: parse_all(&key("env"), &plugin.env)?,
+                            work: plugin.work,
+                            verbosity: plugin.level.map(|x| parse(&key("level"), x)).transpose()?,
+                            tee_files: parse_all(&key("tee"), &plugin.tee)?,
+                            stdout_mode: plugin
+                                .stdout
+                                .map(|x| parse(&key("stdout"), x))
+                                .transpose()?,
+                            stderr_mode: plugin
+                                .stderr
+                                .map(|x| parse(&key("stderr"), x))
+                                .transpose()?,
+                            accept_timeout: plugin
+                                .accept_timeout
+                                .map(|x| parse(&key("accept-timeout"), x))
+                                .transpose()?,
+                            shutdown_timeout: plugin
+                                .shutdown_timeout
+                                .map(|x| parse(&key("shutdown-timeout"), x))
+                                .transpose()?,
+                        },
+                    ))
+                })
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::types::{ArbCmd, ArbData};
+    use structopt::StructOpt;
+
+    #[test]
+    fn yaml() {
+        let config = ConfigFile::from_yaml(
+            r#"
+seed: 42
+level: Debug
+tee: ["warn:dqcsim.log"]
+call: [start, "send:{\"a\": 1}", wait]
+plugins:
+  - spec: cq
+    name: algorithm
+    init: ["a.b"]
+    env: ["KEY=value", "~OTHER"]
+    work: /tmp
+  - spec: qx
+    level: debug,my_module=trace
+    stderr: pass
+    accept-timeout: 10
+    shutdown-timeout: infinity
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.seed, Some(Seed { value: 42 }));
+        assert_eq!(config.stderr_level, Some(LoglevelFilter::Debug));
+        assert_eq!(config.stderr_format, None);
+        assert_eq!(
+            config.tee_files,
+            Some(vec![TeeFileConfiguration::new(
+                LoglevelFilter::Warn,
+                "dqcsim.log"
+            )])
+        );
+        assert_eq!(
+            config.host_calls,
+            Some(vec![
+                HostCall::Start(ArbData::default()),
+                HostCall::Send(ArbData::from_json(r#"{"a": 1}"#, vec![]).unwrap()),
+                HostCall::Wait
+            ])
+        );
+        assert_eq!(config.plugins.len(), 2);
+        let (spec, front) = &config.plugins[0];
+        assert_eq!(spec, "cq");
+        assert_eq!(front.name, Some("algorithm".to_string()));
+        assert_eq!(front.init, vec!["a.b".parse::<ArbCmd>().unwrap()]);
+        assert_eq!(
+            front.env,
+            vec![EnvMod::set("KEY", "value"), EnvMod::remove("OTHER")]
+        );
+        assert_eq!(front.work, Some(PathBuf::from("/tmp")));
+        let (spec, back) = &config.plugins[1];
+        assert_eq!(spec, "qx");
+        assert_eq!(
+            back.verbosity,
+            Some("debug,my_module=trace".parse().unwrap())
+        );
+        assert_eq!(back.stderr_mode, Some(StreamCaptureMode::Pass));
+        assert_eq!(back.accept_timeout, Some(Timeout::from_seconds(10)));
+        assert_eq!(back.shutdown_timeout, Some(Timeout::Infinite));
+    }
+
+    #[test]
+    fn toml() {
+        let config = ConfigFile::from_toml(
+            r#"
+seed = "hello"
+plugin-path = ["/opt/dqcsim/plugins"]
+interpreter = [".jl=julia --project=/opt/env"]
+dqcsim-level = "off"
+output = "json"
+profile = true
+
+[[plugins]]
+spec = "cq"
+tee = ["json:info:front.log"]
+
+[[plugins]]
+spec = "qx"
+accept-timeout = "1.5"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.seed, Some(Seed::from("hello")));
+        assert_eq!(
+            config.plugin_path,
+            Some(vec![PathBuf::from("/opt/dqcsim/plugins")])
+        );
+        assert_eq!(
+            config.interpreters,
+            Some(vec![".jl=julia --project=/opt/env".parse().unwrap()])
+        );
+        assert_eq!(config.dqcsim_level, Some(LoglevelFilter::Off));
+        assert_eq!(config.output_format, Some(OutputFormat::Json));
+        assert_eq!(config.profile, Some(true));
+        assert_eq!(config.host_calls, None);
+        assert_eq!(config.plugins[0].1.tee_files.len(), 1);
+        assert_eq!(
+            config.plugins[1].1.accept_timeout,
+            Some(Timeout::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let config = || {
+            ConfigFile::from_yaml(
+                r#"
+plugin-path: [/file]
+seed: 42
+host-stdout: true
+profile: true
+trace-out: sim.trace
+"#,
+            )
+            .unwrap()
+        };
+        let apply = |args: &[&str]| {
+            let matches = DQCsimStructOpt::clap().get_matches_from(args);
+            let mut opts = DQCsimStructOpt::from_clap(&matches);
+            config().apply(&mut opts, &matches);
+            opts
+        };
+
+        let opts = apply(&["dqcsim"]);
+        assert_eq!(opts.plugin_path, vec![PathBuf::from("/file")]);
+        assert_eq!(opts.seed, Some(Seed { value: 42 }));
+        assert!(opts.host_stdout);
+        assert!(opts.profile);
+        assert_eq!(opts.trace_out, Some(PathBuf::from("sim.trace")));
+
+        let opts = apply(&[
+            "dqcsim",
+            "--plugin-path",
+            "/cli",
+            "--seed",
+            "33",
+            "--no-host-stdout",
+            "--no-profile",
+            "--no-trace-out",
+        ]);
+        assert_eq!(opts.plugin_path, vec![PathBuf::from("/cli")]);
+        assert_eq!(opts.seed, Some(Seed { value: 33 }));
+        assert!(!opts.host_stdout);
+        assert!(!opts.profile);
+        assert_eq!(opts.trace_out, None);
+
+        let opts = apply(&["dqcsim", "--no-profile", "--profile"]);
+        assert!(opts.profile);
+    }
+
+    #[test]
+    fn relative_to() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("front.py");
+        std::fs::write(&script, "").unwrap();
+        let config = ConfigFile::from_yaml(
+            r#"
+plugin-path: [plugins, /opt/plugins]
+tee: ["warn:sim.log"]
+repro-out: sim.repro
+plugins:
+  - spec: front.py
+    work: work
+    tee: ["info:/var/log/front.log"]
+  - spec: ./backend
+  - spec: qx
+"#,
+        )
+        .unwrap()
+        .relative_to(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            config.plugin_path,
+            Some(vec![dir.join("plugins"), PathBuf::from("/opt/plugins")])
+        );
+        assert_eq!(config.tee_files.unwrap()[0].file, dir.join("sim.log"));
+        assert_eq!(config.repro_out, Some(dir.join("sim.repro")));
+        assert_eq!(config.trace_out, None);
+        assert_eq!(config.plugins[0].0, script.to_string_lossy());
+        assert_eq!(config.plugins[0].1.work, Some(dir.join("work")));
+        assert_eq!(
+            config.plugins[0].1.tee_files[0].file,
+            PathBuf::from("/var/log/front.log")
+        );
+        assert_eq!(config.plugins[1].0, dir.join("./backend").to_string_lossy());
+        assert_eq!(config.plugins[2].0, "qx");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(ConfigFile::from_yaml("bogus: 3")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown field `bogus`"));
+        assert!(ConfigFile::from_yaml("plugins: [{name: front}]")
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `spec`"));
+        assert_eq!(
+            ConfigFile::from_yaml("level: loud").unwrap_err().to_string(),
+            "level: Invalid argument: loud is not a valid loglevel filter, valid values are off, fatal, error, warn, note, info, debug, or trace"
+        );
+        assert!(
+            ConfigFile::from_toml("[[plugins]]\nspec = \"cq\"\ninit = [\"a.b\", \"x\"]")
+                .unwrap_err()
+                .to_string()
+                .starts_with("plugins[0].init[1]: ")
+        );
+    }
+}
@@ -2,5 +2,7 @@ mod opts;
 
 mod plugins;
 
+mod config_file;
+
 mod parse;
 pub use parse::{CommandLineConfiguration, CommandLineError};
@@ -1,7 +1,10 @@
-use crate::arg_parse::plugins::*;
+use crate::{arg_parse::plugins::*, output::OutputFormat};
 use dqcsim::{
-    common::{log::tee_file::TeeFileConfiguration, log::*, types::*, util::friendly_enum_parse},
-    host::{configuration::*, reproduction::*},
+    common::{
+        log::module_filter::LogDirectives, log::tee_file::TeeFileConfiguration, log::*, types::*,
+        util::friendly_enum_parse,
+    },
+    host::{configuration::*, debugger::Breakpoint, reproduction::*},
 };
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -15,6 +18,45 @@ use structopt::StructOpt;
     about = "Delft Quantum & Classical Simulator"
 )]
 pub struct DQCsimStructOpt {
+    /// Loads the simulation configuration from the given YAML or TOML file.
+    /// The keys in the file are named after the long command line options.
+    /// Options specified on the command line override the values in the file,
+    /// and plugins defined on the command line replace those in the file.
+    /// Refer to the "configuration files" section for more info.
+    #[structopt(
+        long = "config",
+        value_name = "filename",
+        conflicts_with = "reproduce",
+        conflicts_with = "reproduce-exactly",
+        parse(from_os_str)
+    )]
+    pub config: Option<PathBuf>,
+
+    /// Adds a directory to search for plugins specified by name. These
+    /// directories are searched in the order specified, after the working
+    /// directory but before $DQCSIM_PLUGIN_PATH, the per-user plugin
+    /// directory, the DQCsim binary directory, and $PATH. Can be specified
+    /// more than once.
+    #[structopt(
+        long = "plugin-path",
+        value_name = "dir",
+        number_of_values = 1,
+        parse(from_os_str)
+    )]
+    pub plugin_path: Vec<PathBuf>,
+
+    /// Registers an interpreter command for script plugins, of the form
+    /// <match>=<command>. <match> is either .<extension> or #!<program> to
+    /// match scripts by file extension or by the program in their shebang
+    /// line. <command> is the interpreter followed by its arguments,
+    /// separated by spaces. Can be specified more than once.
+    #[structopt(
+        long = "interpreter",
+        value_name = "match=command",
+        number_of_values = 1
+    )]
+    pub interpreters: Vec<InterpreterEntry>,
+
     /// Used to specify the host API call sequence. Refer to the "host call
     /// sequence" section for more info.
     #[structopt(
@@ -27,12 +69,44 @@ pub struct DQCsimStructOpt {
     )]
     pub host_calls: Vec<HostCall>,
 
+    /// Starts the plugins and then reads host API calls from an interactive
+    /// prompt instead of running a predefined host call sequence. The
+    /// results are printed as they arrive. Type "help" at the prompt for a
+    /// list of commands.
+    #[structopt(
+        long = "interactive",
+        conflicts_with = "host-calls",
+        conflicts_with = "reproduce",
+        conflicts_with = "reproduce-exactly",
+        conflicts_with = "debug"
+    )]
+    pub interactive: bool,
+
     /// Specifies that the return values of host API calls should be printed to
     /// stdout, in addition to being logged with loglevel note. Use this if you
     /// want to send these values to another program through a pipe.
-    #[structopt(long = "host-stdout")]
+    #[structopt(long = "host-stdout", overrides_with = "no-host-stdout")]
     pub host_stdout: bool,
 
+    /// Disables --host-stdout, for instance when it is enabled in a
+    /// configuration file.
+    #[structopt(long = "no-host-stdout", overrides_with = "host-stdout")]
+    pub no_host_stdout: bool,
+
+    /// Selects what DQCsim writes to stdout. "text" only prints the return
+    /// values of host API calls if --host-stdout is specified. "json" writes
+    /// a single JSON document when DQCsim terminates instead, describing the
+    /// plugins, every host API call made, its return value and timing, and
+    /// any errors. Log messages are written to stderr in either case.
+    #[structopt(
+        long = "output",
+        value_name = "format",
+        default_value = "text",
+        case_insensitive = true,
+        parse(try_from_str = friendly_enum_parse)
+    )]
+    pub output_format: OutputFormat,
+
     /// Output a reproduction file to the specified filename. The default is
     /// to output a reproduction file to "<basename(frontend)>.repro".
     #[structopt(
@@ -47,6 +121,24 @@ pub struct DQCsimStructOpt {
     #[structopt(long = "no-repro-out")]
     pub no_repro_out: bool,
 
+    /// Writes a reproduction bundle to the specified filename: a tar archive
+    /// containing the reproduction file along with copies of the plugin
+    /// executables and scripts, which can be run elsewhere using
+    /// --reproduce-bundle.
+    #[structopt(
+        long = "repro-bundle",
+        value_name = "filename",
+        conflicts_with = "no-repro-out",
+        parse(from_os_str)
+    )]
+    pub repro_bundle: Option<PathBuf>,
+
+    /// Includes the files in the working directory of a plugin that are
+    /// referred to by relative paths in the ArbData of its init commands in
+    /// the reproduction bundle.
+    #[structopt(long = "bundle-inputs", requires = "repro-bundle")]
+    pub bundle_inputs: bool,
+
     /// Configures the way paths are stored in the reproduction file. The
     /// default is to save the paths as they were specified on the command
     /// line. The alternatives are to force usage of absolute paths or to force
@@ -87,6 +179,21 @@ pub struct DQCsimStructOpt {
     )]
     pub reproduce_exactly: Option<PathBuf>,
 
+    /// Unpacks the given reproduction bundle and reproduces the simulation run
+    /// it contains exactly, like --reproduce-exactly. The bundle is unpacked
+    /// into a temporary directory, which is removed when DQCsim terminates.
+    #[structopt(
+        long = "reproduce-bundle",
+        value_name = "filename",
+        conflicts_with = "reproduce",
+        conflicts_with = "reproduce-exactly",
+        conflicts_with = "seed",
+        conflicts_with = "host-calls",
+        conflicts_with = "interactive",
+        parse(from_os_str)
+    )]
+    pub reproduce_bundle: Option<PathBuf>,
+
     /// Specifies a random seed for the simulation. If a 64-bit unsigned number
     /// is specified, it is used directly. Otherwise, the specified string is
     /// hashed to such a 64-bit number. If not specified, the current timestamp
@@ -110,16 +217,81 @@ pub struct DQCsimStructOpt {
     )]
     pub stderr_level: LoglevelFilter,
 
+    /// Sets the format for messages written to stderr. The default is "text",
+    /// which is meant to be read by humans. "json" writes each message as a
+    /// single-line JSON object instead, for consumption by other tools.
+    #[structopt(
+        long = "log-format",
+        value_name = "format",
+        default_value = "text",
+        case_insensitive = true,
+        parse(try_from_str = friendly_enum_parse)
+    )]
+    pub stderr_format: LogFormat,
+
     /// Logs messages to the specified file in addition to stderr. level sets
-    /// the minimum importance for a message to be logged to this file.
+    /// the minimum importance for a message to be logged to this file. The
+    /// specification can be prefixed with a comma-separated list of options:
+    /// "json" to log JSON lines instead of text, "append" to append to an
+    /// existing file, "size=<bytes>" to rotate the file when it grows beyond
+    /// the given size (k, M, and G suffixes are allowed), "keep=<count>" to
+    /// set the number of rotated files to keep (default 5), and "compress" to
+    /// compress rotated files with gzip.
     #[structopt(
         short = "T",
         long = "tee",
-        value_name = "level>:<filename",
+        value_name = "options:]<level>:<filename",
         number_of_values = 1
     )]
     pub tee_files: Vec<TeeFileConfiguration>,
 
+    /// Records every message crossing a plugin boundary to the specified
+    /// gatestream trace file. Use "dqcsim trace" to inspect the file
+    /// afterwards.
+    #[structopt(
+        long = "trace-out",
+        value_name = "filename",
+        conflicts_with = "no-trace-out",
+        parse(from_os_str)
+    )]
+    pub trace_out: Option<PathBuf>,
+
+    /// Disables --trace-out, for instance when it is specified in a
+    /// configuration file.
+    #[structopt(long = "no-trace-out")]
+    pub no_trace_out: bool,
+
+    /// Pauses the simulation before gatestream requests reach the plugin with
+    /// the given instance name, allowing you to step through them
+    /// interactively. Commands are read from stdin; type "help" at the prompt
+    /// for a list.
+    #[structopt(long = "debug", value_name = "plugin")]
+    pub debug: Option<String>,
+
+    /// Adds a breakpoint for --debug, being gate:<type>, qubit:<index>, or
+    /// cycle:<cycle>. The gate type is the name of a custom gate or one of
+    /// unitary, measurement, prep, or pauli_measurement. If any breakpoints
+    /// are specified, the simulation runs until the first one is hit instead
+    /// of pausing before the first request.
+    #[structopt(
+        long = "break",
+        value_name = "breakpoint",
+        requires = "debug",
+        number_of_values = 1
+    )]
+    pub breakpoints: Vec<Breakpoint>,
+
+    /// Logs a summary of the time each plugin spent in its callbacks,
+    /// waiting for downstream, and serializing IPC messages at the end of
+    /// the simulation. The summary is logged at info level.
+    #[structopt(long = "profile", overrides_with = "no-profile")]
+    pub profile: bool,
+
+    /// Disables --profile, for instance when it is enabled in a
+    /// configuration file.
+    #[structopt(long = "no-profile", overrides_with = "profile")]
+    pub no_profile: bool,
+
     /// Sets the logging verbosity for DQCsim itself (the driver and host API).
     #[structopt(
         long = "dqcsim-level",
@@ -130,15 +302,13 @@ pub struct DQCsimStructOpt {
     )]
     pub dqcsim_level: LoglevelFilter,
 
-    /// Sets the default logging verbosity for the plugins.
-    #[structopt(
-        long = "plugin-level",
-        value_name = "level",
-        default_value = "trace",
-        case_insensitive = true,
-        parse(try_from_str = friendly_enum_parse)
-    )]
-    pub plugin_level: LoglevelFilter,
+    /// Sets the default logging verbosity for the plugins. In addition to a
+    /// plain loglevel, this accepts a comma-separated list of directives of
+    /// the form <module>=<level>, overriding the level for messages from the
+    /// given module path and its submodules. For instance,
+    /// "warn,my_plugin::my_module=trace".
+    #[structopt(long = "plugin-level", value_name = "level", default_value = "trace")]
+    pub plugin_level: LogDirectives,
 
     /// Shows a more complete help message than --help.
     #[structopt(long = "long-help")]
@@ -179,21 +349,24 @@ pub struct PluginStructOpt {
     pub work: Option<PathBuf>,
 
     /// Sets the logging verbosity for the associated plugin, overriding
-    /// "--plugin-level".
-    #[structopt(
-        short = "l",
-        long = "level",
-        value_name = "level",
-        case_insensitive = true
-    )]
-    pub verbosity: Option<LoglevelFilter>,
+    /// "--plugin-level". Like "--plugin-level", this accepts module
+    /// directives of the form <module>=<level> in addition to a plain
+    /// loglevel, separated by commas.
+    #[structopt(short = "l", long = "level", value_name = "level")]
+    pub verbosity: Option<LogDirectives>,
 
     /// Logs messages to the specified file in addition to stderr. level sets
-    /// the minimum importance for a message to be logged to this file.
+    /// the minimum importance for a message to be logged to this file. The
+    /// specification can be prefixed with a comma-separated list of options:
+    /// "json" to log JSON lines instead of text, "append" to append to an
+    /// existing file, "size=<bytes>" to rotate the file when it grows beyond
+    /// the given size (k, M, and G suffixes are allowed), "keep=<count>" to
+    /// set the number of rotated files to keep (default 5), and "compress" to
+    /// compress rotated files with gzip.
     #[structopt(
         short = "T",
         long = "tee",
-        value_name = "level>:<filename",
+        value_name = "options:]<level>:<filename",
         number_of_values = 1
     )]
     pub tee_files: Vec<TeeFileConfiguration>,
@@ -234,7 +407,12 @@ pub struct PluginStructOpt {
 impl From<&PluginStructOpt> for PluginNonfunctionalOpts {
     fn from(opts: &PluginStructOpt) -> Self {
         PluginNonfunctionalOpts {
-            verbosity: opts.verbosity,
+            verbosity: opts.verbosity.as_ref().and_then(|d| d.level),
+            modules: opts
+                .verbosity
+                .as_ref()
+                .map(|d| d.modules.clone())
+                .unwrap_or_default(),
             tee_files: opts.tee_files.clone(),
             stdout_mode: opts.stdout_mode.clone(),
             stderr_mode: opts.stderr_mode.clone(),
@@ -257,22 +435,39 @@ impl From<&PluginStructOpt> for PluginProcessFunctionalConfiguration {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dqcsim::common::log::module_filter::ModuleFilter;
 
     #[test]
     fn default_sim_struct() {
         let opt = DQCsimStructOpt {
+            config: None,
+            plugin_path: vec![],
+            interpreters: vec![],
             host_calls: vec![],
+            interactive: false,
             host_stdout: false,
+            no_host_stdout: false,
+            output_format: OutputFormat::Text,
             repro_out: None,
             no_repro_out: false,
+            repro_bundle: None,
+            bundle_inputs: false,
             repro_path_style: ReproductionPathStyle::Keep,
             reproduce: None,
             reproduce_exactly: None,
+            reproduce_bundle: None,
             seed: None,
             stderr_level: LoglevelFilter::Info,
+            stderr_format: LogFormat::Text,
             tee_files: vec![],
+            trace_out: None,
+            no_trace_out: false,
+            debug: None,
+            breakpoints: vec![],
+            profile: false,
+            no_profile: false,
             dqcsim_level: LoglevelFilter::Trace,
-            plugin_level: LoglevelFilter::Trace,
+            plugin_level: LoglevelFilter::Trace.into(),
             long_help: false,
         };
         assert_eq!(
@@ -366,7 +561,7 @@ mod tests {
             init: vec![],
             env: vec![],
             work: None,
-            verbosity: Some(LoglevelFilter::Warn),
+            verbosity: Some("warn,a::b=trace".parse().unwrap()),
             tee_files: vec![],
             stdout_mode: None,
             stderr_mode: None,
@@ -378,6 +573,7 @@ mod tests {
             config,
             PluginNonfunctionalOpts {
                 verbosity: Some(LoglevelFilter::Warn),
+                modules: ModuleFilter::new().with("a::b", LoglevelFilter::Trace),
                 tee_files: vec![],
                 stdout_mode: None,
                 stderr_mode: None,
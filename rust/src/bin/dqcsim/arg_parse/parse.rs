@@ -1,4 +1,9 @@
-use crate::arg_parse::{opts::*, plugins::*};
+use crate::{
+    arg_parse::{config_file::ConfigFile, opts::*, plugins::*},
+    bundle::{self, BundleDir},
+    debugger::ConsoleDebugger,
+    output::OutputFormat,
+};
 use ansi_term::Colour;
 use dqcsim::{
     common::types::*,
@@ -26,12 +31,13 @@ pub enum CommandLineError {
 /// this:
 ///
 /// ```rust
-/// let mut parser = PluginConfigParser::new(plugin_clap_app);
+/// let mut parser = PluginConfigParser::new(plugin_clap_app, desugar_options);
 /// parser.parse(&dqcsim_matches);
 /// if reproducing {
 ///     let mods = parser.get_mods();
 /// } else {
-///     let defs = parser.get_defs();
+///     parser.load(config_file.plugins);
+///     let (first_specification, defs, mods) = parser.get_defs();
 /// }
 /// ```
 struct PluginConfigParser<'a, 'b> {
@@ -39,16 +45,24 @@ struct PluginConfigParser<'a, 'b> {
     defs: Vec<PluginDefinition>,
     mods: Vec<PluginModification>,
     first_specification: Option<String>,
+    loaded: bool,
+    desugar_options: DesugarOptions,
 }
 
 impl<'a, 'b> PluginConfigParser<'a, 'b> {
-    /// Constructs a new parser object from the plugin clap app.
-    pub fn new(app: clap::App<'a, 'b>) -> PluginConfigParser<'a, 'b> {
+    /// Constructs a new parser object from the plugin clap app. Sugared
+    /// plugin specifications are desugared using the given options.
+    pub fn new(
+        app: clap::App<'a, 'b>,
+        desugar_options: DesugarOptions,
+    ) -> PluginConfigParser<'a, 'b> {
         PluginConfigParser {
             app,
             defs: vec![],
             mods: vec![],
             first_specification: None,
+            loaded: false,
+            desugar_options,
         }
     }
 
@@ -114,6 +128,7 @@ impl<'a, 'b> PluginConfigParser<'a, 'b> {
             self.mods.push(PluginModification {
                 name: specification[1..].to_string(),
                 verbosity: opts.verbosity,
+                modules: opts.modules,
                 tee_files: opts.tee_files,
                 stdout_mode: opts.stdout_mode,
                 stderr_mode: opts.stderr_mode,
@@ -121,42 +136,84 @@ impl<'a, 'b> PluginConfigParser<'a, 'b> {
                 shutdown_timeout: opts.shutdown_timeout,
             });
         } else {
-            // Figure out a default name for the plugin based on the type.
-            let default_name = match plugin_type {
-                PluginType::Frontend => "front".to_string(),
-                PluginType::Operator => format!("op{}", self.defs.len()),
-                PluginType::Backend => "back".to_string(),
-            };
+            self.push_definition(specification, &opts, plugin_type)?;
+        }
 
-            // If this is the first plugin definition, save the sugared
-            // specification. This is used to construct the default name for
-            // the reproduction output file.
-            if self.first_specification.is_none() {
-                self.first_specification.replace(specification.to_string());
-            }
+        self.parse(&matches)
+    }
 
-            // Push the plugin definition.
-            self.defs.push(PluginDefinition {
-                name: opts.name.clone().unwrap_or(default_name),
-                specification: PluginProcessSpecification::from_sugar(specification, plugin_type)
-                    .or_else(|e| {
-                    format_error_ctxt("While interpreting plugin specification", e)
-                })?,
-                functional: (&opts).into(),
-                nonfunctional: (&opts).into(),
-            });
+    /// Adds a plugin definition, given its specification string, options,
+    /// and type.
+    fn push_definition(
+        &mut self,
+        specification: &str,
+        opts: &PluginStructOpt,
+        plugin_type: PluginType,
+    ) -> Result<(), Error> {
+        // Figure out a default name for the plugin based on the type.
+        let default_name = match plugin_type {
+            PluginType::Frontend => "front".to_string(),
+            PluginType::Operator => format!("op{}", self.defs.len()),
+            PluginType::Backend => "back".to_string(),
+        };
+
+        // If this is the first plugin definition, save the sugared
+        // specification. This is used to construct the default name for the
+        // reproduction output file.
+        if self.first_specification.is_none() {
+            self.first_specification.replace(specification.to_string());
         }
 
-        self.parse(&matches)
+        // Push the plugin definition.
+        self.defs.push(PluginDefinition {
+            name: opts.name.clone().unwrap_or(default_name),
+            specification: PluginProcessSpecification::from_sugar_with(
+                specification,
+                plugin_type,
+                &self.desugar_options,
+            )
+            .or_else(|e| format_error_ctxt("While interpreting plugin specification", e))?,
+            functional: opts.into(),
+            nonfunctional: opts.into(),
+        });
+        Ok(())
     }
 
-    /// Returns the vector of plugin definitions.
+    /// Adds the plugins defined in a configuration file, given as pairs of
+    /// specification strings and options.
+    ///
+    /// This is no-op if any plugins were defined on the command line, as
+    /// these replace the plugins in the file. Otherwise, plugin
+    /// modifications on the command line are applied to the loaded plugins.
+    pub fn load(&mut self, plugins: &[(String, PluginStructOpt)]) -> Result<(), Error> {
+        if !self.defs.is_empty() || plugins.is_empty() {
+            return Ok(());
+        }
+        for (index, (specification, opts)) in plugins.iter().enumerate() {
+            let plugin_type = if index == 0 {
+                PluginType::Frontend
+            } else if index == plugins.len() - 1 {
+                PluginType::Backend
+            } else {
+                PluginType::Operator
+            };
+            self.push_definition(specification, opts, plugin_type)?;
+        }
+        self.loaded = true;
+        Ok(())
+    }
+
+    /// Returns the vector of plugin definitions, along with the plugin
+    /// modifications to apply to them.
     ///
     /// When DQCsim is running in reproduction mode, use `get_mods()` instead.
-    /// If zero plugins were defined or any plugin modifications were
-    /// specified, an error is reported. If only one plugin is defined, QX is
-    /// appended with the default configuration.
-    pub fn get_defs(mut self) -> Result<(PathBuf, Vec<PluginDefinition>), Error> {
+    /// If zero plugins were defined, or if any plugin modifications were
+    /// specified while the plugins were not loaded from a configuration file,
+    /// an error is reported. If only one plugin is defined, QX is appended
+    /// with the default configuration.
+    pub fn get_defs(
+        mut self,
+    ) -> Result<(PathBuf, Vec<PluginDefinition>, Vec<PluginModification>), Error> {
         if self.defs.is_empty() {
             return format_error(CommandLineError::Unknown(
                 "At least one plugin specification is required".to_string(),
@@ -177,15 +234,22 @@ impl<'a, 'b> PluginConfigParser<'a, 'b> {
         //for m in self.mods {
         //    m.apply(&mut self.defs).unwrap_or_else(|e| error(e.to_string()));
         //}
-        if !self.mods.is_empty() {
+        // Plugins loaded from a configuration file don't have this problem,
+        // so modifications are allowed for them.
+        if !self.mods.is_empty() && !self.loaded {
             return format_error(CommandLineError::Unknown(format!(
-                "Cannot modify plugins unless '{}' or '{}' is active",
+                "Cannot modify plugins unless '{}', '{}', or '{}' is active",
                 Colour::Green.paint("--reproduce"),
-                Colour::Green.paint("--reproduce-exactly")
+                Colour::Green.paint("--reproduce-exactly"),
+                Colour::Green.paint("--config")
             )));
         }
 
-        Ok((self.first_specification.unwrap().into(), self.defs))
+        Ok((
+            self.first_specification.unwrap().into(),
+            self.defs,
+            self.mods,
+        ))
     }
 
     /// Returns the vector of plugin modifications.
@@ -219,15 +283,33 @@ pub struct CommandLineConfiguration {
     ///  - before DQCsim terminates, if the accelerator is still running.
     pub host_calls: Vec<HostCall>,
 
+    /// Specifies that host calls should be read from an interactive prompt
+    /// instead of `host_calls`.
+    pub interactive: bool,
+
     /// Specifies that the return values of host API calls should be printed to
     /// stdout, in addition to being logged with loglevel note.
     pub host_stdout: bool,
 
+    /// The format of the output written to stdout.
+    pub output_format: OutputFormat,
+
     /// The simulator configuration.
     pub dqcsim: SimulatorConfiguration,
 
     /// Reproduction output filename.
     pub reproduction_file: Option<PathBuf>,
+
+    /// Reproduction bundle output filename.
+    pub reproduction_bundle: Option<PathBuf>,
+
+    /// Whether the input files of the plugins should be included in the
+    /// reproduction bundle.
+    pub bundle_inputs: bool,
+
+    /// The directory the reproduction bundle being reproduced was unpacked
+    /// into, if any. It is removed when the configuration is dropped.
+    pub bundle_dir: Option<BundleDir>,
 }
 
 git_testament!(TESTAMENT);
@@ -296,7 +378,7 @@ impl CommandLineConfiguration {
 
         // Parse DQCsim's options.
         let dqcsim_matches = dqcsim_clap.clone().get_matches_from_safe(args)?;
-        let dqcsim_opts = DQCsimStructOpt::from_clap(&dqcsim_matches);
+        let mut dqcsim_opts = DQCsimStructOpt::from_clap(&dqcsim_matches);
 
         // Handle the --long-help switch.
         if dqcsim_opts.long_help {
@@ -309,17 +391,36 @@ impl CommandLineConfiguration {
             return Err(CommandLineError::LongHelp(long_help).into());
         }
 
+        // Load the configuration file, if any. Its values are merged into the
+        // options that were not given on the command line.
+        let file_plugins = match &dqcsim_opts.config {
+            Some(file) => ConfigFile::from_file(file)
+                .or_else(|e| format_error_ctxt("While reading configuration file", e))?
+                .apply(&mut dqcsim_opts, &dqcsim_matches),
+            None => vec![],
+        };
+
         // Parse the plugin options.
-        let mut pcp = PluginConfigParser::new(plugin_clap);
+        let mut desugar_options = DesugarOptions {
+            plugin_path: dqcsim_opts.plugin_path.clone(),
+            ..DesugarOptions::default()
+        };
+        for entry in dqcsim_opts.interpreters.iter() {
+            desugar_options.interpreters.register(entry.clone());
+        }
+        let mut pcp = PluginConfigParser::new(plugin_clap, desugar_options);
         pcp.parse(&dqcsim_matches)?;
 
         // Build the DQCsim configuration structure.
         let mut config = CommandLineConfiguration {
             host_calls: vec![],
+            interactive: dqcsim_opts.interactive,
             host_stdout: dqcsim_opts.host_stdout,
+            output_format: dqcsim_opts.output_format,
             dqcsim: SimulatorConfiguration {
                 seed: dqcsim_opts.seed.clone().unwrap_or_default(),
                 stderr_level: dqcsim_opts.stderr_level,
+                stderr_format: dqcsim_opts.stderr_format,
                 tee_files: dqcsim_opts.tee_files.clone(),
                 log_callback: None,
                 dqcsim_level: dqcsim_opts.dqcsim_level,
@@ -329,12 +430,45 @@ impl CommandLineConfiguration {
                 } else {
                     Some(dqcsim_opts.repro_path_style)
                 },
+                gatestream_trace: dqcsim_opts.trace_out.clone(),
+                debugger: dqcsim_opts
+                    .debug
+                    .clone()
+                    .map(|plugin| DebuggerConfiguration {
+                        plugin,
+                        breakpoints: dqcsim_opts.breakpoints.clone(),
+                        pause_at_start: dqcsim_opts.breakpoints.is_empty(),
+                        interface: Box::new(ConsoleDebugger::stdio()),
+                    }),
+                profile: dqcsim_opts.profile,
             },
             reproduction_file: dqcsim_opts.repro_out.clone(),
+            reproduction_bundle: dqcsim_opts.repro_bundle.clone(),
+            bundle_inputs: dqcsim_opts.bundle_inputs,
+            bundle_dir: None,
         };
 
+        // The interactive prompt uses stdout, so it cannot be combined with
+        // JSON output.
+        if config.interactive && config.output_format == OutputFormat::Json {
+            return Err(CommandLineError::Unknown(format_error_msg(
+                "--interactive cannot be combined with --output json",
+            ))
+            .into());
+        }
+
         // Configure the plugins and handle the reconfiguration options.
-        if dqcsim_opts.reproduce.is_some() || dqcsim_opts.reproduce_exactly.is_some() {
+        if let Some(file) = &dqcsim_opts.reproduce_bundle {
+            let plugin_mods = pcp.get_mods()?;
+
+            // Unpack the bundle and reproduce the run it contains exactly.
+            let (reproduction, dir) = bundle::unpack(file)
+                .or_else(|e| format_error_ctxt("While unpacking reproduction bundle", e))?;
+            config.bundle_dir = Some(dir);
+            config.host_calls = reproduction
+                .to_run(&mut config.dqcsim, plugin_mods, true)
+                .or_else(|e| format_error_ctxt("While loading reproduction bundle", e))?;
+        } else if dqcsim_opts.reproduce.is_some() || dqcsim_opts.reproduce_exactly.is_some() {
             let plugin_mods = pcp.get_mods()?;
             let exact = dqcsim_opts.reproduce_exactly.is_some();
 
@@ -352,47 +486,58 @@ impl CommandLineConfiguration {
                 .to_run(&mut config.dqcsim, plugin_mods, exact)
                 .or_else(|e| format_error_ctxt("While loading reproduction file", e))?;
         } else {
-            // Construct the plugin vector from the plugin definitions.
-            let (first_specification, defs) = pcp.get_defs()?;
-            config.dqcsim.plugins = defs
+            // Construct the plugin vector from the plugin definitions and
+            // apply the modifications, if any.
+            pcp.load(&file_plugins)?;
+            let (first_specification, defs, mods) = pcp.get_defs()?;
+            let mut plugins = defs
                 .into_iter()
-                .map(|x| {
-                    Box::new(x.into_config(dqcsim_opts.plugin_level))
-                        as Box<dyn PluginConfiguration>
-                })
+                .map(|x| x.into_config(dqcsim_opts.plugin_level.clone()))
+                .collect::<Vec<_>>();
+            for m in mods {
+                m.apply(&mut plugins).or_else(format_error)?;
+            }
+            config.dqcsim.plugins = plugins
+                .into_iter()
+                .map(|x| Box::new(x) as Box<dyn PluginConfiguration>)
                 .collect();
 
-            // If the user did not explicitly request a start() host call, add
-            // one to the front of the list.
-            let mut running = if !dqcsim_opts.host_calls.iter().any(|x| match x {
-                HostCall::Start(_) => true,
-                _ => false,
-            }) {
-                config.host_calls.push(HostCall::Start(ArbData::default()));
-                true
-            } else {
-                false
-            };
-
-            // Populate the rest of the call list, inserting wait() calls as
-            // late as possible when needed.
-            for host_call in dqcsim_opts.host_calls.iter() {
-                match host_call {
-                    HostCall::Start(_) => {
-                        if running {
-                            config.host_calls.push(HostCall::Wait);
+            // In interactive mode, the host calls are read from the prompt
+            // instead.
+            if !config.interactive {
+                // If the user did not explicitly request a start() host call, add
+                // one to the front of the list.
+                let mut running = if !dqcsim_opts
+                    .host_calls
+                    .iter()
+                    .any(|x| matches!(x, HostCall::Start(_)))
+                {
+                    config.host_calls.push(HostCall::Start(ArbData::default()));
+                    true
+                } else {
+                    false
+                };
+
+                // Populate the rest of the call list, inserting wait() calls as
+                // late as possible when needed.
+                for host_call in dqcsim_opts.host_calls.iter() {
+                    match host_call {
+                        HostCall::Start(_) => {
+                            if running {
+                                config.host_calls.push(HostCall::Wait);
+                            }
+                            running = true;
                         }
-                        running = true;
-                    }
-                    HostCall::Wait => {
-                        running = false;
+                        HostCall::Wait => {
+                            running = false;
+                        }
+                        _ => (),
                     }
-                    _ => (),
+                    config.host_calls.push(host_call.clone());
+                }
+                if running {
+                    config.host_calls.push(HostCall::Wait);
                 }
-                config.host_calls.push(host_call.clone());
-            }
-            if running {
-                config.host_calls.push(HostCall::Wait);
             }
 
             // Even if the user did not specify a reproduction output file, the
@@ -449,12 +594,17 @@ mod tests {
     fn debug() {
         let c = CommandLineConfiguration {
             host_calls: vec![],
+            interactive: false,
             host_stdout: true,
+            output_format: OutputFormat::Text,
             dqcsim: SimulatorConfiguration::default().with_seed("test"),
             reproduction_file: None,
+            reproduction_bundle: None,
+            bundle_inputs: false,
+            bundle_dir: None,
         };
 
-        assert_eq!(format!("{:?}", c), "CommandLineConfiguration { host_calls: [], host_stdout: true, dqcsim: SimulatorConfiguration { seed: Seed { value: 14402189752926126668 }, stderr_level: Info, tee_files: [], log_callback: None, dqcsim_level: Trace, plugins: [], reproduction_path_style: Some(Keep) }, reproduction_file: None }");
+        assert_eq!(format!("{:?}", c), "CommandLineConfiguration { host_calls: [], interactive: false, host_stdout: true, output_format: Text, dqcsim: SimulatorConfiguration { seed: Seed { value: 14402189752926126668 }, stderr_level: Info, stderr_format: Text, tee_files: [], log_callback: None, dqcsim_level: Trace, plugins: [], reproduction_path_style: Some(Keep), gatestream_trace: None, debugger: None, profile: false }, reproduction_file: None, reproduction_bundle: None, bundle_inputs: false, bundle_dir: None }");
     }
 
     #[test]
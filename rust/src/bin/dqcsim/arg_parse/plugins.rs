@@ -1,5 +1,9 @@
 use dqcsim::{
-    common::{log::tee_file::TeeFileConfiguration, log::*},
+    common::{
+        log::module_filter::{LogDirectives, ModuleFilter},
+        log::tee_file::TeeFileConfiguration,
+        log::*,
+    },
     host::configuration::*,
 };
 
@@ -9,12 +13,17 @@ use dqcsim::{
 /// This differs from `PluginProcessNonfunctionalConfiguration` in that
 /// unspecified values have not yet been replaced with their defaults. This
 /// allows the structure to be built incrementally (see `apply()`).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct PluginNonfunctionalOpts {
     /// Specifies the verbosity of the messages sent to DQCsim. If this is
     /// `None`, the value of DQCsim's `--plugin_level` option should be used.
     pub verbosity: Option<LoglevelFilter>,
 
+    /// Overrides the verbosity for messages from specific modules. These are
+    /// combined with the module directives of DQCsim's `--plugin_level`
+    /// option, taking precedence over them.
+    pub modules: ModuleFilter,
+
     /// Specifies the tee files for this plugin.
     pub tee_files: Vec<TeeFileConfiguration>,
 
@@ -40,10 +49,17 @@ impl PluginNonfunctionalOpts {
     /// structure by replacing unset values with their defaults.
     pub fn into_config(
         self,
-        default_verbosity: LoglevelFilter,
+        default_verbosity: impl Into<LogDirectives>,
     ) -> PluginProcessNonfunctionalConfiguration {
+        let default_verbosity = default_verbosity.into();
+        let mut modules = default_verbosity.modules;
+        modules.extend(&self.modules);
         PluginProcessNonfunctionalConfiguration {
-            verbosity: self.verbosity.unwrap_or(default_verbosity),
+            verbosity: self
+                .verbosity
+                .or(default_verbosity.level)
+                .unwrap_or(LoglevelFilter::Trace),
+            modules,
             tee_files: self.tee_files,
             stdout_mode: self
                 .stdout_mode
@@ -61,19 +77,6 @@ impl PluginNonfunctionalOpts {
     }
 }
 
-impl Default for PluginNonfunctionalOpts {
-    fn default() -> Self {
-        PluginNonfunctionalOpts {
-            verbosity: None,
-            tee_files: vec![],
-            stdout_mode: None,
-            stderr_mode: None,
-            accept_timeout: None,
-            shutdown_timeout: None,
-        }
-    }
-}
-
 /// Represents the definition of a plugin.
 ///
 /// In combination with some modifiers and defaults set by DQCsim itself, this
@@ -101,7 +104,10 @@ pub struct PluginDefinition {
 impl PluginDefinition {
     /// Converts this structure to a PluginProcessConfiguration structure by
     /// replacing unset values with their defaults.
-    pub fn into_config(self, default_verbosity: LoglevelFilter) -> PluginProcessConfiguration {
+    pub fn into_config(
+        self,
+        default_verbosity: impl Into<LogDirectives>,
+    ) -> PluginProcessConfiguration {
         PluginProcessConfiguration {
             name: self.name,
             specification: self.specification,
@@ -125,6 +131,7 @@ mod tests {
             pnfo,
             PluginNonfunctionalOpts {
                 verbosity: None,
+                modules: ModuleFilter::default(),
                 tee_files: vec![],
                 stdout_mode: None,
                 stderr_mode: None,
@@ -139,7 +146,7 @@ mod tests {
         let pnfo = PluginNonfunctionalOpts::default();
         assert_eq!(
             format!("{:?}", pnfo),
-            "PluginNonfunctionalOpts { verbosity: None, tee_files: [], stdout_mode: None, stderr_mode: None, accept_timeout: None, shutdown_timeout: None }",
+            "PluginNonfunctionalOpts { verbosity: None, modules: ModuleFilter { directives: {} }, tee_files: [], stdout_mode: None, stderr_mode: None, accept_timeout: None, shutdown_timeout: None }",
         );
     }
 
@@ -151,6 +158,7 @@ mod tests {
             c,
             PluginProcessNonfunctionalConfiguration {
                 verbosity: LoglevelFilter::Debug,
+                modules: ModuleFilter::default(),
                 tee_files: vec![],
                 stdout_mode: StreamCaptureMode::Capture(Loglevel::Info),
                 stderr_mode: StreamCaptureMode::Capture(Loglevel::Info),
@@ -161,6 +169,9 @@ mod tests {
 
         let p = PluginNonfunctionalOpts {
             verbosity: Some(LoglevelFilter::Fatal),
+            modules: ModuleFilter::new()
+                .with("a", LoglevelFilter::Trace)
+                .with("b", LoglevelFilter::Warn),
             tee_files: vec![TeeFileConfiguration::new(
                 LoglevelFilter::Error,
                 "/dev/null",
@@ -170,11 +181,16 @@ mod tests {
             accept_timeout: Some(Timeout::Infinite),
             shutdown_timeout: Some(Timeout::from_seconds(1)),
         };
-        let c: PluginProcessNonfunctionalConfiguration = p.into_config(LoglevelFilter::Debug);
+        let c: PluginProcessNonfunctionalConfiguration =
+            p.into_config("debug,a=info,c=off".parse::<LogDirectives>().unwrap());
         assert_eq!(
             c,
             PluginProcessNonfunctionalConfiguration {
                 verbosity: LoglevelFilter::Fatal,
+                modules: ModuleFilter::new()
+                    .with("a", LoglevelFilter::Trace)
+                    .with("b", LoglevelFilter::Warn)
+                    .with("c", LoglevelFilter::Off),
                 tee_files: vec![TeeFileConfiguration::new(
                     LoglevelFilter::Error,
                     "/dev/null",
@@ -227,6 +243,6 @@ mod tests {
             functional: PluginProcessFunctionalConfiguration::default(),
             nonfunctional: PluginNonfunctionalOpts::default(),
         };
-        assert_eq!(format!("{:?}", p), "PluginDefinition { name: \"name\", specification: PluginProcessSpecification { executable: \"/bin/echo\", script: None, typ: Operator }, functional: PluginProcessFunctionalConfiguration { init: [], env: [], work: \".\" }, nonfunctional: PluginNonfunctionalOpts { verbosity: None, tee_files: [], stdout_mode: None, stderr_mode: None, accept_timeout: None, shutdown_timeout: None } }");
+        assert_eq!(format!("{:?}", p), "PluginDefinition { name: \"name\", specification: PluginProcessSpecification { executable: \"/bin/echo\", script: None, args: [], typ: Operator }, functional: PluginProcessFunctionalConfiguration { init: [], env: [], work: \".\" }, nonfunctional: PluginNonfunctionalOpts { verbosity: None, modules: ModuleFilter { directives: {} }, tee_files: [], stdout_mode: None, stderr_mode: None, accept_timeout: None, shutdown_timeout: None } }");
     }
 }
@@ -0,0 +1,202 @@
+//! Reproduction bundles, being tar archives containing a reproduction file
+//! along with the plugin executables and scripts it refers to, written with
+//! `--repro-bundle` and run with `--reproduce-bundle`.
+//!
+//! A bundle has the following layout:
+//!
+//! ```text
+//! reproduction.repro          the reproduction file, with bundle-relative paths
+//! plugins/<name>/<file>       the executable and script of each plugin
+//! plugins/<name>/work/...     its working directory, with the input files
+//! ```
+
+use dqcsim::{
+    common::types::ArbData,
+    host::reproduction::{PluginReproduction, Reproduction},
+};
+use failure::{format_err, Error};
+use serde_json::Value;
+use std::{
+    collections::BTreeSet,
+    env::current_dir,
+    fs::File,
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The name of the reproduction file within a bundle.
+static REPRODUCTION_FILE: &str = "reproduction.repro";
+
+/// Returns a tar header for an entry that is not copied from a file.
+fn header(entry_type: tar::EntryType, mode: u32, size: u64) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(entry_type);
+    header.set_mode(mode);
+    header.set_size(size);
+    header.set_mtime(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs()),
+    );
+    header
+}
+
+/// Collects the strings in the given JSON value.
+fn json_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(string) => strings.push(string),
+        Value::Array(values) => values.iter().for_each(|x| json_strings(x, strings)),
+        Value::Object(map) => map.values().for_each(|x| json_strings(x, strings)),
+        _ => (),
+    }
+}
+
+/// Returns the relative paths in the given ArbData, being the strings in its
+/// JSON object and its UTF-8 encoded arguments, that refer to files in the
+/// given working directory.
+fn input_files(data: &ArbData, work: &Path) -> Vec<PathBuf> {
+    let json: Value = data.deserialize().unwrap_or(Value::Null);
+    let mut strings = vec![];
+    json_strings(&json, &mut strings);
+    strings.extend(
+        data.get_args()
+            .iter()
+            .filter_map(|arg| std::str::from_utf8(arg).ok()),
+    );
+    strings
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| {
+            path.components().next().is_some()
+                && path
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+                && work.join(path).is_file()
+        })
+        .collect()
+}
+
+/// Writes a reproduction bundle for the given reproduction log. Paths in the
+/// log are taken to be relative to the current working directory. If
+/// `inputs` is set, files in the working directory of a plugin that are
+/// referred to by the ArbData of its init commands are included as well.
+pub fn write(reproduction: &Reproduction, file: &Path, inputs: bool) -> Result<(), Error> {
+    let workdir = current_dir()?;
+    let mut builder = tar::Builder::new(File::create(file)?);
+    builder.follow_symlinks(true);
+    let mut bundled = reproduction.clone();
+    for plugin in bundled.plugins.iter_mut() {
+        let dir = Path::new("plugins").join(&plugin.name);
+        let mut add = |path: &Path| -> Result<PathBuf, Error> {
+            let source = workdir.join(path);
+            let name = dir.join(
+                source
+                    .file_name()
+                    .ok_or_else(|| format_err!("{} is not a file", source.display()))?,
+            );
+            builder
+                .append_path_with_name(&source, &name)
+                .map_err(|e| format_err!("cannot bundle {}: {}", source.display(), e))?;
+            Ok(name)
+        };
+        plugin.executable = add(&plugin.executable)?;
+        if let Some(script) = &plugin.script {
+            plugin.script = Some(add(script)?);
+        }
+
+        // The working directory is replaced by a directory in the bundle, so
+        // it exists on the receiving end.
+        let work = workdir.join(&plugin.functional.work);
+        plugin.functional.work = dir.join("work");
+        builder.append_data(
+            &mut header(tar::EntryType::Directory, 0o755, 0),
+            &plugin.functional.work,
+            std::io::empty(),
+        )?;
+        if inputs {
+            let mut files = BTreeSet::new();
+            for cmd in plugin.functional.init.iter() {
+                files.extend(input_files(cmd.data(), &work));
+            }
+            for path in files {
+                builder
+                    .append_path_with_name(work.join(&path), plugin.functional.work.join(&path))
+                    .map_err(|e| format_err!("cannot bundle {}: {}", path.display(), e))?;
+            }
+        }
+    }
+
+    let yaml = serde_yaml::to_vec(&bundled)?;
+    builder.append_data(
+        &mut header(tar::EntryType::Regular, 0o644, yaml.len() as u64),
+        REPRODUCTION_FILE,
+        &yaml[..],
+    )?;
+    builder.into_inner()?;
+    Ok(())
+}
+
+/// A directory that a reproduction bundle was unpacked into, which is
+/// removed when dropped.
+#[derive(Debug)]
+pub struct BundleDir {
+    dir: tempfile::TempDir,
+}
+
+/// Unpacks the given reproduction bundle into a temporary directory, and
+/// returns its reproduction file with the paths pointing into that
+/// directory. The directory is newly created with a random name and is only
+/// accessible by the current user, such that other users cannot tamper with
+/// the executables before they are run.
+pub fn unpack(file: &Path) -> Result<(Reproduction, BundleDir), Error> {
+    let dir = BundleDir {
+        dir: tempfile::Builder::new()
+            .prefix("dqcsim-bundle-")
+            .tempdir()?,
+    };
+    let path = dir.dir.path();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))?;
+    }
+    tar::Archive::new(File::open(file)?).unpack(path)?;
+    let mut reproduction = Reproduction::from_file(path.join(REPRODUCTION_FILE))?;
+    let relocate = |file: &mut PathBuf| *file = path.join(&file);
+    for PluginReproduction {
+        executable,
+        script,
+        functional,
+        ..
+    } in reproduction.plugins.iter_mut()
+    {
+        relocate(executable);
+        if let Some(script) = script {
+            relocate(script);
+        }
+        relocate(&mut functional.work);
+    }
+    Ok((reproduction, dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn inputs() {
+        let data = ArbData::from_str(
+            r#"{"circuit":"Cargo.toml","opts":["src",{"x":"./src/lib.rs"}],"abs":"/etc/passwd","up":"../rust/Cargo.toml"},src/bin/dqcsim/main.rs,missing"#,
+        )
+        .unwrap();
+        assert_eq!(
+            input_files(&data, &current_dir().unwrap()),
+            vec![
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("./src/lib.rs"),
+                PathBuf::from("src/bin/dqcsim/main.rs")
+            ]
+        );
+    }
+}
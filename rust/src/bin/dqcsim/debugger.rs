@@ -0,0 +1,291 @@
+//! Console interface for the interactive gatestream debugger (`--debug`).
+
+use dqcsim::{
+    common::{error::Result, types::ArbCmd},
+    host::debugger::{Breakpoint, DebugAction, DebugControl, DebugStop, DebuggerInterface},
+};
+use std::io::{self, BufRead, BufReader, Write};
+
+static HELP: &str = "\
+Commands:
+  s, step              forward the request and pause before the next one
+  c, continue          forward the request and run until a breakpoint
+  b, break <bp>        add a breakpoint: gate:<type>, qubit:<index>, or cycle:<cycle>
+  d, delete <index>    delete a breakpoint
+  l, list              list the breakpoints
+  p, print             print the request again
+  a, arb <cmd>         send an ArbCmd to the debugged plugin and print the result
+  q, abort             abort the simulation
+  h, help              print this message
+An empty line repeats step.
+";
+
+/// A command entered at the debugger prompt.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Step,
+    Continue,
+    Break(Breakpoint),
+    Delete(usize),
+    List,
+    Print,
+    Arb(ArbCmd),
+    Abort,
+    Help,
+}
+
+/// Parses a line entered at the debugger prompt.
+fn parse_command(line: &str) -> std::result::Result<Command, String> {
+    let line = line.trim();
+    let mut parts = line.splitn(2, char::is_whitespace);
+    let command = parts.next().unwrap();
+    let argument = parts.next().map(str::trim).unwrap_or_default();
+    let needs_argument = |what: &str| {
+        if argument.is_empty() {
+            Err(format!("{} requires {}", command, what))
+        } else {
+            Ok(argument)
+        }
+    };
+    match command {
+        "" | "s" | "step" => Ok(Command::Step),
+        "c" | "continue" => Ok(Command::Continue),
+        "b" | "break" => needs_argument("a breakpoint")?
+            .parse()
+            .map(Command::Break)
+            .map_err(|e| e.to_string()),
+        "d" | "delete" => needs_argument("a breakpoint index")?
+            .parse()
+            .map(Command::Delete)
+            .map_err(|_| format!("{} is not a valid breakpoint index", argument)),
+        "l" | "list" => Ok(Command::List),
+        "p" | "print" => Ok(Command::Print),
+        "a" | "arb" => needs_argument("an ArbCmd")?
+            .parse()
+            .map(Command::Arb)
+            .map_err(|e| e.to_string()),
+        "q" | "abort" => Ok(Command::Abort),
+        "h" | "help" | "?" => Ok(Command::Help),
+        _ => Err(format!("unknown command {}, try help", command)),
+    }
+}
+
+/// Debugger interface that reads commands from a console and writes its
+/// output to another.
+pub struct ConsoleDebugger {
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+
+    /// Set when the input is exhausted, after which the simulation is no
+    /// longer paused.
+    detached: bool,
+}
+
+impl ConsoleDebugger {
+    /// Constructs a console debugger reading from stdin and writing to
+    /// stderr, such that stdout remains available for --host-stdout.
+    pub fn stdio() -> ConsoleDebugger {
+        ConsoleDebugger {
+            input: Box::new(BufReader::new(io::stdin())),
+            output: Box::new(io::stderr()),
+            detached: false,
+        }
+    }
+
+    fn prompt(&mut self, stop: &DebugStop, control: &mut DebugControl) -> io::Result<DebugAction> {
+        write!(self.output, "{}", stop)?;
+        loop {
+            write!(self.output, "(dqcsim) ")?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                writeln!(self.output)?;
+                writeln!(
+                    self.output,
+                    "End of input, continuing without the debugger."
+                )?;
+                self.detached = true;
+                return Ok(DebugAction::Continue);
+            }
+            match parse_command(&line) {
+                Ok(Command::Step) => return Ok(DebugAction::Step),
+                Ok(Command::Continue) => return Ok(DebugAction::Continue),
+                Ok(Command::Abort) => return Ok(DebugAction::Abort),
+                Ok(Command::Break(breakpoint)) => {
+                    let index = control.add_breakpoint(breakpoint.clone());
+                    writeln!(self.output, "Breakpoint {}: {}", index, breakpoint)?;
+                }
+                Ok(Command::Delete(index)) => match control.remove_breakpoint(index) {
+                    Ok(breakpoint) => {
+                        writeln!(self.output, "Deleted breakpoint {}: {}", index, breakpoint)?
+                    }
+                    Err(e) => writeln!(self.output, "{}", e)?,
+                },
+                Ok(Command::List) => {
+                    if control.breakpoints().is_empty() {
+                        writeln!(self.output, "No breakpoints.")?;
+                    }
+                    for (index, breakpoint) in control.breakpoints().iter().enumerate() {
+                        writeln!(self.output, "Breakpoint {}: {}", index, breakpoint)?;
+                    }
+                }
+                Ok(Command::Print) => write!(self.output, "{}", stop)?,
+                Ok(Command::Arb(cmd)) => match control.arb(cmd) {
+                    Ok(data) => writeln!(self.output, "{}", data)?,
+                    Err(e) => writeln!(self.output, "{}", e)?,
+                },
+                Ok(Command::Help) => write!(self.output, "{}", HELP)?,
+                Err(e) => writeln!(self.output, "{}", e)?,
+            }
+        }
+    }
+}
+
+impl DebuggerInterface for ConsoleDebugger {
+    fn pause(&mut self, stop: &DebugStop, control: &mut DebugControl) -> Result<DebugAction> {
+        if self.detached {
+            return Ok(DebugAction::Continue);
+        }
+        Ok(self.prompt(stop, control)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::{
+        common::{
+            log::LoglevelFilter,
+            types::{ArbData, Gate, Matrix, PluginMetadata, PluginType, QubitRef},
+        },
+        host::{
+            accelerator::Accelerator,
+            configuration::{
+                DebuggerConfiguration, PluginLogConfiguration, PluginThreadConfiguration,
+                SimulatorConfiguration,
+            },
+            simulator::Simulator,
+        },
+        plugin::definition::PluginDefinition,
+    };
+    use std::{
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn console() {
+        let mut frontend = PluginDefinition::new(
+            PluginType::Frontend,
+            PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+        );
+        frontend.run = Box::new(|state, _| {
+            let qubits = state.allocate(1, vec![])?;
+            state.gate(Gate::new_unitary(
+                qubits.clone(),
+                vec![],
+                Matrix::new_identity(2),
+            )?)?;
+            state.advance(3)?;
+            state.free(qubits)?;
+            state.advance(1)?;
+            Ok(ArbData::default())
+        });
+        let mut backend = PluginDefinition::new(
+            PluginType::Backend,
+            PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+        );
+        backend.gate = Box::new(|_, _| Ok(vec![]));
+
+        let output = Arc::new(Mutex::new(vec![]));
+        let interface = ConsoleDebugger {
+            input: Box::new(Cursor::new(
+                "bogus\nbreak cycle:3\nl\nc\nd 0\nd 0\nstep\n".as_bytes(),
+            )),
+            output: Box::new(Output(output.clone())),
+            detached: false,
+        };
+        let configuration = SimulatorConfiguration::default()
+            .without_reproduction()
+            .without_logging()
+            .with_debugger(DebuggerConfiguration::new("back", interface))
+            .with_plugin(PluginThreadConfiguration::new(
+                frontend,
+                PluginLogConfiguration::new("front", LoglevelFilter::Off),
+            ))
+            .with_plugin(PluginThreadConfiguration::new(
+                backend,
+                PluginLogConfiguration::new("back", LoglevelFilter::Off),
+            ));
+        let mut simulator = Simulator::new(configuration).unwrap();
+        simulator.simulation.start(ArbData::default()).unwrap();
+        simulator.simulation.wait().unwrap();
+        drop(simulator);
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            output,
+            "Paused before allocate to back at cycle 0\n  \
+             qubits:    1\n\
+             (dqcsim) unknown command bogus, try help\n\
+             (dqcsim) Breakpoint 0: cycle:3\n\
+             (dqcsim) Breakpoint 0: cycle:3\n\
+             (dqcsim) Paused before free to back at cycle 3 (breakpoint 0)\n  \
+             qubits:    1\n  \
+             registers: 1=-\n\
+             (dqcsim) Deleted breakpoint 0: cycle:3\n\
+             (dqcsim) Invalid argument: there is no breakpoint 0\n\
+             (dqcsim) Paused before advance to back at cycle 3\n  \
+             cycles:    1\n\
+             (dqcsim) \n\
+             End of input, continuing without the debugger.\n"
+        );
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse_command("\n"), Ok(Command::Step));
+        assert_eq!(parse_command("s"), Ok(Command::Step));
+        assert_eq!(parse_command("continue\n"), Ok(Command::Continue));
+        assert_eq!(
+            parse_command("b qubit:2"),
+            Ok(Command::Break(Breakpoint::Qubit(
+                QubitRef::from_foreign(2).unwrap()
+            )))
+        );
+        assert_eq!(
+            parse_command("break"),
+            Err("break requires a breakpoint".to_string())
+        );
+        assert_eq!(
+            parse_command("b line:3"),
+            Err("Invalid argument: invalid breakpoint line:3, expected gate:<type>, qubit:<index>, or cycle:<cycle>".to_string())
+        );
+        assert_eq!(parse_command("d  1 "), Ok(Command::Delete(1)));
+        assert_eq!(
+            parse_command("delete x"),
+            Err("x is not a valid breakpoint index".to_string())
+        );
+        assert_eq!(
+            parse_command("arb a.b"),
+            Ok(Command::Arb("a.b".parse().unwrap()))
+        );
+        assert_eq!(parse_command("q"), Ok(Command::Abort));
+        assert_eq!(
+            parse_command("run"),
+            Err("unknown command run, try help".to_string())
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Line editing with history for the interactive prompt (`--interactive`).
+//!
+//! When stdin is a terminal, lines are read using rustyline, such that they
+//! can be edited with the usual keys and the history can be recalled with
+//! the arrow keys. ^C discards the line, and ^D on an empty line ends the
+//! input. Otherwise, lines are simply read from stdin.
+
+use rustyline::{error::ReadlineError, Config, Editor};
+use std::{io, path::PathBuf};
+
+/// The maximum number of history entries kept in memory and in the history
+/// file.
+const HISTORY_SIZE: usize = 1000;
+
+/// Source of the lines entered at a prompt.
+pub trait Prompt {
+    /// Prints the prompt and reads a line, without line terminator. Returns
+    /// `None` at the end of the input.
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>>;
+}
+
+/// Non-interactive input, for instance when stdin is redirected. The prompt
+/// is not printed.
+impl<R: io::BufRead> Prompt for R {
+    fn read_line(&mut self, _prompt: &str) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if io::BufRead::read_line(self, &mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(&['\r', '\n'][..]).to_string()))
+    }
+}
+
+/// Line editor reading from the terminal, with a history that is optionally
+/// persisted to a file.
+pub struct LineEditor {
+    /// The rustyline editor, which also holds the history.
+    editor: Editor<()>,
+
+    /// The file that the history is written to, if any.
+    history_file: Option<PathBuf>,
+}
+
+impl LineEditor {
+    /// Constructs a line editor, loading the history from the given file if
+    /// it exists. Only the last `HISTORY_SIZE` entries are kept.
+    pub fn new(history_file: Option<PathBuf>) -> LineEditor {
+        let mut editor = Editor::with_config(
+            Config::builder()
+                .max_history_size(HISTORY_SIZE)
+                .history_ignore_dups(true)
+                .auto_add_history(false)
+                .build(),
+        );
+        if let Some(file) = &history_file {
+            let _ = editor.load_history(file);
+        }
+        LineEditor {
+            editor,
+            history_file,
+        }
+    }
+
+    /// Adds a line to the history, unless it is empty or a repetition of
+    /// the previous line, and rewrites the history file. Failure to write
+    /// the history file is not an error.
+    fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || !self.editor.add_history_entry(line) {
+            return;
+        }
+        if let Some(file) = &self.history_file {
+            let _ = self.editor.save_history(file);
+        }
+    }
+}
+
+impl Prompt for LineEditor {
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        loop {
+            match self.editor.readline(prompt) {
+                Ok(line) => {
+                    self.add_history(&line);
+                    return Ok(Some(line));
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => return Ok(None),
+                Err(ReadlineError::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn history() {
+        let file = std::env::temp_dir().join(format!("dqcsim-history-{}", std::process::id()));
+        let lines: Vec<String> = (0..HISTORY_SIZE + 5)
+            .map(|i| format!("arb {}", i))
+            .collect();
+        fs::write(&file, lines.join("\n")).unwrap();
+        let mut editor = LineEditor::new(Some(file.clone()));
+        assert_eq!(editor.editor.history().len(), HISTORY_SIZE);
+        editor.add_history("wait");
+        editor.add_history("wait");
+        editor.add_history(" ");
+        let history = editor.editor.history();
+        assert_eq!(history.len(), HISTORY_SIZE);
+        assert_eq!(history.get(0).unwrap(), "arb 6");
+        assert_eq!(history.last().unwrap(), "wait");
+        let reloaded = LineEditor::new(Some(file.clone()));
+        fs::remove_file(&file).unwrap();
+        assert_eq!(reloaded.editor.history().len(), HISTORY_SIZE);
+        assert_eq!(reloaded.editor.history().last().unwrap(), "wait");
+    }
+}
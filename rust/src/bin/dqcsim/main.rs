@@ -1,92 +1,246 @@
 use dqcsim::{
+    common::types::ArbData,
     error, fatal,
     host::{accelerator::Accelerator, reproduction::HostCall, simulator::Simulator},
     info, note,
 };
 use failure::Error;
-use std::ffi::OsString;
+use std::{ffi::OsString, time::Instant};
 
 mod arg_parse;
 use crate::arg_parse::*;
 
+mod bundle;
+mod debugger;
+mod line_editor;
+mod output;
+use crate::output::*;
+mod plugins;
+mod repl;
+use crate::{line_editor::LineEditor, repl::Repl};
+mod repro;
+mod sweep;
+mod trace;
+
+/// Makes a single host API call, returning the data returned by it, if any.
+fn call(sim: &mut Simulator, host_call: HostCall) -> Result<Option<ArbData>, Error> {
+    match host_call {
+        HostCall::Start(d) => {
+            info!("Executing 'start(...)' host call...");
+            sim.simulation.start(d)?;
+            Ok(None)
+        }
+        HostCall::Wait => {
+            info!("Executing 'wait()' host call...");
+            let ret = sim.simulation.wait()?;
+            note!("'wait()' returned {}", &ret);
+            Ok(Some(ret))
+        }
+        HostCall::Send(d) => {
+            info!("Executing 'send(...)' host call...");
+            sim.simulation.send(d)?;
+            Ok(None)
+        }
+        HostCall::Recv => {
+            info!("Executing 'recv()' host call...");
+            let ret = sim.simulation.recv()?;
+            note!("'recv()' returned {}", &ret);
+            Ok(Some(ret))
+        }
+        HostCall::Yield => {
+            info!("Executing 'yield()' host call...");
+            sim.simulation.yield_to_accelerator()?;
+            Ok(None)
+        }
+        HostCall::Arb(n, d) => {
+            info!("Executing 'arb(...)' host call...");
+            let ret = sim.simulation.arb(n, d)?;
+            note!("'arb()' returned {}", &ret);
+            Ok(Some(ret))
+        }
+    }
+}
+
+/// Makes the given host API calls. If `output` is specified, the calls are
+/// recorded in it; otherwise, their return values are printed to stdout if
+/// `host_stdout` is set.
 fn run(
     sim: &mut Simulator,
     host_stdout: bool,
+    mut output: Option<&mut RunOutput>,
     host_calls: impl IntoIterator<Item = HostCall>,
 ) -> Result<(), Error> {
+    let start = Instant::now();
     for host_call in host_calls {
-        match host_call {
-            HostCall::Start(d) => {
-                info!("Executing 'start(...)' host call...");
-                sim.simulation.start(d)?;
-            }
-            HostCall::Wait => {
-                info!("Executing 'wait()' host call...");
-                let ret = sim.simulation.wait()?;
-                note!("'wait()' returned {}", &ret);
-                if host_stdout {
-                    println!("wait(): {}", ret);
-                }
-            }
-            HostCall::Send(d) => {
-                info!("Executing 'send(...)' host call...");
-                sim.simulation.send(d)?;
-            }
-            HostCall::Recv => {
-                info!("Executing 'recv()' host call...");
-                let ret = sim.simulation.recv()?;
-                note!("'recv()' returned {}", &ret);
-                if host_stdout {
-                    println!("recv: {}", ret);
-                }
-            }
-            HostCall::Yield => {
-                info!("Executing 'yield()' host call...");
-                sim.simulation.yield_to_accelerator()?;
+        let called = Instant::now();
+        let record = output
+            .as_ref()
+            .map(|_| HostCallOutput::new(&host_call, called - start))
+            .transpose()?;
+        let prefix = match host_call {
+            HostCall::Wait => "wait()",
+            HostCall::Recv => "recv",
+            _ => "arb",
+        };
+        let result = call(sim, host_call);
+        match (output.as_mut(), record) {
+            (Some(output), Some(mut record)) => {
+                record.finish(&result, called.elapsed());
+                output.host_calls.push(record);
             }
-            HostCall::Arb(n, d) => {
-                info!("Executing 'arb(...)' host call...");
-                let ret = sim.simulation.arb(n, d)?;
-                note!("'arb()' returned {}", &ret);
-                if host_stdout {
-                    println!("arb: {}", ret);
+            _ => {
+                if let (true, Ok(Some(ret))) = (host_stdout, &result) {
+                    println!("{}: {}", prefix, ret);
                 }
             }
         }
+        result?;
     }
 
     Ok(())
 }
 
+/// Prints an error that occurred before a simulation was started. The help
+/// and version messages that clap reports as errors go to stdout, everything
+/// else goes to stderr, such that stdout only ever contains the output that
+/// was asked for, for instance the JSON document of `--output json`.
+fn report(e: Error) -> Error {
+    match e.downcast_ref::<clap::Error>().map(|e| e.kind) {
+        Some(clap::ErrorKind::HelpDisplayed) | Some(clap::ErrorKind::VersionDisplayed) => {
+            println!("{}", e)
+        }
+        _ => eprintln!("{}", e),
+    }
+    e
+}
+
 fn internal_main<I, T>(args: I) -> Result<(), Error>
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
-    let mut cfg = CommandLineConfiguration::parse_from(args).or_else(|e| {
-        println!("{}", e);
-        Err(e)
-    })?;
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+
+    // Handle the tool subcommands, which don't run a simulation.
+    if args.get(1).is_some_and(|arg| arg == "trace") {
+        return trace::run(&args[1..]).map_err(report);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "plugins") {
+        return plugins::run(&args[1..]).map_err(report);
+    }
+
+    if args.get(1).is_some_and(|arg| arg == "repro") {
+        return repro::run(&args[1..]).map_err(report);
+    }
+
+    // The sweep subcommand runs its simulations in worker processes.
+    if args.get(1).is_some_and(|arg| arg == "sweep") {
+        return sweep::run(&args[1..]).map_err(report);
+    }
+
+    let mut cfg = CommandLineConfiguration::parse_from(args).map_err(report)?;
+
+    // In JSON output mode, describe the plugins up front, so they are
+    // included in the output even if they fail to start.
+    let started = Instant::now();
+    let mut output = match cfg.output_format {
+        OutputFormat::Text => None,
+        OutputFormat::Json => Some(RunOutput {
+            seed: cfg.dqcsim.seed.value,
+            plugins: cfg
+                .dqcsim
+                .plugins
+                .iter()
+                .map(|plugin| PluginOutput::new(plugin.get_name(), plugin.get_type()))
+                .collect(),
+            ..RunOutput::default()
+        }),
+    };
+    let write_output = |output: Option<RunOutput>, result: &Result<(), Error>| {
+        if let Some(mut output) = output {
+            output.duration = started.elapsed().as_secs_f64();
+            output.success = result.is_ok();
+            output.error = result.as_ref().err().map(ToString::to_string);
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        }
+    };
+
+    let plugin_names: Vec<String> = cfg
+        .dqcsim
+        .plugins
+        .iter()
+        .map(|plugin| plugin.get_name())
+        .collect();
+
+    let mut sim = match Simulator::new(cfg.dqcsim) {
+        Ok(sim) => sim,
+        Err(e) => {
+            eprintln!("Failed to construct simulator: {}", e);
+            let result = Err(e.into());
+            write_output(output, &result);
+            return result;
+        }
+    };
 
-    let mut sim = Simulator::new(cfg.dqcsim).or_else(|e| {
-        eprintln!("Failed to construct simulator: {}", e);
-        Err(e)
-    })?;
+    if let Some(output) = output.as_mut() {
+        for (index, plugin) in output.plugins.iter_mut().enumerate() {
+            if let Ok(metadata) = sim.simulation.get_metadata_idx(index as isize) {
+                plugin.set_metadata(metadata);
+            }
+        }
+    }
 
-    let sim_result = run(&mut sim, cfg.host_stdout, cfg.host_calls.drain(..));
+    let sim_result = if cfg.interactive {
+        let mut editor = LineEditor::new(repl::history_file());
+        Repl::new(&mut sim, plugin_names, &mut std::io::stdout()).run(&mut editor)
+    } else {
+        run(
+            &mut sim,
+            cfg.host_stdout,
+            output.as_mut(),
+            cfg.host_calls.drain(..),
+        )
+    };
 
     if let Some(filename) = cfg.reproduction_file {
         match sim.simulation.write_reproduction_file(&filename) {
-            Ok(_) => info!("Reproduction file written to {:?}.", filename),
+            Ok(_) => {
+                info!("Reproduction file written to {:?}.", filename);
+                if let Some(output) = output.as_mut() {
+                    output.reproduction_file = Some(filename);
+                }
+            }
             Err(e) => error!("When trying to write reproduction file: {}", e.to_string()),
         }
     }
 
+    if let Some(filename) = cfg.reproduction_bundle {
+        let inputs = cfg.bundle_inputs;
+        match sim
+            .simulation
+            .get_reproduction_log()
+            .map_err(Error::from)
+            .and_then(|log| bundle::write(log, &filename, inputs))
+        {
+            Ok(_) => info!("Reproduction bundle written to {:?}.", filename),
+            Err(e) => error!(
+                "When trying to write reproduction bundle: {}",
+                e.to_string()
+            ),
+        }
+    }
+
     match &sim_result {
         Ok(_) => info!("Simulation completed successfully."),
         Err(e) => fatal!("Simulation failed: {}", e.to_string()),
     }
 
+    // Drop the simulator before writing the JSON output, such that the
+    // plugins have shut down and the duration includes their teardown.
+    drop(sim);
+    write_output(output, &sim_result);
+
     sim_result
 }
 
@@ -101,6 +255,8 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dqcsim::host::reproduction::Reproduction;
+    use std::path::PathBuf;
 
     macro_rules! cli {
         ( $( $v:expr ),* ) => {{
@@ -284,6 +440,24 @@ mod tests {
         assert!(cli!("--host-stdout", FRONTEND, BACKEND).is_ok());
     }
 
+    #[test]
+    fn output_json() {
+        assert!(cli!("--output", "json", "-C", "arb:back:a.b", FRONTEND, BACKEND).is_ok());
+        assert!(cli!("--output", "json", "-C", "arb:nope:a.b", FRONTEND, BACKEND).is_err());
+        assert!(err!(cli!("--output", "xml", FRONTEND, BACKEND))
+            .contains("xml is not a valid output format"));
+    }
+
+    #[test]
+    fn interactive_bad_arguments() {
+        assert!(err!(cli!("--interactive", "-C", "wait", FRONTEND, BACKEND))
+            .contains("cannot be used with"));
+        assert!(
+            err!(cli!("--interactive", "--output", "json", FRONTEND, BACKEND))
+                .contains("--interactive cannot be combined with --output json")
+        );
+    }
+
     #[test]
     fn with_operator() {
         assert!(cli!(FRONTEND, OPERATOR, BACKEND).is_ok());
@@ -337,6 +511,344 @@ mod tests {
             ));
     }
 
+    #[test]
+    fn trace() {
+        let path = std::env::temp_dir().join(format!("dqcsim-cli-{}.trace", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert!(cli!("--trace-out", path, FRONTEND, OPERATOR, BACKEND).is_ok());
+        assert!(cli!("trace", "dump", path).is_ok());
+        assert!(cli!("trace", "dump", "--json", "-d", "up", "-k", "gate", path).is_ok());
+        assert!(cli!("trace", "summary", "--plugin", "front", path).is_ok());
+        std::fs::remove_file(path).unwrap();
+
+        assert!(err!(cli!("trace", "dump", "-d", "sideways", path))
+            .contains("sideways is not a valid trace direction"));
+        assert!(err!(cli!("trace", "dump", LICENSE)).contains("not a gatestream trace file"));
+        assert!(err!(cli!("trace")).contains("dqcsim trace <SUBCOMMAND>"));
+    }
+
+    #[test]
+    fn plugin_path() {
+        static PLUGIN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/debug");
+        assert!(cli!(
+            "--plugin-path",
+            "/nonexistent",
+            "--plugin-path",
+            PLUGIN_DIR,
+            "--no-repro-out",
+            "null",
+            "null",
+            "null"
+        )
+        .is_ok());
+        assert!(err!(cli!(
+            "--plugin-path",
+            "/nonexistent",
+            "--no-repro-out",
+            "nope"
+        ))
+        .contains("searched the working directory, '/nonexistent', "));
+        assert!(cli!(
+            "plugins",
+            "info",
+            "--plugin-path",
+            PLUGIN_DIR,
+            "-t",
+            "front",
+            "null"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn interpreter() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-cli-interp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("front.nullsh");
+        std::fs::write(&script, format!("exec {} \"$1\"\n", FRONTEND)).unwrap();
+        let shebang = dir.join("back");
+        std::fs::write(&shebang, format!("#!/bin/sh\nexec {} \"$1\"\n", BACKEND)).unwrap();
+        let script = script.to_str().unwrap();
+        let shebang = shebang.to_str().unwrap();
+
+        let result = cli!(
+            "--interpreter",
+            ".nullsh=/bin/sh -e",
+            "--interpreter",
+            "#!sh=sh",
+            "--no-repro-out",
+            script,
+            shebang
+        );
+        let no_interpreter = err!(cli!("--no-repro-out", script, BACKEND));
+        let bad_interpreter = err!(cli!("--interpreter", "nullsh=sh", script, BACKEND));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_ok());
+        assert!(no_interpreter.contains("could not find plugin executable 'dqcsfenullsh'"));
+        assert!(bad_interpreter.contains("invalid interpreter match 'nullsh'"));
+    }
+
+    #[test]
+    fn plugins() {
+        assert!(cli!("plugins", "list").is_ok());
+        assert!(cli!("plugins", "info", FRONTEND).is_ok());
+        assert!(cli!("plugins", "info", OPERATOR).is_ok());
+        assert!(cli!("plugins", "info", "--type", "backend", BACKEND).is_ok());
+
+        assert!(err!(cli!("plugins", "info", "--type", "backend", FRONTEND))
+            .contains("host is expecting a plugin of type Backend"));
+        assert!(err!(cli!("plugins", "info", LICENSE)).contains("cannot derive the type"));
+        assert!(err!(cli!("plugins")).contains("dqcsim plugins <SUBCOMMAND>"));
+    }
+
+    #[test]
+    fn sweep() {
+        static DQCSIM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/debug/dqcsim");
+        let dir = std::env::temp_dir().join(format!("dqcsim-cli-sweep-{}", std::process::id()));
+        let dir_str = dir.to_str().unwrap();
+        let output = dir.join("results.json");
+        let result = cli!(
+            "sweep",
+            "--seeds",
+            "1..2",
+            "-p",
+            "back/0/x=0.5..1:0.5",
+            "-j",
+            "2",
+            "-d",
+            dir_str,
+            "-f",
+            "json",
+            "-o",
+            output.to_str().unwrap(),
+            "--dqcsim",
+            DQCSIM,
+            "--",
+            FRONTEND,
+            BACKEND,
+            "-i",
+            "a.b:{}"
+        );
+        let results = std::fs::read_to_string(&output).ok();
+        let bad_param = err!(cli!(
+            "sweep",
+            "-p",
+            "front/0/x=1",
+            "-d",
+            dir_str,
+            "--",
+            FRONTEND,
+            BACKEND
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_ok());
+        let results: serde_json::Value = serde_json::from_str(&results.unwrap()).unwrap();
+        let runs = results.as_array().unwrap();
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[1]["seed"], 1);
+        assert_eq!(runs[1]["params"]["back/0/x"], 1.0);
+        assert_eq!(runs[2]["seed"], 2);
+        assert!(runs.iter().all(|run| run["status"] == 0));
+        assert_eq!(runs[3]["returns"][0]["function"], "wait");
+        assert!(runs[3]["repro"].as_str().unwrap().ends_with("run-3.repro"));
+        assert!(bad_param.contains("plugin front has no init command with index 0"));
+    }
+
+    #[test]
+    fn repro() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-cli-repro-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("run.repro");
+        let file_str = file.to_str().unwrap();
+        let edited = dir.join("edited.repro");
+        let edited_str = edited.to_str().unwrap();
+        let run = cli!(
+            "--repro-out",
+            file_str,
+            "-C",
+            "send:{}",
+            "-C",
+            "recv",
+            FRONTEND,
+            BACKEND
+        );
+        let calls = cli!("repro", "calls", "-r", "1..2", "-C", "yield", "-o", edited_str, file_str);
+        let seed = cli!("repro", "seed", edited_str, "33");
+        let swap = cli!("repro", "swap", edited_str, "back", OPERATOR);
+        let check = cli!("repro", "check", edited_str);
+        let reproduction = Reproduction::from_file(&edited).ok();
+        let bad_index = err!(cli!("repro", "calls", "-r", "9", file_str));
+        let bad_plugin = err!(cli!("repro", "swap", file_str, "op", OPERATOR));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(run.is_ok());
+        assert!(calls.is_ok());
+        assert!(seed.is_ok());
+        assert!(swap.is_ok());
+        assert!(check.is_ok());
+        let reproduction = reproduction.unwrap();
+        assert_eq!(reproduction.seed, 33);
+        assert_eq!(
+            reproduction.host_calls,
+            vec![
+                HostCall::Start(ArbData::default()),
+                HostCall::Wait,
+                HostCall::Yield
+            ]
+        );
+        assert_eq!(reproduction.plugins[1].executable, PathBuf::from(OPERATOR));
+        assert!(bad_index.contains("host call 9 does not exist, the file has 4 host calls"));
+        assert!(bad_plugin.contains("There is no plugin named op."));
+    }
+
+    #[test]
+    fn repro_bundle() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-cli-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("input.txt"), "hello").unwrap();
+        let bundle = dir.join("run.tar");
+        let bundle_str = bundle.to_str().unwrap();
+        let repro = dir.join("run.repro");
+        let run = cli!(
+            "--repro-bundle",
+            bundle_str,
+            "--bundle-inputs",
+            "--repro-out",
+            repro.to_str().unwrap(),
+            FRONTEND,
+            BACKEND,
+            "--work",
+            dir.to_str().unwrap(),
+            "-i",
+            "a.b:{\"file\":\"input.txt\"}"
+        );
+        let mut entries: Vec<String> = tar::Archive::new(std::fs::File::open(&bundle).unwrap())
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        entries.sort();
+        let reproduce = cli!("--reproduce-bundle", bundle_str);
+        let with_seed = err!(cli!("--reproduce-bundle", bundle_str, "--seed", "3"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(run.is_ok());
+        assert_eq!(
+            entries,
+            vec![
+                "plugins/back/dqcsbenull",
+                "plugins/back/work",
+                "plugins/back/work/input.txt",
+                "plugins/front/dqcsfenull",
+                "plugins/front/work",
+                "reproduction.repro"
+            ]
+        );
+        assert!(reproduce.is_ok());
+        assert!(with_seed.contains("cannot be used with"));
+    }
+
+    #[test]
+    fn debug_bad_arguments() {
+        assert!(err!(cli!("--break", "cycle:3", FRONTEND, BACKEND))
+            .contains("The following required arguments were not provided:"));
+        assert!(err!(cli!(
+            "--debug", "back", "--break", "line:3", FRONTEND, BACKEND
+        ))
+        .contains("invalid breakpoint line:3"));
+        assert!(err!(cli!("--debug", "front", FRONTEND, BACKEND))
+            .contains("the frontend cannot be debugged"));
+    }
+
+    #[test]
+    fn config() {
+        let dir = std::env::temp_dir();
+        let yaml = dir.join(format!("dqcsim-cli-{}.yml", std::process::id()));
+        std::fs::write(
+            &yaml,
+            format!(
+                "seed: 42\nno-such-option: 1\nplugins:\n  - spec: {}\n  - spec: {}\n",
+                FRONTEND, BACKEND
+            ),
+        )
+        .unwrap();
+        let yaml = yaml.to_str().unwrap();
+        assert!(err!(cli!("--config", yaml)).contains("unknown field `no-such-option`"));
+
+        std::fs::write(
+            yaml,
+            format!(
+                "seed: 42\nlevel: bogus\ncall: [start, wait]\nplugins:\n  - spec: {}\n    name: alg\n  - spec: {}\n",
+                FRONTEND, BACKEND
+            ),
+        )
+        .unwrap();
+        assert!(err!(cli!("--config", yaml, "--no-repro-out"))
+            .contains("While reading configuration file: level: Invalid argument: bogus"));
+
+        std::fs::write(
+            yaml,
+            format!(
+                "seed: 42\ncall: [start, wait]\nplugins:\n  - spec: {}\n    name: alg\n    env: [\"key=value\"]\n  - spec: {}\n",
+                FRONTEND, BACKEND
+            ),
+        )
+        .unwrap();
+        assert!(cli!("--config", yaml, "--no-repro-out").is_ok());
+        assert!(cli!(
+            "--config",
+            yaml,
+            "--no-repro-out",
+            "-l",
+            "warn",
+            "@alg",
+            "-l",
+            "fatal"
+        )
+        .is_ok());
+        assert!(err!(cli!(
+            "--config",
+            yaml,
+            "--no-repro-out",
+            "@front",
+            "-l",
+            "fatal"
+        ))
+        .contains("There is no plugin named front"));
+        assert!(cli!(
+            "--config",
+            yaml,
+            "--no-repro-out",
+            FRONTEND,
+            OPERATOR,
+            BACKEND
+        )
+        .is_ok());
+        assert!(
+            err!(cli!("--config", yaml, "--reproduce", "x.repro")).contains("cannot be used with")
+        );
+        std::fs::remove_file(yaml).unwrap();
+
+        let toml = dir.join(format!("dqcsim-cli-{}.toml", std::process::id()));
+        std::fs::write(
+            &toml,
+            format!(
+                "seed = 42\n[[plugins]]\nspec = \"{}\"\n[[plugins]]\nspec = \"{}\"\naccept-timeout = 10\n",
+                FRONTEND, BACKEND
+            ),
+        )
+        .unwrap();
+        let toml = toml.to_str().unwrap();
+        assert!(cli!("--config", toml, "--no-repro-out").is_ok());
+        std::fs::remove_file(toml).unwrap();
+
+        assert!(
+            err!(cli!("--config", "/nonexistent.yml")).contains("While reading configuration file")
+        );
+    }
+
     #[test]
     fn no_repro_out() {
         assert!(cli!("--no-repro-out", FRONTEND, BACKEND).is_ok());
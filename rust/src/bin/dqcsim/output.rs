@@ -0,0 +1,281 @@
+//! Structured output of the command line interface, written to stdout with
+//! `--output json`.
+
+use dqcsim::{
+    common::{
+        error::Error,
+        types::{ArbCmd, ArbData, PluginMetadata, PluginType},
+    },
+    host::reproduction::HostCall,
+};
+use named_type::NamedType;
+use named_type_derive::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{convert::TryFrom, path::PathBuf, time::Duration};
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// The format of the output written to stdout.
+#[derive(Display, NamedType, EnumIter, EnumString, Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    /// Host API call return values are printed as text if requested with
+    /// `--host-stdout`.
+    #[strum(to_string = "Text", serialize = "text")]
+    Text,
+
+    /// A single `RunOutput` JSON document is printed when DQCsim terminates.
+    #[strum(to_string = "Json", serialize = "json")]
+    Json,
+}
+
+/// An `ArbData` object, with its binary arguments and shared data encoded
+/// using base64.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbDataOutput {
+    /// The JSON object.
+    pub json: Value,
+
+    /// The binary arguments, encoded using base64.
+    pub args: Vec<String>,
+
+    /// The shared data, encoded using base64. Omitted if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared: Vec<String>,
+}
+
+impl TryFrom<&ArbData> for ArbDataOutput {
+    type Error = Error;
+
+    fn try_from(data: &ArbData) -> Result<ArbDataOutput, Error> {
+        Ok(ArbDataOutput {
+            json: data.deserialize()?,
+            args: data.get_args().iter().map(base64::encode).collect(),
+            shared: data
+                .get_shared()
+                .iter()
+                .map(|shared| base64::encode(&shared[..]))
+                .collect(),
+        })
+    }
+}
+
+/// An `ArbCmd` object.
+#[derive(Debug, Serialize)]
+pub struct ArbCmdOutput {
+    /// The interface identifier.
+    pub interface: String,
+
+    /// The operation identifier.
+    pub operation: String,
+
+    /// The argument data.
+    pub data: ArbDataOutput,
+}
+
+impl TryFrom<&ArbCmd> for ArbCmdOutput {
+    type Error = Error;
+
+    fn try_from(cmd: &ArbCmd) -> Result<ArbCmdOutput, Error> {
+        Ok(ArbCmdOutput {
+            interface: cmd.interface_identifier().to_string(),
+            operation: cmd.operation_identifier().to_string(),
+            data: ArbDataOutput::try_from(cmd.data())?,
+        })
+    }
+}
+
+/// A host API call made by the command line interface.
+#[derive(Debug, Serialize)]
+pub struct HostCallOutput {
+    /// The name of the API call, i.e. start, wait, send, recv, yield, or arb.
+    pub call: &'static str,
+
+    /// The data passed to start or send.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<ArbDataOutput>,
+
+    /// The name of the plugin that an arb call was sent to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+
+    /// The command passed to arb.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<ArbCmdOutput>,
+
+    /// The time at which the call was made, in seconds since the simulation
+    /// started.
+    pub time: f64,
+
+    /// The time the call took, in seconds.
+    pub duration: f64,
+
+    /// The data returned by wait, recv, or arb.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<ArbDataOutput>,
+
+    /// The error returned by the call, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HostCallOutput {
+    /// Records a host API call, made at `time` relative to the start of the
+    /// simulation. Fails if the data passed to the call is not a valid CBOR
+    /// object.
+    pub fn new(host_call: &HostCall, time: Duration) -> Result<HostCallOutput, Error> {
+        let mut output = HostCallOutput {
+            call: "",
+            data: None,
+            plugin: None,
+            cmd: None,
+            time: time.as_secs_f64(),
+            duration: 0.0,
+            result: None,
+            error: None,
+        };
+        match host_call {
+            HostCall::Start(data) => {
+                output.call = "start";
+                output.data = Some(ArbDataOutput::try_from(data)?);
+            }
+            HostCall::Wait => output.call = "wait",
+            HostCall::Send(data) => {
+                output.call = "send";
+                output.data = Some(ArbDataOutput::try_from(data)?);
+            }
+            HostCall::Recv => output.call = "recv",
+            HostCall::Yield => output.call = "yield",
+            HostCall::Arb(plugin, cmd) => {
+                output.call = "arb";
+                output.plugin = Some(plugin.clone());
+                output.cmd = Some(ArbCmdOutput::try_from(cmd)?);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Records the outcome of the call and the time it took. If the returned
+    /// data is not a valid CBOR object, this is recorded as the error of the
+    /// call.
+    pub fn finish(&mut self, result: &Result<Option<ArbData>, failure::Error>, duration: Duration) {
+        self.duration = duration.as_secs_f64();
+        match result
+            .as_ref()
+            .map_err(ToString::to_string)
+            .and_then(|data| {
+                data.as_ref()
+                    .map(ArbDataOutput::try_from)
+                    .transpose()
+                    .map_err(|e| format!("returned data could not be recorded: {}", e))
+            }) {
+            Ok(data) => self.result = data,
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+/// The metadata of a plugin in the simulation.
+#[derive(Debug, Serialize)]
+pub struct PluginOutput {
+    /// The instance name of the plugin.
+    pub name: String,
+
+    /// The plugin type, i.e. frontend, operator, or backend.
+    #[serde(rename = "type")]
+    pub typ: String,
+
+    /// The name of the plugin implementation, as reported by the plugin.
+    /// `None` if the plugin was never initialized.
+    pub implementation: Option<String>,
+
+    /// The author of the plugin implementation.
+    pub author: Option<String>,
+
+    /// The version of the plugin implementation.
+    pub version: Option<String>,
+}
+
+impl PluginOutput {
+    /// Describes a plugin that has not been initialized (yet).
+    pub fn new(name: impl Into<String>, typ: PluginType) -> PluginOutput {
+        PluginOutput {
+            name: name.into(),
+            typ: typ.to_string().to_lowercase(),
+            implementation: None,
+            author: None,
+            version: None,
+        }
+    }
+
+    /// Adds the metadata reported by the plugin.
+    pub fn set_metadata(&mut self, metadata: &PluginMetadata) {
+        self.implementation = Some(metadata.get_name().to_string());
+        self.author = Some(metadata.get_author().to_string());
+        self.version = Some(metadata.get_version().to_string());
+    }
+}
+
+/// The JSON document written to stdout with `--output json`.
+#[derive(Debug, Default, Serialize)]
+pub struct RunOutput {
+    /// The random seed used for the simulation.
+    pub seed: u64,
+
+    /// The plugins in the simulation, from front to back.
+    pub plugins: Vec<PluginOutput>,
+
+    /// The host API calls that were made, in order. If a call failed, it is
+    /// the last one.
+    pub host_calls: Vec<HostCallOutput>,
+
+    /// The total time DQCsim took to run the simulation, including starting
+    /// and stopping the plugins, in seconds.
+    pub duration: f64,
+
+    /// The reproduction file that was written, if any.
+    pub reproduction_file: Option<PathBuf>,
+
+    /// Whether the simulation completed successfully.
+    pub success: bool,
+
+    /// The error that caused the simulation to fail, if any.
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn host_call() {
+        let host_call = HostCall::from_str("arb:back:a.b:{\"x\":1},hi").unwrap();
+        let mut output = HostCallOutput::new(&host_call, Duration::from_millis(1500)).unwrap();
+        output.finish(
+            &Ok(Some(ArbData::from_str("{\"y\":2}").unwrap())),
+            Duration::from_millis(250),
+        );
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"call":"arb","plugin":"back","cmd":{"interface":"a","operation":"b","data":{"json":{"x":1},"args":["aGk="]}},"time":1.5,"duration":0.25,"result":{"json":{"y":2},"args":[]}}"#
+        );
+
+        let mut output = HostCallOutput::new(&HostCall::Wait, Duration::default()).unwrap();
+        output.finish(&Err(failure::format_err!("boom")), Duration::default());
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"call":"wait","time":0.0,"duration":0.0,"error":"boom"}"#
+        );
+
+        let mut output = HostCallOutput::new(&HostCall::Recv, Duration::default()).unwrap();
+        output.finish(
+            &Ok(Some(ArbData::from_cbor([0x41, 0x00], vec![]).unwrap())),
+            Duration::default(),
+        );
+        assert!(output.result.is_none());
+        assert!(output
+            .error
+            .unwrap()
+            .starts_with("returned data could not be recorded: "));
+    }
+}
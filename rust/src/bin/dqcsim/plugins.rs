@@ -0,0 +1,225 @@
+//! Implementation of the `dqcsim plugins` subcommand, used to find out which
+//! plugins are available and what they are.
+
+use dqcsim::{
+    common::{
+        error::inv_arg,
+        log::{thread::LogThread, LoglevelFilter},
+        types::PluginType,
+        util::friendly_enum_parse,
+    },
+    host::{
+        configuration::{
+            discover_plugins, DesugarOptions, InterpreterEntry, PluginConfiguration,
+            PluginProcessConfiguration, PluginProcessSpecification,
+        },
+        plugin::Plugin,
+    },
+};
+use failure::Error;
+use std::{ffi::OsString, path::PathBuf};
+use structopt::{clap::AppSettings, StructOpt};
+use strum::IntoEnumIterator;
+
+/// Options for extending the plugin search path.
+#[derive(Debug, StructOpt, PartialEq)]
+pub struct PluginPathOpts {
+    /// Adds a directory to search for plugins specified by name, like the
+    /// --plugin-path option of the main command. Can be specified more than
+    /// once.
+    #[structopt(
+        long = "plugin-path",
+        value_name = "dir",
+        number_of_values = 1,
+        parse(from_os_str)
+    )]
+    pub plugin_path: Vec<PathBuf>,
+}
+
+/// The StructOpt structure for the `dqcsim plugins` subcommand.
+#[derive(Debug, StructOpt, PartialEq)]
+#[structopt(
+    name = "dqcsim plugins",
+    about = "Lists the available plugins and queries their metadata"
+)]
+pub enum PluginsStructOpt {
+    /// Lists the plugins that can be referred to by name, in the order in
+    /// which they are searched for: the working directory, the --plugin-path
+    /// directories, $DQCSIM_PLUGIN_PATH, the per-user plugin directory, the
+    /// directory DQCsim resides in, and the system $PATH. Plugins hidden by
+    /// an earlier plugin with the same type and name are marked as shadowed.
+    #[structopt(name = "list")]
+    List {
+        #[structopt(flatten)]
+        path: PluginPathOpts,
+    },
+
+    /// Spawns a plugin and prints its type, metadata, and supported ArbCmd
+    /// interfaces without running a simulation.
+    #[structopt(name = "info")]
+    Info {
+        /// The type of the plugin, frontend, operator, or backend. Only
+        /// needed when it cannot be derived from the executable filename.
+        #[structopt(
+            short = "t",
+            long = "type",
+            value_name = "type",
+            case_insensitive = true,
+            parse(try_from_str = friendly_enum_parse)
+        )]
+        typ: Option<PluginType>,
+
+        #[structopt(flatten)]
+        path: PluginPathOpts,
+
+        /// Registers an interpreter command for script plugins, like the
+        /// --interpreter option of the main command. Can be specified more
+        /// than once.
+        #[structopt(
+            long = "interpreter",
+            value_name = "match=command",
+            number_of_values = 1
+        )]
+        interpreters: Vec<InterpreterEntry>,
+
+        /// The plugin specification, using the same syntax as the plugin
+        /// specifications of the main command.
+        #[structopt(value_name = "plugin")]
+        spec: String,
+    },
+}
+
+/// Desugars the given plugin specification. If the plugin type is not
+/// specified, the specification must unambiguously resolve to an executable
+/// with a "dqcsfe"/"dqcsop"/"dqcsbe" prefix.
+fn resolve(
+    spec: &str,
+    typ: Option<PluginType>,
+    options: &DesugarOptions,
+) -> Result<PluginProcessSpecification, Error> {
+    let desugar = |typ| PluginProcessSpecification::from_sugar_with(spec, typ, options);
+    if let Some(typ) = typ {
+        return Ok(desugar(typ)?);
+    }
+    let candidates: Vec<_> = PluginType::iter()
+        .filter_map(|typ| desugar(typ).ok())
+        .filter(|candidate| {
+            candidate.executable.file_name().is_some_and(|name| {
+                name.to_string_lossy()
+                    .starts_with(candidate.typ.executable_prefix())
+            })
+        })
+        .collect();
+    match candidates.len() {
+        0 => {
+            // If the plugin can't be found at all, report that instead.
+            desugar(PluginType::Frontend)?;
+            inv_arg(format!(
+                "cannot derive the type of plugin '{}', please specify it with --type",
+                spec
+            ))
+            .map_err(Error::from)
+        }
+        1 => Ok(candidates.into_iter().next().unwrap()),
+        _ => inv_arg(format!(
+            "plugin '{}' exists as more than one type ({}), please specify it with --type",
+            spec,
+            candidates
+                .iter()
+                .map(|candidate| candidate.typ.to_string().to_lowercase())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .map_err(Error::from),
+    }
+}
+
+/// Runs the `dqcsim plugins` subcommand. `args` should start with the name of
+/// the subcommand, i.e. `plugins`.
+pub fn run<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = PluginsStructOpt::clap()
+        .bin_name("dqcsim plugins")
+        .settings(&[
+            AppSettings::DeriveDisplayOrder,
+            AppSettings::UnifiedHelpMessage,
+            AppSettings::ColoredHelp,
+            AppSettings::DisableVersion,
+            AppSettings::SubcommandRequiredElseHelp,
+            AppSettings::VersionlessSubcommands,
+        ])
+        .get_matches_from_safe(args)?;
+    match PluginsStructOpt::from_clap(&matches) {
+        PluginsStructOpt::List { path } => {
+            for plugin in discover_plugins(&path.plugin_path)? {
+                println!(
+                    "{:<10} {:<20} {}{}",
+                    plugin.typ.to_string().to_lowercase(),
+                    plugin.name,
+                    plugin.executable.display(),
+                    if plugin.shadowed { " (shadowed)" } else { "" }
+                );
+            }
+        }
+        PluginsStructOpt::Info {
+            typ,
+            path,
+            interpreters,
+            spec,
+        } => {
+            let mut options = DesugarOptions {
+                plugin_path: path.plugin_path,
+                ..DesugarOptions::default()
+            };
+            for entry in interpreters {
+                options.interpreters.register(entry);
+            }
+            let specification = resolve(&spec, typ, &options)?;
+            let executable = specification.executable.clone();
+            let args = specification.args().to_vec();
+            let script = specification.script.clone();
+            let typ = specification.typ;
+
+            // Only warnings and errors are shown, so the plugin's own output
+            // doesn't get mixed with the information we print.
+            let logger = LogThread::spawn(
+                "dqcsim",
+                LoglevelFilter::Warn,
+                LoglevelFilter::Warn,
+                None,
+                vec![],
+            )?;
+            let mut plugin: Box<dyn Plugin> =
+                Box::new(PluginProcessConfiguration::new(spec, specification)).instantiate();
+            let response = plugin.probe(&logger);
+            drop(plugin);
+            let response = response?;
+
+            println!("Executable: {}", executable.display());
+            if !args.is_empty() {
+                println!("Arguments:  {}", args.join(" "));
+            }
+            if let Some(script) = script {
+                println!("Script:     {}", script.display());
+            }
+            println!("Type:       {}", typ.to_string().to_lowercase());
+            println!("Name:       {}", response.metadata.get_name());
+            println!("Author:     {}", response.metadata.get_author());
+            println!("Version:    {}", response.metadata.get_version());
+            if response.interfaces.is_empty() {
+                println!("Interfaces: none registered, accepts any ArbCmd");
+            } else {
+                println!("Interfaces:");
+                for interface in response.interfaces.get_interfaces() {
+                    for operation in response.interfaces.get_operations(interface).unwrap() {
+                        println!("  {}.{}", interface, operation);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! Interactive host for the command line interface (`--interactive`).
+
+use crate::line_editor::Prompt;
+use dqcsim::{
+    common::types::ArbData,
+    host::{accelerator::Accelerator, reproduction::HostCall, simulator::Simulator},
+};
+use failure::Error;
+use std::{env, io::Write, path::PathBuf};
+
+static HELP: &str = "\
+Commands:
+  start[:<ArbData>]         start the accelerator, passing it the given data
+  wait                      wait for the accelerator to finish and print its return value
+  send:<ArbData>            queue data to be sent to the accelerator
+  recv                      receive data from the accelerator and print it
+  yield                     yield to the accelerator until it blocks
+  arb:<plugin>:<ArbCmd>     send an ArbCmd to the given plugin and print the result
+  plugins                   list the plugins and the ArbCmds they support
+  q, quit                   wait for the accelerator if it is running and stop the simulation
+  h, help                   print this message
+Host calls use the syntax of -C/--call, so their names may be abbreviated.
+";
+
+/// Returns the file that the prompt history is stored in, being
+/// `~/.dqcsim_history`.
+pub fn history_file() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".dqcsim_history"))
+}
+
+/// A command entered at the prompt.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Call(HostCall),
+    Plugins,
+    Quit,
+    Help,
+}
+
+/// Parses a line entered at the prompt. Returns `None` for empty lines.
+fn parse_command(line: &str) -> Result<Option<Command>, String> {
+    match line.trim() {
+        "" => Ok(None),
+        "plugins" => Ok(Some(Command::Plugins)),
+        "q" | "quit" | "exit" => Ok(Some(Command::Quit)),
+        "h" | "help" | "?" => Ok(Some(Command::Help)),
+        line => line
+            .parse()
+            .map(|call| Some(Command::Call(call)))
+            .map_err(|e: dqcsim::common::error::Error| e.to_string()),
+    }
+}
+
+/// Interactive host, reading host calls from a prompt and writing their
+/// results to an output stream.
+pub struct Repl<'a> {
+    simulator: &'a mut Simulator,
+
+    /// The instance names of the plugins, from front to back.
+    plugins: Vec<String>,
+
+    output: &'a mut dyn Write,
+
+    /// Whether the accelerator has been started and not yet waited for.
+    running: bool,
+}
+
+impl<'a> Repl<'a> {
+    /// Constructs an interactive host for the given simulation.
+    pub fn new(
+        simulator: &'a mut Simulator,
+        plugins: Vec<String>,
+        output: &'a mut dyn Write,
+    ) -> Repl<'a> {
+        Repl {
+            simulator,
+            plugins,
+            output,
+            running: false,
+        }
+    }
+
+    /// Makes a host call, returning the data returned by it, if any. Like
+    /// all host calls, it is recorded in the reproduction log.
+    fn call(&mut self, host_call: HostCall) -> Result<Option<ArbData>, Error> {
+        let simulation = &mut self.simulator.simulation;
+        Ok(match host_call {
+            HostCall::Start(data) => {
+                simulation.start(data)?;
+                self.running = true;
+                None
+            }
+            HostCall::Wait => {
+                self.running = false;
+                Some(simulation.wait()?)
+            }
+            HostCall::Send(data) => {
+                simulation.send(data)?;
+                None
+            }
+            HostCall::Recv => Some(simulation.recv()?),
+            HostCall::Yield => {
+                simulation.yield_to_accelerator()?;
+                None
+            }
+            HostCall::Arb(plugin, cmd) => Some(simulation.arb(plugin, cmd)?),
+        })
+    }
+
+    /// Lists the plugins and the ArbCmds they support.
+    fn list_plugins(&mut self) -> Result<(), Error> {
+        let simulation = &self.simulator.simulation;
+        for (index, name) in self.plugins.iter().enumerate() {
+            let metadata = simulation.get_metadata_idx(index as isize)?;
+            writeln!(self.output, "{}: {}", name, metadata)?;
+            let interfaces = simulation.get_interfaces_idx(index as isize)?;
+            if interfaces.is_empty() {
+                writeln!(self.output, "  accepts any ArbCmd")?;
+            }
+            for interface in interfaces.get_interfaces() {
+                for operation in interfaces.get_operations(interface).unwrap() {
+                    writeln!(self.output, "  {}.{}", interface, operation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads and executes commands until the input ends or the user quits.
+    /// If the accelerator is still running at that point, it is waited for.
+    /// Failing host calls are reported, but do not end the session.
+    pub fn run(&mut self, prompt: &mut dyn Prompt) -> Result<(), Error> {
+        while let Some(line) = prompt.read_line("dqcsim> ")? {
+            let result = match parse_command(&line) {
+                Ok(None) => Ok(()),
+                Ok(Some(Command::Call(host_call))) => {
+                    let name = match host_call {
+                        HostCall::Wait => "wait()",
+                        HostCall::Recv => "recv",
+                        _ => "arb",
+                    };
+                    match self.call(host_call) {
+                        Ok(Some(data)) => writeln!(self.output, "{}: {}", name, data),
+                        Ok(None) => Ok(()),
+                        Err(e) => writeln!(self.output, "{}", e),
+                    }
+                }
+                Ok(Some(Command::Plugins)) => match self.list_plugins() {
+                    Ok(()) => Ok(()),
+                    Err(e) => writeln!(self.output, "{}", e),
+                },
+                Ok(Some(Command::Quit)) => break,
+                Ok(Some(Command::Help)) => write!(self.output, "{}", HELP),
+                Err(e) => writeln!(self.output, "{}", e),
+            };
+            result?;
+        }
+        if self.running {
+            if let Some(data) = self.call(HostCall::Wait)? {
+                writeln!(self.output, "wait(): {}", data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::{
+        common::{
+            log::LoglevelFilter,
+            types::{PluginMetadata, PluginType},
+        },
+        host::configuration::{
+            PluginLogConfiguration, PluginThreadConfiguration, SimulatorConfiguration,
+        },
+        plugin::definition::PluginDefinition,
+    };
+    use std::io::Cursor;
+
+    #[test]
+    fn session() {
+        let mut frontend = PluginDefinition::new(
+            PluginType::Frontend,
+            PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+        );
+        frontend.run = Box::new(|state, arg| {
+            let data = state.recv()?;
+            state.send(data)?;
+            Ok(arg)
+        });
+        let mut backend = PluginDefinition::new(
+            PluginType::Backend,
+            PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+        );
+        backend.register_arb_operation("a", "b", None).unwrap();
+        backend.host_arb = Box::new(|_, cmd| Ok(cmd.data().clone()));
+
+        let configuration = SimulatorConfiguration::default()
+            .without_reproduction()
+            .without_logging()
+            .with_plugin(PluginThreadConfiguration::new(
+                frontend,
+                PluginLogConfiguration::new("front", LoglevelFilter::Off),
+            ))
+            .with_plugin(PluginThreadConfiguration::new(
+                backend,
+                PluginLogConfiguration::new("back", LoglevelFilter::Off),
+            ));
+        let mut simulator = Simulator::new(configuration).unwrap();
+        let mut output = vec![];
+        let mut input = Cursor::new(
+            "bogus\n\nstart:{\"x\":1}\nsend:{\"y\":2}\nrecv\narb:back:a.c\narb:back:a.b:{\"z\":3}\n\
+             plugins\nhelp\nquit\nrecv\n"
+                .as_bytes(),
+        );
+        Repl::new(
+            &mut simulator,
+            vec!["front".to_string(), "back".to_string()],
+            &mut output,
+        )
+        .run(&mut input)
+        .unwrap();
+
+        drop(simulator);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "Invalid argument: bogus is not a valid host call function, valid values are start, wait, send, recv, yield, or arb\n\
+                 recv: {{\"y\":2}}\n\
+                 Invalid argument: plugin back does not support ArbCmd a.c\n\
+                 arb: {{\"z\":3}}\n\
+                 front: frontend version 0.1.0 by dqcsim\n  \
+                 accepts any ArbCmd\n\
+                 back: backend version 0.1.0 by dqcsim\n  \
+                 a.b\n\
+                 {}\
+                 wait(): {{\"x\":1}}\n",
+                HELP
+            )
+        );
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse_command("  "), Ok(None));
+        assert_eq!(parse_command("w"), Ok(Some(Command::Call(HostCall::Wait))));
+        assert_eq!(parse_command("quit"), Ok(Some(Command::Quit)));
+        assert_eq!(
+            parse_command("send"),
+            Err("Invalid argument: the send API call requires an ArbData argument".to_string())
+        );
+    }
+}
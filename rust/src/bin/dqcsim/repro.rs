@@ -0,0 +1,322 @@
+//! Implementation of the `dqcsim repro` subcommand, used to inspect and edit
+//! reproduction files.
+
+use crate::plugins::PluginPathOpts;
+use dqcsim::{
+    common::{error::inv_arg, util::friendly_enum_parse},
+    host::{
+        configuration::{
+            DesugarOptions, InterpreterEntry, PluginProcessSpecification, ReproductionPathStyle,
+            Seed,
+        },
+        reproduction::{HostCall, Reproduction},
+    },
+};
+use failure::{bail, Error};
+use std::{ffi::OsString, path::PathBuf};
+use structopt::{clap::AppSettings, StructOpt};
+
+/// Options for subcommands that modify a reproduction file.
+#[derive(Debug, StructOpt, PartialEq)]
+pub struct EditOpts {
+    /// Writes the modified reproduction file to the given file instead of
+    /// overwriting the original.
+    #[structopt(
+        short = "o",
+        long = "output",
+        value_name = "filename",
+        parse(from_os_str)
+    )]
+    pub output: Option<PathBuf>,
+
+    /// The reproduction file.
+    #[structopt(value_name = "filename", parse(from_os_str))]
+    pub file: PathBuf,
+}
+
+impl EditOpts {
+    /// Loads the reproduction file, applies the given modification to it,
+    /// and writes it back.
+    fn edit(
+        &self,
+        modify: impl FnOnce(&mut Reproduction) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let mut reproduction = Reproduction::from_file(&self.file)?;
+        modify(&mut reproduction)?;
+        reproduction.to_file(self.output.as_ref().unwrap_or(&self.file))?;
+        Ok(())
+    }
+}
+
+/// The StructOpt structure for the `dqcsim repro` subcommand.
+#[derive(Debug, StructOpt, PartialEq)]
+#[structopt(name = "dqcsim repro", about = "Inspects and edits reproduction files")]
+pub enum ReproStructOpt {
+    /// Prints the seed, the plugins, and the numbered host calls of a
+    /// reproduction file.
+    #[structopt(name = "show")]
+    Show {
+        /// The reproduction file.
+        #[structopt(value_name = "filename", parse(from_os_str))]
+        file: PathBuf,
+    },
+
+    /// Checks whether a reproduction file can be reproduced from the current
+    /// working directory, i.e. whether the executables, scripts, and working
+    /// directories of its plugins exist, and whether its arb host calls refer
+    /// to existing plugins.
+    #[structopt(name = "check")]
+    Check {
+        /// The reproduction file.
+        #[structopt(value_name = "filename", parse(from_os_str))]
+        file: PathBuf,
+    },
+
+    /// Removes and/or appends host calls. Removal is applied first.
+    #[structopt(name = "calls")]
+    Calls {
+        /// Removes all host calls.
+        #[structopt(long = "clear")]
+        clear: bool,
+
+        /// Removes the host calls with the given indices, as listed by "dqcsim
+        /// repro show". Indices are separated by commas, and <a>..<b> denotes
+        /// all indices from a to b inclusive. Can be specified more than once.
+        #[structopt(
+            short = "r",
+            long = "remove",
+            value_name = "indices",
+            conflicts_with = "clear",
+            number_of_values = 1
+        )]
+        remove: Vec<String>,
+
+        /// Appends a host call, using the syntax of the -C/--call option of
+        /// the main command. Can be specified more than once.
+        #[structopt(short = "C", long = "call", value_name = "call", number_of_values = 1)]
+        calls: Vec<HostCall>,
+
+        #[structopt(flatten)]
+        edit: EditOpts,
+    },
+
+    /// Changes the random seed.
+    #[structopt(name = "seed")]
+    Seed {
+        #[structopt(flatten)]
+        edit: EditOpts,
+
+        /// The new seed. If a 64-bit unsigned number is specified, it is used
+        /// directly. Otherwise, the specified string is hashed to such a
+        /// 64-bit number.
+        #[structopt(value_name = "seed", parse(from_str))]
+        seed: Seed,
+    },
+
+    /// Replaces the executable of a plugin, keeping its name and functional
+    /// configuration, i.e. its init commands, environment, and working
+    /// directory.
+    #[structopt(name = "swap")]
+    Swap {
+        #[structopt(flatten)]
+        path: PluginPathOpts,
+
+        /// Registers an interpreter command for script plugins, like the
+        /// --interpreter option of the main command. Can be specified more
+        /// than once.
+        #[structopt(
+            long = "interpreter",
+            value_name = "match=command",
+            number_of_values = 1
+        )]
+        interpreters: Vec<InterpreterEntry>,
+
+        #[structopt(flatten)]
+        edit: EditOpts,
+
+        /// The name of the plugin to replace.
+        #[structopt(value_name = "plugin")]
+        plugin: String,
+
+        /// The new plugin specification, using the same syntax as the plugin
+        /// specifications of the main command.
+        #[structopt(value_name = "spec")]
+        spec: String,
+    },
+
+    /// Converts the executable, script, and working directory paths of the
+    /// plugins to the given style, like --repro-paths of the main command
+    /// does when the file is written. Relative paths in the file are taken to
+    /// be relative to the current working directory.
+    #[structopt(name = "paths")]
+    Paths {
+        #[structopt(flatten)]
+        edit: EditOpts,
+
+        /// The path style, keep, relative, or absolute.
+        #[structopt(
+            value_name = "style",
+            case_insensitive = true,
+            parse(try_from_str = friendly_enum_parse)
+        )]
+        style: ReproductionPathStyle,
+    },
+}
+
+/// Parses a comma-separated list of indices and inclusive <a>..<b> ranges.
+fn indices(list: &str) -> Result<Vec<usize>, Error> {
+    let mut indices = vec![];
+    for item in list.split(',').map(str::trim) {
+        let parse = |index: &str| {
+            index
+                .trim()
+                .parse::<usize>()
+                .or_else(|_| inv_arg(format!("{} is not a valid host call index", index)))
+        };
+        if let Some(pos) = item.find("..") {
+            let (first, last) = (parse(&item[..pos])?, parse(&item[pos + 2..])?);
+            if first > last {
+                inv_arg(format!("{} is an empty range", item))?;
+            }
+            indices.extend(first..=last);
+        } else {
+            indices.push(parse(item)?);
+        }
+    }
+    Ok(indices)
+}
+
+/// Runs the `dqcsim repro` subcommand. `args` should start with the name of
+/// the subcommand, i.e. `repro`.
+pub fn run<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = ReproStructOpt::clap()
+        .bin_name("dqcsim repro")
+        .settings(&[
+            AppSettings::DeriveDisplayOrder,
+            AppSettings::UnifiedHelpMessage,
+            AppSettings::ColoredHelp,
+            AppSettings::DisableVersion,
+            AppSettings::SubcommandRequiredElseHelp,
+            AppSettings::VersionlessSubcommands,
+        ])
+        .get_matches_from_safe(args)?;
+    match ReproStructOpt::from_clap(&matches) {
+        ReproStructOpt::Show { file } => {
+            let reproduction = Reproduction::from_file(file)?;
+            println!("Seed:    {}", reproduction.seed);
+            println!(
+                "Origin:  {}@{}:{}",
+                reproduction.username,
+                reproduction.hostname,
+                reproduction.workdir.display()
+            );
+            println!("Plugins:");
+            for plugin in reproduction.plugins.iter() {
+                print!("  {}: {}", plugin.name, plugin.executable.display());
+                for arg in plugin.args.iter() {
+                    print!(" {}", arg);
+                }
+                if let Some(script) = &plugin.script {
+                    print!(" {}", script.display());
+                }
+                println!();
+            }
+            println!("Host calls:");
+            for (index, host_call) in reproduction.host_calls.iter().enumerate() {
+                println!("  {:>3}  {}", index, host_call);
+            }
+        }
+        ReproStructOpt::Check { file } => {
+            let problems = Reproduction::from_file(&file)?.check();
+            for problem in problems.iter() {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                bail!(
+                    "reproduction file {} cannot be reproduced from here",
+                    file.display()
+                );
+            }
+        }
+        ReproStructOpt::Calls {
+            clear,
+            remove,
+            calls,
+            edit,
+        } => edit.edit(|reproduction| {
+            if clear {
+                reproduction.host_calls.clear();
+            }
+            let mut removed = vec![];
+            for list in remove {
+                removed.extend(indices(&list)?);
+            }
+            if let Some(index) = removed
+                .iter()
+                .find(|&&index| index >= reproduction.host_calls.len())
+            {
+                inv_arg(format!(
+                    "host call {} does not exist, the file has {} host calls",
+                    index,
+                    reproduction.host_calls.len()
+                ))?;
+            }
+            let mut index = 0;
+            reproduction.host_calls.retain(|_| {
+                index += 1;
+                !removed.contains(&(index - 1))
+            });
+            reproduction.host_calls.extend(calls);
+            Ok(())
+        })?,
+        ReproStructOpt::Seed { edit, seed } => edit.edit(|reproduction| {
+            reproduction.seed = seed.value;
+            Ok(())
+        })?,
+        ReproStructOpt::Swap {
+            path,
+            interpreters,
+            edit,
+            plugin,
+            spec,
+        } => edit.edit(|reproduction| {
+            let mut options = DesugarOptions {
+                plugin_path: path.plugin_path,
+                ..DesugarOptions::default()
+            };
+            for entry in interpreters {
+                options.interpreters.register(entry);
+            }
+            let typ = reproduction.plugin_type(&plugin)?;
+            let specification = PluginProcessSpecification::from_sugar_with(&spec, typ, &options)?;
+            Ok(reproduction.replace_plugin(&plugin, specification)?)
+        })?,
+        ReproStructOpt::Paths { edit, style } => {
+            edit.edit(|reproduction| Ok(reproduction.convert_paths(style)?))?
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_call_indices() {
+        assert_eq!(indices("3").unwrap(), vec![3]);
+        assert_eq!(indices("0, 2..4,1").unwrap(), vec![0, 2, 3, 4, 1]);
+        assert_eq!(
+            indices("4..2").unwrap_err().to_string(),
+            "Invalid argument: 4..2 is an empty range"
+        );
+        assert_eq!(
+            indices("x").unwrap_err().to_string(),
+            "Invalid argument: x is not a valid host call index"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Implementation of the `dqcsim sweep` subcommand, used to run a simulation
+//! for every combination of a number of parameter values.
+
+use crate::{arg_parse::CommandLineConfiguration, output::ArbDataOutput};
+use dqcsim::host::{
+    configuration::ReproductionPathStyle,
+    reproduction::{PluginReproduction, Reproduction},
+};
+use failure::{bail, format_err, Error};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    env::current_exe,
+    ffi::OsString,
+    fmt::Write as _,
+    fs::{create_dir_all, File},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    sync::Arc,
+    thread,
+};
+use structopt::{clap::AppSettings, StructOpt};
+
+/// The StructOpt structure for the `dqcsim sweep` subcommand.
+#[derive(Debug, StructOpt, PartialEq)]
+#[structopt(
+    name = "dqcsim sweep",
+    about = "Runs a simulation for every combination of the given parameter values"
+)]
+pub struct SweepStructOpt {
+    /// The random seeds to run the simulation with. Values are separated by
+    /// commas, and <a>..<b> denotes all integers from a to b inclusive. If
+    /// not specified, every run uses the seed of the base configuration.
+    #[structopt(short = "s", long = "seeds", value_name = "values")]
+    pub seeds: Option<String>,
+
+    /// Sweeps a field of the JSON data of an initialization ArbCmd, of the
+    /// form <plugin>/<index><pointer>=<values>. <plugin> is the plugin
+    /// name, <index> selects the init command of that plugin, and <pointer>
+    /// is a JSON pointer into its JSON object, for instance
+    /// "back/0/error/rate=0.001,0.01". The values are JSON values separated
+    /// by commas (strings may be unquoted), or numeric ranges of the form
+    /// <a>..<b>:<step>. Commas and backslashes within a value must be
+    /// escaped with a backslash, for instance "[1\,2]" for a JSON array.
+    /// Can be specified more than once.
+    #[structopt(
+        short = "p",
+        long = "param",
+        value_name = "param=values",
+        number_of_values = 1
+    )]
+    pub params: Vec<String>,
+
+    /// The number of simulations to run in parallel, each in its own worker
+    /// process.
+    #[structopt(short = "j", long = "jobs", value_name = "count", default_value = "1")]
+    pub jobs: usize,
+
+    /// The directory to write the reproduction file and log of each run to.
+    #[structopt(
+        short = "d",
+        long = "dir",
+        value_name = "dir",
+        default_value = "sweep",
+        parse(from_os_str)
+    )]
+    pub dir: PathBuf,
+
+    /// The format of the results table, csv or json.
+    #[structopt(
+        short = "f",
+        long = "format",
+        value_name = "format",
+        default_value = "csv",
+        possible_values = &["csv", "json"]
+    )]
+    pub format: String,
+
+    /// Writes the results table to the given file instead of stdout.
+    #[structopt(
+        short = "o",
+        long = "output",
+        value_name = "filename",
+        parse(from_os_str)
+    )]
+    pub output: Option<PathBuf>,
+
+    /// Passes an additional argument to each worker, for instance
+    /// "--worker-arg=-lwarn" to change its log level. Plugin options can be
+    /// changed using the @<name> syntax of --reproduce. Can be specified more
+    /// than once.
+    #[structopt(
+        long = "worker-arg",
+        value_name = "arg",
+        number_of_values = 1,
+        allow_hyphen_values = true
+    )]
+    pub worker_args: Vec<String>,
+
+    /// The dqcsim executable used for the workers. Defaults to this one.
+    #[structopt(long = "dqcsim", value_name = "filename", parse(from_os_str))]
+    pub dqcsim: Option<PathBuf>,
+
+    /// The base configuration, specified using the command line syntax of
+    /// dqcsim itself, for instance "--config sim.yml" or "alg.py qx".
+    #[structopt(value_name = "base", last = true, parse(from_os_str))]
+    pub base: Vec<OsString>,
+}
+
+/// Splits a comma-separated list of sweep values. Commas and backslashes
+/// that are part of a value are escaped with a backslash.
+fn split_values(s: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => items.last_mut().unwrap().extend(chars.next()),
+            ',' => items.push(String::new()),
+            c => items.last_mut().unwrap().push(c),
+        }
+    }
+    items
+}
+
+/// Parses a comma-separated list of sweep values. Items of the form
+/// `<a>..<b>` expand to the integers from a to b inclusive, and items of the
+/// form `<a>..<b>:<step>` to a..b in steps of step. Other items are parsed as
+/// JSON, falling back to a string.
+fn parse_values(s: &str) -> Result<Vec<Value>, Error> {
+    let mut values = vec![];
+    for item in split_values(s).iter().map(|item| item.trim()) {
+        if let Some(index) = item.find("..") {
+            let (start, rest) = (&item[..index], &item[index + 2..]);
+            let (end, step) = match rest.find(':') {
+                Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+                None => (rest, None),
+            };
+            let range_err = || format_err!("invalid range '{}'", item);
+            if let (None, Ok(start), Ok(end)) = (step, i64::from_str(start), i64::from_str(end)) {
+                values.extend((start..=end).map(Value::from));
+                continue;
+            }
+            let start = f64::from_str(start).map_err(|_| range_err())?;
+            let end = f64::from_str(end).map_err(|_| range_err())?;
+            let step = f64::from_str(step.unwrap_or("1")).map_err(|_| range_err())?;
+            if step.is_nan() || step <= 0.0 {
+                bail!("invalid range '{}', step must be positive", item);
+            }
+            // Allow for rounding errors at the end of the range.
+            let count = ((end - start) / step + 1e-9).floor();
+            for i in 0..=(count.max(-1.0) as i64) {
+                values.push(Value::from(start + step * i as f64));
+            }
+        } else if item.is_empty() {
+            bail!("empty value in '{}'", s);
+        } else {
+            values.push(serde_json::from_str(item).unwrap_or_else(|_| Value::from(item)));
+        }
+    }
+    Ok(values)
+}
+
+/// A swept initialization command field.
+#[derive(Debug, Clone, PartialEq)]
+struct InitField {
+    /// The name of the plugin.
+    plugin: String,
+
+    /// The index of the initialization command.
+    index: usize,
+
+    /// The JSON pointer into the JSON data of the command.
+    pointer: String,
+}
+
+impl FromStr for InitField {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut parts = s.splitn(3, '/');
+        let plugin = parts.next().unwrap();
+        let index = parts.next().and_then(|index| usize::from_str(index).ok());
+        match (plugin.is_empty(), index) {
+            (false, Some(index)) => Ok(InitField {
+                plugin: plugin.to_string(),
+                index,
+                pointer: parts.next().map(|x| format!("/{}", x)).unwrap_or_default(),
+            }),
+            _ => bail!(
+                "invalid parameter '{}', expected <plugin>/<index><pointer>",
+                s
+            ),
+        }
+    }
+}
+
+impl InitField {
+    /// Sets this field to the given value in a reproduction structure. Object
+    /// members that do not exist yet are added.
+    fn apply(&self, plugins: &mut [PluginReproduction], value: &Value) -> Result<(), Error> {
+        let plugin = plugins
+            .iter_mut()
+            .find(|plugin| plugin.name == self.plugin)
+            .ok_or_else(|| format_err!("there is no plugin named {}", self.plugin))?;
+        let cmd = plugin.functional.init.get_mut(self.index).ok_or_else(|| {
+            format_err!(
+                "plugin {} has no init command with index {}",
+                self.plugin,
+                self.index
+            )
+        })?;
+        let mut json: Value = cmd.data().deserialize()?;
+        let mut target = &mut json;
+        for token in self.pointer.split('/').skip(1) {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            target = match target {
+                Value::Object(map) => map.entry(token).or_insert(Value::Null),
+                Value::Array(array) => usize::from_str(&token)
+                    .ok()
+                    .and_then(move |index| array.get_mut(index))
+                    .ok_or_else(|| format_err!("{} does not exist", self))?,
+                Value::Null => {
+                    *target = json!({});
+                    target
+                        .as_object_mut()
+                        .unwrap()
+                        .entry(token)
+                        .or_insert(Value::Null)
+                }
+                _ => bail!("{} is not inside an object or array", self),
+            };
+        }
+        *target = value.clone();
+        cmd.data_mut().set_serialize(&json)?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for InitField {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}{}", self.plugin, self.index, self.pointer)
+    }
+}
+
+/// A swept parameter.
+#[derive(Debug, Clone, PartialEq)]
+enum Axis {
+    /// The random seed.
+    Seed(Vec<u64>),
+
+    /// A field of the JSON data of an initialization command.
+    Init(InitField, Vec<Value>),
+}
+
+impl Axis {
+    fn len(&self) -> usize {
+        match self {
+            Axis::Seed(values) => values.len(),
+            Axis::Init(_, values) => values.len(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Axis::Seed(_) => "seed".to_string(),
+            Axis::Init(field, _) => field.to_string(),
+        }
+    }
+}
+
+/// Parses the swept parameters from the command line options.
+fn parse_axes(opts: &SweepStructOpt) -> Result<Vec<Axis>, Error> {
+    let mut axes = vec![];
+    if let Some(seeds) = &opts.seeds {
+        axes.push(Axis::Seed(
+            parse_values(seeds)?
+                .into_iter()
+                .map(|seed| {
+                    seed.as_u64()
+                        .ok_or_else(|| format_err!("invalid seed {}, seeds must be integers", seed))
+                })
+                .collect::<Result<_, Error>>()?,
+        ));
+    }
+    for param in opts.params.iter() {
+        let index = param.find('=').ok_or_else(|| {
+            format_err!("invalid parameter '{}', expected <param>=<values>", param)
+        })?;
+        axes.push(Axis::Init(
+            param[..index].parse()?,
+            parse_values(&param[index + 1..])?,
+        ));
+    }
+    Ok(axes)
+}
+
+/// A single run of the sweep.
+struct Run {
+    /// The index of each parameter value, in the order of the axes.
+    point: Vec<usize>,
+
+    /// The reproduction file for the run, which serves as its configuration.
+    repro: PathBuf,
+
+    /// The file that the log output of the run is written to.
+    log: PathBuf,
+}
+
+/// A host API call in the `--output json` document of a worker.
+#[derive(Deserialize)]
+struct WorkerHostCall {
+    call: String,
+    result: Option<ArbDataOutput>,
+}
+
+/// The parts of the `--output json` document of a worker that are used for
+/// the results table.
+#[derive(Deserialize)]
+struct WorkerOutput {
+    host_calls: Vec<WorkerHostCall>,
+    error: Option<String>,
+}
+
+/// The outcome of a run.
+struct RunResult {
+    /// The exit status of the worker process, or `None` if it was killed.
+    status: Option<i32>,
+
+    /// The values returned by the host API calls, as pairs of function name
+    /// and data.
+    returns: Vec<(String, ArbDataOutput)>,
+
+    /// The error reported by the worker, if any.
+    error: Option<String>,
+}
+
+/// Runs a worker process for a single run.
+fn execute(dqcsim: &Path, worker_args: &[String], run: &Run) -> Result<RunResult, Error> {
+    let output = Command::new(dqcsim)
+        .arg("--reproduce-exactly")
+        .arg(&run.repro)
+        .arg("--output")
+        .arg("json")
+        .arg("--no-repro-out")
+        .args(worker_args)
+        .stdin(Stdio::null())
+        .stderr(File::create(&run.log)?)
+        .output()?;
+    let status = output.status.code();
+
+    // A worker that fails before it starts the simulation, for instance
+    // because of an invalid worker argument, may not produce a document.
+    let output: WorkerOutput = match serde_json::from_slice(&output.stdout) {
+        Ok(output) => output,
+        Err(e) if status != Some(0) => WorkerOutput {
+            host_calls: vec![],
+            error: Some(format!("worker produced no output: {}", e)),
+        },
+        Err(e) => bail!(
+            "failed to parse the output of {}: {}",
+            run.repro.display(),
+            e
+        ),
+    };
+    Ok(RunResult {
+        status,
+        returns: output
+            .host_calls
+            .into_iter()
+            .filter_map(|call| Some((call.call, call.result?)))
+            .collect(),
+        error: output.error,
+    })
+}
+
+/// Quotes a CSV field if necessary.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Runs the `dqcsim sweep` subcommand. `args` should start with the name of
+/// the subcommand, i.e. `sweep`.
+pub fn run<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = SweepStructOpt::clap()
+        .bin_name("dqcsim sweep")
+        .settings(&[
+            AppSettings::DeriveDisplayOrder,
+            AppSettings::UnifiedHelpMessage,
+            AppSettings::ColoredHelp,
+            AppSettings::DisableVersion,
+            AppSettings::ArgRequiredElseHelp,
+        ])
+        .get_matches_from_safe(args)?;
+    let opts = SweepStructOpt::from_clap(&matches);
+    let axes = parse_axes(&opts)?;
+
+    // Build the base reproduction structure from the base configuration.
+    let mut base_args = vec![OsString::from("dqcsim")];
+    base_args.extend(opts.base.iter().cloned());
+    let mut base = CommandLineConfiguration::parse_from(base_args)?;
+    base.dqcsim
+        .reproduction_path_style
+        .get_or_insert(ReproductionPathStyle::Keep);
+    let mut base_repro = Reproduction::new_logger(&base.dqcsim)?;
+    base_repro.host_calls = base.host_calls;
+
+    // Write the reproduction files for all the points in the cartesian
+    // product of the parameter values. The last parameter varies fastest.
+    create_dir_all(&opts.dir)?;
+    let count: usize = axes.iter().map(Axis::len).product();
+    let width = (count.max(1) - 1).to_string().len();
+    let mut runs = vec![];
+    for index in 0..count {
+        let mut point = vec![0; axes.len()];
+        let mut remainder = index;
+        for (axis, value) in axes.iter().zip(point.iter_mut()).rev() {
+            *value = remainder % axis.len();
+            remainder /= axis.len();
+        }
+        let mut repro = base_repro.clone();
+        for (axis, &value) in axes.iter().zip(point.iter()) {
+            match axis {
+                Axis::Seed(seeds) => repro.seed = seeds[value],
+                Axis::Init(field, values) => field.apply(&mut repro.plugins, &values[value])?,
+            }
+        }
+        let name = format!("run-{:0width$}", index, width = width);
+        let run = Run {
+            point,
+            repro: opts.dir.join(format!("{}.repro", name)),
+            log: opts.dir.join(format!("{}.log", name)),
+        };
+        repro.to_file(&run.repro)?;
+        runs.push((repro.seed, run));
+    }
+
+    // Execute the runs using the requested number of worker threads, each
+    // of which runs one worker process at a time.
+    let dqcsim = match &opts.dqcsim {
+        Some(dqcsim) => dqcsim.clone(),
+        None => current_exe()?,
+    };
+    let runs = Arc::new(runs);
+    let worker_args = Arc::new(opts.worker_args.clone());
+    let (job_tx, job_rx) = crossbeam_channel::unbounded();
+    let (result_tx, result_rx) = crossbeam_channel::unbounded();
+    for index in 0..runs.len() {
+        job_tx.send(index).unwrap();
+    }
+    drop(job_tx);
+    let workers: Vec<_> = (0..opts.jobs.max(1))
+        .map(|_| {
+            let (runs, worker_args, dqcsim) = (runs.clone(), worker_args.clone(), dqcsim.clone());
+            let (job_rx, result_tx) = (job_rx.clone(), result_tx.clone());
+            thread::spawn(move || {
+                for index in job_rx.iter() {
+                    let result = execute(&dqcsim, &worker_args, &runs[index].1);
+                    result_tx.send((index, result)).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(result_tx);
+    let mut results: Vec<Option<RunResult>> = (0..runs.len()).map(|_| None).collect();
+    for (index, result) in result_rx.iter() {
+        results[index] = Some(result?);
+    }
+    for worker in workers {
+        worker.join().expect("sweep worker thread panicked");
+    }
+
+    // Produce the results table.
+    let seed_column = !axes.iter().any(|axis| matches!(axis, Axis::Seed(_)));
+    let mut table = String::new();
+    let mut rows = vec![];
+    if opts.format == "csv" {
+        let mut header = vec!["run".to_string()];
+        if seed_column {
+            header.push("seed".to_string());
+        }
+        header.extend(axes.iter().map(Axis::name));
+        header.extend(
+            ["status", "error", "returns", "repro", "log"]
+                .iter()
+                .map(|x| x.to_string()),
+        );
+        writeln!(
+            table,
+            "{}",
+            header
+                .iter()
+                .map(|x| csv_field(x))
+                .collect::<Vec<_>>()
+                .join(",")
+        )?;
+    }
+    let mut failures = 0;
+    for (index, ((seed, run), result)) in runs.iter().zip(results).enumerate() {
+        let result = result.unwrap();
+        if result.status != Some(0) {
+            failures += 1;
+        }
+        let params: Vec<(String, Value)> = axes
+            .iter()
+            .zip(run.point.iter())
+            .map(|(axis, &value)| {
+                let value = match axis {
+                    Axis::Seed(seeds) => Value::from(seeds[value]),
+                    Axis::Init(_, values) => values[value].clone(),
+                };
+                (axis.name(), value)
+            })
+            .collect();
+        if opts.format == "csv" {
+            let mut row = vec![index.to_string()];
+            if seed_column {
+                row.push(seed.to_string());
+            }
+            row.extend(params.iter().map(|(_, value)| match value {
+                Value::String(s) => s.clone(),
+                value => value.to_string(),
+            }));
+            row.push(result.status.map(|x| x.to_string()).unwrap_or_default());
+            row.push(result.error.clone().unwrap_or_default());
+            row.push(
+                result
+                    .returns
+                    .iter()
+                    .map(|(function, data)| {
+                        Ok(format!("{}: {}", function, serde_json::to_string(data)?))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?
+                    .join("; "),
+            );
+            row.push(run.repro.to_string_lossy().to_string());
+            row.push(run.log.to_string_lossy().to_string());
+            writeln!(
+                table,
+                "{}",
+                row.iter()
+                    .map(|x| csv_field(x))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )?;
+        } else {
+            rows.push(json!({
+                "run": index,
+                "seed": seed,
+                "params": params.into_iter().collect::<serde_json::Map<_, _>>(),
+                "status": result.status,
+                "error": result.error,
+                "returns": result
+                    .returns
+                    .iter()
+                    .map(|(function, data)| {
+                        json!({
+                            "function": function,
+                            "json": data.json,
+                            "args": data.args,
+                            "shared": data.shared,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+                "repro": run.repro,
+                "log": run.log,
+            }));
+        }
+    }
+    if opts.format == "json" {
+        table = serde_json::to_string_pretty(&rows)? + "\n";
+    }
+    match &opts.output {
+        Some(output) => std::fs::write(output, table)?,
+        None => print!("{}", table),
+    }
+
+    if failures > 0 {
+        bail!(
+            "{} of {} runs failed, refer to their logs for details",
+            failures,
+            runs.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::types::{ArbCmd, ArbData};
+
+    #[test]
+    fn values() {
+        assert_eq!(
+            parse_values("1..3, 7").unwrap(),
+            vec![json!(1), json!(2), json!(3), json!(7)]
+        );
+        assert_eq!(
+            parse_values("0.5..1:0.25").unwrap(),
+            vec![json!(0.5), json!(0.75), json!(1.0)]
+        );
+        assert_eq!(
+            parse_values("true,hello,\"x\"").unwrap(),
+            vec![json!(true), json!("hello"), json!("x")]
+        );
+        assert_eq!(
+            parse_values(r"[1\,2],a\\b\,c").unwrap(),
+            vec![json!([1, 2]), json!("a\\b,c")]
+        );
+        assert_eq!(
+            parse_values("1..x").unwrap_err().to_string(),
+            "invalid range '1..x'"
+        );
+        assert_eq!(
+            parse_values("0..1:0").unwrap_err().to_string(),
+            "invalid range '0..1:0', step must be positive"
+        );
+    }
+
+    #[test]
+    fn init_field() {
+        let mut plugins = vec![PluginReproduction {
+            name: "back".to_string(),
+            executable: "dqcsbeqx".into(),
+            script: None,
+            args: vec![],
+            functional: Default::default(),
+        }];
+        plugins[0].functional.init = vec![ArbCmd::new("a", "b", ArbData::default())];
+
+        let field: InitField = "back/0/error/rate".parse().unwrap();
+        assert_eq!(field.to_string(), "back/0/error/rate");
+        field.apply(&mut plugins, &json!(0.1)).unwrap();
+        assert_eq!(
+            plugins[0].functional.init[0].data().get_json().unwrap(),
+            r#"{"error":{"rate":0.1}}"#
+        );
+
+        assert_eq!(
+            "back".parse::<InitField>().unwrap_err().to_string(),
+            "invalid parameter 'back', expected <plugin>/<index><pointer>"
+        );
+        assert_eq!(
+            "front/0/x"
+                .parse::<InitField>()
+                .unwrap()
+                .apply(&mut plugins, &json!(1))
+                .unwrap_err()
+                .to_string(),
+            "there is no plugin named front"
+        );
+        assert_eq!(
+            "back/1"
+                .parse::<InitField>()
+                .unwrap()
+                .apply(&mut plugins, &json!(1))
+                .unwrap_err()
+                .to_string(),
+            "plugin back has no init command with index 1"
+        );
+        assert_eq!(
+            "back/0/error/rate/x"
+                .parse::<InitField>()
+                .unwrap()
+                .apply(&mut plugins, &json!(1))
+                .unwrap_err()
+                .to_string(),
+            "back/0/error/rate/x is not inside an object or array"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Implementation of the `dqcsim trace` subcommand, used to inspect the
+//! gatestream trace files written by `--trace-out`.
+
+use dqcsim::common::{
+    trace::{TraceDirection, TraceFilter, TraceReader, TraceSummary},
+    util::friendly_enum_parse,
+};
+use failure::Error;
+use std::{ffi::OsString, path::PathBuf};
+use structopt::{clap::AppSettings, StructOpt};
+
+/// Options for selecting trace records.
+#[derive(Debug, StructOpt, PartialEq)]
+pub struct TraceFilterOpts {
+    /// Only show messages crossing the boundary downstream of the plugin with
+    /// the given instance name.
+    #[structopt(short = "p", long = "plugin", value_name = "name")]
+    pub plugin: Option<String>,
+
+    /// Only show messages sent in the given direction, down or up.
+    #[structopt(
+        short = "d",
+        long = "direction",
+        value_name = "direction",
+        case_insensitive = true,
+        parse(try_from_str = friendly_enum_parse)
+    )]
+    pub direction: Option<TraceDirection>,
+
+    /// Only show messages of the given kind. Downstream messages are
+    /// allocate, free, gate, reset, advance, arb, and host_arb; upstream
+    /// messages are completed, failure, measured, advanced, arb_success, and
+    /// arb_failure.
+    /// Can be specified more than once.
+    #[structopt(short = "k", long = "kind", value_name = "kind", number_of_values = 1)]
+    pub kinds: Vec<String>,
+
+    /// Only show messages recorded at or after the given cycle.
+    #[structopt(long = "from-cycle", value_name = "cycle")]
+    pub from_cycle: Option<i64>,
+
+    /// Only show messages recorded at or before the given cycle.
+    #[structopt(long = "to-cycle", value_name = "cycle")]
+    pub to_cycle: Option<i64>,
+
+    /// The trace file to read.
+    #[structopt(value_name = "filename", parse(from_os_str))]
+    pub file: PathBuf,
+}
+
+impl From<&TraceFilterOpts> for TraceFilter {
+    fn from(opts: &TraceFilterOpts) -> TraceFilter {
+        TraceFilter {
+            plugin: opts.plugin.clone(),
+            direction: opts.direction,
+            kinds: opts.kinds.clone(),
+            from_cycle: opts.from_cycle,
+            to_cycle: opts.to_cycle,
+        }
+    }
+}
+
+/// The StructOpt structure for the `dqcsim trace` subcommand.
+#[derive(Debug, StructOpt, PartialEq)]
+#[structopt(
+    name = "dqcsim trace",
+    about = "Inspects gatestream trace files written by dqcsim --trace-out"
+)]
+pub enum TraceStructOpt {
+    /// Prints the selected messages, one per line.
+    #[structopt(name = "dump")]
+    Dump {
+        /// Prints each message as a single-line JSON object instead.
+        #[structopt(long = "json")]
+        json: bool,
+
+        #[structopt(flatten)]
+        filter: TraceFilterOpts,
+    },
+
+    /// Prints message counts and sequence number and cycle statistics for
+    /// each plugin boundary, taking only the selected messages into account.
+    #[structopt(name = "summary")]
+    Summary {
+        #[structopt(flatten)]
+        filter: TraceFilterOpts,
+    },
+}
+
+/// Runs the `dqcsim trace` subcommand. `args` should start with the name of
+/// the subcommand, i.e. `trace`.
+pub fn run<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = TraceStructOpt::clap()
+        .bin_name("dqcsim trace")
+        .settings(&[
+            AppSettings::DeriveDisplayOrder,
+            AppSettings::UnifiedHelpMessage,
+            AppSettings::ColoredHelp,
+            AppSettings::DisableVersion,
+            AppSettings::SubcommandRequiredElseHelp,
+            AppSettings::VersionlessSubcommands,
+        ])
+        .get_matches_from_safe(args)?;
+    match TraceStructOpt::from_clap(&matches) {
+        TraceStructOpt::Dump { json, filter } => {
+            let reader = TraceReader::open(&filter.file)?;
+            let filter = TraceFilter::from(&filter);
+            for record in reader {
+                let record = record?;
+                if !filter.matches(&record) {
+                    continue;
+                }
+                if json {
+                    println!("{}", record.to_json());
+                } else {
+                    println!("{}", record);
+                }
+            }
+        }
+        TraceStructOpt::Summary { filter } => {
+            let reader = TraceReader::open(&filter.file)?;
+            let filter = TraceFilter::from(&filter);
+            let mut summary = TraceSummary::new(reader.header().clone());
+            for record in reader {
+                let record = record?;
+                if filter.matches(&record) {
+                    summary.add(&record);
+                }
+            }
+            print!("{}", summary);
+        }
+    }
+    Ok(())
+}
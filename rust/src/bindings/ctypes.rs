@@ -1040,6 +1040,25 @@ pub enum dqcs_gate_type_t {
     ///  - exactly one measurement result is reported upstream for exactly the
     ///    qubits in the measures set.
     DQCS_GATE_TYPE_CUSTOM,
+
+    /// Pauli measurement gates have one or more target qubits and a Hermitian
+    /// unitary matrix sized for the number of target qubits, representing the
+    /// measured observable. This is usually a product of Pauli matrices, such
+    /// as ZZ or XXXX. The first target qubit is also the only measured qubit.
+    ///
+    /// The semantics are:
+    ///
+    ///  - the qubits are projected onto the +1 or -1 eigenspace of the
+    ///    observable, with the probabilities dictated by the current state;
+    ///  - a single measurement result is propagated upstream for the first
+    ///    target qubit, being zero for the +1 eigenspace and one for the -1
+    ///    eigenspace.
+    ///
+    /// The data field may add pragma-like hints to the gate, for instance to
+    /// represent the line number in the source file that generated the gate,
+    /// error modelling information, and so on. This data may be silently
+    /// ignored.
+    DQCS_GATE_TYPE_PAULI_MEASUREMENT,
 }
 
 impl From<&GateType> for dqcs_gate_type_t {
@@ -1049,6 +1068,7 @@ impl From<&GateType> for dqcs_gate_type_t {
             GateType::Measurement => dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT,
             GateType::Prep => dqcs_gate_type_t::DQCS_GATE_TYPE_PREP,
             GateType::Custom(_) => dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM,
+            GateType::PauliMeasurement => dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT,
         }
     }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use std::ptr::null_mut;
+use std::ptr::{null, null_mut};
 
 /// Creates a new `ArbData` object.
 ///
@@ -321,6 +321,105 @@ pub extern "C" fn dqcs_arb_clear(arb: dqcs_handle_t) -> dqcs_return_t {
     })
 }
 
+/// Attaches a shared memory argument to the back of the shared memory
+/// argument list.
+///
+/// The data is copied into a newly allocated shared memory segment once.
+/// After that, sending the `ArbData` to another process only transfers a
+/// handle to the segment, making this suitable for large payloads.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_push(
+    arb: dqcs_handle_t,
+    obj: *const c_void,
+    obj_size: size_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(arb as &mut ArbData);
+        arb.get_shared_mut()
+            .push(SharedBytes::from_bytes(receive_raw(obj, obj_size)?));
+        Ok(())
+    })
+}
+
+/// Returns the shared memory argument at the specified index.
+///
+/// If the actual size of the object differs from the specified object size,
+/// this function will copy the minimum of the actual and specified sizes
+/// number of bytes, and return what the actual size was.
+///
+/// If the specified object size is zero, `obj` is allowed to be `NULL`. You
+/// can use this to determine the size of the argument prior to actually
+/// reading it, so you can allocate the right buffer size first.
+///
+/// This function returns -1 on failure.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_get(
+    arb: dqcs_handle_t,
+    index: ssize_t,
+    obj: *mut c_void,
+    obj_size: size_t,
+) -> ssize_t {
+    api_return(-1, || {
+        resolve!(arb as &ArbData);
+        return_raw(
+            &arb.get_shared()[receive_index(arb.get_shared().len(), index, false)?],
+            obj,
+            obj_size,
+        )
+    })
+}
+
+/// Returns the size in bytes of the shared memory argument at the specified
+/// index.
+///
+/// Returns -1 when the function fails.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_size(arb: dqcs_handle_t, index: ssize_t) -> ssize_t {
+    dqcs_arb_shm_get(arb, index, null_mut(), 0)
+}
+
+/// Returns a pointer to the contents of the shared memory argument at the
+/// specified index, without copying.
+///
+/// The returned pointer is only valid as long as the `ArbData` object exists
+/// and its shared memory arguments are not modified. The memory must not be
+/// written to or freed. Use `dqcs_arb_shm_size()` to get the size of the
+/// buffer.
+///
+/// Returns `NULL` when the function fails. Note that `NULL` may also be
+/// returned for empty arguments.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_ptr(arb: dqcs_handle_t, index: ssize_t) -> *const c_void {
+    api_return(null(), || {
+        resolve!(arb as &ArbData);
+        let shared = &arb.get_shared()[receive_index(arb.get_shared().len(), index, false)?];
+        Ok(if shared.is_empty() {
+            null()
+        } else {
+            shared.as_ptr() as *const c_void
+        })
+    })
+}
+
+/// Returns the number of shared memory arguments, or -1 to indicate failure.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_len(arb: dqcs_handle_t) -> ssize_t {
+    api_return(-1, || {
+        resolve!(arb as &ArbData);
+        Ok(arb.get_shared().len() as ssize_t)
+    })
+}
+
+/// Clears the shared memory argument list.
+#[no_mangle]
+pub extern "C" fn dqcs_arb_shm_clear(arb: dqcs_handle_t) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(arb as &mut ArbData);
+        arb.clear_shared();
+        Ok(())
+    })
+}
+
 /// Copies the data from one object to another.
 #[no_mangle]
 pub extern "C" fn dqcs_arb_assign(dest: dqcs_handle_t, src: dqcs_handle_t) -> dqcs_return_t {
@@ -282,6 +282,46 @@ pub extern "C" fn dqcs_gate_new_measurement(
     })
 }
 
+/// Constructs a new Pauli measurement gate.
+///
+/// `targets` must be a handle to a non-empty qubit set. `matrix` must be a
+/// handle to a Hermitian unitary matrix sized for the number of target qubits,
+/// representing the measured observable, for instance one constructed using
+/// `dqcs_mat_pauli()`. The qubits are projected onto the +1 or -1 eigenspace
+/// of the observable, and a single measurement result (zero or one
+/// respectively) is returned for the first target qubit.
+///
+/// This function returns the handle to the gate, or 0 to indicate failure.
+/// The `targets` qubit set and `matrix` handle are consumed/deleted by this
+/// function if and only if it succeeds.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_new_pauli_measurement(
+    targets: dqcs_handle_t,
+    matrix: dqcs_handle_t,
+) -> dqcs_handle_t {
+    api_return(0, || {
+        // Interpret targets set.
+        resolve!(targets as pending QubitReferenceSet);
+        let target_vec: Vec<QubitRef> = {
+            let x: &QubitReferenceSet = targets.as_ref().unwrap();
+            x.iter().cloned().collect()
+        };
+
+        // Interpret matrix.
+        resolve!(matrix as pending Matrix);
+        let matrix_ref: &Matrix = matrix.as_ref().unwrap();
+
+        // Construct the gate.
+        let gate = insert(Gate::new_pauli_measurement(target_vec, matrix_ref.clone())?);
+
+        // Everything went OK. Now make sure that the target set and matrix
+        // handles are deleted.
+        delete!(resolved targets);
+        delete!(resolved matrix);
+        Ok(gate)
+    })
+}
+
 /// Constructs a new prep gate.
 ///
 /// `targets` must be a handle to a qubit set. `matrix` is an optional matrix
@@ -577,6 +617,74 @@ pub extern "C" fn dqcs_gate_matrix(gate: dqcs_handle_t) -> dqcs_handle_t {
     })
 }
 
+/// Makes the given gate conditional on the measurement register of a qubit.
+///
+/// The gate is only executed if the measurement register of `qubit` equals
+/// `value` at the time the gate is executed. The condition is evaluated by the
+/// backend, i.e. the plugin that produces the measurement results, so the
+/// frontend does not need to wait for the measurement result to become
+/// available using `dqcs_plugin_get_measurement()`. Any previous condition is
+/// replaced.
+///
+/// Gates that measure qubits cannot be made conditional.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_set_condition(
+    gate: dqcs_handle_t,
+    qubit: dqcs_qubit_t,
+    value: dqcs_measurement_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(gate as &mut Gate);
+        let qubit = QubitRef::from_foreign(qubit)
+            .ok_or_else(oe_inv_arg("0 is not a valid qubit reference"))?;
+        let value: Option<QubitMeasurementValue> = value.into();
+        let value = value.ok_or_else(oe_inv_arg("invalid measurement value specified"))?;
+        *gate = gate.clone().with_condition(qubit, value)?;
+        Ok(())
+    })
+}
+
+/// Returns whether the specified gate is conditional.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_has_condition(gate: dqcs_handle_t) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        resolve!(gate as &Gate);
+        Ok(gate.get_condition().is_some())
+    })
+}
+
+/// Returns the qubit whose measurement register controls the execution of the
+/// given conditional gate.
+///
+/// This function fails if the gate is not conditional, returning 0.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_condition_qubit(gate: dqcs_handle_t) -> dqcs_qubit_t {
+    api_return(0, || {
+        resolve!(gate as &Gate);
+        gate.get_condition()
+            .ok_or_else(oe_inv_arg("gate is not conditional"))?
+            .qubit
+            .to_foreign()
+    })
+}
+
+/// Returns the measurement register value for which the given conditional
+/// gate is executed.
+///
+/// This function fails if the gate is not conditional, returning
+/// `DQCS_MEAS_INVALID`.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_condition_value(gate: dqcs_handle_t) -> dqcs_measurement_t {
+    api_return(dqcs_measurement_t::DQCS_MEAS_INVALID, || {
+        resolve!(gate as &Gate);
+        Ok(gate
+            .get_condition()
+            .ok_or_else(oe_inv_arg("gate is not conditional"))?
+            .value
+            .into())
+    })
+}
+
 /// Utility function that detects control qubits in the `targets` list of the
 /// gate by means of the gate matrix, and reduces them into `controls` qubits.
 ///>
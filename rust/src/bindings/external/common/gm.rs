@@ -131,6 +131,15 @@ pub extern "C" fn dqcs_gm_new(
 ///> of binary string arguments to the `ArbData` upon detection, and pop these
 ///> when constructing. The specs for this can be found in the docs for
 ///> `dqcs_predefined_gate_t`.
+///>
+///> The parameterized detectors furthermore recognize the exact parameters
+///> recorded in the JSON object of the gate data by mappings added with
+///> `dqcs_gm_add_predef_unitary_exact()`, as
+///> `{"dqcsim.gate": {"type": "RX", "positional": [theta]}}`, or
+///> `{"dqcsim.gate": {"type": "PhaseK", "integers": [k]}}`. When detecting a
+///> gate that carries such a description for the same gate type, these
+///> parameters are returned instead of the ones recovered from the matrix,
+///> and the description is removed from the `ArbData`.
 #[no_mangle]
 pub extern "C" fn dqcs_gm_add_predef_unitary(
     gm: dqcs_handle_t,
@@ -155,6 +164,45 @@ pub extern "C" fn dqcs_gm_add_predef_unitary(
     })
 }
 
+/// Adds a unitary gate mapping for the given DQCsim-defined gate to the
+/// given gate map, recording the exact parameters of the gates it
+/// constructs.
+///>
+///> This behaves like `dqcs_gm_add_predef_unitary()`, except that the
+///> parameterized converters also record the exact parameters of the gates
+///> they construct in the JSON object of the gate data, as
+///> `{"dqcsim.gate": {"type": "RX", "positional": [theta]}}`, or
+///> `{"dqcsim.gate": {"type": "PhaseK", "integers": [k]}}`. Any gate map
+///> that detects the gate with a predefined mapping then returns these
+///> parameters instead of the ones recovered from the matrix. This allows
+///> parameters to be passed along the gatestream without floating-point
+///> loss. Nothing is recorded if the JSON data is not an object. Note that
+///> downstream plugins that don't use predefined gate mappings see the
+///> description as part of the gate data.
+#[no_mangle]
+pub extern "C" fn dqcs_gm_add_predef_unitary_exact(
+    gm: dqcs_handle_t,
+    key_free: Option<extern "C" fn(user_data: *mut c_void)>,
+    key_data: *mut c_void,
+    gate: dqcs_predefined_gate_t,
+    num_controls: isize,
+    epsilon: c_double,
+    ignore_gphase: bool,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        let key = UserKeyData::new(key_free, key_data);
+        resolve!(gm as &mut GateMap);
+        let key = gm.make_key(key);
+        let gate = UnitaryGateType::try_from(gate)?;
+        let num_controls = expected_qubit_count(num_controls);
+        gm.map.push(
+            key,
+            gate.into_parametric_gate_converter(num_controls, epsilon, ignore_gphase),
+        );
+        Ok(())
+    })
+}
+
 /// Adds a unitary gate mapping for the given gate matrix to the given gate
 /// map.
 ///>
@@ -451,6 +499,51 @@ pub extern "C" fn dqcs_gm_add_measure(
     })
 }
 
+/// Adds a Pauli measurement gate mapping to the given gate map.
+///>
+///> `gm` must be a handle to a gate map object (`dqcs_gm_new()`).
+///> `key_free` is an optional callback function used to free `key_data` when
+///> the gate map is destroyed, or when this function fails.
+///> `key_data` is the user-specified value used to identify this mapping.
+///> `observable` must be a handle to a Hermitian unitary matrix specifying
+///> the measured observable, for instance one constructed using
+///> `dqcs_mat_pauli()`. The number of qubits of the gate is implied by its
+///> size. The matrix is deleted by the call iff the function succeeds.
+///> `epsilon` specifies the maximum RMS deviation between the specified
+///> observable and the incoming observable.
+///>
+///> The parameterization `ArbData` object returned by detection and consumed
+///> by construction is mapped one-to-one to the user data of the gate in the
+///> DQCsim-protocol.
+#[no_mangle]
+pub extern "C" fn dqcs_gm_add_pauli_measure(
+    gm: dqcs_handle_t,
+    key_free: Option<extern "C" fn(user_data: *mut c_void)>,
+    key_data: *mut c_void,
+    observable: dqcs_handle_t,
+    epsilon: f64,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        let key = UserKeyData::new(key_free, key_data);
+        resolve!(gm as &mut GateMap);
+        let key = gm.make_key(key);
+        resolve!(observable as pending Matrix);
+        let observable_ref: &Matrix = observable.as_ref().unwrap();
+        if observable_ref.num_qubits().unwrap_or(0) == 0 {
+            inv_arg("observable matrix must be sized for at least one qubit")?;
+        }
+        gm.map.push(
+            key,
+            Box::new(MeasurementGateConverter::new_pauli(
+                observable_ref.clone(),
+                epsilon,
+            )),
+        );
+        delete!(resolved observable);
+        Ok(())
+    })
+}
+
 /// Adds a prep gate mapping to the given gate map.
 ///>
 ///> `gm` must be a handle to a gate map object (`dqcs_gm_new()`).
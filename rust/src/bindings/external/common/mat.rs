@@ -96,6 +96,20 @@ pub extern "C" fn dqcs_mat_basis(basis: dqcs_basis_t) -> dqcs_handle_t {
     })
 }
 
+/// Constructs a matrix representing a product of Pauli matrices.
+///>
+///> `paulis` must be a string consisting of the characters `I`, `X`, `Y`,
+///> and `Z` (in either case), one for each qubit, such as `"ZZ"` or `"XXXX"`.
+///> The first character corresponds to the first qubit. This can be used for
+///> constructing Pauli measurement gates. Returns a new handle to the
+///> constructed matrix or returns 0 if an error occurs.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_pauli(paulis: *const c_char) -> dqcs_handle_t {
+    api_return(0, || {
+        Ok(insert(Matrix::new_pauli_product(receive_str(paulis)?)?))
+    })
+}
+
 /// Returns the number of complex entries in the given matrix.
 ///>
 ///> This function returns -1 when an error occurs.
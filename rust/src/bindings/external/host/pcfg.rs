@@ -1,5 +1,5 @@
 use super::*;
-use crate::common::log::tee_file::TeeFileConfiguration;
+use crate::common::log::tee_file::{TeeFileConfiguration, TeeFileRotation};
 use std::ptr::null;
 
 /// Creates a new plugin process configuration object using sugared syntax.
@@ -236,6 +236,43 @@ pub extern "C" fn dqcs_pcfg_tee(
     })
 }
 
+/// Configures a plugin process to also output its log messages to a file, with
+/// optional appending and size-based rotation.
+///
+/// `verbosity` configures the verbosity level for the file only. If `append`
+/// is set, messages are appended to the file if it already exists instead of
+/// truncating it. If `max_size` is nonzero, the file is rotated when it would
+/// exceed this many bytes: it is renamed to `<filename>.1`, previously rotated
+/// files are shifted up to `<filename>.<keep>`, and older files are deleted.
+/// If `compress` is set, rotated files are compressed with gzip and get a
+/// `.gz` extension.
+#[no_mangle]
+pub extern "C" fn dqcs_pcfg_tee_rotate(
+    pcfg: dqcs_handle_t,
+    verbosity: dqcs_loglevel_t,
+    filename: *const c_char,
+    append: bool,
+    max_size: size_t,
+    keep: size_t,
+    compress: bool,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(pcfg as &mut PluginProcessConfiguration);
+        let mut tee =
+            TeeFileConfiguration::new(verbosity.into_loglevel_filter()?, receive_str(filename)?)
+                .with_append(append);
+        if max_size > 0 {
+            tee = tee.with_rotation(
+                TeeFileRotation::new(max_size as u64)
+                    .with_keep(keep)
+                    .with_compression(compress),
+            );
+        }
+        pcfg.nonfunctional.tee_files.push(tee);
+        Ok(())
+    })
+}
+
 /// Configures the capture mode for the stdout stream of the specified plugin
 /// process.
 #[no_mangle]
@@ -1,7 +1,10 @@
 use super::*;
 use crate::common::{
     log,
-    log::{callback::LogCallback, tee_file::TeeFileConfiguration},
+    log::{
+        callback::LogCallback,
+        tee_file::{TeeFileConfiguration, TeeFileRotation},
+    },
 };
 use std::time::*;
 
@@ -116,6 +119,80 @@ pub extern "C" fn dqcs_scfg_tee(
     })
 }
 
+/// Configures DQCsim to also output its log messages to a file, with
+/// optional appending and size-based rotation.
+///
+/// `verbosity` configures the verbosity level for the file only. If `append`
+/// is set, messages are appended to the file if it already exists instead of
+/// truncating it. If `max_size` is nonzero, the file is rotated when it would
+/// exceed this many bytes: it is renamed to `<filename>.1`, previously rotated
+/// files are shifted up to `<filename>.<keep>`, and older files are deleted.
+/// If `compress` is set, rotated files are compressed with gzip and get a
+/// `.gz` extension.
+#[no_mangle]
+pub extern "C" fn dqcs_scfg_tee_rotate(
+    scfg: dqcs_handle_t,
+    verbosity: dqcs_loglevel_t,
+    filename: *const c_char,
+    append: bool,
+    max_size: size_t,
+    keep: size_t,
+    compress: bool,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(scfg as &mut SimulatorConfiguration);
+        let mut tee =
+            TeeFileConfiguration::new(verbosity.into_loglevel_filter()?, receive_str(filename)?)
+                .with_append(append);
+        if max_size > 0 {
+            tee = tee.with_rotation(
+                TeeFileRotation::new(max_size as u64)
+                    .with_keep(keep)
+                    .with_compression(compress),
+            );
+        }
+        scfg.tee_files.push(tee);
+        Ok(())
+    })
+}
+
+/// Configures DQCsim to record all gatestream messages crossing the plugin
+/// boundaries to a trace file.
+///
+/// If `filename` is null, gatestream tracing is disabled again. Trace files
+/// can be inspected using `dqcsim trace` or the Rust API in
+/// `dqcsim::common::trace`.
+#[no_mangle]
+pub extern "C" fn dqcs_scfg_gatestream_trace(
+    scfg: dqcs_handle_t,
+    filename: *const c_char,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(scfg as &mut SimulatorConfiguration);
+        scfg.gatestream_trace = if filename.is_null() {
+            None
+        } else {
+            Some(receive_str(filename)?.into())
+        };
+        Ok(())
+    })
+}
+
+/// Configures DQCsim to log a summary of the performance profile of each
+/// plugin at the end of the simulation.
+///
+/// The profile of each plugin lists the time it spent in each of its
+/// callbacks, waiting for downstream, and serializing IPC messages. The
+/// summary is logged at info level.
+#[no_mangle]
+pub extern "C" fn dqcs_scfg_profile(scfg: dqcs_handle_t, profile: bool) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(scfg as &mut SimulatorConfiguration);
+        scfg.profile = profile;
+        Ok(())
+    })
+}
+
 /// Configures DQCsim to also output its log messages to callback function.
 ///
 /// `verbosity` specifies the minimum importance of a message required for the
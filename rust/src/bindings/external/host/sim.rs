@@ -276,6 +276,92 @@ pub extern "C" fn dqcs_sim_get_version_idx(sim: dqcs_handle_t, index: ssize_t) -
     })
 }
 
+/// Queries whether a plugin, referenced by instance name, supports the given
+/// `ArbCmd` operation.
+///
+/// Plugins that did not register any supported operations are assumed to
+/// accept any `ArbCmd`, so this always returns true for them.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_supports_arb(
+    sim: dqcs_handle_t,
+    name: *const c_char,
+    iface: *const c_char,
+    oper: *const c_char,
+) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        resolve!(sim as &Simulator);
+        let cmd = ArbCmd::try_from(receive_str(iface)?, receive_str(oper)?, ArbData::default())?;
+        Ok(sim.supported_interfaces(receive_str(name)?)?.accepts(&cmd))
+    })
+}
+
+/// Queries whether a plugin, referenced by index, supports the given `ArbCmd`
+/// operation.
+///
+/// Plugins that did not register any supported operations are assumed to
+/// accept any `ArbCmd`, so this always returns true for them.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_supports_arb_idx(
+    sim: dqcs_handle_t,
+    index: ssize_t,
+    iface: *const c_char,
+    oper: *const c_char,
+) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        resolve!(sim as &Simulator);
+        let cmd = ArbCmd::try_from(receive_str(iface)?, receive_str(oper)?, ArbData::default())?;
+        Ok(sim.simulation.get_interfaces_idx(index)?.accepts(&cmd))
+    })
+}
+
+/// Queries the performance profile of a plugin, referenced by instance name.
+///
+/// The profile is returned as a JSON object with the following structure,
+/// with all times in seconds:
+///
+/// ```text
+/// {
+///     "callbacks": { "<name>": { "calls": <count>, "total": <time> }, ... },
+///     "downstream_wait": <time>,
+///     "ipc": <time>
+/// }
+/// ```
+///
+/// `callbacks` contains an entry for each user callback that was called at
+/// least once. The callback times exclude the time spent waiting for
+/// downstream and serializing IPC messages. This call yields to the
+/// simulator first, so the profile includes all pending asynchronous calls.
+///
+/// On success, this **returns a newly allocated string containing the
+/// JSON object. Free it with `free()` when you're done with it to avoid
+/// memory leaks.** On failure, this returns `NULL`.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_get_profile(sim: dqcs_handle_t, name: *const c_char) -> *mut c_char {
+    api_return_string(|| {
+        resolve!(sim as &mut Simulator);
+        Ok(sim
+            .simulation
+            .get_profile(receive_str(name)?)?
+            .to_json()
+            .to_string())
+    })
+}
+
+/// Queries the performance profile of a plugin, referenced by index.
+///
+/// See `dqcs_sim_get_profile()` for the format of the returned JSON object.
+///
+/// On success, this **returns a newly allocated string containing the
+/// JSON object. Free it with `free()` when you're done with it to avoid
+/// memory leaks.** On failure, this returns `NULL`.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_get_profile_idx(sim: dqcs_handle_t, index: ssize_t) -> *mut c_char {
+    api_return_string(|| {
+        resolve!(sim as &mut Simulator);
+        Ok(sim.simulation.get_profile_idx(index)?.to_json().to_string())
+    })
+}
+
 /// Writes a reproduction file for the simulation so far.
 #[no_mangle]
 pub extern "C" fn dqcs_sim_write_reproduction_file(
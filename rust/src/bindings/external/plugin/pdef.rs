@@ -87,6 +87,45 @@ pub extern "C" fn dqcs_pdef_version(pdef: dqcs_handle_t) -> *mut c_char {
     })
 }
 
+/// Registers an `ArbCmd` operation supported by the plugin.
+///
+/// The registered interfaces and operations are advertised to the host when
+/// the plugin is initialized. As long as no operations are registered, the
+/// host assumes that the plugin silently ignores unsupported `ArbCmd`s, as
+/// has always been the convention. Once at least one operation is registered,
+/// initialization commands and `ArbCmd`s from the host or from upstream
+/// plugins that the plugin does not support are rejected, both before they
+/// are sent and by the plugin itself. Note that operators also accept the
+/// operations supported by the plugins downstream of them, since they may
+/// forward those.
+///
+/// `schema` optionally specifies a JSON schema for the JSON/CBOR payload of
+/// the operation, in its JSON string representation. It may be `NULL` or
+/// empty to register the operation without a schema. A subset of JSON Schema
+/// is supported: the `type`, `enum`, `properties`, `required`,
+/// `additionalProperties`, `items`, `minimum`, `maximum`, `minLength`,
+/// `maxLength`, `minItems`, and `maxItems` keywords. Payloads are validated
+/// by both the host and the plugin; commands that do not match the schema
+/// fail with an error naming the offending path within the payload.
+#[no_mangle]
+pub extern "C" fn dqcs_pdef_add_arb_op(
+    pdef: dqcs_handle_t,
+    iface: *const c_char,
+    oper: *const c_char,
+    schema: *const c_char,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(pdef as &mut PluginDefinition);
+        pdef.register_arb_operation(
+            receive_str(iface)?,
+            receive_str(oper)?,
+            receive_optional_str(schema)?
+                .filter(|x| !x.is_empty())
+                .map(str::to_string),
+        )
+    })
+}
+
 /// Sets the user logic initialization callback.
 ///
 /// This is always called before any of the other callbacks are run. The
@@ -323,6 +362,54 @@ pub extern "C" fn dqcs_pdef_set_free_cb(
     })
 }
 
+/// Sets the qubit reset callback for operators and backends.
+///
+/// The default for operators is to pass through to `dqcs_plugin_reset()`.
+/// The default for backends is to call the gate execution callback with a
+/// prep gate in the Z basis for the qubits. This callback is never called for
+/// frontend plugins.
+///
+/// Besides the common arguments, the callback receives a handle to a qubit
+/// set containing the qubits that are to be reset to |0>. This is a borrowed
+/// handle; the caller will delete it. The measurement registers of the qubits
+/// are cleared automatically.
+///
+/// The callback can return an error by setting an error message using
+/// `dqcs_error_set()` and returning `DQCS_FAILURE`. Otherwise, it should
+/// return `DQCS_SUCCESS`.
+#[no_mangle]
+pub extern "C" fn dqcs_pdef_set_reset_cb(
+    pdef: dqcs_handle_t,
+    callback: Option<
+        extern "C" fn(
+            user_data: *mut c_void,
+            state: dqcs_plugin_state_t,
+            qubits: dqcs_handle_t,
+        ) -> dqcs_return_t,
+    >,
+    user_free: Option<extern "C" fn(user_data: *mut c_void)>,
+    user_data: *mut c_void,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        let data = UserData::new(user_free, user_data);
+        let callback = callback.ok_or_else(oe_inv_arg("callback cannot be null"))?;
+        resolve!(pdef as &mut PluginDefinition);
+        if pdef.get_type() == PluginType::Frontend {
+            return inv_op("the reset() callback is not supported for frontends");
+        }
+        pdef.reset = Box::new(
+            move |state: &mut PluginState, qubits: Vec<QubitRef>| -> Result<()> {
+                let qubits: QubitReferenceSet = qubits.into_iter().collect();
+                let qubits = insert(qubits);
+                let result = cb_return_none(callback(data.data(), state.into(), qubits));
+                delete!(qubits);
+                result
+            },
+        );
+        Ok(())
+    })
+}
+
 /// Sets the gate execution callback for operators and backends.
 ///
 /// Besides the common arguments, the callback receives a handle to the
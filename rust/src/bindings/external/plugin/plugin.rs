@@ -158,6 +158,32 @@ pub extern "C" fn dqcs_plugin_free(
     })
 }
 
+/// Resets the given downstream qubits to the |0> state.
+///
+/// Besides resetting the quantum state, this clears the measurement registers
+/// of the qubits, such that `dqcs_plugin_get_measurement()` and the
+/// cycles-since-measure functions behave as if the qubits were freshly
+/// allocated once the reset completes.
+///
+/// Backend plugins are not allowed to call this. Doing so will result in an
+/// error.
+///
+/// `qubits` must be a valid set of qubit references. The set is consumed by
+/// this function, i.e. the handle becomes invalid, if and only if it succeeds.
+#[no_mangle]
+pub extern "C" fn dqcs_plugin_reset(
+    plugin: dqcs_plugin_state_t,
+    qbset: dqcs_handle_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(qbset as pending QubitReferenceSet);
+        clone!(qbset_ob: QubitReferenceSet = resolved qbset);
+        plugin.resolve()?.reset_qubits(qbset_ob.into())?;
+        delete!(resolved qbset);
+        Ok(())
+    })
+}
+
 /// Tells the downstream plugin to execute a gate.
 ///
 /// Backend plugins are not allowed to call this. Doing so will result in an
@@ -268,6 +294,22 @@ pub extern "C" fn dqcs_plugin_get_cycle(plugin: dqcs_plugin_state_t) -> dqcs_cyc
     api_return(-1, || Ok(plugin.resolve()?.get_cycle()?.into()))
 }
 
+/// Sends the requests queued by `dqcs_plugin_allocate()`,
+/// `dqcs_plugin_free()`, `dqcs_plugin_gate()`, and
+/// `dqcs_plugin_reset()` downstream.
+///
+/// These requests are queued until the next synchronization point, such as a
+/// measurement query, `dqcs_plugin_arb()`, or `dqcs_plugin_advance()`, such
+/// that many of them can be sent in a single message. Calling this function
+/// is never required.
+///
+/// Backend plugins are not allowed to call this. Doing so will result in an
+/// error.
+#[no_mangle]
+pub extern "C" fn dqcs_plugin_flush(plugin: dqcs_plugin_state_t) -> dqcs_return_t {
+    api_return_none(|| plugin.resolve()?.flush())
+}
+
 /// Sends an arbitrary command downstream.
 ///
 /// Backend plugins are not allowed to call this. Doing so will result in an
@@ -9,7 +9,7 @@
 use crate::common::{
     error::{inv_arg, oe_err, oe_inv_arg, Result},
     gates::UnboundUnitaryGate,
-    types::{ArbData, Gate, GateType, Matrix, QubitRef},
+    types::{ArbData, Gate, GateParameters, GateType, Matrix, QubitRef},
 };
 use integer_sqrt::IntegerSquareRoot;
 use num_complex::Complex64;
@@ -34,37 +34,83 @@ impl FromArb for () {
     }
 }
 
-impl FromArb for u64 {
+/// Implements `FromArb` and `ToArb` for a primitive number type, represented
+/// as a single binary argument containing its little-endian representation.
+macro_rules! le_bytes_arb {
+    ($($t:ty => $name:expr),*) => {
+        $(
+            impl FromArb for $t {
+                fn from_arb(src: &mut ArbData) -> Result<Self> {
+                    let args = src.get_args_mut();
+                    let value = args
+                        .first()
+                        .and_then(|arg| arg[..].try_into().ok())
+                        .map(<$t>::from_le_bytes)
+                        .ok_or_else(oe_inv_arg(concat!(
+                            "expected ",
+                            $name,
+                            " argument in ArbData"
+                        )))?;
+                    args.drain(..1);
+                    Ok(value)
+                }
+            }
+
+            impl ToArb for $t {
+                fn to_arb(self, dest: &mut ArbData) {
+                    dest.get_args_mut().insert(0, self.to_le_bytes().to_vec());
+                }
+            }
+        )*
+    };
+}
+
+le_bytes_arb!(
+    u8 => "8-bit integer",
+    u16 => "16-bit integer",
+    u32 => "32-bit integer",
+    u64 => "64-bit integer",
+    i8 => "8-bit signed integer",
+    i16 => "16-bit signed integer",
+    i32 => "32-bit signed integer",
+    i64 => "64-bit signed integer",
+    f32 => "float",
+    f64 => "double"
+);
+
+impl FromArb for bool {
     fn from_arb(src: &mut ArbData) -> Result<Self> {
-        let args = src.get_args_mut();
-        if args.is_empty() {
-            inv_arg("expected 64-bit integer argument in ArbData")?;
+        match u8::from_arb(src) {
+            Ok(0) => Ok(false),
+            Ok(1) => Ok(true),
+            Ok(x) => {
+                x.to_arb(src);
+                inv_arg("expected boolean argument in ArbData")
+            }
+            Err(_) => inv_arg("expected boolean argument in ArbData"),
         }
-        let i = u64::from_le_bytes(
-            args[0][..]
-                .try_into()
-                .ok()
-                .ok_or_else(oe_inv_arg("expected 64-bit integer argument in ArbData"))?,
-        );
-        args.drain(..1);
-        Ok(i)
     }
 }
 
-impl FromArb for f64 {
+impl FromArb for Vec<u8> {
     fn from_arb(src: &mut ArbData) -> Result<Self> {
         let args = src.get_args_mut();
         if args.is_empty() {
-            inv_arg("expected double argument in ArbData")?;
+            inv_arg("expected binary string argument in ArbData")?;
         }
-        let f = f64::from_le_bytes(
-            args[0][..]
-                .try_into()
-                .ok()
-                .ok_or_else(oe_inv_arg("expected double argument in ArbData"))?,
-        );
-        args.drain(..1);
-        Ok(f)
+        Ok(args.remove(0))
+    }
+}
+
+impl FromArb for String {
+    fn from_arb(src: &mut ArbData) -> Result<Self> {
+        let arg = Vec::<u8>::from_arb(src)
+            .or_else(|_| inv_arg("expected UTF-8 string argument in ArbData"))?;
+        String::from_utf8(arg).or_else(|e| {
+            let arg = e.into_bytes();
+            arg.to_arb(src);
+            inv_arg("expected UTF-8 string argument in ArbData")
+        })
     }
 }
 
@@ -137,15 +183,21 @@ impl ToArb for () {
     fn to_arb(self, _: &mut ArbData) {}
 }
 
-impl ToArb for u64 {
+impl ToArb for bool {
+    fn to_arb(self, dest: &mut ArbData) {
+        (self as u8).to_arb(dest);
+    }
+}
+
+impl ToArb for Vec<u8> {
     fn to_arb(self, dest: &mut ArbData) {
-        dest.get_args_mut().insert(0, self.to_le_bytes().to_vec());
+        dest.get_args_mut().insert(0, self);
     }
 }
 
-impl ToArb for f64 {
+impl ToArb for String {
     fn to_arb(self, dest: &mut ArbData) {
-        dest.get_args_mut().insert(0, self.to_le_bytes().to_vec());
+        self.into_bytes().to_arb(dest);
     }
 }
 
@@ -168,6 +220,170 @@ impl ToArb for Matrix {
     }
 }
 
+/// Implements `FromArb` and `ToArb` for a struct with named fields that all
+/// implement `FromArb` and `ToArb` themselves.
+///
+/// Each field is stored in the binary argument list of the `ArbData` in
+/// declaration order, using the field type's own representation. This allows
+/// Rust plugins to exchange structures through the binary arguments without
+/// manually encoding and decoding every field. For instance:
+///
+/// ```
+/// use dqcsim::{arb_args, common::{converter::{FromArb, ToArb}, types::ArbData}};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Pulse {
+///     channel: u32,
+///     amplitude: f64,
+///     label: String,
+/// }
+///
+/// arb_args!(Pulse { channel, amplitude, label });
+///
+/// let pulse = Pulse { channel: 2, amplitude: 0.5, label: "x90".to_string() };
+/// let mut data = ArbData::default();
+/// pulse.to_arb(&mut data);
+/// assert_eq!(data.get_args().len(), 3);
+/// assert_eq!(
+///     Pulse::from_arb(&mut data).unwrap(),
+///     Pulse { channel: 2, amplitude: 0.5, label: "x90".to_string() }
+/// );
+/// assert!(data.get_args().is_empty());
+/// ```
+///
+/// If any of the fields fails to convert, `from_arb()` fails without
+/// consuming any arguments.
+#[macro_export]
+macro_rules! arb_args {
+    ($t:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::common::converter::FromArb for $t {
+            fn from_arb(
+                src: &mut $crate::common::types::ArbData,
+            ) -> $crate::common::error::Result<Self> {
+                let mut data = src.clone();
+                let value = $t {
+                    $($field: $crate::common::converter::FromArb::from_arb(&mut data)?,)*
+                };
+                *src = data;
+                Ok(value)
+            }
+        }
+
+        impl $crate::common::converter::ToArb for $t {
+            fn to_arb(self, dest: &mut $crate::common::types::ArbData) {
+                $crate::__arb_args_rev!(self, dest, [$($field),*]);
+            }
+        }
+    };
+}
+
+/// Helper for `arb_args!` that calls `to_arb()` for the given fields in
+/// reverse order, such that `from_arb()` can consume them in order.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arb_args_rev {
+    ($self:ident, $dest:ident, []) => {};
+    ($self:ident, $dest:ident, [$first:ident $(, $rest:ident)*]) => {
+        $crate::__arb_args_rev!($self, $dest, [$($rest),*]);
+        $crate::common::converter::ToArb::to_arb($self.$first, $dest);
+    };
+}
+
+/// A type that can be constructed from the parameters of a parametric gate
+/// description.
+pub trait FromParameters
+where
+    Self: std::marker::Sized,
+{
+    /// Construct from the named or positional parameters of the given
+    /// parametric gate description.
+    fn from_parameters(src: &GateParameters) -> Result<Self>;
+}
+
+impl FromParameters for () {
+    fn from_parameters(_: &GateParameters) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl FromParameters for u64 {
+    fn from_parameters(src: &GateParameters) -> Result<Self> {
+        src.get_integer(0, "k")
+    }
+}
+
+impl FromParameters for f64 {
+    fn from_parameters(src: &GateParameters) -> Result<Self> {
+        src.get(0, "theta")
+    }
+}
+
+impl FromParameters for (f64, f64, f64) {
+    fn from_parameters(src: &GateParameters) -> Result<Self> {
+        Ok((
+            src.get(0, "theta")?,
+            src.get(1, "phi")?,
+            src.get(2, "lambda")?,
+        ))
+    }
+}
+
+impl FromParameters for Matrix {
+    fn from_parameters(src: &GateParameters) -> Result<Self> {
+        let params = src.get_positional();
+        if params.len() % 2 != 0 {
+            inv_arg("invalid matrix size")?;
+        }
+        params
+            .chunks(2)
+            .map(|c| Complex64::new(c[0], c[1]))
+            .collect::<Vec<_>>()
+            .try_into()
+    }
+}
+
+/// A type that can be converted into the parameters of a parametric gate
+/// description.
+///
+/// This is the reverse of `FromParameters`. Parameters are always added
+/// positionally; the names are only used as an alternative when parsing.
+pub trait ToParameters {
+    /// Add the parameters represented by self to the given description.
+    fn to_parameters(&self, dest: &mut GateParameters);
+}
+
+impl ToParameters for () {
+    fn to_parameters(&self, _: &mut GateParameters) {}
+}
+
+impl ToParameters for u64 {
+    fn to_parameters(&self, dest: &mut GateParameters) {
+        dest.get_integers_mut().push(*self);
+    }
+}
+
+impl ToParameters for f64 {
+    fn to_parameters(&self, dest: &mut GateParameters) {
+        dest.get_positional_mut().push(*self);
+    }
+}
+
+impl ToParameters for (f64, f64, f64) {
+    fn to_parameters(&self, dest: &mut GateParameters) {
+        dest.get_positional_mut()
+            .extend_from_slice(&[self.0, self.1, self.2]);
+    }
+}
+
+impl ToParameters for Matrix {
+    fn to_parameters(&self, dest: &mut GateParameters) {
+        for i in 0..self.len() {
+            dest.get_positional_mut().push(self[i].re);
+            dest.get_positional_mut().push(self[i].im);
+        }
+    }
+}
+
 /// A type that can be used as a Converter.
 ///
 /// Types implementing Converter can be used to detect inputs and link them to
@@ -684,12 +900,27 @@ where
 /// unwrapped. In the other direction, None means that the number of control
 /// qubits can be freely derived from the number of qubit arguments, while
 /// Some places a constraint on the number of expected control qubits.
+///
+/// When a gate type name is configured using `with_parametric_name()`,
+/// detection recognizes the exact parameters recorded in the gate data using
+/// the `GateParameters` schema, i.e. in the `"dqcsim.gate"` key of its JSON
+/// object. Parameters recorded this way take precedence over the parameters
+/// recovered from the matrix, such that they round-trip without
+/// floating-point loss. They are only used if they reproduce the gate matrix
+/// exactly. Recording the parameters of constructed gates changes the data
+/// seen by downstream plugins, so it must be enabled explicitly using
+/// `with_recorded_parameters()`.
 pub struct UnitaryGateConverter<M>
 where
     M: Converter<Input = (Matrix, Option<usize>)>,
 {
     /// The wrapped matrix converter.
     matrix_converter: M,
+    /// The gate type name used for the parametric gate description, or None
+    /// if no such description should be used.
+    parametric_name: Option<String>,
+    /// Whether constructed gates carry the parametric gate description.
+    record_parameters: bool,
 }
 
 impl<M> From<M> for UnitaryGateConverter<M>
@@ -697,14 +928,72 @@ where
     M: Converter<Input = (Matrix, Option<usize>)>,
 {
     fn from(matrix_converter: M) -> Self {
-        Self { matrix_converter }
+        Self {
+            matrix_converter,
+            parametric_name: None,
+            record_parameters: false,
+        }
+    }
+}
+
+impl<M> UnitaryGateConverter<M>
+where
+    M: Converter<Input = (Matrix, Option<usize>)>,
+{
+    /// Sets the gate type name used for the parametric gate description,
+    /// builder style.
+    pub fn with_parametric_name(mut self, name: impl Into<String>) -> Self {
+        self.parametric_name = Some(name.into());
+        self
+    }
+
+    /// Sets whether the exact parameters of constructed gates are recorded
+    /// in their data, builder style. This has no effect unless a gate type
+    /// name is configured using `with_parametric_name()`.
+    pub fn with_recorded_parameters(mut self, record: bool) -> Self {
+        self.record_parameters = record;
+        self
+    }
+
+    /// Returns the parameters from the parametric description carried by the
+    /// given unitary gate, if it matches this converter and reproduces the
+    /// gate matrix exactly. Descriptions with missing or invalid parameters
+    /// are ignored, such that the parameters are recovered from the matrix
+    /// instead.
+    fn detect_parameters(&self, gate: &Gate, matrix: &Matrix) -> Result<Option<M::Output>>
+    where
+        M::Output: FromParameters,
+    {
+        if let Some(name) = &self.parametric_name {
+            if let Some(description) = gate.get_parameters()? {
+                if description.get_type() == name {
+                    let params = match M::Output::from_parameters(&description) {
+                        Ok(params) => params,
+                        Err(_) => return Ok(None),
+                    };
+                    let (expected, num_controls) = match self.matrix_converter.construct(&params) {
+                        Ok(x) => x,
+                        Err(_) => return Ok(None),
+                    };
+                    if let Some(num_controls) = num_controls {
+                        if num_controls != gate.get_controls().len() {
+                            return Ok(None);
+                        }
+                    }
+                    if &expected == matrix {
+                        return Ok(Some(params));
+                    }
+                }
+            }
+        }
+        Ok(None)
     }
 }
 
 impl<M> Converter for UnitaryGateConverter<M>
 where
     M: Converter<Input = (Matrix, Option<usize>)>,
-    M::Output: FromArb + ToArb,
+    M::Output: FromArb + ToArb + FromParameters + ToParameters,
 {
     type Input = Gate;
     type Output = (Vec<QubitRef>, ArbData);
@@ -714,17 +1003,25 @@ where
             // Not a unitary so no match.
             Ok(None)
         } else if let Some(matrix) = gate.get_matrix() {
-            // Unitary gate. Check conditions.
-            if let Some(params) = self
-                .matrix_converter
-                .detect(&(matrix.clone(), Some(gate.get_controls().len())))?
-            {
+            // Unitary gate. If the gate carries a matching parametric
+            // description, use its exact parameters instead of the ones
+            // recovered from the matrix, but only if they produce exactly the
+            // gate matrix; otherwise the description is stale.
+            let mut data = gate.data.clone();
+            let mut params = self.detect_parameters(gate, matrix)?;
+            if params.is_some() {
+                GateParameters::take_from(&mut data)?;
+            } else {
+                params = self
+                    .matrix_converter
+                    .detect(&(matrix.clone(), Some(gate.get_controls().len())))?;
+            }
+            if let Some(params) = params {
                 // Matrix match; construct qubit argument vector.
                 let mut qubits = vec![];
                 qubits.extend(gate.get_controls().iter());
                 qubits.extend(gate.get_targets().iter());
                 // Construct data.
-                let mut data = gate.data.clone();
                 params.to_arb(&mut data);
                 Ok(Some((qubits, data)))
             } else {
@@ -769,16 +1066,34 @@ where
             Gate::new_unitary(targets.iter().cloned(), controls.iter().cloned(), matrix)?;
         gate.data.copy_from(&data);
 
+        // Record the exact parameters if requested. This is only possible
+        // when the JSON object of the data is a map; the gate is constructed
+        // without the description otherwise.
+        if let (true, Some(name)) = (self.record_parameters, &self.parametric_name) {
+            if GateParameters::can_insert_into(&gate.data) {
+                let mut description = GateParameters::new(name.clone());
+                params.to_parameters(&mut description);
+                gate.set_parameters(&description)?;
+            }
+        }
+
         Ok(gate)
     }
 }
 
 /// Converter implementation for measurement gates.
+///
+/// This handles both regular measurement gates, for which each qubit is
+/// measured in the given basis individually, and Pauli measurement gates, for
+/// which a single result is obtained for the given observable.
 pub struct MeasurementGateConverter {
     /// The number of expected measurement qubits, or None if not constrained.
     num_measures: Option<usize>,
-    /// The measurement basis.
+    /// The measurement basis, or the observable for Pauli measurements.
     basis: Matrix,
+    /// Whether this converter handles Pauli measurements instead of regular
+    /// measurements.
+    pauli: bool,
     /// The maximum RMS deviation in the basis when detecting.
     epsilon: f64,
 }
@@ -788,6 +1103,20 @@ impl MeasurementGateConverter {
         Self {
             num_measures,
             basis,
+            pauli: false,
+            epsilon,
+        }
+    }
+
+    /// Constructs a converter for Pauli measurements of the given
+    /// observable, for instance one constructed using
+    /// `Matrix::new_pauli_product()`. The number of qubits is implied by the
+    /// size of the observable.
+    pub fn new_pauli(observable: Matrix, epsilon: f64) -> Self {
+        Self {
+            num_measures: observable.num_qubits(),
+            basis: observable,
+            pauli: true,
             epsilon,
         }
     }
@@ -798,7 +1127,18 @@ impl Converter for MeasurementGateConverter {
     type Output = (Vec<QubitRef>, ArbData);
 
     fn detect(&self, gate: &Gate) -> Result<Option<Self::Output>> {
-        if gate.get_type() != &GateType::Measurement {
+        if self.pauli {
+            if gate.get_type() != &GateType::PauliMeasurement
+                || Some(gate.get_targets().len()) != self.num_measures
+                || !self
+                    .basis
+                    .approx_eq(gate.get_matrix().unwrap(), self.epsilon, false)
+            {
+                // Not a Pauli measurement of the expected observable.
+                return Ok(None);
+            }
+            Ok(Some((gate.get_targets().to_vec(), gate.data.clone())))
+        } else if gate.get_type() != &GateType::Measurement {
             // Not a measurement gate.
             Ok(None)
         } else {
@@ -831,7 +1171,11 @@ impl Converter for MeasurementGateConverter {
         }
 
         // Construct the gate.
-        let mut gate = Gate::new_measurement(qubits.clone(), self.basis.clone())?;
+        let mut gate = if self.pauli {
+            Gate::new_pauli_measurement(qubits.clone(), self.basis.clone())?
+        } else {
+            Gate::new_measurement(qubits.clone(), self.basis.clone())?
+        };
         gate.data.copy_from(&data);
 
         Ok(gate)
@@ -1224,6 +1568,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arb_primitives() {
+        let mut arb = ArbData::default();
+        "hello".to_string().to_arb(&mut arb);
+        vec![1u8, 2].to_arb(&mut arb);
+        true.to_arb(&mut arb);
+        (-3i32).to_arb(&mut arb);
+        1.5f32.to_arb(&mut arb);
+        7u8.to_arb(&mut arb);
+        assert_eq!(arb.get_args().len(), 6);
+        assert_eq!(u8::from_arb(&mut arb).unwrap(), 7);
+        assert_eq!(f32::from_arb(&mut arb).unwrap(), 1.5);
+        assert_eq!(i32::from_arb(&mut arb).unwrap(), -3);
+        assert!(bool::from_arb(&mut arb).unwrap());
+        assert_eq!(Vec::<u8>::from_arb(&mut arb).unwrap(), vec![1, 2]);
+        assert_eq!(String::from_arb(&mut arb).unwrap(), "hello");
+        assert!(arb.get_args().is_empty());
+
+        let mut arb = ArbData::from_args(vec![vec![2u8], vec![0xFF]]);
+        assert_eq!(
+            bool::from_arb(&mut arb).unwrap_err().to_string(),
+            "Invalid argument: expected boolean argument in ArbData"
+        );
+        assert_eq!(arb.get_args().len(), 2);
+        assert_eq!(
+            i16::from_arb(&mut arb).unwrap_err().to_string(),
+            "Invalid argument: expected 16-bit signed integer argument in ArbData"
+        );
+        arb.get_args_mut().remove(0);
+        assert_eq!(
+            String::from_arb(&mut arb).unwrap_err().to_string(),
+            "Invalid argument: expected UTF-8 string argument in ArbData"
+        );
+        assert_eq!(arb.get_args().len(), 1);
+    }
+
+    #[test]
+    fn arb_args_macro() {
+        #[derive(Debug, PartialEq)]
+        struct Pulse {
+            channel: u32,
+            amplitude: f64,
+            label: String,
+        }
+        arb_args!(Pulse {
+            channel,
+            amplitude,
+            label,
+        });
+
+        let mut arb = ArbData::from_args(vec![b"rest".to_vec()]);
+        Pulse {
+            channel: 2,
+            amplitude: 0.5,
+            label: "x90".to_string(),
+        }
+        .to_arb(&mut arb);
+        assert_eq!(
+            arb,
+            ArbData::from_args(vec![
+                2u32.to_le_bytes().to_vec(),
+                0.5f64.to_le_bytes().to_vec(),
+                b"x90".to_vec(),
+                b"rest".to_vec(),
+            ])
+        );
+        assert_eq!(
+            Pulse::from_arb(&mut arb).unwrap(),
+            Pulse {
+                channel: 2,
+                amplitude: 0.5,
+                label: "x90".to_string(),
+            }
+        );
+        assert_eq!(arb, ArbData::from_args(vec![b"rest".to_vec()]));
+
+        let mut arb = ArbData::from_args(vec![2u32.to_le_bytes().to_vec(), vec![1u8]]);
+        assert_eq!(
+            Pulse::from_arb(&mut arb).unwrap_err().to_string(),
+            "Invalid argument: expected double argument in ArbData"
+        );
+        assert_eq!(arb.get_args().len(), 2);
+    }
+
     #[test]
     fn matrix_converter_arb() {
         let rx = RxMatrixConverter::default();
@@ -1737,6 +2165,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parametric_gate_converter() {
+        let map: ConverterMap<UnitaryGateType, Gate, (Vec<QubitRef>, ArbData)> =
+            ConverterMap::default()
+                .with(
+                    UnitaryGateType::RX,
+                    UnitaryGateType::RX.into_parametric_gate_converter(None, 1e-9, false),
+                )
+                .with(
+                    UnitaryGateType::R,
+                    UnitaryGateType::R.into_parametric_gate_converter(None, 1e-9, false),
+                );
+        let q = vec![QubitRef::from_foreign(1).unwrap()];
+
+        // Angles that cannot be recovered exactly from the matrix must
+        // round-trip exactly through the parametric description.
+        for theta in &[0.1f64, 1e-9, PI / 7., -2.5, 5.] {
+            let mut data = ArbData::from_json(r#"{"line": 42}"#, vec![]).unwrap();
+            theta.to_arb(&mut data);
+            let gate = map
+                .construct(&(UnitaryGateType::RX, (q.clone(), data.clone())))
+                .unwrap();
+            assert_eq!(
+                gate.get_parameters().unwrap(),
+                Some(GateParameters::new("RX").with_positional(*theta))
+            );
+            assert_eq!(
+                map.detect(&gate).unwrap(),
+                Some((UnitaryGateType::RX, (q.clone(), data)))
+            );
+        }
+
+        let mut data = ArbData::default();
+        (0.1, 0.2, 0.3).to_arb(&mut data);
+        let gate = map
+            .construct(&(UnitaryGateType::R, (q.clone(), data.clone())))
+            .unwrap();
+        assert_eq!(
+            map.detect(&gate).unwrap(),
+            Some((UnitaryGateType::R, (q.clone(), data)))
+        );
+
+        // Named parameters are accepted as well.
+        let mut gate = Gate::from(BoundUnitaryGate::RX(0.3, q[0]));
+        gate.set_parameters(&GateParameters::new("RX").with_named("theta", 0.3))
+            .unwrap();
+        let mut data = ArbData::default();
+        0.3.to_arb(&mut data);
+        assert_eq!(
+            map.detect(&gate).unwrap(),
+            Some((UnitaryGateType::RX, (q.clone(), data)))
+        );
+
+        // Integer parameters are reproduced exactly.
+        let phasek = UnitaryGateType::PhaseK.into_parametric_gate_converter(None, 1e-9, false);
+        let k = (1 << 60) + 1;
+        let mut data = ArbData::default();
+        k.to_arb(&mut data);
+        let gate = phasek.construct(&(q.clone(), data.clone())).unwrap();
+        assert_eq!(
+            gate.get_parameters().unwrap(),
+            Some(GateParameters::new("PhaseK").with_integer(k))
+        );
+        assert_eq!(phasek.detect(&gate).unwrap(), Some((q.clone(), data)));
+
+        // Descriptions of a different gate type are left alone.
+        let mut gate = Gate::from(BoundUnitaryGate::RX(0.5, q[0]));
+        gate.set_parameters(&GateParameters::new("RY").with_positional(0.5))
+            .unwrap();
+        let (key, (_, data)) = map.detect(&gate).unwrap().unwrap();
+        assert_eq!(key, UnitaryGateType::RX);
+        assert_eq!(
+            GateParameters::from_arb_data(&data).unwrap(),
+            Some(GateParameters::new("RY").with_positional(0.5))
+        );
+
+        // Stale descriptions that don't reproduce the matrix are ignored.
+        let mut gate = Gate::from(BoundUnitaryGate::RX(0.5, q[0]));
+        gate.set_parameters(&GateParameters::new("RX").with_positional(0.4))
+            .unwrap();
+        let (_, (_, mut data)) = map.detect(&gate).unwrap().unwrap();
+        assert!(approx_eq!(
+            f64,
+            f64::from_arb(&mut data).unwrap(),
+            0.5,
+            ulps = 4
+        ));
+        assert_eq!(
+            GateParameters::from_arb_data(&data).unwrap(),
+            Some(GateParameters::new("RX").with_positional(0.4))
+        );
+
+        // Malformed descriptions fall back to the matrix.
+        let mut gate = Gate::from(BoundUnitaryGate::RX(0.5, q[0]));
+        gate.data = ArbData::from_json(
+            r#"{"dqcsim.gate": {"type": "RX", "positional": "x"}}"#,
+            vec![],
+        )
+        .unwrap();
+        let (_, (_, mut data)) = map.detect(&gate).unwrap().unwrap();
+        assert!(approx_eq!(
+            f64,
+            f64::from_arb(&mut data).unwrap(),
+            0.5,
+            ulps = 4
+        ));
+        let mut gate = Gate::from(BoundUnitaryGate::RX(0.5, q[0]));
+        gate.set_parameters(&GateParameters::new("RX")).unwrap();
+        assert_eq!(map.detect(&gate).unwrap().unwrap().0, UnitaryGateType::RX);
+
+        // Data that is not a JSON object is passed through without a
+        // description.
+        let mut data = ArbData::from_json("[1, 2]", vec![]).unwrap();
+        0.1.to_arb(&mut data);
+        let gate = map
+            .construct(&(UnitaryGateType::RX, (q.clone(), data.clone())))
+            .unwrap();
+        assert_eq!(gate.get_parameters().unwrap(), None);
+        assert_eq!(
+            map.detect(&gate).unwrap().unwrap().1 .1.get_json().unwrap(),
+            "[1,2]"
+        );
+
+        // The regular predefined converters recognize descriptions, but
+        // don't record them.
+        let rx = UnitaryGateType::RX.into_gate_converter(None, 1e-9, false);
+        let mut data = ArbData::default();
+        0.1.to_arb(&mut data);
+        let gate = rx.construct(&(q.clone(), data.clone())).unwrap();
+        assert_eq!(gate.get_parameters().unwrap(), None);
+        assert_eq!(gate.data.get_json().unwrap(), "{}");
+        let gate = map
+            .construct(&(UnitaryGateType::RX, (q.clone(), data.clone())))
+            .unwrap();
+        assert_eq!(rx.detect(&gate).unwrap(), Some((q.clone(), data)));
+
+        // Converters without a parametric name don't record anything.
+        let rx = UnitaryGateConverter::from(UnitaryConverter::new(
+            RxMatrixConverter::default(),
+            None,
+            0.,
+            false,
+        ));
+        let mut data = ArbData::default();
+        0.1.to_arb(&mut data);
+        assert_eq!(
+            rx.construct(&(q, data)).unwrap().get_parameters().unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn measurement_gate_converter() {
         let mn = MeasurementGateConverter::new(None, Matrix::new_identity(2), 0.001);
@@ -1836,6 +2415,57 @@ mod tests {
         assert!(m1.construct(&two).is_err());
     }
 
+    #[test]
+    fn pauli_measurement_gate_converter() {
+        let zz =
+            MeasurementGateConverter::new_pauli(Matrix::new_pauli_product("ZZ").unwrap(), 0.001);
+        let xx =
+            MeasurementGateConverter::new_pauli(Matrix::new_pauli_product("XX").unwrap(), 0.001);
+
+        let qubits = vec![
+            QubitRef::from_foreign(1).unwrap(),
+            QubitRef::from_foreign(2).unwrap(),
+        ];
+        let zz_gate =
+            Gate::new_pauli_measurement(qubits.clone(), Matrix::new_pauli_product("ZZ").unwrap())
+                .unwrap();
+        let zzz_gate = Gate::new_pauli_measurement(
+            vec![
+                QubitRef::from_foreign(1).unwrap(),
+                QubitRef::from_foreign(2).unwrap(),
+                QubitRef::from_foreign(3).unwrap(),
+            ],
+            Matrix::new_pauli_product("ZZZ").unwrap(),
+        )
+        .unwrap();
+        let measure_gate = Gate::new_measurement(qubits.clone(), Matrix::new_identity(2)).unwrap();
+
+        assert_eq!(
+            zz.detect(&zz_gate).unwrap(),
+            Some((qubits.clone(), ArbData::default()))
+        );
+        assert!(xx.detect(&zz_gate).unwrap().is_none());
+        assert!(zz.detect(&zzz_gate).unwrap().is_none());
+        assert!(zz.detect(&measure_gate).unwrap().is_none());
+        assert!(
+            MeasurementGateConverter::new(None, Matrix::new_identity(2), 0.001)
+                .detect(&zz_gate)
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(
+            zz.construct(&(qubits.clone(), ArbData::default())).unwrap(),
+            zz_gate
+        );
+        assert_eq!(
+            zz.construct(&(qubits[..1].to_vec(), ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected 2 measurement qubits"
+        );
+    }
+
     #[test]
     fn custom_gate_converter() {
         let gate = Gate::new_measurement(
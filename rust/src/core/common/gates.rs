@@ -586,44 +586,90 @@ impl UnitaryGateType {
         num_controls: Option<usize>,
         epsilon: f64,
         ignore_global_phase: bool,
+    ) -> Box<dyn Converter<Input = Gate, Output = (Vec<QubitRef>, ArbData)>> {
+        self.gate_converter(num_controls, epsilon, ignore_global_phase, false)
+    }
+
+    /// Like `into_gate_converter()`, but for the parameterized gate types,
+    /// the exact parameters of constructed gates are also recorded in their
+    /// data, such that they can be detected without floating-point loss.
+    pub fn into_parametric_gate_converter(
+        self,
+        num_controls: Option<usize>,
+        epsilon: f64,
+        ignore_global_phase: bool,
+    ) -> Box<dyn Converter<Input = Gate, Output = (Vec<QubitRef>, ArbData)>> {
+        self.gate_converter(num_controls, epsilon, ignore_global_phase, true)
+    }
+
+    fn gate_converter(
+        self,
+        num_controls: Option<usize>,
+        epsilon: f64,
+        ignore_global_phase: bool,
+        record_parameters: bool,
     ) -> Box<dyn Converter<Input = Gate, Output = (Vec<QubitRef>, ArbData)>> {
         match self {
-            UnitaryGateType::RX => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                RxMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
-            UnitaryGateType::RY => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                RyMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
-            UnitaryGateType::RZ => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                RzMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
-            UnitaryGateType::Phase => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                PhaseMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
-            UnitaryGateType::PhaseK => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                PhaseKMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
-            UnitaryGateType::R => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
-                RMatrixConverter::default(),
-                num_controls,
-                epsilon,
-                ignore_global_phase,
-            ))),
+            UnitaryGateType::RX => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    RxMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("RX")
+                .with_recorded_parameters(record_parameters),
+            ),
+            UnitaryGateType::RY => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    RyMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("RY")
+                .with_recorded_parameters(record_parameters),
+            ),
+            UnitaryGateType::RZ => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    RzMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("RZ")
+                .with_recorded_parameters(record_parameters),
+            ),
+            UnitaryGateType::Phase => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    PhaseMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("Phase")
+                .with_recorded_parameters(record_parameters),
+            ),
+            UnitaryGateType::PhaseK => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    PhaseKMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("PhaseK")
+                .with_recorded_parameters(record_parameters),
+            ),
+            UnitaryGateType::R => Box::new(
+                UnitaryGateConverter::from(UnitaryConverter::new(
+                    RMatrixConverter::default(),
+                    num_controls,
+                    epsilon,
+                    ignore_global_phase,
+                ))
+                .with_parametric_name("R")
+                .with_recorded_parameters(record_parameters),
+            ),
             UnitaryGateType::U(num_qubits) => {
                 Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
                     UMatrixConverter::new(Some(num_qubits)),
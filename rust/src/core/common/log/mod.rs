@@ -34,7 +34,8 @@
 //! A [`LogProxy`] forwards log [`Records`] to a [`LogThread`]. It logs records
 //! with it's logger name if the generated log [`LogRecord`] [`Loglevel`] is
 //! smaller or equal than the configured [`LoglevelFilter`] of the
-//! [`LogProxy`].
+//! [`LogProxy`]. A [`ModuleFilter`] can be added to override this filter
+//! for records originating from specific modules.
 //!
 //! ## TeeFile
 //!
@@ -42,6 +43,15 @@
 //! logger name if the generated log [`LogRecord`] [`Loglevel`] is smaller or
 //! equal than the configured [`LoglevelFilter`] of the [`TeeFile`].
 //!
+//! ## LogFormat
+//!
+//! Both Standard Error output and [`TeeFile`]s normally output records in a
+//! human-readable text format. Either can be switched to [`LogFormat::Json`],
+//! which outputs every record as a single-line JSON object containing all its
+//! fields, for consumption by other tools. The format for Standard Error is
+//! selected by spawning the [`LogThread`] with
+//! [`LogThread::spawn_with_format`].
+//!
 //! # Basic Example
 //!
 //! ```rust
@@ -91,13 +101,16 @@
 //! * sfackler's [comment](https://github.com/rust-lang-nursery/log/issues/57#issuecomment-143383896)
 //!
 //! [`LogThread`]: ./thread/struct.LogThread.html
+//! [`LogThread::spawn_with_format`]: ./thread/struct.LogThread.html#method.spawn_with_format
 //! [`spawn`]: ./thread/struct.LogThread.html#method.spawn
 //! [`LogProxy`]: ./proxy/struct.LogProxy.html
 //! [`TeeFile`]: ./tee_file/struct.TeeFile.html
+//! [`ModuleFilter`]: ./module_filter/struct.ModuleFilter.html
 //! [`LogRecord`]: ./struct.LogRecord.html
 //! [`Records`]: ./struct.LogRecord.html
 //! [`Loglevel`]: ./enum.Loglevel.html
 //! [`LoglevelFilter`]: ./enum.LoglevelFilter.html
+//! [`LogFormat::Json`]: ./enum.LogFormat.html
 //! [`LogLevelFilter::Off`]: ./enum.LogLevelFilter.html
 //! [`LogCallback`]: ../configuration/struct.LogCallback.html
 //! [`macros`]: ../index.html#macros
@@ -109,6 +122,7 @@
 pub use ref_thread_local as _ref_thread_local;
 
 pub mod callback;
+pub mod module_filter;
 pub mod proxy;
 pub mod stdio;
 pub mod tee_file;
@@ -295,6 +309,32 @@ pub enum LoglevelFilter {
     Trace,
 }
 
+/// Output format for log sinks.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    EnumIter,
+    NamedType,
+)]
+pub enum LogFormat {
+    /// Human-readable text, one record per line.
+    #[default]
+    #[strum(to_string = "Text", serialize = "text")]
+    Text,
+    /// JSON lines; one JSON object per record, as produced by
+    /// `LogRecord::to_json()`.
+    #[strum(to_string = "Json", serialize = "json")]
+    Json,
+}
+
 impl Loglevel {
     /// Attempt to convert a LoglevelFilter to a Loglevel.
     ///
@@ -382,6 +422,27 @@ impl LogRecord {
     pub fn logger(&self) -> &str {
         self.logger.as_str()
     }
+
+    /// Formats the record as a single-line JSON object containing all its
+    /// fields.
+    ///
+    /// The object has the keys `timestamp` (RFC 3339 with nanosecond
+    /// precision), `logger`, `level`, `payload`, `module`, `file`, `line`,
+    /// `pid`, and `tid`. The source location keys are `null` when unknown.
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "timestamp": humantime::format_rfc3339_nanos(self.metadata.timestamp).to_string(),
+            "logger": self.logger,
+            "level": self.metadata.level.to_string(),
+            "payload": self.payload,
+            "module": self.metadata.module_path,
+            "file": self.metadata.file,
+            "line": self.metadata.line,
+            "pid": self.metadata.process,
+            "tid": self.metadata.thread,
+        })
+        .to_string()
+    }
 }
 
 impl LogRecord {
@@ -583,7 +644,8 @@ macro_rules! trace {
 
 #[cfg(test)]
 mod tests {
-    use super::{LogRecord, Loglevel, LoglevelFilter};
+    use super::{LogFormat, LogRecord, Loglevel, LoglevelFilter};
+    use crate::common::util::friendly_enum_parse;
 
     #[test]
     fn level_order() {
@@ -642,4 +704,48 @@ mod tests {
             "  Trace     1:1  logger                  message"
         );
     }
+
+    #[test]
+    fn json_record() {
+        let record = LogRecord::new(
+            "logger",
+            "message with \"quotes\"",
+            Loglevel::Note,
+            "path",
+            "file",
+            1234u32,
+            1u32,
+            2u64,
+        );
+        let json = record.to_json();
+        assert!(!json.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["logger"], "logger");
+        assert_eq!(value["level"], "Note");
+        assert_eq!(value["payload"], "message with \"quotes\"");
+        assert_eq!(value["module"], "path");
+        assert_eq!(value["file"], "file");
+        assert_eq!(value["line"], 1234);
+        assert_eq!(value["pid"], 1);
+        assert_eq!(value["tid"], 2);
+        assert_eq!(
+            humantime::parse_rfc3339(value["timestamp"].as_str().unwrap()).unwrap(),
+            record.timestamp()
+        );
+    }
+
+    #[test]
+    fn format_parse() {
+        assert_eq!(LogFormat::default(), LogFormat::Text);
+        assert_eq!(
+            friendly_enum_parse::<LogFormat, _>("JSON").unwrap(),
+            LogFormat::Json
+        );
+        assert_eq!(
+            friendly_enum_parse::<LogFormat, _>("x")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: x is not a valid log format, valid values are text or json"
+        );
+    }
 }
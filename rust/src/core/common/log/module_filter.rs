@@ -0,0 +1,261 @@
+//! Module path based loglevel filtering.
+//!
+//! A [`ModuleFilter`] overrides the loglevel filter of a logger for records
+//! originating from specific modules, as indicated by
+//! [`LogRecord::module_path()`]. A directive for module path `a::b` applies
+//! to records from `a::b` and its submodules, such as `a::b::c`, but not to
+//! `a::bc`. When multiple directives apply, the most specific one wins.
+//!
+//! [`LogDirectives`] combines a [`ModuleFilter`] with an optional default
+//! loglevel filter, and can be parsed from strings of the form
+//! `warn,my_plugin::my_module=trace`.
+//!
+//! [`ModuleFilter`]: ./struct.ModuleFilter.html
+//! [`LogDirectives`]: ./struct.LogDirectives.html
+//! [`LogRecord::module_path()`]: ../struct.LogRecord.html#method.module_path
+
+use crate::common::{
+    error::{inv_arg, Error, Result},
+    log::{Loglevel, LoglevelFilter},
+    util::friendly_enum_parse,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Loglevel filter overrides for specific module paths.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleFilter {
+    /// Map from module path to the loglevel filter for that module.
+    directives: BTreeMap<String, LoglevelFilter>,
+}
+
+impl ModuleFilter {
+    /// Constructs an empty module filter.
+    pub fn new() -> ModuleFilter {
+        ModuleFilter::default()
+    }
+
+    /// Sets the loglevel filter for the given module path and its submodules.
+    pub fn insert(&mut self, module_path: impl Into<String>, filter: impl Into<LoglevelFilter>) {
+        self.directives.insert(module_path.into(), filter.into());
+    }
+
+    /// Sets the loglevel filter for the given module path and its submodules,
+    /// builder style.
+    pub fn with(
+        mut self,
+        module_path: impl Into<String>,
+        filter: impl Into<LoglevelFilter>,
+    ) -> ModuleFilter {
+        self.insert(module_path, filter);
+        self
+    }
+
+    /// Copies all directives from the given filter into this one, overriding
+    /// directives for the same module path.
+    pub fn extend(&mut self, other: &ModuleFilter) {
+        self.directives.extend(
+            other
+                .directives
+                .iter()
+                .map(|(path, filter)| (path.clone(), *filter)),
+        );
+    }
+
+    /// Returns whether there are no directives.
+    pub fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    /// Iterates over the directives in module path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, LoglevelFilter)> {
+        self.directives
+            .iter()
+            .map(|(path, filter)| (path.as_str(), *filter))
+    }
+
+    /// Returns the loglevel filter of the most specific directive matching
+    /// the given module path, if any.
+    pub fn get(&self, module_path: &str) -> Option<LoglevelFilter> {
+        self.directives
+            .iter()
+            .filter(|(path, _)| {
+                module_path.starts_with(path.as_str())
+                    && (module_path.len() == path.len()
+                        || module_path[path.len()..].starts_with("::"))
+            })
+            .max_by_key(|(path, _)| path.len())
+            .map(|(_, filter)| *filter)
+    }
+
+    /// Returns the most verbose loglevel filter of any directive.
+    pub fn max_filter(&self) -> Option<LoglevelFilter> {
+        self.directives
+            .values()
+            .copied()
+            .fold(None, |max, filter| match max {
+                Some(max) if max >= filter => Some(max),
+                _ => Some(filter),
+            })
+    }
+
+    /// Limits the verbosity of all directives to the given filter.
+    pub fn limit(&mut self, max_filter: LoglevelFilter) {
+        for filter in self.directives.values_mut() {
+            if *filter > max_filter {
+                *filter = max_filter;
+            }
+        }
+    }
+
+    /// Returns whether a record with the given level and module path passes
+    /// this filter, using `default` for module paths that don't match any
+    /// directive.
+    pub fn enabled(
+        &self,
+        default: LoglevelFilter,
+        level: Loglevel,
+        module_path: Option<&str>,
+    ) -> bool {
+        let filter = module_path
+            .and_then(|module_path| self.get(module_path))
+            .unwrap_or(default);
+        LoglevelFilter::from(level) <= filter
+    }
+}
+
+/// A loglevel filter specification consisting of an optional default
+/// loglevel filter and any number of module path directives.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogDirectives {
+    /// The loglevel filter for module paths that don't match any directive,
+    /// if specified.
+    pub level: Option<LoglevelFilter>,
+
+    /// The module path directives.
+    pub modules: ModuleFilter,
+}
+
+impl From<LoglevelFilter> for LogDirectives {
+    fn from(level: LoglevelFilter) -> LogDirectives {
+        LogDirectives {
+            level: Some(level),
+            modules: ModuleFilter::default(),
+        }
+    }
+}
+
+impl ::std::str::FromStr for LogDirectives {
+    type Err = Error;
+
+    /// Parses a comma-separated list of directives. Each directive is either
+    /// a loglevel filter, setting the default, or of the form
+    /// `<module_path>=<filter>`. Loglevel filters are parsed by
+    /// `friendly_enum_parse()` and thus support abbreviations.
+    fn from_str(s: &str) -> Result<LogDirectives> {
+        let mut directives = LogDirectives::default();
+        for directive in s.split(',').map(str::trim) {
+            let mut splitter = directive.splitn(2, '=');
+            let first = splitter.next().unwrap();
+            match splitter.next() {
+                Some(filter) => {
+                    if first.is_empty() {
+                        inv_arg(format!("missing module path in directive {}", directive))?;
+                    }
+                    directives
+                        .modules
+                        .insert(first, friendly_enum_parse::<LoglevelFilter, _>(filter)?);
+                }
+                None => directives.level = Some(friendly_enum_parse(first)?),
+            }
+        }
+        Ok(directives)
+    }
+}
+
+impl fmt::Display for LogDirectives {
+    /// Turns the directives into a string that can be parsed by `from_str()`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut directives = vec![];
+        if let Some(level) = self.level {
+            directives.push(level.to_string());
+        }
+        for (path, filter) in self.modules.iter() {
+            directives.push(format!("{}={}", path, filter));
+        }
+        write!(f, "{}", directives.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching() {
+        let filter = ModuleFilter::new()
+            .with("a", LoglevelFilter::Warn)
+            .with("a::b", LoglevelFilter::Trace)
+            .with("c", LoglevelFilter::Off);
+        assert_eq!(filter.get("a"), Some(LoglevelFilter::Warn));
+        assert_eq!(filter.get("a::x"), Some(LoglevelFilter::Warn));
+        assert_eq!(filter.get("a::b"), Some(LoglevelFilter::Trace));
+        assert_eq!(filter.get("a::b::c"), Some(LoglevelFilter::Trace));
+        assert_eq!(filter.get("a::bc"), Some(LoglevelFilter::Warn));
+        assert_eq!(filter.get("ab"), None);
+        assert_eq!(filter.get("x::a"), None);
+        assert_eq!(filter.max_filter(), Some(LoglevelFilter::Trace));
+        assert_eq!(ModuleFilter::new().max_filter(), None);
+
+        assert!(filter.enabled(LoglevelFilter::Info, Loglevel::Trace, Some("a::b")));
+        assert!(!filter.enabled(LoglevelFilter::Info, Loglevel::Info, Some("a")));
+        assert!(!filter.enabled(LoglevelFilter::Info, Loglevel::Fatal, Some("c")));
+        assert!(filter.enabled(LoglevelFilter::Info, Loglevel::Info, Some("d")));
+        assert!(!filter.enabled(LoglevelFilter::Info, Loglevel::Debug, None));
+
+        let mut limited = filter.clone();
+        limited.limit(LoglevelFilter::Debug);
+        assert_eq!(limited.get("a::b"), Some(LoglevelFilter::Debug));
+        assert_eq!(limited.get("a"), Some(LoglevelFilter::Warn));
+
+        let mut extended = filter;
+        extended.extend(&ModuleFilter::new().with("a", LoglevelFilter::Error));
+        assert_eq!(extended.get("a"), Some(LoglevelFilter::Error));
+        assert_eq!(extended.get("a::b"), Some(LoglevelFilter::Trace));
+    }
+
+    #[test]
+    fn parse() {
+        let directives: LogDirectives = "warn, plugin::my_module=trace,plugin=e".parse().unwrap();
+        assert_eq!(
+            directives,
+            LogDirectives {
+                level: Some(LoglevelFilter::Warn),
+                modules: ModuleFilter::new()
+                    .with("plugin", LoglevelFilter::Error)
+                    .with("plugin::my_module", LoglevelFilter::Trace),
+            }
+        );
+        assert_eq!(
+            directives.to_string(),
+            "Warn,plugin=Error,plugin::my_module=Trace"
+        );
+        assert_eq!(
+            directives.to_string().parse::<LogDirectives>().unwrap(),
+            directives
+        );
+
+        let directives: LogDirectives = "a=d".parse().unwrap();
+        assert_eq!(directives.level, None);
+        assert_eq!(directives.to_string(), "a=Debug");
+
+        assert_eq!(
+            "=trace".parse::<LogDirectives>().unwrap_err().to_string(),
+            "Invalid argument: missing module path in directive =trace"
+        );
+        assert_eq!(
+            "a=x".parse::<LogDirectives>().unwrap_err().to_string(),
+            "Invalid argument: x is not a valid loglevel filter, valid values are off, fatal, error, warn, note, info, debug, or trace"
+        );
+    }
+}
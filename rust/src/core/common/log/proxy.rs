@@ -10,7 +10,9 @@
 //! [`LogProxy`]: ./struct.LogProxy.html
 //! [`Log`]: ../trait.Log.html
 
-use crate::common::log::{Log, LogRecord, Loglevel, LoglevelFilter, Sender};
+use crate::common::log::{
+    module_filter::ModuleFilter, Log, LogRecord, Loglevel, LoglevelFilter, Sender,
+};
 
 /// A [`LogProxy`] is a logger implementation (`Log`) which sends log records
 /// using its Sender side of a Channel.
@@ -20,6 +22,7 @@ use crate::common::log::{Log, LogRecord, Loglevel, LoglevelFilter, Sender};
 pub struct LogProxy<T: Sender> {
     name: String,
     level: LoglevelFilter,
+    modules: ModuleFilter,
     sender: T,
 }
 
@@ -28,6 +31,7 @@ impl<T: Sender<Item = LogRecord>> LogProxy<T> {
         LogProxy {
             name: name.into(),
             level,
+            modules: ModuleFilter::default(),
             sender,
         }
     }
@@ -36,6 +40,20 @@ impl<T: Sender<Item = LogRecord>> LogProxy<T> {
     pub fn boxed(name: impl Into<String>, level: LoglevelFilter, sender: T) -> Box<LogProxy<T>> {
         Box::new(LogProxy::new(name, level, sender))
     }
+
+    /// Return a new boxed LogProxy for the provided sender and level, with
+    /// the level overridden for records from the modules specified in the
+    /// given module filter.
+    pub fn boxed_with_modules(
+        name: impl Into<String>,
+        level: LoglevelFilter,
+        modules: ModuleFilter,
+        sender: T,
+    ) -> Box<LogProxy<T>> {
+        let mut proxy = LogProxy::new(name, level, sender);
+        proxy.modules = modules;
+        Box::new(proxy)
+    }
 }
 
 impl<T: Sender<Item = LogRecord>> Log for LogProxy<T> {
@@ -43,9 +61,21 @@ impl<T: Sender<Item = LogRecord>> Log for LogProxy<T> {
         self.name.as_ref()
     }
     fn enabled(&self, level: Loglevel) -> bool {
-        LoglevelFilter::from(level) <= self.level
+        let level = LoglevelFilter::from(level);
+        level <= self.level
+            || self
+                .modules
+                .max_filter()
+                .is_some_and(|filter| level <= filter)
     }
     fn log(&self, record: &LogRecord) {
+        if !self.modules.is_empty()
+            && !self
+                .modules
+                .enabled(self.level, record.level(), record.module_path())
+        {
+            return;
+        }
         self.sender
             .send(record.clone())
             .expect("LogProxy failed to send record");
@@ -54,7 +84,10 @@ impl<T: Sender<Item = LogRecord>> Log for LogProxy<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::log::{proxy::LogProxy, thread::LogThread, Log, Loglevel, LoglevelFilter};
+    use crate::common::log::{
+        module_filter::ModuleFilter, proxy::LogProxy, thread::LogThread, Log, LogRecord, Loglevel,
+        LoglevelFilter,
+    };
 
     #[allow(clippy::cognitive_complexity)]
     #[test]
@@ -133,4 +166,29 @@ mod tests {
         assert!(!log_proxy.enabled(Loglevel::Debug));
         assert!(!log_proxy.enabled(Loglevel::Trace));
     }
+
+    #[test]
+    fn module_filter() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let log_proxy = LogProxy::boxed_with_modules(
+            "proxy",
+            LoglevelFilter::Info,
+            ModuleFilter::new()
+                .with("a::b", LoglevelFilter::Trace)
+                .with("c", LoglevelFilter::Off),
+            sender,
+        );
+        assert!(log_proxy.enabled(Loglevel::Trace));
+
+        let record = |level, module| LogRecord::new("", "", level, module, "", 0u32, 0u32, 0u64);
+        log_proxy.log(&record(Loglevel::Trace, "a::b::x"));
+        log_proxy.log(&record(Loglevel::Trace, "a"));
+        log_proxy.log(&record(Loglevel::Info, "a"));
+        log_proxy.log(&record(Loglevel::Fatal, "c"));
+        let modules: Vec<_> = receiver
+            .try_iter()
+            .map(|record| record.module_path().unwrap().to_string())
+            .collect();
+        assert_eq!(modules, vec!["a::b::x", "a"]);
+    }
 }
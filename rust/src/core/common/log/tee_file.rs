@@ -1,11 +1,19 @@
 use crate::common::{
     error::Result,
-    log::{Log, LogRecord, Loglevel, LoglevelFilter},
+    log::{Log, LogFormat, LogRecord, Loglevel, LoglevelFilter},
     util::friendly_enum_parse,
 };
 use failure::Fail;
+use flate2::{write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::Write, path::PathBuf};
+use std::{
+    cell::{Cell, RefCell},
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
 
 /// Error structure used for reporting TeeFile errors.
 #[derive(Debug, Fail, PartialEq)]
@@ -14,11 +22,63 @@ pub enum TeeFileError {
     ParseError(String),
 }
 
+/// Represents the size-based rotation settings for a tee file.
+///
+/// When writing a record would make the file larger than `max_size`, the file
+/// is renamed to `<file>.1` and a new file is started. Previously rotated
+/// files are shifted up to `<file>.<keep>`; older files are deleted. If
+/// rotation fails, the error is reported on stderr and the records are
+/// written to the current file from then on.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, Serialize)]
+pub struct TeeFileRotation {
+    /// The maximum size of the tee file in bytes.
+    pub max_size: u64,
+    /// The number of rotated files to keep.
+    pub keep: usize,
+    /// Whether rotated files should be compressed using gzip, which adds a
+    /// `.gz` extension to their filenames.
+    pub compress: bool,
+}
+
+impl TeeFileRotation {
+    /// Constructs a new TeeFileRotation with the provided maximum size,
+    /// keeping 5 uncompressed rotated files.
+    pub fn new(max_size: u64) -> TeeFileRotation {
+        TeeFileRotation {
+            max_size,
+            keep: 5,
+            compress: false,
+        }
+    }
+
+    /// Sets the number of rotated files to keep, builder style.
+    pub fn with_keep(mut self, keep: usize) -> TeeFileRotation {
+        self.keep = keep;
+        self
+    }
+
+    /// Sets whether rotated files are compressed, builder style.
+    pub fn with_compression(mut self, compress: bool) -> TeeFileRotation {
+        self.compress = compress;
+        self
+    }
+}
+
 /// Represents a tee file configuration for the logging system.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub struct TeeFileConfiguration {
     pub filter: LoglevelFilter,
     pub file: PathBuf,
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Whether to append to the file if it already exists, instead of
+    /// truncating it.
+    #[serde(default)]
+    pub append: bool,
+    /// Size-based rotation settings, or `None` to let the file grow
+    /// indefinitely.
+    #[serde(default)]
+    pub rotation: Option<TeeFileRotation>,
 }
 
 impl TeeFileConfiguration {
@@ -31,8 +91,40 @@ impl TeeFileConfiguration {
         TeeFileConfiguration {
             filter: filter.into(),
             file: file.into(),
+            format: LogFormat::default(),
+            append: false,
+            rotation: None,
         }
     }
+
+    /// Sets the output format, builder style.
+    pub fn with_format(mut self, format: LogFormat) -> TeeFileConfiguration {
+        self.format = format;
+        self
+    }
+
+    /// Sets whether to append to an existing file, builder style.
+    pub fn with_append(mut self, append: bool) -> TeeFileConfiguration {
+        self.append = append;
+        self
+    }
+
+    /// Enables size-based rotation, builder style.
+    pub fn with_rotation(mut self, rotation: TeeFileRotation) -> TeeFileConfiguration {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Returns the path of the rotated file with the given index, with or
+    /// without the `.gz` extension of compressed files.
+    fn rotated_path(&self, index: usize, compressed: bool) -> PathBuf {
+        let mut path = OsString::from(self.file.as_os_str());
+        path.push(format!(".{}", index));
+        if compressed {
+            path.push(".gz");
+        }
+        path.into()
+    }
 }
 
 /// TeeFile is the combination of a TeeFileConfiguration and a handle to the
@@ -42,20 +134,115 @@ impl TeeFileConfiguration {
 pub struct TeeFile {
     /// The TeeFileConfiguration
     pub configuration: TeeFileConfiguration,
-    /// The file handle, wrapper in an Option to allow implementation of the
-    /// Log trait.
-    buffer: Option<File>,
+    /// The file handle, wrapped in a RefCell to allow the file to be replaced
+    /// on rotation from within the Log trait.
+    buffer: RefCell<Option<File>>,
+    /// The current size of the file, used to determine when to rotate.
+    size: Cell<u64>,
+    /// Set when rotation failed, after which rotation is disabled.
+    rotation_failed: Cell<bool>,
+    /// The thread compressing the most recently rotated file, if any.
+    compression: RefCell<Option<JoinHandle<()>>>,
 }
 
 impl TeeFile {
     /// Constructs a new tee file. Consumes the provided configuration.
     pub fn new(configuration: TeeFileConfiguration) -> Result<TeeFile> {
-        let buffer = Some(File::create(&configuration.file)?);
+        let (buffer, size) = TeeFile::open(&configuration.file, configuration.append)?;
         Ok(TeeFile {
-            buffer,
+            buffer: RefCell::new(Some(buffer)),
+            size: Cell::new(size),
+            rotation_failed: Cell::new(false),
+            compression: RefCell::new(None),
             configuration,
         })
     }
+
+    /// Opens the file at the given path, returning its handle and current
+    /// size.
+    fn open(path: &Path, append: bool) -> io::Result<(File, u64)> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    /// Waits for the compression of the most recently rotated file to
+    /// complete, if it is still running.
+    fn wait_for_compression(&self) {
+        if let Some(compression) = self.compression.borrow_mut().take() {
+            let _ = compression.join();
+        }
+    }
+
+    /// Shifts the rotated files up by one index, deleting the oldest, and
+    /// moves the current file to index 1. Both compressed and uncompressed
+    /// files are shifted, as the compression setting may have changed since
+    /// the files were written.
+    fn shift(&self, rotation: TeeFileRotation) -> io::Result<()> {
+        let file = &self.configuration.file;
+        if rotation.keep == 0 {
+            return fs::remove_file(file);
+        }
+        for &compressed in &[false, true] {
+            let oldest = self.configuration.rotated_path(rotation.keep, compressed);
+            if oldest.exists() {
+                fs::remove_file(oldest)?;
+            }
+            for index in (1..rotation.keep).rev() {
+                let path = self.configuration.rotated_path(index, compressed);
+                if path.exists() {
+                    fs::rename(path, self.configuration.rotated_path(index + 1, compressed))?;
+                }
+            }
+        }
+        let newest = self.configuration.rotated_path(1, false);
+        fs::rename(file, &newest)?;
+
+        // Compress in the background, such that logging does not stall in
+        // the meantime.
+        if rotation.compress {
+            let compressed = self.configuration.rotated_path(1, true);
+            self.compression.replace(Some(thread::spawn(move || {
+                if let Err(e) = TeeFile::compress(&newest, &compressed) {
+                    eprintln!("Failed to compress {}: {}", newest.display(), e);
+                    let _ = fs::remove_file(&compressed);
+                }
+            })));
+        }
+        Ok(())
+    }
+
+    /// Compresses the file at `source` to `destination` using gzip and
+    /// deletes the source file.
+    fn compress(source: &Path, destination: &Path) -> io::Result<()> {
+        let mut encoder = GzEncoder::new(File::create(destination)?, Compression::default());
+        io::copy(&mut File::open(source)?, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+        fs::remove_file(source)
+    }
+
+    /// Moves the current file out of the way and starts a new one. If this
+    /// fails, the records continue to be appended to the current file.
+    fn rotate(&self, rotation: TeeFileRotation) -> io::Result<()> {
+        self.wait_for_compression();
+        self.buffer.replace(None);
+        let result = self.shift(rotation);
+        let (buffer, size) = TeeFile::open(&self.configuration.file, true)?;
+        self.buffer.replace(Some(buffer));
+        self.size.set(size);
+        result
+    }
+}
+
+impl Drop for TeeFile {
+    fn drop(&mut self) {
+        self.wait_for_compression();
+    }
 }
 
 impl Log for TeeFile {
@@ -66,29 +253,120 @@ impl Log for TeeFile {
         LoglevelFilter::from(level) <= self.configuration.filter
     }
     fn log(&self, record: &LogRecord) {
-        if let Some(mut buffer) = self.buffer.as_ref() {
-            writeln!(buffer, "{}", record).expect("Failed to write to file");
+        let line = match self.configuration.format {
+            LogFormat::Text => format!("{}\n", record),
+            LogFormat::Json => format!("{}\n", record.to_json()),
+        };
+        let len = line.len() as u64;
+        if let Some(rotation) = self.configuration.rotation {
+            if !self.rotation_failed.get()
+                && self.size.get() > 0
+                && self.size.get() + len > rotation.max_size
+            {
+                if let Err(e) = self.rotate(rotation) {
+                    eprintln!(
+                        "Failed to rotate {}, no longer rotating it: {}",
+                        self.configuration.file.display(),
+                        e
+                    );
+                    self.rotation_failed.set(true);
+                }
+            }
+        }
+        if let Some(buffer) = self.buffer.borrow_mut().as_mut() {
+            buffer
+                .write_all(line.as_bytes())
+                .expect("Failed to write to file");
+            self.size.set(self.size.get() + len);
         }
     }
 }
 
+/// Parses a size in bytes, optionally suffixed with k, M, or G for binary
+/// kilo-, mega-, or gigabytes.
+fn parse_size(s: &str) -> Option<u64> {
+    let (digits, multiplier) = match s.chars().last()?.to_ascii_lowercase() {
+        'k' => (&s[..s.len() - 1], 1 << 10),
+        'm' => (&s[..s.len() - 1], 1 << 20),
+        'g' => (&s[..s.len() - 1], 1 << 30),
+        _ => (s, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
 impl ::std::str::FromStr for TeeFileConfiguration {
     type Err = failure::Error;
 
     /// Constructs a TeeFile from its string representation, which is of the
-    /// form <filter>:<file>. <filter> is parsed by
+    /// form [<options>:]<filter>:<file>. <filter> is parsed by
     /// `LoglevelFilter::from_str()` and thus supports abbreviations.
+    /// <options> is a comma-separated list of the following:
+    ///
+    ///  - `text` or `json`: selects the output format (default `text`);
+    ///  - `append`: appends to the file if it exists instead of truncating;
+    ///  - `size=<bytes>`: rotates the file when it would exceed the given
+    ///    size, which may be suffixed with k, M, or G;
+    ///  - `keep=<count>`: the number of rotated files to keep (default 5);
+    ///  - `compress`: compresses rotated files with gzip.
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let mut splitter = s.splitn(2, ':');
-        let log_filter = splitter.next().unwrap();
-        let filter: LoglevelFilter = friendly_enum_parse(log_filter)?;
-        let file: PathBuf = splitter
-            .next()
+        let mut log_filter = splitter.next().unwrap();
+        let mut rest = splitter.next();
+        let mut configuration = TeeFileConfiguration::new(LoglevelFilter::Off, "");
+
+        // If the first part isn't a loglevel filter but there's more, it's
+        // the option list.
+        if let (Err(_), Some(remain)) = (friendly_enum_parse::<LoglevelFilter, _>(log_filter), rest)
+        {
+            let mut keep = None;
+            let mut compress = false;
+            for option in log_filter.split(',') {
+                let mut kv = option.splitn(2, '=');
+                let key = kv.next().unwrap().to_lowercase();
+                match (key.as_str(), kv.next()) {
+                    ("text", None) => configuration.format = LogFormat::Text,
+                    ("json", None) => configuration.format = LogFormat::Json,
+                    ("append", None) => configuration.append = true,
+                    ("compress", None) => compress = true,
+                    ("size", Some(size)) => {
+                        configuration.rotation =
+                            Some(TeeFileRotation::new(parse_size(size).ok_or_else(|| {
+                                TeeFileError::ParseError(format!("invalid tee file size {}", size))
+                            })?))
+                    }
+                    ("keep", Some(count)) => {
+                        keep = Some(count.parse().map_err(|_| {
+                            TeeFileError::ParseError(format!("invalid tee file count {}", count))
+                        })?)
+                    }
+                    _ => Err(TeeFileError::ParseError(format!(
+                        "invalid tee file option {}, valid options are text, json, append, size=<bytes>, keep=<count>, or compress",
+                        option
+                    )))?,
+                }
+            }
+            match configuration.rotation.as_mut() {
+                Some(rotation) => {
+                    rotation.keep = keep.unwrap_or(rotation.keep);
+                    rotation.compress = compress;
+                }
+                None if keep.is_some() || compress => Err(TeeFileError::ParseError(
+                    "tee file options keep and compress require size".to_string(),
+                ))?,
+                None => (),
+            }
+            splitter = remain.splitn(2, ':');
+            log_filter = splitter.next().unwrap();
+            rest = splitter.next();
+        }
+
+        configuration.filter = friendly_enum_parse(log_filter)?;
+        configuration.file = rest
             .ok_or_else(|| {
                 TeeFileError::ParseError("expected a colon in tee file description".to_string())
             })?
             .into();
-        Ok(TeeFileConfiguration { filter, file })
+        Ok(configuration)
     }
 }
 
@@ -96,6 +374,23 @@ impl ::std::fmt::Display for TeeFileConfiguration {
     /// Turns the TeeFile object into a string representation that can be
     /// parsed by `from_str()`.
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let mut options = vec![];
+        if self.format != LogFormat::default() {
+            options.push(self.format.to_string().to_lowercase());
+        }
+        if self.append {
+            options.push("append".to_string());
+        }
+        if let Some(rotation) = self.rotation {
+            options.push(format!("size={}", rotation.max_size));
+            options.push(format!("keep={}", rotation.keep));
+            if rotation.compress {
+                options.push("compress".to_string());
+            }
+        }
+        if !options.is_empty() {
+            write!(f, "{}:", options.join(","))?;
+        }
         write!(f, "{}:{}", self.filter, self.file.to_str().unwrap())
     }
 }
@@ -107,12 +402,63 @@ mod test {
     use super::*;
     use std::str::FromStr;
 
+    /// Returns a log record with the given level for the tests to write.
+    fn record(level: Loglevel) -> LogRecord {
+        LogRecord::new(
+            "logger", "message", level, "path", "file", 1234u32, 1u32, 1u64,
+        )
+    }
+
     #[test]
     fn from_str() {
         assert_eq!(
             TeeFileConfiguration::from_str("info:/tmp/hello:/there").unwrap(),
             TeeFileConfiguration::new(LoglevelFilter::Info, "/tmp/hello:/there"),
         );
+        assert_eq!(
+            TeeFileConfiguration::from_str("JSON:t:/tmp/log.jsonl").unwrap(),
+            TeeFileConfiguration::new(LoglevelFilter::Trace, "/tmp/log.jsonl")
+                .with_format(LogFormat::Json),
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("t:json").unwrap(),
+            TeeFileConfiguration::new(LoglevelFilter::Trace, "json"),
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("json,append,size=10M,keep=3,compress:d:/tmp/x")
+                .unwrap(),
+            TeeFileConfiguration::new(LoglevelFilter::Debug, "/tmp/x")
+                .with_format(LogFormat::Json)
+                .with_append(true)
+                .with_rotation(
+                    TeeFileRotation::new(10 << 20)
+                        .with_keep(3)
+                        .with_compression(true)
+                ),
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("size=1024:i:/tmp/x").unwrap(),
+            TeeFileConfiguration::new(LoglevelFilter::Info, "/tmp/x")
+                .with_rotation(TeeFileRotation::new(1024)),
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("size=1x:i:/tmp/x")
+                .unwrap_err()
+                .to_string(),
+            "invalid tee file size 1x"
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("keep=2:i:/tmp/x")
+                .unwrap_err()
+                .to_string(),
+            "tee file options keep and compress require size"
+        );
+        assert_eq!(
+            TeeFileConfiguration::from_str("hello:/tmp/x")
+                .unwrap_err()
+                .to_string(),
+            "invalid tee file option hello, valid options are text, json, append, size=<bytes>, keep=<count>, or compress"
+        );
 
         let tfc = TeeFileConfiguration::from_str("hello");
         assert!(tfc.is_err());
@@ -132,6 +478,19 @@ mod test {
             TeeFileConfiguration::new(LoglevelFilter::Info, "/tmp/hello:/there").to_string(),
             "Info:/tmp/hello:/there",
         );
+        assert_eq!(
+            TeeFileConfiguration::new(LoglevelFilter::Info, "/tmp/log.jsonl")
+                .with_format(LogFormat::Json)
+                .to_string(),
+            "json:Info:/tmp/log.jsonl",
+        );
+        assert_eq!(
+            TeeFileConfiguration::new(LoglevelFilter::Info, "/tmp/log")
+                .with_append(true)
+                .with_rotation(TeeFileRotation::new(1024).with_compression(true))
+                .to_string(),
+            "append,size=1024,keep=5,compress:Info:/tmp/log",
+        );
     }
 
     #[test]
@@ -139,14 +498,14 @@ mod test {
         let tf = TeeFileConfiguration::new(LoglevelFilter::Info, "hello:/there");
         assert_eq!(
             format!("{:?}", tf),
-            "TeeFileConfiguration { filter: Info, file: \"hello:/there\" }"
+            "TeeFileConfiguration { filter: Info, file: \"hello:/there\", format: Text, append: false, rotation: None }"
         );
 
         let tfc = TeeFileConfiguration::new(LoglevelFilter::Trace, "/dev/zero");
         let tf = TeeFile::new(tfc);
 
         assert!(
-            format!("{:?}", tf.unwrap()).starts_with("TeeFile { configuration: TeeFileConfiguration { filter: Trace, file: \"/dev/zero\" }, buffer: Some(File {")
+            format!("{:?}", tf.unwrap()).starts_with("TeeFile { configuration: TeeFileConfiguration { filter: Trace, file: \"/dev/zero\", format: Text, append: false, rotation: None }, buffer: RefCell { value: Some(File {")
         );
     }
 
@@ -156,11 +515,11 @@ mod test {
         let tf_clone = tf.clone();
         assert_eq!(
             format!("{:?}", tf),
-            "TeeFileConfiguration { filter: Info, file: \"/tmp/log.info\" }"
+            "TeeFileConfiguration { filter: Info, file: \"/tmp/log.info\", format: Text, append: false, rotation: None }"
         );
         assert_eq!(
             format!("{:?}", tf_clone),
-            "TeeFileConfiguration { filter: Info, file: \"/tmp/log.info\" }"
+            "TeeFileConfiguration { filter: Info, file: \"/tmp/log.info\", format: Text, append: false, rotation: None }"
         );
     }
 
@@ -180,16 +539,103 @@ mod test {
         assert_eq!(tf.name(), "/tmp/log.info");
         assert!(tf.enabled(Loglevel::Info));
         assert!(!tf.enabled(Loglevel::Trace));
-        let record = LogRecord::new(
-            "logger",
-            "message",
-            Loglevel::Trace,
-            "path",
-            "file",
-            1234u32,
-            1u32,
-            1u64,
-        );
+        let record = record(Loglevel::Trace);
+        tf.log(&record);
+    }
+
+    #[test]
+    fn log_json() {
+        let path = std::env::temp_dir().join(format!("dqcsim-tee-{}.jsonl", std::process::id()));
+        let tfc =
+            TeeFileConfiguration::new(LoglevelFilter::Info, &path).with_format(LogFormat::Json);
+        let tf = TeeFile::new(tfc).unwrap();
+        let record = record(Loglevel::Info);
         tf.log(&record);
+        tf.log(&record);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines, vec![record.to_json(), record.to_json()]);
+    }
+
+    #[test]
+    fn rotate() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-tee-rotate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("log");
+        let record = record(Loglevel::Info);
+        let line_len = record.to_json().len() as u64 + 1;
+
+        // Three records fit in a file, so ten records result in four files,
+        // of which the oldest is deleted.
+        let tfc = TeeFileConfiguration::new(LoglevelFilter::Info, &path)
+            .with_format(LogFormat::Json)
+            .with_rotation(TeeFileRotation::new(line_len * 3).with_keep(2));
+        let tf = TeeFile::new(tfc).unwrap();
+        for _ in 0..10 {
+            tf.log(&record);
+        }
+        drop(tf);
+        let lines = |path: &Path| fs::read_to_string(path).unwrap().lines().count();
+        assert_eq!(lines(&path), 1);
+        assert_eq!(lines(&dir.join("log.1")), 3);
+        assert_eq!(lines(&dir.join("log.2")), 3);
+        assert!(!dir.join("log.3").exists());
+
+        // Appending continues where we left off.
+        let tfc = TeeFileConfiguration::new(LoglevelFilter::Info, &path)
+            .with_format(LogFormat::Json)
+            .with_append(true)
+            .with_rotation(
+                TeeFileRotation::new(line_len * 3)
+                    .with_keep(2)
+                    .with_compression(true),
+            );
+        let tf = TeeFile::new(tfc).unwrap();
+        for _ in 0..3 {
+            tf.log(&record);
+        }
+        drop(tf);
+        assert_eq!(lines(&path), 1);
+        assert!(!dir.join("log.1").exists());
+        let mut decompressed = String::new();
+        io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(File::open(dir.join("log.1.gz")).unwrap()),
+            &mut decompressed,
+        )
+        .unwrap();
+        assert_eq!(decompressed.lines().count(), 3);
+
+        // The uncompressed files of the previous run are shifted and pruned
+        // as well.
+        assert_eq!(lines(&dir.join("log.2")), 3);
+        assert!(!dir.join("log.2.gz").exists());
+        assert!(!dir.join("log.3").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotate_failure() {
+        let dir =
+            std::env::temp_dir().join(format!("dqcsim-tee-rotate-failure-{}", std::process::id()));
+        let path = dir.join("log");
+
+        // A non-empty directory in the place of the oldest rotated file
+        // cannot be deleted.
+        fs::create_dir_all(dir.join("log.1").join("x")).unwrap();
+        let record = record(Loglevel::Info);
+        let line_len = record.to_json().len() as u64 + 1;
+        let tfc = TeeFileConfiguration::new(LoglevelFilter::Info, &path)
+            .with_format(LogFormat::Json)
+            .with_rotation(TeeFileRotation::new(line_len).with_keep(1));
+        let tf = TeeFile::new(tfc).unwrap();
+        for _ in 0..3 {
+            tf.log(&record);
+        }
+        drop(tf);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -8,7 +8,7 @@ use crate::{
             deinit, init,
             proxy::LogProxy,
             tee_file::{TeeFile, TeeFileConfiguration},
-            Log, LogRecord, Loglevel, LoglevelFilter, PID,
+            Log, LogFormat, LogRecord, Loglevel, LoglevelFilter, PID,
         },
     },
     trace,
@@ -37,6 +37,26 @@ impl LogThread {
         stderr_level: LoglevelFilter,
         callback: Option<LogCallback>,
         tee_files: Vec<TeeFileConfiguration>,
+    ) -> Result<LogThread> {
+        LogThread::spawn_with_format(
+            name,
+            proxy_level,
+            stderr_level,
+            LogFormat::Text,
+            callback,
+            tee_files,
+        )
+    }
+
+    /// Spawn a [`LogThread`] like [`spawn`], writing to Standard Error in the
+    /// given [`stderr_format`], being colorized text or JSON lines.
+    pub fn spawn_with_format(
+        name: impl Into<String>,
+        proxy_level: LoglevelFilter,
+        stderr_level: LoglevelFilter,
+        stderr_format: LogFormat,
+        callback: Option<LogCallback>,
+        tee_files: Vec<TeeFileConfiguration>,
     ) -> Result<LogThread> {
         // Create the log channel.
         let (sender, receiver): (_, crossbeam_channel::Receiver<LogRecord>) =
@@ -47,7 +67,8 @@ impl LogThread {
 
         // Spawn the local channel log thread.
         let handler = thread::spawn(move || {
-            let mut t = if stderr_level > LoglevelFilter::Off {
+            let json = stderr_format == LogFormat::Json;
+            let mut t = if stderr_level > LoglevelFilter::Off && !json {
                 // This may return None which results in no logging to stderr
                 stderr()
             } else {
@@ -83,6 +104,11 @@ impl LogThread {
                     .filter(|tf| tf.enabled(record.level()))
                     .for_each(|tf| tf.log(&record));
 
+                // Standard Error as JSON lines
+                if json && level <= stderr_level {
+                    eprintln!("{}", record.to_json());
+                }
+
                 // Standard Error
                 if t.is_some() && level <= stderr_level {
                     let t = t.as_mut().unwrap();
@@ -8,4 +8,5 @@ pub mod error;
 pub mod gates;
 pub mod log;
 pub mod protocol;
+pub mod trace;
 pub mod types;
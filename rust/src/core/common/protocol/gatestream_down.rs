@@ -2,7 +2,7 @@ use crate::common::types::{ArbCmd, Cycles, Gate, QubitRef, SequenceNumber};
 use serde::{Deserialize, Serialize};
 
 /// Gatestream requests/downstream messages.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GatestreamDown {
     /// A pipelined gatestream request.
     ///
@@ -18,6 +18,15 @@ pub enum GatestreamDown {
     /// should not do anything with the sequence number.
     Pipelined(SequenceNumber, PipelinedGatestreamDown),
 
+    /// A batch of pipelined gatestream requests.
+    ///
+    /// This is equivalent to sending the requests as individual `Pipelined`
+    /// messages in the given order, but needs only a single IPC message,
+    /// which matters when there are many cheap requests. The downstream
+    /// plugin may respond to the batch as a whole with a
+    /// `GatestreamUp::Batch` message.
+    PipelinedBatch(Vec<(SequenceNumber, PipelinedGatestreamDown)>),
+
     /// Requests execution of the given `ArbCmd` by the plugin.
     ///
     /// The valid responses to this message are:
@@ -25,10 +34,18 @@ pub enum GatestreamDown {
     ///  - success: `GatestreamUp::ArbSuccess`
     ///  - failure: `GatestreamUp::ArbFailure`
     ArbRequest(ArbCmd),
+
+    /// Requests execution of the given `ArbCmd` by the `host_arb()` callback
+    /// of the plugin, as if it were sent by the host.
+    ///
+    /// This is only sent by the debugger operator, which uses it to inspect
+    /// the plugin it is debugging while the simulation is paused. The valid
+    /// responses are the same as for `ArbRequest`.
+    HostArbRequest(ArbCmd),
 }
 
 /// Enumeration of pipelined gatestream messages.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PipelinedGatestreamDown {
     /// Requests allocation of a number of qubits.
     ///
@@ -43,6 +60,13 @@ pub enum PipelinedGatestreamDown {
     /// Requests execution of a gate.
     Gate(Gate),
 
+    /// Requests that one or more qubits be reset to the |0> state.
+    ///
+    /// In addition to resetting the quantum state, this clears the
+    /// measurement registers and the associated timers of the qubits, such
+    /// that they are indistinguishable from freshly allocated qubits.
+    Reset(Vec<QubitRef>),
+
     /// Advances the simulation by the specified number of cycles.
     Advance(Cycles),
 }
@@ -2,7 +2,7 @@ use crate::common::types::{ArbData, Cycles, QubitMeasurementResult, SequenceNumb
 use serde::{Deserialize, Serialize};
 
 /// Gatestream responses/upstream messages.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GatestreamUp {
     /// Acknowledges one or more requests.
     ///
@@ -31,4 +31,10 @@ pub enum GatestreamUp {
 
     /// Indicates that a `GatestreamDown::ArbRequest` failed.
     ArbFailure(String),
+
+    /// A batch of responses to the requests in a
+    /// `GatestreamDown::PipelinedBatch`, equivalent to sending them as
+    /// individual messages in the given order. Batches do not contain
+    /// `ArbSuccess` or `ArbFailure` messages, nor other batches.
+    Batch(Vec<GatestreamUp>),
 }
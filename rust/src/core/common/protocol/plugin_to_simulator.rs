@@ -1,4 +1,4 @@
-use crate::common::types::{ArbData, PluginMetadata};
+use crate::common::types::{ArbData, ArbInterfaces, PluginMetadata, PluginProfile};
 use serde::{Deserialize, Serialize};
 
 /// Plugin to simulator responses.
@@ -18,6 +18,9 @@ pub enum PluginToSimulator {
 
     /// Success response to `SimulatorToPlugin::ArbRequest`.
     ArbResponse(ArbData),
+
+    /// Success response to `SimulatorToPlugin::ProfileRequest`.
+    ProfileResponse(PluginProfile),
 }
 
 /// Initialization response.
@@ -31,6 +34,10 @@ pub struct PluginInitializeResponse {
 
     /// Plugin metadata information from the `PluginDefinition` structure.
     pub metadata: PluginMetadata,
+
+    /// The `ArbCmd` interfaces and operations supported by the plugin, from
+    /// the `PluginDefinition` structure.
+    pub interfaces: ArbInterfaces,
 }
 
 /// Frontend run response.
@@ -1,7 +1,8 @@
 use crate::{
     common::{
         log::LogRecord,
-        types::{ArbCmd, ArbData, PluginType},
+        trace::TraceRecord,
+        types::{ArbCmd, ArbData, ArbInterfaces, PluginType},
     },
     host::configuration::PluginLogConfiguration,
 };
@@ -93,6 +94,16 @@ pub enum SimulatorToPlugin {
     ///  - success: `PluginToSimulator::ArbResponse`
     ///  - failure: `PluginToSimulator::Failure`
     ArbRequest(ArbCmd),
+
+    /// Requests the performance profile of the plugin, i.e. the time spent in
+    /// its user callbacks, waiting for downstream, and serializing IPC
+    /// messages.
+    ///
+    /// The valid responses to this message are:
+    ///
+    ///  - success: `PluginToSimulator::ProfileResponse`
+    ///  - failure: `PluginToSimulator::Failure`
+    ProfileRequest,
 }
 
 impl Into<SimulatorToPlugin> for ArbCmd {
@@ -110,6 +121,11 @@ pub struct PluginInitializeRequest {
     /// for backends.
     pub downstream: Option<String>,
 
+    /// The `ArbCmd` interfaces and operations understood by the downstream
+    /// plugin, including those forwarded by it to plugins further
+    /// downstream. Ignored by backends.
+    pub downstream_interfaces: ArbInterfaces,
+
     /// The expected plugin type.
     pub plugin_type: PluginType,
 
@@ -122,6 +138,11 @@ pub struct PluginInitializeRequest {
     /// Sender side of the log channel. Can be used by a Plugin to send log
     /// records to the simulator.
     pub log_channel: IpcSender<LogRecord>,
+
+    /// Sender side of the gatestream trace channel, if gatestream tracing is
+    /// enabled. Plugins with a downstream connection must use this to record
+    /// every message they send to and receive from the downstream plugin.
+    pub trace_channel: Option<IpcSender<TraceRecord>>,
 }
 
 impl Into<SimulatorToPlugin> for PluginInitializeRequest {
@@ -133,6 +154,7 @@ impl Into<SimulatorToPlugin> for PluginInitializeRequest {
 impl PartialEq for PluginInitializeRequest {
     fn eq(&self, other: &PluginInitializeRequest) -> bool {
         self.downstream == other.downstream
+            && self.downstream_interfaces == other.downstream_interfaces
             && self.plugin_type == other.plugin_type
             && self.log_configuration == other.log_configuration
     }
@@ -0,0 +1,687 @@
+//! Gatestream trace recording.
+//!
+//! When enabled through [`SimulatorConfiguration::with_gatestream_trace()`],
+//! every message that crosses a plugin boundary is recorded into a trace
+//! file, for post-mortem analysis of what the plugins actually sent each
+//! other.
+//!
+//! The recording is done by the plugin on the upstream side of each
+//! boundary. It sends a [`TraceRecord`] to the [`TraceThread`] of the
+//! simulator for every `GatestreamDown` message it sends and for every
+//! `GatestreamUp` message it receives. Each record carries the wall-clock
+//! time at which it was made and the downstream simulation cycle as known by
+//! the upstream plugin at that time; sequence numbers are part of the
+//! messages themselves. Batched messages are recorded as the individual
+//! messages they contain, so traces do not depend on how messages are
+//! batched.
+//!
+//! Trace files are CBOR sequences: a [`TraceHeader`] followed by any number
+//! of [`TraceRecord`]s, in the order in which the simulator received them.
+//! Records of a single boundary are always in order; records of different
+//! boundaries may interleave arbitrarily. A [`TraceWriter`] produces such a
+//! file, a [`TraceReader`] reads it back. [`TraceFilter`] and
+//! [`TraceSummary`] can be used to analyze the records.
+//!
+//! [`SimulatorConfiguration::with_gatestream_trace()`]: ../../host/configuration/struct.SimulatorConfiguration.html#method.with_gatestream_trace
+//! [`TraceRecord`]: ./struct.TraceRecord.html
+//! [`TraceThread`]: ./thread/struct.TraceThread.html
+//! [`TraceHeader`]: ./struct.TraceHeader.html
+//! [`TraceWriter`]: ./struct.TraceWriter.html
+//! [`TraceReader`]: ./struct.TraceReader.html
+//! [`TraceFilter`]: ./struct.TraceFilter.html
+//! [`TraceSummary`]: ./struct.TraceSummary.html
+
+pub mod thread;
+
+use crate::common::{
+    error::{inv_arg, Result},
+    protocol::{GatestreamDown, GatestreamUp, PipelinedGatestreamDown},
+    types::{with_inline_serialization, Cycle, SequenceNumber},
+};
+use ipc_channel::ipc::IpcSender;
+use named_type::NamedType;
+use named_type_derive::*;
+use serde::{Deserialize, Serialize};
+use serde_cbor::de::{IoRead, StreamDeserializer};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    time::SystemTime,
+};
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// The version of the trace file format written by this version of DQCsim.
+pub const TRACE_VERSION: u32 = 1;
+
+/// Header at the start of every trace file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    /// Trace file format version, see `TRACE_VERSION`.
+    pub version: u32,
+
+    /// The instance names of the plugins in the pipeline, from front to
+    /// back.
+    pub plugins: Vec<String>,
+}
+
+impl TraceHeader {
+    /// Constructs a header for the current trace file format version.
+    pub fn new(plugins: Vec<String>) -> TraceHeader {
+        TraceHeader {
+            version: TRACE_VERSION,
+            plugins,
+        }
+    }
+
+    /// Returns the instance name of the plugin downstream of the given
+    /// plugin, if known.
+    pub fn downstream_of(&self, plugin: &str) -> Option<&str> {
+        self.plugins
+            .iter()
+            .position(|name| name == plugin)
+            .and_then(|idx| self.plugins.get(idx + 1))
+            .map(String::as_str)
+    }
+}
+
+/// The direction in which a traced message crossed a plugin boundary.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    EnumIter,
+    NamedType,
+)]
+pub enum TraceDirection {
+    /// A `GatestreamDown` message, sent from upstream to downstream.
+    #[strum(to_string = "Down", serialize = "down")]
+    Down,
+    /// A `GatestreamUp` message, sent from downstream to upstream.
+    #[strum(to_string = "Up", serialize = "up")]
+    Up,
+}
+
+/// A traced gatestream message. This is never a batch; batched messages are
+/// recorded as the individual messages they contain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TraceMessage {
+    /// A message sent from upstream to downstream.
+    Down(GatestreamDown),
+    /// A message sent from downstream to upstream.
+    Up(GatestreamUp),
+}
+
+impl TraceMessage {
+    /// Returns the direction in which the message was sent.
+    pub fn direction(&self) -> TraceDirection {
+        match self {
+            TraceMessage::Down(_) => TraceDirection::Down,
+            TraceMessage::Up(_) => TraceDirection::Up,
+        }
+    }
+
+    /// Returns whether this is a batch message, which is never recorded.
+    fn is_batch(&self) -> bool {
+        matches!(
+            self,
+            TraceMessage::Down(GatestreamDown::PipelinedBatch(_))
+                | TraceMessage::Up(GatestreamUp::Batch(_))
+        )
+    }
+
+    /// Returns a short name for the kind of message. These names are used by
+    /// `TraceFilter` and `TraceSummary`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message is a batch.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TraceMessage::Down(GatestreamDown::Pipelined(_, message)) => match message {
+                PipelinedGatestreamDown::Allocate(..) => "allocate",
+                PipelinedGatestreamDown::Free(_) => "free",
+                PipelinedGatestreamDown::Gate(_) => "gate",
+                PipelinedGatestreamDown::Reset(_) => "reset",
+                PipelinedGatestreamDown::Advance(_) => "advance",
+            },
+            TraceMessage::Down(GatestreamDown::PipelinedBatch(_)) => {
+                unreachable!("batches are recorded as their individual messages")
+            }
+            TraceMessage::Down(GatestreamDown::ArbRequest(_)) => "arb",
+            TraceMessage::Down(GatestreamDown::HostArbRequest(_)) => "host_arb",
+            TraceMessage::Up(message) => match message {
+                GatestreamUp::CompletedUpTo(_) => "completed",
+                GatestreamUp::Failure(..) => "failure",
+                GatestreamUp::Measured(_) => "measured",
+                GatestreamUp::Advanced(_) => "advanced",
+                GatestreamUp::ArbSuccess(_) => "arb_success",
+                GatestreamUp::ArbFailure(_) => "arb_failure",
+                GatestreamUp::Batch(_) => {
+                    unreachable!("batches are recorded as their individual messages")
+                }
+            },
+        }
+    }
+
+    /// Returns the sequence number carried by the message, if any.
+    pub fn sequence(&self) -> Option<SequenceNumber> {
+        match self {
+            TraceMessage::Down(GatestreamDown::Pipelined(sequence, _)) => Some(*sequence),
+            TraceMessage::Up(GatestreamUp::CompletedUpTo(sequence)) => Some(*sequence),
+            TraceMessage::Up(GatestreamUp::Failure(sequence, _)) => Some(*sequence),
+            _ => None,
+        }
+    }
+}
+
+/// A single message crossing a plugin boundary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// The instance name of the plugin on the upstream side of the boundary,
+    /// which made the record.
+    pub plugin: String,
+
+    /// The time at which the record was made.
+    pub timestamp: SystemTime,
+
+    /// The downstream simulation cycle as known by the upstream plugin when
+    /// the record was made. For downstream messages this is the cycle at
+    /// which the request was issued, for upstream messages this is the cycle
+    /// up to which the downstream plugin had reported advancing before the
+    /// message was received.
+    pub cycle: Cycle,
+
+    /// The message.
+    pub message: TraceMessage,
+}
+
+impl TraceRecord {
+    /// Constructs a record for the given message with the current time as
+    /// its timestamp.
+    pub fn new(plugin: impl Into<String>, cycle: Cycle, message: TraceMessage) -> TraceRecord {
+        TraceRecord {
+            plugin: plugin.into(),
+            timestamp: SystemTime::now(),
+            cycle,
+            message,
+        }
+    }
+
+    /// Returns this record as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "timestamp": humantime::format_rfc3339_nanos(self.timestamp).to_string(),
+            "plugin": self.plugin,
+            "direction": self.message.direction().to_string(),
+            "kind": self.message.kind(),
+            "sequence": self.message.sequence().map(|sequence| sequence.to_string()),
+            "cycle": self.cycle.to_string(),
+            "message": self.message,
+        })
+        .to_string()
+    }
+}
+
+impl fmt::Display for TraceRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (arrow, message) = match &self.message {
+            TraceMessage::Down(message) => ("->", format!("{:?}", message)),
+            TraceMessage::Up(message) => ("<-", format!("{:?}", message)),
+        };
+        write!(
+            f,
+            "{} {:>8} {} {} {}",
+            humantime::format_rfc3339_nanos(self.timestamp),
+            self.cycle,
+            self.plugin,
+            arrow,
+            message
+        )
+    }
+}
+
+/// Plugin-side handle for recording gatestream messages.
+#[derive(Debug)]
+pub struct Tracer {
+    /// The instance name of the plugin making the records.
+    plugin: String,
+
+    /// Channel to the trace thread of the simulator.
+    sender: IpcSender<TraceRecord>,
+}
+
+impl Tracer {
+    /// Constructs a tracer for the plugin with the given instance name.
+    pub fn new(plugin: impl Into<String>, sender: IpcSender<TraceRecord>) -> Tracer {
+        Tracer {
+            plugin: plugin.into(),
+            sender,
+        }
+    }
+
+    /// Records a message at the given downstream simulation cycle.
+    pub fn record(&self, cycle: Cycle, message: TraceMessage) -> Result<()> {
+        self.sender
+            .send(TraceRecord::new(self.plugin.clone(), cycle, message))?;
+        Ok(())
+    }
+}
+
+/// Writes trace files.
+pub struct TraceWriter<W: Write> {
+    writer: W,
+}
+
+impl TraceWriter<BufWriter<File>> {
+    /// Creates a trace file at the given path, overwriting any existing file.
+    pub fn create(path: impl AsRef<Path>, header: &TraceHeader) -> Result<Self> {
+        TraceWriter::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Constructs a trace writer, immediately writing the header.
+    pub fn new(mut writer: W, header: &TraceHeader) -> Result<TraceWriter<W>> {
+        serde_cbor::to_writer(&mut writer, header)?;
+        Ok(TraceWriter { writer })
+    }
+
+    /// Writes a record. Any shared memory segments in the message are written
+    /// out inline.
+    pub fn write(&mut self, record: &TraceRecord) -> Result<()> {
+        with_inline_serialization(|| serde_cbor::to_writer(&mut self.writer, record))?;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads trace files.
+///
+/// The header is read on construction, after which the reader iterates over
+/// the records.
+pub struct TraceReader<R: Read> {
+    header: TraceHeader,
+    records: StreamDeserializer<'static, IoRead<R>, TraceRecord>,
+}
+
+impl TraceReader<BufReader<File>> {
+    /// Opens the trace file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        TraceReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> TraceReader<R> {
+    /// Constructs a trace reader, immediately reading and checking the
+    /// header.
+    pub fn new(reader: R) -> Result<TraceReader<R>> {
+        let mut deserializer = serde_cbor::Deserializer::from_reader(reader);
+        let header = TraceHeader::deserialize(&mut deserializer)
+            .or_else(|e| inv_arg(format!("not a gatestream trace file: {}", e)))?;
+        if header.version != TRACE_VERSION {
+            inv_arg(format!(
+                "unsupported gatestream trace file version {}, expected {}",
+                header.version, TRACE_VERSION
+            ))?;
+        }
+        Ok(TraceReader {
+            header,
+            records: deserializer.into_iter(),
+        })
+    }
+
+    /// Returns the header of the trace file.
+    pub fn header(&self) -> &TraceHeader {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for TraceReader<R> {
+    type Item = Result<TraceRecord>;
+
+    fn next(&mut self) -> Option<Result<TraceRecord>> {
+        let records = &mut self.records;
+        with_inline_serialization(|| records.next()).map(|record| {
+            let record = record?;
+            if record.message.is_batch() {
+                inv_arg("gatestream trace file contains a batch message")?;
+            }
+            Ok(record)
+        })
+    }
+}
+
+/// Selects trace records by boundary, direction, message kind, and cycle.
+///
+/// An unset criterion matches all records.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceFilter {
+    /// Only match records made by the plugin with this instance name, i.e.
+    /// the boundary downstream of it.
+    pub plugin: Option<String>,
+
+    /// Only match records in this direction.
+    pub direction: Option<TraceDirection>,
+
+    /// Only match records with one of these message kinds, as returned by
+    /// `TraceMessage::kind()`. An empty list matches all kinds.
+    pub kinds: Vec<String>,
+
+    /// Only match records at or after this cycle.
+    pub from_cycle: Option<i64>,
+
+    /// Only match records at or before this cycle.
+    pub to_cycle: Option<i64>,
+}
+
+impl TraceFilter {
+    /// Constructs a filter that matches all records.
+    pub fn new() -> TraceFilter {
+        TraceFilter::default()
+    }
+
+    /// Only match records made by the given plugin, builder style.
+    pub fn with_plugin(mut self, plugin: impl Into<String>) -> TraceFilter {
+        self.plugin = Some(plugin.into());
+        self
+    }
+
+    /// Only match records in the given direction, builder style.
+    pub fn with_direction(mut self, direction: TraceDirection) -> TraceFilter {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Also match records of the given message kind, builder style.
+    pub fn with_kind(mut self, kind: impl Into<String>) -> TraceFilter {
+        self.kinds.push(kind.into());
+        self
+    }
+
+    /// Only match records within the given (inclusive) cycle range, builder
+    /// style.
+    pub fn with_cycles(mut self, from: Option<i64>, to: Option<i64>) -> TraceFilter {
+        self.from_cycle = from;
+        self.to_cycle = to;
+        self
+    }
+
+    /// Returns whether the given record matches this filter.
+    pub fn matches(&self, record: &TraceRecord) -> bool {
+        let cycle: i64 = record.cycle.into();
+        self.plugin.as_ref().is_none_or(|p| *p == record.plugin)
+            && self
+                .direction
+                .is_none_or(|d| d == record.message.direction())
+            && (self.kinds.is_empty() || self.kinds.iter().any(|k| k == record.message.kind()))
+            && self.from_cycle.is_none_or(|from| cycle >= from)
+            && self.to_cycle.is_none_or(|to| cycle <= to)
+    }
+}
+
+/// Statistics for the records of a single plugin boundary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoundarySummary {
+    /// Number of records per message kind.
+    pub counts: BTreeMap<&'static str, usize>,
+
+    /// Timestamp of the first record.
+    pub first: Option<SystemTime>,
+
+    /// Timestamp of the last record.
+    pub last: Option<SystemTime>,
+
+    /// The cycle of the last record.
+    pub cycle: Option<Cycle>,
+
+    /// The last sequence number sent downstream.
+    pub sent_up_to: Option<SequenceNumber>,
+
+    /// The last sequence number acknowledged by the downstream plugin.
+    pub completed_up_to: Option<SequenceNumber>,
+}
+
+impl BoundarySummary {
+    /// Returns the total number of records in the given direction.
+    pub fn total(&self, direction: TraceDirection) -> usize {
+        let down = matches!(direction, TraceDirection::Down);
+        self.counts
+            .iter()
+            .filter(|(kind, _)| DOWN_KINDS.contains(kind) == down)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// The message kinds of downstream messages.
+const DOWN_KINDS: &[&str] = &[
+    "allocate", "free", "gate", "reset", "advance", "arb", "host_arb",
+];
+
+/// Summary of a trace, per plugin boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSummary {
+    /// The header of the summarized trace.
+    pub header: TraceHeader,
+
+    /// Summary per boundary, identified by the instance name of the upstream
+    /// plugin.
+    pub boundaries: BTreeMap<String, BoundarySummary>,
+}
+
+impl TraceSummary {
+    /// Constructs an empty summary for the trace with the given header.
+    pub fn new(header: TraceHeader) -> TraceSummary {
+        TraceSummary {
+            header,
+            boundaries: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a record to the summary.
+    pub fn add(&mut self, record: &TraceRecord) {
+        let boundary = self.boundaries.entry(record.plugin.clone()).or_default();
+        *boundary.counts.entry(record.message.kind()).or_default() += 1;
+        boundary.first.get_or_insert(record.timestamp);
+        boundary.last.replace(record.timestamp);
+        boundary.cycle.replace(record.cycle);
+        match &record.message {
+            TraceMessage::Down(GatestreamDown::Pipelined(sequence, _)) => {
+                boundary.sent_up_to.replace(*sequence);
+            }
+            TraceMessage::Up(GatestreamUp::CompletedUpTo(sequence)) => {
+                boundary.completed_up_to.replace(*sequence);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for TraceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // List the boundaries in pipeline order, followed by any boundaries
+        // recorded by plugins that are not in the header.
+        let mut names: Vec<&str> = self
+            .header
+            .plugins
+            .iter()
+            .map(String::as_str)
+            .filter(|name| self.boundaries.contains_key(*name))
+            .collect();
+        for name in self.boundaries.keys() {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        if names.is_empty() {
+            return writeln!(f, "No gatestream messages recorded.");
+        }
+        for name in names {
+            let boundary = &self.boundaries[name];
+            writeln!(
+                f,
+                "{} -> {}:",
+                name,
+                self.header.downstream_of(name).unwrap_or("?")
+            )?;
+            writeln!(
+                f,
+                "  messages: {} down, {} up",
+                boundary.total(TraceDirection::Down),
+                boundary.total(TraceDirection::Up)
+            )?;
+            for (kind, count) in &boundary.counts {
+                writeln!(f, "    {:<12} {}", kind, count)?;
+            }
+            if let (Some(first), Some(last)) = (boundary.first, boundary.last) {
+                writeln!(
+                    f,
+                    "  duration: {}",
+                    humantime::format_duration(last.duration_since(first).unwrap_or_default())
+                )?;
+            }
+            if let Some(cycle) = boundary.cycle {
+                writeln!(f, "  final cycle: {}", cycle)?;
+            }
+            let sequence = |s: Option<SequenceNumber>| match s {
+                Some(s) => s.to_string(),
+                None => "-".to_string(),
+            };
+            writeln!(
+                f,
+                "  sequence: sent up to {}, completed up to {}",
+                sequence(boundary.sent_up_to),
+                sequence(boundary.completed_up_to)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{ArbCmd, ArbData, QubitRef, SequenceNumberGenerator};
+
+    fn records() -> Vec<TraceRecord> {
+        let mut sequence = SequenceNumberGenerator::new();
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let arb = ArbData::default().with_shared(vec![1, 2, 3]);
+        vec![
+            TraceRecord::new(
+                "front",
+                Cycle::t_zero(),
+                TraceMessage::Down(GatestreamDown::Pipelined(
+                    sequence.get_next(),
+                    PipelinedGatestreamDown::Allocate(1, vec![]),
+                )),
+            ),
+            TraceRecord::new(
+                "front",
+                Cycle::t_zero(),
+                TraceMessage::Down(GatestreamDown::Pipelined(
+                    sequence.get_next(),
+                    PipelinedGatestreamDown::Free(vec![q1]),
+                )),
+            ),
+            TraceRecord::new(
+                "op1",
+                Cycle::t_zero().advance(5),
+                TraceMessage::Down(GatestreamDown::ArbRequest(ArbCmd::new("a", "b", arb))),
+            ),
+            TraceRecord::new(
+                "front",
+                Cycle::t_zero(),
+                TraceMessage::Up(GatestreamUp::CompletedUpTo(sequence.get_previous())),
+            ),
+        ]
+    }
+
+    #[test]
+    fn write_read() {
+        let header = TraceHeader::new(vec!["front".into(), "op1".into(), "back".into()]);
+        let records = records();
+        let mut writer = TraceWriter::new(vec![], &header).unwrap();
+        for record in &records {
+            writer.write(record).unwrap();
+        }
+        let data = writer.writer;
+
+        let reader = TraceReader::new(&data[..]).unwrap();
+        assert_eq!(reader.header(), &header);
+        assert_eq!(reader.header().downstream_of("op1"), Some("back"));
+        assert_eq!(reader.header().downstream_of("back"), None);
+        let read: Vec<_> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(read, records);
+
+        // Truncated records result in an error.
+        let mut reader = TraceReader::new(&data[..data.len() - 1]).unwrap();
+        assert!(reader.nth(3).unwrap().is_err());
+
+        assert_eq!(
+            TraceReader::new(&b"hello"[..])
+                .err()
+                .unwrap()
+                .to_string()
+                .split(':')
+                .take(2)
+                .collect::<Vec<_>>(),
+            vec!["Invalid argument", " not a gatestream trace file"]
+        );
+    }
+
+    #[test]
+    fn filter() {
+        let records = records();
+        let count = |filter: TraceFilter| records.iter().filter(|r| filter.matches(r)).count();
+        assert_eq!(count(TraceFilter::new()), 4);
+        assert_eq!(count(TraceFilter::new().with_plugin("front")), 3);
+        assert_eq!(
+            count(TraceFilter::new().with_direction(TraceDirection::Up)),
+            1
+        );
+        assert_eq!(
+            count(TraceFilter::new().with_kind("free").with_kind("arb")),
+            2
+        );
+        assert_eq!(count(TraceFilter::new().with_cycles(Some(1), None)), 1);
+        assert_eq!(count(TraceFilter::new().with_cycles(None, Some(4))), 3);
+        assert_eq!(records[2].message.kind(), "arb");
+        assert_eq!(records[2].message.sequence(), None);
+    }
+
+    #[test]
+    fn summary() {
+        let mut summary = TraceSummary::new(TraceHeader::new(vec![
+            "front".into(),
+            "op1".into(),
+            "back".into(),
+        ]));
+        for record in records() {
+            summary.add(&record);
+        }
+        let front = &summary.boundaries["front"];
+        assert_eq!(front.total(TraceDirection::Down), 2);
+        assert_eq!(front.total(TraceDirection::Up), 1);
+        assert_eq!(front.sent_up_to.unwrap().to_string(), "2");
+        assert_eq!(front.completed_up_to, front.sent_up_to);
+        let text = summary.to_string();
+        assert!(text.starts_with("front -> op1:\n  messages: 2 down, 1 up\n"));
+        assert!(text.contains("op1 -> back:\n  messages: 1 down, 0 up\n    arb          1\n"));
+    }
+}
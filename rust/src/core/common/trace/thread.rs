@@ -0,0 +1,119 @@
+//! A trace thread implementation.
+
+use crate::{
+    common::{
+        error::{err, Result},
+        trace::{TraceHeader, TraceRecord, TraceWriter},
+    },
+    error, trace,
+};
+use ipc_channel::ipc::IpcSender;
+use std::{path::PathBuf, thread};
+
+/// Thread that receives gatestream trace records from the plugins and writes
+/// them to a trace file.
+///
+/// The thread terminates when all senders have been dropped. Thus, the
+/// plugins must be dropped before the `TraceThread` is.
+#[derive(Debug)]
+pub struct TraceThread {
+    ipc_sender: Option<IpcSender<TraceRecord>>,
+    handler: Option<thread::JoinHandle<Result<()>>>,
+}
+
+impl TraceThread {
+    /// Spawns a [`TraceThread`] writing to the trace file at the given path
+    /// for a pipeline consisting of the given plugins.
+    ///
+    /// The file is created before this returns, so failure to do so is
+    /// reported immediately.
+    pub fn spawn(path: impl Into<PathBuf>, plugins: Vec<String>) -> Result<TraceThread> {
+        let path = path.into();
+        let mut writer = TraceWriter::create(&path, &TraceHeader::new(plugins))
+            .or_else(|e| err(format!("failed to create trace file {:?}: {}", path, e)))?;
+
+        let (ipc_sender, ipc_receiver) = ipc_channel::ipc::channel::<TraceRecord>()?;
+
+        let handler = thread::spawn(move || {
+            // Keep receiving records after a write error, such that the
+            // plugins don't fail on a closed channel.
+            let mut result = Ok(());
+            while let Ok(record) = ipc_receiver.recv() {
+                if result.is_ok() {
+                    result = writer.write(&record);
+                }
+            }
+            result.and_then(|_| writer.flush())
+        });
+
+        trace!("TraceThread started");
+
+        Ok(TraceThread {
+            ipc_sender: Some(ipc_sender),
+            handler: Some(handler),
+        })
+    }
+
+    pub fn get_ipc_sender(&self) -> IpcSender<TraceRecord> {
+        self.ipc_sender.clone().unwrap()
+    }
+}
+
+/// Drops the sender side of the trace channel and waits for the trace thread
+/// to finish writing.
+impl Drop for TraceThread {
+    fn drop(&mut self) {
+        trace!("Dropping TraceThread");
+
+        self.ipc_sender = None;
+
+        match self
+            .handler
+            .take()
+            .expect("TraceThread failed to start")
+            .join()
+            .expect("TraceThread failed to terminate")
+        {
+            Ok(_) => (),
+            Err(e) => error!("Failed to write gatestream trace: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        protocol::GatestreamUp,
+        trace::{TraceMessage, TraceReader},
+        types::Cycle,
+    };
+
+    #[test]
+    fn write() {
+        let path = std::env::temp_dir().join(format!("dqcsim-trace-{}", std::process::id()));
+        let thread = TraceThread::spawn(&path, vec!["a".into(), "b".into()]).unwrap();
+        let sender = thread.get_ipc_sender();
+        sender
+            .send(TraceRecord::new(
+                "a",
+                Cycle::t_zero(),
+                TraceMessage::Up(GatestreamUp::Advanced(3)),
+            ))
+            .unwrap();
+        drop(sender);
+        drop(thread);
+
+        let reader = TraceReader::open(&path).unwrap();
+        assert_eq!(reader.header().plugins, vec!["a", "b"]);
+        let records: Vec<_> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message.kind(), "advanced");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(TraceThread::spawn("/nonexistent/trace", vec![])
+            .unwrap_err()
+            .to_string()
+            .contains("failed to create trace file"));
+    }
+}
@@ -15,7 +15,7 @@ pub struct ArbCmd {
 
 impl ArbCmd {
     /// Verifies that the given identifier does not contain invalid characters.
-    fn verify_id(id: String) -> Result<String> {
+    pub(crate) fn verify_id(id: String) -> Result<String> {
         if id.chars().any(|x| !(x.is_ascii_alphanumeric() || x == '_')) {
             inv_arg(format!(
                 "\"{}\" is not a valid identifier; it contains characters outside [a-zA-Z0-9_]",
@@ -1,5 +1,8 @@
-use crate::common::error::{inv_arg, Error, Result};
-use serde::{Deserialize, Serialize};
+use crate::common::{
+    error::{inv_arg, Error, Result},
+    types::SharedBytes,
+};
+use serde::{de::DeserializeOwned, ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::fmt;
 
 mod cbor_canon {
@@ -442,14 +445,39 @@ const EMPTY_CBOR: &[u8] = &[0xA0];
 
 /// Represents an ArbData structure, consisting of an (unparsed, TODO) JSON
 /// string and a list of binary strings.
-#[derive(Clone, Hash, PartialEq, Deserialize, Serialize)]
+///
+/// Large binary strings can additionally be attached as shared memory
+/// segments, which are transferred between processes without copying. These
+/// are kept in a separate list, and are not part of the string
+/// representation of the object.
+#[derive(Clone, Hash, PartialEq, Deserialize)]
 pub struct ArbData {
     cbor: Vec<u8>,
     args: Vec<Vec<u8>>,
+    #[serde(default)]
+    shared: Vec<SharedBytes>,
 }
 
 impl Eq for ArbData {}
 
+impl Serialize for ArbData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        // Leave out the shared memory arguments in human-readable formats
+        // when there are none, such that reproduction files look the same as
+        // before they were introduced. Binary formats always need all fields.
+        let with_shared = !serializer.is_human_readable() || !self.shared.is_empty();
+        let mut state = serializer.serialize_struct("ArbData", 2 + with_shared as usize)?;
+        state.serialize_field("cbor", &self.cbor)?;
+        state.serialize_field("args", &self.args)?;
+        if with_shared {
+            state.serialize_field("shared", &self.shared)?;
+        } else {
+            state.skip_field("shared")?;
+        }
+        state.end()
+    }
+}
+
 impl fmt::Debug for ArbData {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         /*let mut output: Vec<u8> = vec![];
@@ -459,10 +487,12 @@ impl fmt::Debug for ArbData {
         let output = String::from_utf8(output).unwrap();*/
         let value: serde_cbor::Value = serde_cbor::from_slice(&self.cbor).unwrap();
 
-        fmt.debug_struct("ArbData")
-            .field("json", &value)
-            .field("args", &self.args)
-            .finish()
+        let mut fmt = fmt.debug_struct("ArbData");
+        fmt.field("json", &value).field("args", &self.args);
+        if !self.shared.is_empty() {
+            fmt.field("shared", &self.shared);
+        }
+        fmt.finish()
     }
 }
 
@@ -645,6 +675,7 @@ impl ArbData {
         Ok(ArbData {
             cbor: EMPTY_CBOR.to_owned(),
             args: ArbData::scan_unstructured_args(&mut s.chars())?,
+            shared: vec![],
         })
     }
 
@@ -654,6 +685,7 @@ impl ArbData {
         ArbData {
             cbor: EMPTY_CBOR.to_owned(),
             args: args.into(),
+            shared: vec![],
         }
     }
 
@@ -663,6 +695,7 @@ impl ArbData {
         let mut arb_data = ArbData {
             cbor: vec![],
             args: args.into(),
+            shared: vec![],
         };
         arb_data.set_cbor(cbor)?;
         Ok(arb_data)
@@ -674,11 +707,39 @@ impl ArbData {
         let mut arb_data = ArbData {
             cbor: vec![],
             args: args.into(),
+            shared: vec![],
         };
         arb_data.set_json(json)?;
         Ok(arb_data)
     }
 
+    /// Construct an `ArbData` from a serializable value for the JSON/CBOR
+    /// object and binary arguments.
+    ///
+    /// This allows Rust plugins to exchange their own `#[derive(Serialize)]`
+    /// structures directly. Use `deserialize()` to get the value back.
+    pub fn from_serialize(value: &impl Serialize, args: impl Into<Vec<Vec<u8>>>) -> Result<Self> {
+        let mut arb_data = ArbData {
+            cbor: vec![],
+            args: args.into(),
+            shared: vec![],
+        };
+        arb_data.set_serialize(value)?;
+        Ok(arb_data)
+    }
+
+    /// Deserializes the JSON/CBOR data field into a value of the given type.
+    ///
+    /// This is the reverse of `from_serialize()` and `set_serialize()`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_cbor::from_slice(&self.cbor).or_else(|e| {
+            inv_arg(format!(
+                "failed to deserialize ArbData JSON/CBOR object: {}",
+                e
+            ))
+        })
+    }
+
     /// Returns the JSON/CBOR data field as a JSON string.
     pub fn get_json(&self) -> Result<String> {
         let mut output: Vec<u8> = vec![];
@@ -703,6 +764,16 @@ impl ArbData {
         &mut self.args
     }
 
+    /// Provides a reference to the shared memory argument vector.
+    pub fn get_shared(&self) -> &[SharedBytes] {
+        &self.shared
+    }
+
+    /// Provides a mutable reference to the shared memory argument vector.
+    pub fn get_shared_mut(&mut self) -> &mut Vec<SharedBytes> {
+        &mut self.shared
+    }
+
     /// Sets the JSON/CBOR data field by means of a JSON string.
     pub fn set_json(&mut self, json: impl AsRef<str>) -> Result<()> {
         let mut output: Vec<u8> = vec![];
@@ -722,11 +793,27 @@ impl ArbData {
         Ok(())
     }
 
+    /// Sets the JSON/CBOR data field by serializing the given value.
+    pub fn set_serialize(&mut self, value: &impl Serialize) -> Result<()> {
+        self.set_cbor(serde_cbor::to_vec(value)?)
+    }
+
     /// Provides a reference to the binary argument vector.
     pub fn set_args(&mut self, args: impl Into<Vec<Vec<u8>>>) {
         self.args = args.into();
     }
 
+    /// Replaces the shared memory argument vector.
+    pub fn set_shared(&mut self, shared: impl Into<Vec<SharedBytes>>) {
+        self.shared = shared.into();
+    }
+
+    /// Attaches a shared memory argument, builder style.
+    pub fn with_shared(mut self, shared: impl Into<SharedBytes>) -> Self {
+        self.shared.push(shared.into());
+        self
+    }
+
     /// Resets the CBOR to an empty object.
     pub fn clear_cbor(&mut self) {
         self.set_cbor(EMPTY_CBOR).unwrap();
@@ -737,16 +824,24 @@ impl ArbData {
         self.args.clear();
     }
 
-    /// Clears the CBOR object and binary arguments vector.
+    /// Clears the shared memory arguments vector.
+    pub fn clear_shared(&mut self) {
+        self.shared.clear();
+    }
+
+    /// Clears the CBOR object, binary arguments vector, and shared memory
+    /// arguments vector.
     pub fn clear(&mut self) {
         self.clear_cbor();
         self.clear_args();
+        self.clear_shared();
     }
 
     /// Copies the data from another ArbData to this one.
     pub fn copy_from(&mut self, src: &ArbData) {
         self.cbor = src.get_cbor().to_vec();
         self.args = src.get_args().to_vec();
+        self.shared = src.get_shared().to_vec();
     }
 }
 
@@ -772,6 +867,7 @@ impl ::std::str::FromStr for ArbData {
         let mut output = ArbData {
             cbor: cbor_canon::canonizalize(&ArbData::scan_json_arg(&mut iterator)?)?,
             args: vec![],
+            shared: vec![],
         };
         match iterator.next() {
             Some(',') => {
@@ -789,7 +885,7 @@ impl ::std::str::FromStr for ArbData {
 
 impl ::std::fmt::Display for ArbData {
     /// Turns the ArbData object into a string representation that can be
-    /// parsed by `from_str()`.
+    /// parsed by `from_str()`. Shared memory arguments are not included.
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         let mut output = self.get_json().map_err(|_| std::fmt::Error)?;
         for arg in self.args.iter() {
@@ -819,6 +915,7 @@ impl Default for ArbData {
         ArbData {
             cbor: EMPTY_CBOR.to_owned(),
             args: vec![],
+            shared: vec![],
         }
     }
 }
@@ -826,6 +923,7 @@ impl Default for ArbData {
 #[cfg(test)]
 mod test {
     use super::ArbData;
+    use serde::{Deserialize, Serialize};
     use serde_json::json;
     use std::str::FromStr;
 
@@ -892,6 +990,43 @@ mod test {
         );
     }
 
+    #[test]
+    fn serialize() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            name: String,
+            shots: u64,
+            noise: Option<f64>,
+            qubits: Vec<u32>,
+        }
+
+        let config = Config {
+            name: "bell".to_string(),
+            shots: 1000,
+            noise: None,
+            qubits: vec![1, 2],
+        };
+        let data = ArbData::from_serialize(&config, vec![b"x".to_vec()]).unwrap();
+        assert_eq!(
+            data,
+            ArbData::from_json(
+                r#"{"name": "bell", "shots": 1000, "noise": null, "qubits": [1, 2]}"#,
+                vec![b"x".to_vec()]
+            )
+            .unwrap()
+        );
+        assert_eq!(data.deserialize::<Config>().unwrap(), config);
+
+        let mut data = ArbData::default();
+        data.set_serialize(&vec![1, 2, 3]).unwrap();
+        assert_eq!(data.get_json().unwrap(), "[1,2,3]");
+        assert!(data
+            .deserialize::<Config>()
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid argument: failed to deserialize ArbData JSON/CBOR object: "));
+    }
+
     #[test]
     fn eq() {
         let args: Vec<Vec<u8>> = vec![b"x", b"y", b"z"]
@@ -0,0 +1,302 @@
+use crate::common::{
+    error::{inv_arg, Result},
+    types::{ArbCmd, ArbSchema},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Registry of the `ArbCmd` interfaces and operations supported by a plugin.
+///
+/// Each operation can optionally be annotated with a JSON schema describing
+/// the JSON/CBOR payload of the `ArbCmd`'s `ArbData`. See `ArbSchema` for the
+/// supported subset of JSON Schema.
+///
+/// A plugin that doesn't register any interfaces is assumed to follow the
+/// legacy convention of silently ignoring unsupported commands, so any
+/// command is accepted for it. As soon as one operation is registered, only
+/// the registered operations are accepted.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct ArbInterfaces {
+    /// Map from interface identifier to a map from operation identifier to
+    /// the optional JSON schema for the payload.
+    interfaces: BTreeMap<String, BTreeMap<String, Option<ArbSchema>>>,
+}
+
+impl ArbInterfaces {
+    /// Constructs an empty interface registry.
+    pub fn new() -> ArbInterfaces {
+        ArbInterfaces::default()
+    }
+
+    /// Registers an operation, optionally with a JSON schema for its payload.
+    ///
+    /// The identifiers must be valid `ArbCmd` identifiers, and the schema, if
+    /// specified, must be a valid `ArbSchema`. Registering an operation that was
+    /// already registered replaces its schema.
+    pub fn register(
+        &mut self,
+        interface_identifier: impl Into<String>,
+        operation_identifier: impl Into<String>,
+        schema: Option<String>,
+    ) -> Result<()> {
+        let interface_identifier = ArbCmd::verify_id(interface_identifier.into())?;
+        let operation_identifier = ArbCmd::verify_id(operation_identifier.into())?;
+        let schema = match schema.map(ArbSchema::parse).transpose() {
+            Ok(schema) => schema,
+            Err(e) => inv_arg(format!(
+                "invalid JSON schema for operation {}.{}: {}",
+                interface_identifier, operation_identifier, e
+            ))?,
+        };
+        self.interfaces
+            .entry(interface_identifier)
+            .or_default()
+            .insert(operation_identifier, schema);
+        Ok(())
+    }
+
+    /// Registers an operation, builder style.
+    pub fn with(
+        mut self,
+        interface_identifier: impl Into<String>,
+        operation_identifier: impl Into<String>,
+        schema: Option<String>,
+    ) -> Result<ArbInterfaces> {
+        self.register(interface_identifier, operation_identifier, schema)?;
+        Ok(self)
+    }
+
+    /// Returns whether no interfaces have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.interfaces.is_empty()
+    }
+
+    /// Returns the identifiers of the registered interfaces.
+    pub fn get_interfaces(&self) -> impl Iterator<Item = &str> {
+        self.interfaces.keys().map(String::as_str)
+    }
+
+    /// Returns the identifiers of the registered operations for the given
+    /// interface, or `None` if the interface is not registered.
+    pub fn get_operations(
+        &self,
+        interface_identifier: impl AsRef<str>,
+    ) -> Option<impl Iterator<Item = &str>> {
+        self.interfaces
+            .get(interface_identifier.as_ref())
+            .map(|operations| operations.keys().map(String::as_str))
+    }
+
+    /// Returns whether the given operation is registered.
+    pub fn supports(
+        &self,
+        interface_identifier: impl AsRef<str>,
+        operation_identifier: impl AsRef<str>,
+    ) -> bool {
+        self.interfaces
+            .get(interface_identifier.as_ref())
+            .map(|operations| operations.contains_key(operation_identifier.as_ref()))
+            .unwrap_or(false)
+    }
+
+    /// Returns the JSON schema registered for the given operation, if any.
+    pub fn get_schema(
+        &self,
+        interface_identifier: impl AsRef<str>,
+        operation_identifier: impl AsRef<str>,
+    ) -> Option<&ArbSchema> {
+        self.interfaces
+            .get(interface_identifier.as_ref())
+            .and_then(|operations| operations.get(operation_identifier.as_ref()))
+            .and_then(Option::as_ref)
+    }
+
+    /// Returns whether the given `ArbCmd` would be understood by a plugin
+    /// with this registry.
+    ///
+    /// This is always true if the registry is empty.
+    pub fn accepts(&self, cmd: &ArbCmd) -> bool {
+        self.is_empty() || self.supports(cmd.interface_identifier(), cmd.operation_identifier())
+    }
+
+    /// Validates the payload of the given `ArbCmd` against the schema
+    /// registered for its operation.
+    ///
+    /// This succeeds if the operation is not registered or has no schema;
+    /// use `accepts()` to check whether the operation is supported at all.
+    /// On failure, the error message names the offending path within the
+    /// payload.
+    pub fn validate(&self, cmd: &ArbCmd) -> Result<()> {
+        let schema = self.get_schema(cmd.interface_identifier(), cmd.operation_identifier());
+        if let Some(violation) = schema.and_then(|schema| schema.violation(cmd.data())) {
+            inv_arg(format!(
+                "invalid payload for ArbCmd {}.{}: {}",
+                cmd.interface_identifier(),
+                cmd.operation_identifier(),
+                violation
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that the given `ArbCmd` would be understood by a plugin with
+    /// this registry and that its payload matches the registered schema.
+    pub fn check(&self, cmd: &ArbCmd) -> Result<()> {
+        if self.accepts(cmd) {
+            self.validate(cmd)
+        } else {
+            inv_arg(format!(
+                "ArbCmd {}.{} is not supported",
+                cmd.interface_identifier(),
+                cmd.operation_identifier()
+            ))
+        }
+    }
+
+    /// Returns the registry of the `ArbCmd`s that are understood by a plugin
+    /// with this registry or by one with the `other` registry, such as an
+    /// operator and the plugins downstream of it. Operations registered in
+    /// both keep the schema registered here.
+    ///
+    /// Since an empty registry accepts any command, the union is empty if
+    /// either registry is empty.
+    pub fn union(&self, other: &ArbInterfaces) -> ArbInterfaces {
+        if self.is_empty() || other.is_empty() {
+            return ArbInterfaces::new();
+        }
+        let mut interfaces = other.interfaces.clone();
+        for (interface, operations) in &self.interfaces {
+            interfaces
+                .entry(interface.clone())
+                .or_default()
+                .extend(operations.clone());
+        }
+        ArbInterfaces { interfaces }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::ArbData;
+
+    #[test]
+    fn registry() {
+        let mut interfaces = ArbInterfaces::new();
+        assert!(interfaces.is_empty());
+        assert!(interfaces.accepts(&ArbCmd::new("a", "b", ArbData::default())));
+
+        interfaces = interfaces
+            .with("a", "b", None)
+            .unwrap()
+            .with("a", "c", Some(r#"{"type": "object"}"#.to_string()))
+            .unwrap()
+            .with("d", "e", None)
+            .unwrap();
+        assert!(!interfaces.is_empty());
+        assert_eq!(
+            interfaces.get_interfaces().collect::<Vec<_>>(),
+            vec!["a", "d"]
+        );
+        assert_eq!(
+            interfaces.get_operations("a").unwrap().collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+        assert!(interfaces.get_operations("b").is_none());
+        assert!(interfaces.supports("a", "c"));
+        assert!(!interfaces.supports("a", "e"));
+        assert!(!interfaces.supports("x", "b"));
+        assert_eq!(interfaces.get_schema("a", "b"), None);
+        assert_eq!(
+            interfaces.get_schema("a", "c").unwrap().as_str(),
+            r#"{"type": "object"}"#
+        );
+
+        assert!(interfaces.accepts(&ArbCmd::new("d", "e", ArbData::default())));
+        assert!(!interfaces.accepts(&ArbCmd::new("x", "b", ArbData::default())));
+        assert!(!interfaces.accepts(&ArbCmd::new("a", "e", ArbData::default())));
+
+        assert!(interfaces
+            .validate(&ArbCmd::new("a", "c", ArbData::default()))
+            .is_ok());
+        assert!(interfaces
+            .validate(&ArbCmd::new(
+                "a",
+                "b",
+                ArbData::from_json("3", vec![]).unwrap()
+            ))
+            .is_ok());
+        assert_eq!(
+            interfaces
+                .validate(&ArbCmd::new(
+                    "a",
+                    "c",
+                    ArbData::from_json("3", vec![]).unwrap()
+                ))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: invalid payload for ArbCmd a.c: $: expected object, found integer"
+        );
+        assert!(interfaces
+            .check(&ArbCmd::new("a", "c", ArbData::default()))
+            .is_ok());
+        assert_eq!(
+            interfaces
+                .check(&ArbCmd::new("a", "e", ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: ArbCmd a.e is not supported"
+        );
+    }
+
+    #[test]
+    fn union() {
+        let a = ArbInterfaces::new()
+            .with("a", "b", Some(r#"{"type": "object"}"#.to_string()))
+            .unwrap();
+        let b = ArbInterfaces::new()
+            .with("a", "b", None)
+            .unwrap()
+            .with("a", "c", None)
+            .unwrap()
+            .with("d", "e", None)
+            .unwrap();
+        assert!(a.union(&ArbInterfaces::new()).is_empty());
+        assert!(ArbInterfaces::new().union(&b).is_empty());
+
+        let union = a.union(&b);
+        assert_eq!(union.get_interfaces().collect::<Vec<_>>(), vec!["a", "d"]);
+        assert_eq!(
+            union.get_operations("a").unwrap().collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+        assert_eq!(
+            union.get_schema("a", "b").unwrap().as_str(),
+            r#"{"type": "object"}"#
+        );
+        assert!(b.union(&a).get_schema("a", "b").is_none());
+    }
+
+    #[test]
+    fn invalid() {
+        let mut interfaces = ArbInterfaces::new();
+        assert_eq!(
+            interfaces.register("a-b", "c", None).unwrap_err().to_string(),
+            "Invalid argument: \"a-b\" is not a valid identifier; it contains characters outside [a-zA-Z0-9_]"
+        );
+        assert!(interfaces
+            .register("a", "b", Some("{".to_string()))
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid argument: invalid JSON schema for operation a.b: "));
+        assert_eq!(
+            interfaces
+                .register("a", "b", Some(r#"{"type": "float"}"#.to_string()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: invalid JSON schema for operation a.b: #/type: unknown type \"float\""
+        );
+        assert!(interfaces.is_empty());
+    }
+}
@@ -0,0 +1,622 @@
+use crate::common::{
+    error::{inv_arg, Error, Result},
+    types::ArbData,
+};
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use std::{collections::BTreeMap, convert::TryFrom, fmt};
+
+/// The JSON types that can be used in the `type` keyword of a schema.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SchemaType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl SchemaType {
+    /// Parses a JSON schema type name.
+    fn from_name(name: &str) -> Option<SchemaType> {
+        match name {
+            "null" => Some(SchemaType::Null),
+            "boolean" => Some(SchemaType::Boolean),
+            "integer" => Some(SchemaType::Integer),
+            "number" => Some(SchemaType::Number),
+            "string" => Some(SchemaType::String),
+            "array" => Some(SchemaType::Array),
+            "object" => Some(SchemaType::Object),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the given CBOR value is of this type.
+    fn matches(self, value: &Value) -> bool {
+        match (self, value) {
+            (SchemaType::Null, Value::Null) => true,
+            (SchemaType::Boolean, Value::Bool(_)) => true,
+            (SchemaType::Integer, Value::Integer(_)) => true,
+            (SchemaType::Integer, Value::Float(x)) => x.fract() == 0.0,
+            (SchemaType::Number, Value::Integer(_)) => true,
+            (SchemaType::Number, Value::Float(_)) => true,
+            (SchemaType::String, Value::Text(_)) => true,
+            (SchemaType::Array, Value::Array(_)) => true,
+            (SchemaType::Object, Value::Map(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for SchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SchemaType::Null => "null",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Integer => "integer",
+            SchemaType::Number => "number",
+            SchemaType::String => "string",
+            SchemaType::Array => "array",
+            SchemaType::Object => "object",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Returns a human-readable description of the type of a CBOR value.
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "number",
+        Value::Bytes(_) => "binary string",
+        Value::Text(_) => "string",
+        Value::Array(_) => "array",
+        Value::Map(_) => "object",
+        _ => "tagged value",
+    }
+}
+
+/// Compares two CBOR values, treating integers and floats with the same value
+/// as equal, as JSON does.
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Integer(x), Value::Float(y)) | (Value::Float(y), Value::Integer(x)) => {
+            *x as f64 == *y
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y.iter()).all(|(x, y)| values_equal(x, y))
+        }
+        _ => a == b,
+    }
+}
+
+/// Returns the numeric value of a CBOR value, if it is a number.
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(x) => Some(*x as f64),
+        Value::Float(x) => Some(*x),
+        _ => None,
+    }
+}
+
+/// Appends an object key to a JSONPath-like path.
+fn key_path(path: &str, key: &Value) -> String {
+    match key {
+        Value::Text(key)
+            if !key.is_empty() && key.chars().all(|x| x.is_ascii_alphanumeric() || x == '_') =>
+        {
+            format!("{}.{}", path, key)
+        }
+        Value::Text(key) => format!("{}[{:?}]", path, key),
+        Value::Integer(key) => format!("{}[{}]", path, key),
+        key => format!("{}[<{}>]", path, describe(key)),
+    }
+}
+
+/// A parsed (sub)schema.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct SchemaNode {
+    /// Set for the `false` schema, which rejects everything.
+    never: bool,
+
+    /// The `type` keyword.
+    types: Option<Vec<SchemaType>>,
+
+    /// The `enum` keyword, in both JSON form (for error messages) and CBOR
+    /// form (for comparison).
+    enumeration: Option<(serde_json::Value, Vec<Value>)>,
+
+    /// The `properties` keyword.
+    properties: BTreeMap<String, SchemaNode>,
+
+    /// The `required` keyword.
+    required: Vec<String>,
+
+    /// The `additionalProperties` keyword.
+    additional_properties: Option<Box<SchemaNode>>,
+
+    /// The `items` keyword.
+    items: Option<Box<SchemaNode>>,
+
+    /// The `minimum` keyword.
+    minimum: Option<f64>,
+
+    /// The `maximum` keyword.
+    maximum: Option<f64>,
+
+    /// The `minLength` keyword.
+    min_length: Option<usize>,
+
+    /// The `maxLength` keyword.
+    max_length: Option<usize>,
+
+    /// The `minItems` keyword.
+    min_items: Option<usize>,
+
+    /// The `maxItems` keyword.
+    max_items: Option<usize>,
+}
+
+impl SchemaNode {
+    /// Parses a (sub)schema located at the given JSON pointer within the
+    /// schema document.
+    fn parse(json: &serde_json::Value, at: &str) -> std::result::Result<SchemaNode, String> {
+        use serde_json::Value as Json;
+        let map = match json {
+            Json::Bool(true) => return Ok(SchemaNode::default()),
+            Json::Bool(false) => {
+                return Ok(SchemaNode {
+                    never: true,
+                    ..SchemaNode::default()
+                })
+            }
+            Json::Object(map) => map,
+            _ => return Err(format!("{}: schema must be an object or a boolean", at)),
+        };
+
+        let size = |keyword: &str| -> std::result::Result<Option<usize>, String> {
+            match map.get(keyword) {
+                None => Ok(None),
+                Some(Json::Number(x)) if x.is_u64() => Ok(Some(x.as_u64().unwrap() as usize)),
+                Some(_) => Err(format!(
+                    "{}/{}: expected a non-negative integer",
+                    at, keyword
+                )),
+            }
+        };
+        let number = |keyword: &str| -> std::result::Result<Option<f64>, String> {
+            match map.get(keyword) {
+                None => Ok(None),
+                Some(Json::Number(x)) => Ok(x.as_f64()),
+                Some(_) => Err(format!("{}/{}: expected a number", at, keyword)),
+            }
+        };
+        let subschema = |keyword: &str| -> std::result::Result<Option<Box<SchemaNode>>, String> {
+            map.get(keyword)
+                .map(|x| SchemaNode::parse(x, &format!("{}/{}", at, keyword)).map(Box::new))
+                .transpose()
+        };
+
+        let parse_type = |name: &Json| -> std::result::Result<SchemaType, String> {
+            name.as_str()
+                .and_then(SchemaType::from_name)
+                .ok_or_else(|| format!("{}/type: unknown type {}", at, name))
+        };
+        let types = match map.get("type") {
+            None => None,
+            Some(Json::Array(names)) => Some(
+                names
+                    .iter()
+                    .map(parse_type)
+                    .collect::<std::result::Result<Vec<_>, _>>()?,
+            ),
+            Some(name) => Some(vec![parse_type(name)?]),
+        };
+
+        let enumeration = match map.get("enum") {
+            None => None,
+            Some(Json::Array(values)) => Some((
+                Json::Array(values.clone()),
+                values
+                    .iter()
+                    .map(serde_cbor::value::to_value)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| format!("{}/enum: {}", at, e))?,
+            )),
+            Some(_) => return Err(format!("{}/enum: expected an array", at)),
+        };
+
+        let properties = match map.get("properties") {
+            None => BTreeMap::new(),
+            Some(Json::Object(properties)) => properties
+                .iter()
+                .map(|(key, value)| {
+                    SchemaNode::parse(value, &format!("{}/properties/{}", at, key))
+                        .map(|node| (key.clone(), node))
+                })
+                .collect::<std::result::Result<_, _>>()?,
+            Some(_) => return Err(format!("{}/properties: expected an object", at)),
+        };
+
+        let required = match map.get("required") {
+            None => vec![],
+            Some(Json::Array(keys)) => keys
+                .iter()
+                .map(|key| {
+                    key.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| format!("{}/required: expected an array of strings", at))
+                })
+                .collect::<std::result::Result<_, _>>()?,
+            Some(_) => return Err(format!("{}/required: expected an array of strings", at)),
+        };
+
+        Ok(SchemaNode {
+            never: false,
+            types,
+            enumeration,
+            properties,
+            required,
+            additional_properties: subschema("additionalProperties")?,
+            items: subschema("items")?,
+            minimum: number("minimum")?,
+            maximum: number("maximum")?,
+            min_length: size("minLength")?,
+            max_length: size("maxLength")?,
+            min_items: size("minItems")?,
+            max_items: size("maxItems")?,
+        })
+    }
+
+    /// Checks the given value, located at the given JSONPath-like path within
+    /// the payload, against this schema. Returns a description of the first
+    /// violation found, if any.
+    fn check(&self, value: &Value, path: &str) -> Option<String> {
+        if self.never {
+            return Some(format!("{}: no value is allowed here", path));
+        }
+
+        if let Some(types) = &self.types {
+            if !types.iter().any(|typ| typ.matches(value)) {
+                let expected: Vec<_> = types.iter().map(ToString::to_string).collect();
+                return Some(format!(
+                    "{}: expected {}, found {}",
+                    path,
+                    expected.join(" or "),
+                    describe(value)
+                ));
+            }
+        }
+
+        if let Some((json, values)) = &self.enumeration {
+            if !values.iter().any(|x| values_equal(x, value)) {
+                return Some(format!("{}: expected one of {}", path, json));
+            }
+        }
+
+        if let Some(x) = as_number(value) {
+            if let Some(minimum) = self.minimum {
+                if x < minimum {
+                    return Some(format!(
+                        "{}: {} is less than the minimum {}",
+                        path, x, minimum
+                    ));
+                }
+            }
+            if let Some(maximum) = self.maximum {
+                if x > maximum {
+                    return Some(format!(
+                        "{}: {} is greater than the maximum {}",
+                        path, x, maximum
+                    ));
+                }
+            }
+        }
+
+        match value {
+            Value::Text(text) => {
+                let length = text.chars().count();
+                if let Some(min_length) = self.min_length {
+                    if length < min_length {
+                        return Some(format!(
+                            "{}: string is shorter than {} characters",
+                            path, min_length
+                        ));
+                    }
+                }
+                if let Some(max_length) = self.max_length {
+                    if length > max_length {
+                        return Some(format!(
+                            "{}: string is longer than {} characters",
+                            path, max_length
+                        ));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(min_items) = self.min_items {
+                    if items.len() < min_items {
+                        return Some(format!(
+                            "{}: array has fewer than {} items",
+                            path, min_items
+                        ));
+                    }
+                }
+                if let Some(max_items) = self.max_items {
+                    if items.len() > max_items {
+                        return Some(format!("{}: array has more than {} items", path, max_items));
+                    }
+                }
+                if let Some(schema) = &self.items {
+                    for (index, item) in items.iter().enumerate() {
+                        if let Some(violation) = schema.check(item, &format!("{}[{}]", path, index))
+                        {
+                            return Some(violation);
+                        }
+                    }
+                }
+            }
+            Value::Map(map) => {
+                for key in self.required.iter() {
+                    if !map.contains_key(&Value::Text(key.clone())) {
+                        return Some(format!("{}: missing required property {:?}", path, key));
+                    }
+                }
+                for (key, value) in map.iter() {
+                    let property = match key {
+                        Value::Text(key) => self.properties.get(key),
+                        _ => None,
+                    };
+                    let path = key_path(path, key);
+                    if let Some(schema) = property {
+                        if let Some(violation) = schema.check(value, &path) {
+                            return Some(violation);
+                        }
+                    } else if let Some(schema) = &self.additional_properties {
+                        if schema.never {
+                            return Some(format!("{}: unexpected property", path));
+                        } else if let Some(violation) = schema.check(value, &path) {
+                            return Some(violation);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// Schema for the JSON/CBOR payload of an `ArbData` object.
+///
+/// This supports a subset of JSON Schema: the `type`, `enum`, `properties`,
+/// `required`, `additionalProperties`, `items`, `minimum`, `maximum`,
+/// `minLength`, `maxLength`, `minItems`, and `maxItems` keywords, as well as
+/// the `true` and `false` schemas. Other keywords (such as `description`) are
+/// ignored, as JSON Schema prescribes for unknown keywords. Binary strings
+/// and tagged values, which have no JSON equivalent, only match schemas
+/// without a `type` keyword.
+///
+/// Schemas are serialized in their JSON string representation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ArbSchema {
+    /// The JSON representation of the schema.
+    source: String,
+
+    /// The parsed schema.
+    root: SchemaNode,
+}
+
+impl ArbSchema {
+    /// Parses a schema from its JSON string representation, returning a
+    /// description of the problem on failure.
+    pub(crate) fn parse(source: impl Into<String>) -> std::result::Result<ArbSchema, String> {
+        let source = source.into();
+        let json: serde_json::Value = serde_json::from_str(&source).map_err(|e| e.to_string())?;
+        let root = SchemaNode::parse(&json, "#")?;
+        Ok(ArbSchema { source, root })
+    }
+
+    /// Parses a schema from its JSON string representation.
+    pub fn new(source: impl Into<String>) -> Result<ArbSchema> {
+        ArbSchema::parse(source).or_else(|e| inv_arg(format!("invalid JSON schema: {}", e)))
+    }
+
+    /// Returns the JSON string representation of the schema.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Checks the JSON/CBOR payload of the given `ArbData` against this
+    /// schema, returning a description of the first violation found, if any.
+    ///
+    /// The description starts with the JSONPath-like path of the offending
+    /// value, for instance `$.gates[2].name`.
+    pub(crate) fn violation(&self, data: &ArbData) -> Option<String> {
+        match serde_cbor::from_slice::<Value>(data.get_cbor()) {
+            Ok(value) => self.root.check(&value, "$"),
+            Err(e) => Some(format!("$: {}", e)),
+        }
+    }
+
+    /// Validates the JSON/CBOR payload of the given `ArbData` against this
+    /// schema.
+    pub fn validate(&self, data: &ArbData) -> Result<()> {
+        match self.violation(data) {
+            Some(violation) => inv_arg(violation),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TryFrom<String> for ArbSchema {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<ArbSchema> {
+        ArbSchema::new(source)
+    }
+}
+
+impl From<ArbSchema> for String {
+    fn from(schema: ArbSchema) -> String {
+        schema.source
+    }
+}
+
+impl fmt::Display for ArbSchema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(schema: &ArbSchema, json: &str) -> Option<String> {
+        schema.violation(&ArbData::from_json(json, vec![]).unwrap())
+    }
+
+    #[test]
+    fn validate() {
+        let schema = ArbSchema::new(
+            r#"{
+                "type": "object",
+                "description": "ignored",
+                "properties": {
+                    "name": {"type": "string", "minLength": 1, "maxLength": 4},
+                    "angle": {"type": "number", "minimum": -3.5, "maximum": 3.5},
+                    "qubits": {
+                        "type": "array",
+                        "items": {"type": "integer"},
+                        "minItems": 1,
+                        "maxItems": 2
+                    },
+                    "basis": {"enum": ["x", "y", "z", 1]},
+                    "noise": {"type": ["number", "null"]},
+                    "nested": {"additionalProperties": {"type": "boolean"}}
+                },
+                "required": ["name"],
+                "additionalProperties": false
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(check(&schema, r#"{"name": "rx"}"#), None);
+        assert_eq!(
+            check(
+                &schema,
+                r#"{"name": "rx", "angle": 1, "qubits": [1, 2.0], "basis": 1.0, "noise": null,
+                "nested": {"a": true, "b c": false}}"#
+            ),
+            None
+        );
+        assert_eq!(
+            check(&schema, r#"[1]"#),
+            Some("$: expected object, found array".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{}"#),
+            Some("$: missing required property \"name\"".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "nmae": "ry"}"#),
+            Some("$.nmae: unexpected property".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": 3}"#),
+            Some("$.name: expected string, found integer".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": ""}"#),
+            Some("$.name: string is shorter than 1 characters".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "abcde"}"#),
+            Some("$.name: string is longer than 4 characters".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "angle": -4}"#),
+            Some("$.angle: -4 is less than the minimum -3.5".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "angle": 4.5}"#),
+            Some("$.angle: 4.5 is greater than the maximum 3.5".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "qubits": [1, 2.5]}"#),
+            Some("$.qubits[1]: expected integer, found number".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "qubits": []}"#),
+            Some("$.qubits: array has fewer than 1 items".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "qubits": [1, 2, 3]}"#),
+            Some("$.qubits: array has more than 2 items".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "basis": "w"}"#),
+            Some(r#"$.basis: expected one of ["x","y","z",1]"#.to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "noise": "yes"}"#),
+            Some("$.noise: expected number or null, found string".to_string())
+        );
+        assert_eq!(
+            check(&schema, r#"{"name": "rx", "nested": {"b c": 1}}"#),
+            Some(r#"$.nested["b c"]: expected boolean, found integer"#.to_string())
+        );
+
+        assert_eq!(check(&ArbSchema::new("true").unwrap(), "[1]"), None);
+        assert_eq!(
+            check(&ArbSchema::new("false").unwrap(), "[1]"),
+            Some("$: no value is allowed here".to_string())
+        );
+
+        assert_eq!(
+            schema
+                .validate(&ArbData::from_json(r#"{"name": 3}"#, vec![]).unwrap())
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: $.name: expected string, found integer"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let error = |source| ArbSchema::new(source).unwrap_err().to_string();
+        assert!(error("{").starts_with("Invalid argument: invalid JSON schema: "));
+        assert_eq!(
+            error("3"),
+            "Invalid argument: invalid JSON schema: #: schema must be an object or a boolean"
+        );
+        assert_eq!(
+            error(r#"{"properties": {"a": {"type": "float"}}}"#),
+            "Invalid argument: invalid JSON schema: #/properties/a/type: unknown type \"float\""
+        );
+        assert_eq!(
+            error(r#"{"items": {"minItems": -1}}"#),
+            "Invalid argument: invalid JSON schema: #/items/minItems: expected a non-negative integer"
+        );
+        assert_eq!(
+            error(r#"{"required": [1]}"#),
+            "Invalid argument: invalid JSON schema: #/required: expected an array of strings"
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let schema = ArbSchema::new(r#"{"type": "integer"}"#).unwrap();
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#""{\"type\": \"integer\"}""#);
+        assert_eq!(serde_json::from_str::<ArbSchema>(&json).unwrap(), schema);
+        assert!(serde_json::from_str::<ArbSchema>(r#""{\"type\": 1}""#).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::common::{
     error::{inv_arg, Result},
-    types::{ArbData, Matrix, QubitRef},
+    types::{ArbData, GateParameters, Matrix, QubitMeasurementValue, QubitRef},
 };
 use num_complex::Complex64;
 use serde::{Deserialize, Serialize};
@@ -55,6 +55,28 @@ pub enum GateType {
     /// ignored.
     Prep,
 
+    /// Pauli measurement gates have one or more target qubits and a Hermitian
+    /// unitary matrix sized for the number of target qubits, representing the
+    /// measured observable. This is usually a product of Pauli matrices, such
+    /// as ZZ or XXXX. The first target qubit is also the only measured qubit.
+    ///
+    /// The semantics are:
+    ///
+    ///  - the qubits are projected onto the +1 or -1 eigenspace of the
+    ///    observable, with the probabilities dictated by the current state;
+    ///  - a single measurement result is propagated upstream for the first
+    ///    target qubit, being zero for the +1 eigenspace and one for the -1
+    ///    eigenspace.
+    ///
+    /// This allows stabilizer measurements to be expressed without ancilla
+    /// qubits.
+    ///
+    /// The data field may add pragma-like hints to the gate, for instance to
+    /// represent the line number in the source file that generated the gate,
+    /// error modelling information, and so on. This data may be silently
+    /// ignored.
+    PauliMeasurement,
+
     /// Custom gates perform a user-defined mixed quantum-classical operation,
     /// identified by a name. They can have zero or more target, control, and
     /// measured qubits, of which only the target and control sets must be
@@ -71,6 +93,31 @@ pub enum GateType {
     Custom(String),
 }
 
+/// Represents a classical condition on the execution of a gate.
+///
+/// A gate with a condition is only executed if the measurement register of
+/// the given qubit has the given value at the time the gate is executed. The
+/// condition is evaluated by the backend, i.e. by the plugin that produces
+/// the measurement results, such that the frontend does not need to wait for
+/// the measurement result to become available to it.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize, Hash)]
+pub struct GateCondition {
+    /// The qubit whose measurement register is tested.
+    pub qubit: QubitRef,
+
+    /// The value that the measurement register must have for the gate to be
+    /// executed.
+    pub value: QubitMeasurementValue,
+}
+
+impl GateCondition {
+    /// Returns whether the condition is satisfied by the given measurement
+    /// register value.
+    pub fn is_satisfied_by(&self, value: QubitMeasurementValue) -> bool {
+        self.value == value
+    }
+}
+
 /// Represents a type of quantum or mixed quantum-classical gate.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Hash)]
 pub struct Gate {
@@ -92,6 +139,9 @@ pub struct Gate {
 
     /// User-defined classical data to pass along with the gate.
     pub data: ArbData,
+
+    /// An optional classical condition on the execution of the gate.
+    condition: Option<GateCondition>,
 }
 
 impl Gate {
@@ -140,6 +190,7 @@ impl Gate {
             measures: vec![],
             matrix: Some(matrix),
             data: ArbData::default(),
+            condition: None,
         })
     }
 
@@ -180,6 +231,7 @@ impl Gate {
             measures,
             matrix: Some(matrix),
             data: ArbData::default(),
+            condition: None,
         })
     }
 
@@ -220,6 +272,63 @@ impl Gate {
             measures: vec![],
             matrix: Some(matrix),
             data: ArbData::default(),
+            condition: None,
+        })
+    }
+
+    /// Constructs a new Pauli measurement gate.
+    ///
+    /// `observable` must be a Hermitian unitary matrix sized for the number of
+    /// qubits, for instance one constructed using
+    /// `Matrix::new_pauli_product()`. The measurement result is reported for
+    /// the first qubit.
+    pub fn new_pauli_measurement(
+        qubits: impl IntoIterator<Item = QubitRef>,
+        observable: impl IntoIterator<Item = Complex64>,
+    ) -> Result<Gate> {
+        let targets: Vec<QubitRef> = qubits.into_iter().collect();
+        let matrix = Matrix::new(observable)?;
+
+        // We need at least one qubit.
+        if targets.is_empty() {
+            return inv_arg("at least one qubit is required");
+        }
+
+        // Enforce uniqueness of the qubits.
+        let mut set = HashSet::new();
+        for qubit in targets.iter() {
+            if !set.insert(qubit) {
+                return inv_arg(format!("qubit {} is used more than once", qubit));
+            }
+        }
+
+        // Check the size of the matrix.
+        if matrix.num_qubits() != Some(targets.len()) {
+            return inv_arg(format!(
+                "the matrix is expected to be sized for {} qubits but has dimension {}",
+                targets.len(),
+                matrix.dimension()
+            ));
+        }
+
+        // Validate that the matrix is a valid observable with eigenvalues
+        // +1 and -1.
+        if !matrix.approx_unitary(1.0e-6) {
+            return inv_arg("provided matrix is not unitary within 1e-6 tolerance");
+        }
+        if !matrix.approx_hermitian(1.0e-6) {
+            return inv_arg("provided matrix is not Hermitian within 1e-6 tolerance");
+        }
+
+        // Construct the Gate structure.
+        Ok(Gate {
+            typ: GateType::PauliMeasurement,
+            measures: vec![targets[0]],
+            targets,
+            controls: vec![],
+            matrix: Some(matrix),
+            data: ArbData::default(),
+            condition: None,
         })
     }
 
@@ -268,6 +377,7 @@ impl Gate {
             measures,
             matrix,
             data,
+            condition: None,
         })
     }
 
@@ -305,6 +415,51 @@ impl Gate {
         self.matrix.as_ref()
     }
 
+    /// Returns the classical condition on the execution of the gate, if any.
+    pub fn get_condition(&self) -> Option<&GateCondition> {
+        self.condition.as_ref()
+    }
+
+    /// Makes the execution of the gate conditional on the measurement
+    /// register of the given qubit having the given value, builder style.
+    ///
+    /// Conditional gates cannot measure qubits, since the plugin that issued
+    /// the gate would not know in advance whether to expect a measurement
+    /// result.
+    pub fn with_condition(
+        mut self,
+        qubit: QubitRef,
+        value: impl Into<QubitMeasurementValue>,
+    ) -> Result<Gate> {
+        if !self.measures.is_empty() {
+            return inv_arg("conditional gates cannot measure qubits");
+        }
+        self.condition.replace(GateCondition {
+            qubit,
+            value: value.into(),
+        });
+        Ok(self)
+    }
+
+    /// Returns a copy of this gate without its classical condition.
+    pub fn without_condition(&self) -> Self {
+        let mut gate = self.clone();
+        gate.condition.take();
+        gate
+    }
+
+    /// Returns the parametric description of the gate carried in its data,
+    /// if any.
+    pub fn get_parameters(&self) -> Result<Option<GateParameters>> {
+        GateParameters::from_arb_data(&self.data)
+    }
+
+    /// Attaches the given parametric description to the gate data, replacing
+    /// any previous description.
+    pub fn set_parameters(&mut self, parameters: &GateParameters) -> Result<()> {
+        parameters.insert_into(&mut self.data)
+    }
+
     /// Returns a new Gate with its controls moved to the matrix.
     pub fn with_matrix_controls(&self) -> Self {
         let num_controls = self.controls.len();
@@ -319,6 +474,7 @@ impl Gate {
                 measures: self.measures.to_vec(),
                 matrix: Some(matrix),
                 data: self.data.clone(),
+                condition: self.condition,
             }
         } else {
             self.clone()
@@ -344,12 +500,128 @@ impl Gate {
                 measures: self.measures.to_vec(),
                 matrix: Some(matrix),
                 data: self.data.clone(),
+                condition: self.condition,
             }
         } else {
             self.clone()
         }
     }
 
+    /// Returns the canonical form of this Gate.
+    ///
+    /// Gates that differ only in the order of qubits that do not affect the
+    /// semantics of the gate have the same canonical form. Specifically:
+    ///
+    ///  - the control qubits of unitary gates are sorted;
+    ///  - the target qubits of unitary gates are sorted, and the matrix is
+    ///    permuted accordingly, such that for instance a SWAP or CZ gate on
+    ///    qubits (2, 1) and one on (1, 2) have the same canonical form;
+    ///  - the measured qubits of measurement gates and the targets of prep
+    ///    gates are sorted, since their basis is applied to each qubit
+    ///    individually;
+    ///  - the target qubits of Pauli measurement gates except for the first
+    ///    are sorted, and the observable is permuted accordingly. The first
+    ///    qubit is left in place, since the result is reported for it.
+    ///
+    /// Custom gates are returned as-is, because the significance of their
+    /// qubit order is user-defined.
+    ///
+    /// Note that controls are not moved between the `controls` field and the
+    /// matrix. Use `with_matrix_controls()` or `with_gate_controls()` first
+    /// if this is needed.
+    pub fn canonicalize(&self) -> Self {
+        match self.typ {
+            GateType::Unitary => {
+                let mut order: Vec<usize> = (0..self.targets.len()).collect();
+                order.sort_by_key(|&i| self.targets[i]);
+                let mut controls = self.controls.clone();
+                controls.sort();
+                Gate {
+                    typ: self.typ.clone(),
+                    targets: order.iter().map(|&i| self.targets[i]).collect(),
+                    controls,
+                    measures: self.measures.clone(),
+                    matrix: self
+                        .matrix
+                        .as_ref()
+                        .map(|matrix| matrix.permute_qubits(&order)),
+                    data: self.data.clone(),
+                    condition: self.condition,
+                }
+            }
+            GateType::Measurement | GateType::Prep => {
+                let mut gate = self.clone();
+                gate.targets.sort();
+                gate.measures.sort();
+                gate
+            }
+            GateType::PauliMeasurement => {
+                let mut order: Vec<usize> = (1..self.targets.len()).collect();
+                order.sort_by_key(|&i| self.targets[i]);
+                order.insert(0, 0);
+                let mut gate = self.clone();
+                gate.targets = order.iter().map(|&i| self.targets[i]).collect();
+                gate.matrix = self
+                    .matrix
+                    .as_ref()
+                    .map(|matrix| matrix.permute_qubits(&order));
+                gate
+            }
+            GateType::Custom(_) => self.clone(),
+        }
+    }
+
+    /// Approximately compares this Gate with another Gate, considering gates
+    /// that are equal up to qubit order and control representation to be
+    /// equal.
+    ///
+    /// Unitary gates are compared by moving their controls into the matrix
+    /// and canonicalizing them, after which the matrices are compared using
+    /// `Matrix::approx_eq()` with the given `epsilon` and
+    /// `ignore_global_phase` arguments. Measurement and prep gates are
+    /// compared using `Matrix::basis_approx_eq()`, ignoring the order of
+    /// their qubits. Pauli measurement gates are compared like unitary gates,
+    /// but the first qubit must be the same and global phase is never
+    /// ignored, since negating the observable inverts the measurement
+    /// outcome. Custom gates must have the same
+    /// name and qubit lists, and their matrices (if any) are compared as for
+    /// unitary gates. The data and conditions of the gates must always be
+    /// equal, except for the parametric gate descriptions in the data; the
+    /// parameters these describe are already compared approximately through
+    /// the matrices.
+    pub fn approx_eq(&self, other: &Gate, epsilon: f64, ignore_global_phase: bool) -> bool {
+        let data_without_parameters = |gate: &Gate| {
+            let mut data = gate.data.clone();
+            GateParameters::take_from(&mut data).ok();
+            data
+        };
+        if self.typ != other.typ
+            || self.condition != other.condition
+            || data_without_parameters(self) != data_without_parameters(other)
+        {
+            return false;
+        }
+        let (a, b) = match self.typ {
+            GateType::Unitary => (
+                self.with_matrix_controls().canonicalize(),
+                other.with_matrix_controls().canonicalize(),
+            ),
+            _ => (self.canonicalize(), other.canonicalize()),
+        };
+        if a.targets != b.targets || a.controls != b.controls || a.measures != b.measures {
+            return false;
+        }
+        match (a.matrix.as_ref(), b.matrix.as_ref()) {
+            (Some(x), Some(y)) => match self.typ {
+                GateType::Measurement | GateType::Prep => x.basis_approx_eq(y, epsilon),
+                GateType::PauliMeasurement => x.approx_eq(y, epsilon, false),
+                _ => x.approx_eq(y, epsilon, ignore_global_phase),
+            },
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
     /// Replaces all qubit references in the gate with undefined qubits. This
     /// is used as a gate detector cache preprocessing step when the detector
     /// functions do not depend on which qubits are bound to the gate, only the
@@ -362,6 +634,10 @@ impl Gate {
             measures: vec![QubitRef::null(); self.measures.len()],
             matrix: self.matrix.clone(),
             data: self.data.clone(),
+            condition: self.condition.map(|condition| GateCondition {
+                qubit: QubitRef::null(),
+                ..condition
+            }),
         }
     }
 }
@@ -369,6 +645,7 @@ impl Gate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::gates::{BoundUnitaryGate, UnboundUnitaryGate};
 
     fn qref(q: u64) -> QubitRef {
         QubitRef::from_foreign(q).unwrap()
@@ -658,7 +935,7 @@ mod tests {
         let g = Gate::new_custom(name, targets, controls, measures, matrix, data);
         assert!(g.is_ok());
         let g = g.unwrap();
-        assert_eq!(format!("{:?}", g), "Gate { typ: Custom(\"I\"), targets: [QubitRef(1)], controls: [QubitRef(2)], measures: [QubitRef(3)], matrix: Some(Matrix { data: [Complex { re: 1.0, im: 0.0 }, Complex { re: 0.0, im: 0.0 }, Complex { re: 0.0, im: 0.0 }, Complex { re: 1.0, im: 0.0 }], dimension: 2 }), data: ArbData { json: Map({}), args: [] } }");
+        assert_eq!(format!("{:?}", g), "Gate { typ: Custom(\"I\"), targets: [QubitRef(1)], controls: [QubitRef(2)], measures: [QubitRef(3)], matrix: Some(Matrix { data: [Complex { re: 1.0, im: 0.0 }, Complex { re: 0.0, im: 0.0 }, Complex { re: 0.0, im: 0.0 }, Complex { re: 1.0, im: 0.0 }], dimension: 2 }), data: ArbData { json: Map({}), args: [] }, condition: None }");
     }
 
     #[test]
@@ -672,7 +949,7 @@ mod tests {
             Complex64::new(1f64, 0f64),
         ];
         let g = Gate::new_unitary(targets, controls, matrix).unwrap();
-        assert_eq!(serde_json::to_string(&g).unwrap(), "{\"typ\":\"Unitary\",\"targets\":[1],\"controls\":[2],\"measures\":[],\"matrix\":{\"data\":[{\"re\":1.0,\"im\":0.0},{\"re\":0.0,\"im\":0.0},{\"re\":0.0,\"im\":0.0},{\"re\":1.0,\"im\":0.0}],\"dimension\":2},\"data\":{\"cbor\":[160],\"args\":[]}}");
+        assert_eq!(serde_json::to_string(&g).unwrap(), "{\"typ\":\"Unitary\",\"targets\":[1],\"controls\":[2],\"measures\":[],\"matrix\":{\"data\":[{\"re\":1.0,\"im\":0.0},{\"re\":0.0,\"im\":0.0},{\"re\":0.0,\"im\":0.0},{\"re\":1.0,\"im\":0.0}],\"dimension\":2},\"data\":{\"cbor\":[160],\"args\":[]},\"condition\":null}");
     }
 
     #[test]
@@ -722,4 +999,200 @@ mod tests {
         assert_eq!(cnot.get_controls(), &[]);
         assert_eq!(cnot.get_targets(), &[qref(2), qref(1)]);
     }
+
+    #[test]
+    fn canonicalize() {
+        let z: Matrix = UnboundUnitaryGate::Z.into();
+        let cz_a = Gate::new_unitary(vec![qref(2)], vec![qref(1)], z.clone()).unwrap();
+        let cz_b = Gate::new_unitary(vec![qref(1)], vec![qref(2)], z.clone()).unwrap();
+        assert_ne!(cz_a.canonicalize(), cz_b.canonicalize());
+        assert_eq!(
+            cz_a.with_matrix_controls().canonicalize(),
+            cz_b.with_matrix_controls().canonicalize()
+        );
+
+        let swap: Matrix = UnboundUnitaryGate::SWAP.into();
+        let swap_a = Gate::new_unitary(vec![qref(2), qref(1)], vec![], swap.clone()).unwrap();
+        let swap_b = Gate::new_unitary(vec![qref(1), qref(2)], vec![], swap).unwrap();
+        assert_eq!(swap_a.canonicalize(), swap_b);
+
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        let toffoli_a =
+            Gate::new_unitary(vec![qref(3)], vec![qref(2), qref(1)], x.clone()).unwrap();
+        let toffoli_b =
+            Gate::new_unitary(vec![qref(3)], vec![qref(1), qref(2)], x.clone()).unwrap();
+        assert_eq!(toffoli_a.canonicalize(), toffoli_b);
+
+        let measure_a = Gate::new_measurement(vec![qref(2), qref(1)], z.clone()).unwrap();
+        let measure_b = Gate::new_measurement(vec![qref(1), qref(2)], z).unwrap();
+        assert_eq!(measure_a.canonicalize(), measure_b);
+
+        let custom = Gate::new_custom(
+            "custom",
+            vec![qref(2), qref(1)],
+            vec![],
+            vec![],
+            None as Option<Matrix>,
+            ArbData::default(),
+        )
+        .unwrap();
+        assert_eq!(custom.canonicalize(), custom);
+    }
+
+    #[test]
+    fn approx_eq() {
+        let z: Matrix = UnboundUnitaryGate::Z.into();
+        let cz_a = Gate::new_unitary(vec![qref(2)], vec![qref(1)], z.clone()).unwrap();
+        let cz_b = Gate::new_unitary(vec![qref(1)], vec![qref(2)], z.clone()).unwrap();
+        let cz_c = Gate::new_unitary(vec![qref(1), qref(2)], vec![], z.add_controls(1)).unwrap();
+        assert!(cz_a.approx_eq(&cz_b, 0., false));
+        assert!(cz_b.approx_eq(&cz_c, 0., false));
+        assert!(cz_c.approx_eq(&cz_a, 0., false));
+
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        let cnot_a = Gate::new_unitary(vec![qref(2)], vec![qref(1)], x.clone()).unwrap();
+        let cnot_b = Gate::new_unitary(vec![qref(1)], vec![qref(2)], x.clone()).unwrap();
+        assert!(!cnot_a.approx_eq(&cnot_b, 0.001, false));
+
+        let rx_a = Gate::from(BoundUnitaryGate::RX(1., qref(1)));
+        let rx_b = Gate::from(BoundUnitaryGate::RX(1.0001, qref(1)));
+        assert!(!rx_a.approx_eq(&rx_b, 0., false));
+        assert!(rx_a.approx_eq(&rx_b, 0.001, false));
+        let rx_c = Gate::from(BoundUnitaryGate::RX(1., qref(2)));
+        assert!(!rx_a.approx_eq(&rx_c, 0.001, false));
+
+        // Parametric descriptions are not compared exactly.
+        let mut rx_d = rx_a.clone();
+        rx_d.set_parameters(&GateParameters::new("RX").with_positional(1.))
+            .unwrap();
+        let mut rx_e = rx_b.clone();
+        rx_e.set_parameters(&GateParameters::new("RX").with_positional(1.0001))
+            .unwrap();
+        assert!(rx_d.approx_eq(&rx_e, 0.001, false));
+        assert!(rx_d.approx_eq(&rx_b, 0.001, false));
+        assert!(!rx_d.approx_eq(&rx_e, 0., false));
+
+        let phase = Gate::new_unitary(vec![qref(1)], vec![], {
+            let mut m = x.clone();
+            m[(0, 1)] *= c!(0., 1.);
+            m[(1, 0)] *= c!(0., 1.);
+            m
+        })
+        .unwrap();
+        let x_gate = Gate::new_unitary(vec![qref(1)], vec![], x).unwrap();
+        assert!(!phase.approx_eq(&x_gate, 0.001, false));
+        assert!(phase.approx_eq(&x_gate, 0.001, true));
+
+        let measure_a = Gate::new_measurement(vec![qref(2), qref(1)], z.clone()).unwrap();
+        let measure_b = Gate::new_measurement(vec![qref(1), qref(2)], z).unwrap();
+        assert!(measure_a.approx_eq(&measure_b, 0., false));
+        assert!(!measure_a.approx_eq(&cz_a, 0., false));
+
+        let mut cz_d = cz_b.clone();
+        cz_d.data.set_args(vec![b"x".to_vec()]);
+        assert!(!cz_a.approx_eq(&cz_d, 0., false));
+    }
+
+    #[test]
+    fn condition() {
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        let gate = Gate::new_unitary(vec![qref(1)], vec![], x.clone()).unwrap();
+        assert_eq!(gate.get_condition(), None);
+
+        let conditional = gate.clone().with_condition(qref(2), true).unwrap();
+        assert_eq!(
+            conditional.get_condition(),
+            Some(&GateCondition {
+                qubit: qref(2),
+                value: QubitMeasurementValue::One
+            })
+        );
+        assert!(conditional
+            .get_condition()
+            .unwrap()
+            .is_satisfied_by(QubitMeasurementValue::One));
+        assert!(!conditional
+            .get_condition()
+            .unwrap()
+            .is_satisfied_by(QubitMeasurementValue::Undefined));
+        assert_eq!(conditional.without_condition(), gate);
+        assert!(!conditional.approx_eq(&gate, 0.001, false));
+        assert_eq!(
+            conditional.with_matrix_controls().get_condition(),
+            conditional.get_condition()
+        );
+
+        let measure = Gate::new_measurement(vec![qref(1)], x).unwrap();
+        assert_eq!(
+            measure
+                .with_condition(qref(2), false)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: conditional gates cannot measure qubits"
+        );
+    }
+
+    #[test]
+    fn new_pauli_measurement() {
+        let zz = Matrix::new_pauli_product("ZZ").unwrap();
+        let g = Gate::new_pauli_measurement(vec![qref(2), qref(1)], zz.clone()).unwrap();
+        assert_eq!(g.get_type(), &GateType::PauliMeasurement);
+        assert_eq!(g.get_targets(), &[qref(2), qref(1)]);
+        assert_eq!(g.get_measures(), &[qref(2)]);
+        assert_eq!(g.get_matrix(), Some(&zz));
+
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![], Matrix::new_identity(1))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: at least one qubit is required"
+        );
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![qref(1), qref(1)], zz.clone())
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 1 is used more than once"
+        );
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![qref(1)], zz)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: the matrix is expected to be sized for 1 qubits but has dimension 4"
+        );
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![qref(1)], Matrix::from(UnboundUnitaryGate::S))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: provided matrix is not Hermitian within 1e-6 tolerance"
+        );
+    }
+
+    #[test]
+    fn canonicalize_pauli_measurement() {
+        let xz = Matrix::new_pauli_product("XZI").unwrap();
+        let zx = Matrix::new_pauli_product("XIZ").unwrap();
+        let a = Gate::new_pauli_measurement(vec![qref(3), qref(2), qref(1)], xz.clone()).unwrap();
+        let b = Gate::new_pauli_measurement(vec![qref(3), qref(1), qref(2)], zx).unwrap();
+        assert_eq!(a.canonicalize(), b);
+        assert!(a.approx_eq(&b, 0., false));
+
+        let c = Gate::new_pauli_measurement(vec![qref(2), qref(3), qref(1)], xz).unwrap();
+        assert!(!a.approx_eq(&c, 0.001, false));
+    }
+
+    #[test]
+    fn approx_eq_pauli_measurement_phase() {
+        let zz = Matrix::new_pauli_product("ZZ").unwrap();
+        let mut minus_zz = zz.clone();
+        for row in 0..4 {
+            for col in 0..4 {
+                minus_zz[(row, col)] = -minus_zz[(row, col)];
+            }
+        }
+        let a = Gate::new_pauli_measurement(vec![qref(1), qref(2)], zz).unwrap();
+        let b = Gate::new_pauli_measurement(vec![qref(1), qref(2)], minus_zz).unwrap();
+        assert!(a.approx_eq(&a, 0.001, true));
+        assert!(!a.approx_eq(&b, 0.001, false));
+        assert!(!a.approx_eq(&b, 0.001, true));
+    }
 }
@@ -0,0 +1,277 @@
+use crate::common::{
+    error::{inv_arg, oe_inv_arg, Result},
+    types::ArbData,
+};
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use std::collections::BTreeMap;
+
+/// The key in the CBOR/JSON object of an `ArbData` that is used to store the
+/// parametric description of a gate. It is prefixed with `dqcsim.` to avoid
+/// collisions with user data.
+const ARB_KEY: &str = "dqcsim.gate";
+
+/// Parametric description of a gate, consisting of a gate type name and its
+/// named and/or positional floating point parameters and its positional
+/// integer parameters.
+///
+/// This description is carried in the JSON/CBOR object of the gate's
+/// `ArbData`, using the following schema:
+///
+/// ```json
+/// {
+///     "dqcsim.gate": {
+///         "type": "RX",
+///         "positional": [1.5707963267948966],
+///         "named": {"theta": 1.5707963267948966},
+///         "integers": []
+///     }
+/// }
+/// ```
+///
+/// `positional`, `named`, and `integers` are optional. Because the parameters
+/// are stored as CBOR floating point numbers and integers, they are
+/// reproduced exactly by the receiver, unlike parameters recovered from the
+/// gate matrix. A description that does not follow this schema is treated as
+/// if there is no description.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateParameters {
+    /// Name of the gate type, for instance `"RX"`.
+    #[serde(rename = "type")]
+    typ: String,
+
+    /// The positional parameters.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    positional: Vec<f64>,
+
+    /// The named parameters.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    named: BTreeMap<String, f64>,
+
+    /// The positional integer parameters. These are kept separate from the
+    /// floating point parameters so that they are reproduced exactly, even
+    /// when they exceed the precision of a double.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    integers: Vec<u64>,
+}
+
+impl GateParameters {
+    /// Constructs a new parametric gate description without parameters.
+    pub fn new(typ: impl Into<String>) -> GateParameters {
+        GateParameters {
+            typ: typ.into(),
+            positional: vec![],
+            named: BTreeMap::new(),
+            integers: vec![],
+        }
+    }
+
+    /// Appends a positional parameter, builder style.
+    pub fn with_positional(mut self, value: f64) -> GateParameters {
+        self.positional.push(value);
+        self
+    }
+
+    /// Sets a named parameter, builder style.
+    pub fn with_named(mut self, name: impl Into<String>, value: f64) -> GateParameters {
+        self.named.insert(name.into(), value);
+        self
+    }
+
+    /// Appends a positional integer parameter, builder style.
+    pub fn with_integer(mut self, value: u64) -> GateParameters {
+        self.integers.push(value);
+        self
+    }
+
+    /// Returns the name of the gate type.
+    pub fn get_type(&self) -> &str {
+        &self.typ
+    }
+
+    /// Returns the positional parameters.
+    pub fn get_positional(&self) -> &[f64] {
+        &self.positional
+    }
+
+    /// Returns a mutable reference to the positional parameters.
+    pub fn get_positional_mut(&mut self) -> &mut Vec<f64> {
+        &mut self.positional
+    }
+
+    /// Returns the named parameters.
+    pub fn get_named(&self) -> &BTreeMap<String, f64> {
+        &self.named
+    }
+
+    /// Returns a mutable reference to the named parameters.
+    pub fn get_named_mut(&mut self) -> &mut BTreeMap<String, f64> {
+        &mut self.named
+    }
+
+    /// Returns the positional integer parameters.
+    pub fn get_integers(&self) -> &[u64] {
+        &self.integers
+    }
+
+    /// Returns a mutable reference to the positional integer parameters.
+    pub fn get_integers_mut(&mut self) -> &mut Vec<u64> {
+        &mut self.integers
+    }
+
+    /// Returns the parameter with the given name, or the positional parameter
+    /// with the given index if there is no such named parameter.
+    pub fn get(&self, index: usize, name: &str) -> Result<f64> {
+        self.named
+            .get(name)
+            .or_else(|| self.positional.get(index))
+            .cloned()
+            .ok_or_else(oe_inv_arg(format!(
+                "gate {} is missing parameter {} (index {})",
+                self.typ, name, index
+            )))
+    }
+
+    /// Returns the positional integer parameter with the given index. If
+    /// there is no such parameter, the floating point parameter with the
+    /// given name or index is used instead, as long as it is a non-negative
+    /// integer.
+    pub fn get_integer(&self, index: usize, name: &str) -> Result<u64> {
+        if let Some(value) = self.integers.get(index) {
+            return Ok(*value);
+        }
+        let value = self.get(index, name)?;
+        if value < 0. || value.fract() != 0. {
+            inv_arg(format!(
+                "expected non-negative integer for parameter {}, got {}",
+                name, value
+            ))?;
+        }
+        Ok(value as u64)
+    }
+
+    /// Reads the parametric gate description from the given `ArbData`, if
+    /// there is one. Descriptions that do not follow the schema are ignored.
+    pub fn from_arb_data(data: &ArbData) -> Result<Option<GateParameters>> {
+        if let Value::Map(mut map) = serde_cbor::from_slice(data.get_cbor())? {
+            if let Some(value) = map.remove(&Value::Text(ARB_KEY.to_string())) {
+                return Ok(serde_cbor::value::from_value(value).ok());
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether a parametric gate description can be written to the
+    /// given `ArbData`, i.e. whether its JSON/CBOR object is a map.
+    pub fn can_insert_into(data: &ArbData) -> bool {
+        matches!(
+            serde_cbor::from_slice::<Value>(data.get_cbor()),
+            Ok(Value::Map(_))
+        )
+    }
+
+    /// Removes the parametric gate description from the given `ArbData` and
+    /// returns it, if there is one.
+    pub fn take_from(data: &mut ArbData) -> Result<Option<GateParameters>> {
+        let params = GateParameters::from_arb_data(data)?;
+        if params.is_some() {
+            if let Value::Map(mut map) = serde_cbor::from_slice(data.get_cbor())? {
+                map.remove(&Value::Text(ARB_KEY.to_string()));
+                data.set_cbor(serde_cbor::to_vec(&Value::Map(map))?)?;
+            }
+        }
+        Ok(params)
+    }
+
+    /// Writes this parametric gate description to the given `ArbData`,
+    /// replacing any previous description. The JSON/CBOR object of the
+    /// `ArbData` must be a map.
+    pub fn insert_into(&self, data: &mut ArbData) -> Result<()> {
+        if let Value::Map(mut map) = serde_cbor::from_slice(data.get_cbor())? {
+            map.insert(
+                Value::Text(ARB_KEY.to_string()),
+                serde_cbor::value::to_value(self)?,
+            );
+            data.set_cbor(serde_cbor::to_vec(&Value::Map(map))?)
+        } else {
+            inv_arg("cannot add parametric gate description to non-object JSON data")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let params = GateParameters::new("R")
+            .with_positional(0.1)
+            .with_positional(std::f64::consts::PI / 3.)
+            .with_named("lambda", 1. / 3.);
+        let mut data = ArbData::from_json(r#"{"line": 3}"#, vec![b"x".to_vec()]).unwrap();
+        params.insert_into(&mut data).unwrap();
+        assert_eq!(
+            GateParameters::from_arb_data(&data).unwrap(),
+            Some(params.clone())
+        );
+        assert_eq!(params.get(0, "theta").unwrap(), 0.1);
+        assert_eq!(params.get(1, "phi").unwrap(), std::f64::consts::PI / 3.);
+        assert_eq!(params.get(2, "lambda").unwrap(), 1. / 3.);
+        assert_eq!(
+            params.get(3, "other").unwrap_err().to_string(),
+            "Invalid argument: gate R is missing parameter other (index 3)"
+        );
+
+        assert_eq!(GateParameters::take_from(&mut data).unwrap(), Some(params));
+        assert_eq!(
+            data,
+            ArbData::from_json(r#"{"line": 3}"#, vec![b"x".to_vec()]).unwrap()
+        );
+        assert_eq!(GateParameters::take_from(&mut data).unwrap(), None);
+
+        let phasek = GateParameters::new("PhaseK").with_integer(u64::MAX);
+        let mut data = ArbData::default();
+        phasek.insert_into(&mut data).unwrap();
+        let phasek = GateParameters::from_arb_data(&data).unwrap().unwrap();
+        assert_eq!(phasek.get_integer(0, "k").unwrap(), u64::MAX);
+        let phasek = GateParameters::new("PhaseK").with_named("k", 3.);
+        assert_eq!(phasek.get_integer(0, "k").unwrap(), 3);
+        assert_eq!(
+            GateParameters::new("PhaseK")
+                .with_positional(0.5)
+                .get_integer(0, "k")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected non-negative integer for parameter k, got 0.5"
+        );
+    }
+
+    #[test]
+    fn schema() {
+        let data = ArbData::from_json(
+            r#"{"dqcsim.gate": {"type": "RX", "named": {"theta": 0.5}}}"#,
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            GateParameters::from_arb_data(&data).unwrap(),
+            Some(GateParameters::new("RX").with_named("theta", 0.5))
+        );
+
+        let data = ArbData::from_json(r#"{"dqcsim.gate": 3, "gate": "x"}"#, vec![]).unwrap();
+        assert_eq!(GateParameters::from_arb_data(&data).unwrap(), None);
+        assert!(GateParameters::can_insert_into(&data));
+
+        let mut data = ArbData::from_json(r#"[1, 2]"#, vec![]).unwrap();
+        assert_eq!(GateParameters::from_arb_data(&data).unwrap(), None);
+        assert!(!GateParameters::can_insert_into(&data));
+        assert_eq!(
+            GateParameters::new("RX")
+                .insert_into(&mut data)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: cannot add parametric gate description to non-object JSON data"
+        );
+    }
+}
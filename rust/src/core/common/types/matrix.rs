@@ -284,6 +284,75 @@ impl Matrix {
         true
     }
 
+    /// Checks whether this Matrix is approximately Hermitian by comparing it
+    /// with its conjugate transpose. If the RMS difference is more than
+    /// epsilon this function returns false, otherwise it returns true.
+    pub fn approx_hermitian(&self, epsilon: f64) -> bool {
+        let mut tolerance = epsilon * epsilon;
+        for i in 0..self.dimension {
+            for j in 0..self.dimension {
+                tolerance -= (self[(i, j)] - self[(j, i)].conj()).norm_sqr();
+                if tolerance < 0. {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the Kronecker product of this Matrix and `other`. In terms of
+    /// qubits, the qubits of this Matrix come first, followed by the qubits
+    /// of `other`.
+    pub fn kron(&self, other: &Matrix) -> Self {
+        let dimension = self.dimension * other.dimension;
+        let mut output = Matrix::new(vec![c!(0.); dimension * dimension]).unwrap();
+        for row in 0..dimension {
+            for col in 0..dimension {
+                output[(row, col)] = self[(row / other.dimension, col / other.dimension)]
+                    * other[(row % other.dimension, col % other.dimension)];
+            }
+        }
+        output
+    }
+
+    /// Returns a new Matrix representing the product of the Pauli matrices
+    /// described by the given string, for instance `"ZZ"` or `"XIXX"`. The
+    /// first character corresponds to the first qubit. The string must
+    /// consist of at least one of the characters `I`, `X`, `Y`, and `Z`, in
+    /// either case.
+    pub fn new_pauli_product(paulis: impl AsRef<str>) -> Result<Self> {
+        let paulis = paulis.as_ref();
+        if paulis.is_empty() {
+            return inv_arg("Pauli product must consist of at least one Pauli matrix");
+        }
+        paulis
+            .chars()
+            .try_fold(Matrix::new_identity(1), |product, pauli| {
+                let pauli = match pauli.to_ascii_uppercase() {
+                    'I' => Matrix::new_identity(2),
+                    'X' => matrix!(
+                        0., 1.;
+                        1., 0.;
+                    ),
+                    'Y' => matrix!(
+                        0., (0., -1.);
+                        (0., 1.), 0.;
+                    ),
+                    'Z' => matrix!(
+                        1., 0.;
+                        0., (-1.);
+                    ),
+                    _ => {
+                        return inv_arg(format!(
+                            "invalid Pauli matrix {}, expected I, X, Y, or Z",
+                            pauli
+                        ))
+                    }
+                };
+                Ok(product.kron(&pauli))
+            })
+    }
+
     /// Returns new Matrix with `number_of_control` qubits added.
     pub fn add_controls(&self, number_of_controls: usize) -> Self {
         let dimension = self.dimension() * 2usize.pow(number_of_controls as u32);
@@ -394,6 +463,44 @@ impl Matrix {
         (controls, Matrix::new(entries).unwrap())
     }
 
+    /// Returns a new Matrix with its qubits reordered, such that qubit `i` of
+    /// the returned Matrix corresponds to qubit `order[i]` of this Matrix.
+    ///
+    /// Applying the returned Matrix to a list of qubits reordered the same
+    /// way is equivalent to applying this Matrix to the original list.
+    ///
+    /// Panics if `order` is not a permutation of the qubit indices.
+    pub fn permute_qubits(&self, order: &[usize]) -> Self {
+        let num_qubits = self.num_qubits().unwrap();
+        assert_eq!(order.len(), num_qubits, "invalid qubit permutation");
+        let mut seen = vec![false; num_qubits];
+        for &q in order {
+            assert!(q < num_qubits && !seen[q], "invalid qubit permutation");
+            seen[q] = true;
+        }
+
+        // Note that the qubit indices used in DQCsim are reversed with
+        // respect to the matrix indices.
+        let map_index = |new: usize| {
+            (0..num_qubits).fold(0, |old, i| {
+                if new & (1 << (num_qubits - i - 1)) != 0 {
+                    old | (1 << (num_qubits - order[i] - 1))
+                } else {
+                    old
+                }
+            })
+        };
+        let mapping: Vec<usize> = (0..self.dimension()).map(map_index).collect();
+
+        let mut output = self.clone();
+        for row in 0..self.dimension() {
+            for col in 0..self.dimension() {
+                output[(row, col)] = self[(mapping[row], mapping[col])];
+            }
+        }
+        output
+    }
+
     /// Returns the number of elements in the Matrix.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -600,6 +707,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn permute_qubits() {
+        let cnot = matrix!(
+            1., 0., 0., 0.;
+            0., 1., 0., 0.;
+            0., 0., 0., 1.;
+            0., 0., 1., 0.;
+        );
+        let cnot_rev = matrix!(
+            1., 0., 0., 0.;
+            0., 0., 0., 1.;
+            0., 0., 1., 0.;
+            0., 1., 0., 0.;
+        );
+        assert_eq!(cnot.permute_qubits(&[0, 1]), cnot);
+        assert_eq!(cnot.permute_qubits(&[1, 0]), cnot_rev);
+        assert_eq!(cnot_rev.permute_qubits(&[1, 0]), cnot);
+
+        let swap: Matrix = UnboundUnitaryGate::SWAP.into();
+        assert_eq!(swap.permute_qubits(&[1, 0]), swap);
+
+        let toffoli = Matrix::from(UnboundUnitaryGate::X).add_controls(2);
+        assert_eq!(toffoli.permute_qubits(&[1, 0, 2]), toffoli);
+        assert_eq!(
+            toffoli
+                .permute_qubits(&[2, 0, 1])
+                .permute_qubits(&[1, 2, 0]),
+            toffoli
+        );
+        assert_ne!(toffoli.permute_qubits(&[2, 0, 1]), toffoli);
+    }
+
     #[test]
     fn strip_control() {
         let cnot_a = matrix!(
@@ -670,4 +809,43 @@ mod tests {
         let x: Matrix = UnboundUnitaryGate::X.into();
         assert!(matrix_a.approx_eq(&x, 0.001, false));
     }
+
+    #[test]
+    fn pauli_product() {
+        let zz = Matrix::new_pauli_product("ZZ").unwrap();
+        assert!(zz.approx_eq(
+            &matrix!(
+                1., 0., 0., 0.;
+                0., (-1.), 0., 0.;
+                0., 0., (-1.), 0.;
+                0., 0., 0., 1.;
+            ),
+            0.,
+            false
+        ));
+        assert!(zz.approx_hermitian(0.));
+        assert!(zz.approx_unitary(0.));
+
+        let xz = Matrix::new_pauli_product("xZ").unwrap();
+        let x = Matrix::from(UnboundUnitaryGate::X);
+        let z = Matrix::from(UnboundUnitaryGate::Z);
+        assert_eq!(xz, x.kron(&z));
+        assert!(xz.permute_qubits(&[1, 0]).approx_eq(&z.kron(&x), 0., false));
+        assert!(Matrix::new_pauli_product("Y").unwrap().approx_eq(
+            &Matrix::from(UnboundUnitaryGate::Y),
+            0.,
+            false
+        ));
+        assert_eq!(Matrix::new_pauli_product("XXXX").unwrap().dimension(), 16);
+
+        assert!(!Matrix::from(UnboundUnitaryGate::S).approx_hermitian(0.1));
+        assert_eq!(
+            Matrix::new_pauli_product("").unwrap_err().to_string(),
+            "Invalid argument: Pauli product must consist of at least one Pauli matrix"
+        );
+        assert_eq!(
+            Matrix::new_pauli_product("XA").unwrap_err().to_string(),
+            "Invalid argument: invalid Pauli matrix A, expected I, X, Y, or Z"
+        );
+    }
 }
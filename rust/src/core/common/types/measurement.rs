@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 /// Represents the state of a single qubit measurement register.
 use std::fmt;
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum QubitMeasurementValue {
     /// The value is unknown because the qubit has not been measured yet, or
     /// the most recent measurement failed.
@@ -13,6 +13,11 @@ pub use cycle::{Cycle, CycleDelta, Cycles};
 mod qubit_ref;
 pub use qubit_ref::{QubitRef, QubitRefGenerator};
 
+// Binary strings stored in shared memory, for transferring large arguments
+// without copying.
+mod shared_bytes;
+pub use shared_bytes::{with_inline_serialization, SharedBytes};
+
 // User-defined/implementation-specific data.
 mod arb_data;
 pub use arb_data::ArbData;
@@ -21,9 +26,21 @@ pub use arb_data::ArbData;
 mod arb_cmd;
 pub use arb_cmd::ArbCmd;
 
+// Schemas for the JSON/CBOR payload of ArbData objects.
+mod arb_schema;
+pub use arb_schema::ArbSchema;
+
+// Registry of the ArbCmd interfaces and operations supported by a plugin.
+mod arb_interfaces;
+pub use arb_interfaces::ArbInterfaces;
+
 // Generic representation of a quantum or mixed quantum-classical gate.
 mod gate;
-pub use gate::{Gate, GateType};
+pub use gate::{Gate, GateCondition, GateType};
+
+// Exact parametric description of a gate, carried in its ArbData.
+mod gate_parameters;
+pub use gate_parameters::GateParameters;
 
 // Generic representation of a qubit measurement result.
 mod measurement;
@@ -37,6 +54,10 @@ pub use plugin_type::PluginType;
 mod plugin_metadata;
 pub use plugin_metadata::PluginMetadata;
 
+// Performance profiles reported by plugins.
+mod plugin_profile;
+pub use plugin_profile::{CallbackProfile, PluginProfile};
+
 // Matrix wrapper for gate matrices.
 mod matrix;
 pub use matrix::{Basis, Matrix};
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// Timing statistics for a single user callback of a plugin.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CallbackProfile {
+    /// The number of times the callback was called.
+    pub calls: u64,
+
+    /// The total time spent in the callback. This excludes the time spent
+    /// blocked waiting for other plugins or the host, the time spent
+    /// serializing and deserializing IPC messages, and the time spent in
+    /// nested callbacks.
+    pub total: Duration,
+}
+
+impl CallbackProfile {
+    /// Returns the average time spent per call.
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::default()
+        } else {
+            self.total / self.calls as u32
+        }
+    }
+}
+
+/// Performance profile of a plugin, as measured by the plugin itself.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct PluginProfile {
+    /// Timing statistics for each user callback that was called at least
+    /// once, by callback name.
+    pub callbacks: BTreeMap<String, CallbackProfile>,
+
+    /// The total time spent blocked waiting for responses from the
+    /// downstream plugin.
+    pub downstream_wait: Duration,
+
+    /// The total time spent serializing, sending, and deserializing IPC
+    /// messages.
+    pub ipc: Duration,
+}
+
+impl PluginProfile {
+    /// Records a call to the given callback that took the given amount of
+    /// time.
+    pub fn record(&mut self, callback: impl AsRef<str>, time: Duration) {
+        let callback = callback.as_ref();
+        let profile = match self.callbacks.get_mut(callback) {
+            Some(profile) => profile,
+            None => self.callbacks.entry(callback.to_string()).or_default(),
+        };
+        profile.calls += 1;
+        profile.total += time;
+    }
+
+    /// Returns the total time spent in all callbacks.
+    pub fn callback_total(&self) -> Duration {
+        self.callbacks.values().map(|profile| profile.total).sum()
+    }
+
+    /// Converts the profile to a JSON object, with all durations represented
+    /// as floating point seconds.
+    pub fn to_json(&self) -> serde_json::Value {
+        let callbacks: serde_json::Map<String, serde_json::Value> = self
+            .callbacks
+            .iter()
+            .map(|(name, profile)| {
+                (
+                    name.clone(),
+                    json!({
+                        "calls": profile.calls,
+                        "total": profile.total.as_secs_f64(),
+                    }),
+                )
+            })
+            .collect();
+        json!({
+            "callbacks": callbacks,
+            "downstream_wait": self.downstream_wait.as_secs_f64(),
+            "ipc": self.ipc.as_secs_f64(),
+        })
+    }
+}
+
+impl fmt::Display for PluginProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>10} {:>12} {:>12}",
+            "callback", "calls", "total", "average"
+        )?;
+        for (name, profile) in self.callbacks.iter() {
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>12} {:>12}",
+                name,
+                profile.calls,
+                format!("{:.3?}", profile.total),
+                format!("{:.3?}", profile.average())
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<31} {:>12}",
+            "waiting for downstream",
+            format!("{:.3?}", self.downstream_wait)
+        )?;
+        writeln!(
+            f,
+            "{:<31} {:>12}",
+            "IPC overhead",
+            format!("{:.3?}", self.ipc)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let mut profile = PluginProfile::default();
+        profile.record("gate", Duration::from_millis(3));
+        profile.record("gate", Duration::from_millis(1));
+        profile.record("advance", Duration::from_millis(2));
+        assert_eq!(
+            profile.callbacks["gate"],
+            CallbackProfile {
+                calls: 2,
+                total: Duration::from_millis(4),
+            }
+        );
+        assert_eq!(
+            profile.callbacks["gate"].average(),
+            Duration::from_millis(2)
+        );
+        assert_eq!(profile.callback_total(), Duration::from_millis(6));
+        assert_eq!(CallbackProfile::default().average(), Duration::default());
+    }
+
+    #[test]
+    fn json_and_display() {
+        let mut profile = PluginProfile::default();
+        profile.record("gate", Duration::from_millis(500));
+        profile.downstream_wait = Duration::from_secs(2);
+        profile.ipc = Duration::from_millis(250);
+        assert_eq!(
+            profile.to_json(),
+            json!({
+                "callbacks": { "gate": { "calls": 1, "total": 0.5 } },
+                "downstream_wait": 2.0,
+                "ipc": 0.25,
+            })
+        );
+        assert_eq!(
+            profile.to_string(),
+            "callback                  calls        total      average\n\
+             gate                          1    500.000ms    500.000ms\n\
+             waiting for downstream                2.000s\n\
+             IPC overhead                       250.000ms\n"
+        );
+    }
+}
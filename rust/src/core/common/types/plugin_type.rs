@@ -1,9 +1,29 @@
+use named_type::NamedType;
+use named_type_derive::*;
 use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
 
 /// Enumeration of the three types of plugins.
-#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Deserialize, Serialize, EnumString, Display, EnumIter, NamedType,
+)]
 pub enum PluginType {
+    #[strum(to_string = "Frontend", serialize = "frontend")]
     Frontend,
+    #[strum(to_string = "Operator", serialize = "operator")]
     Operator,
+    #[strum(to_string = "Backend", serialize = "backend")]
     Backend,
 }
+
+impl PluginType {
+    /// Returns the prefix of the executable filenames of plugins of this type,
+    /// used to desugar plugin specifications.
+    pub fn executable_prefix(self) -> &'static str {
+        match self {
+            PluginType::Frontend => "dqcsfe",
+            PluginType::Operator => "dqcsop",
+            PluginType::Backend => "dqcsbe",
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::fmt;
 
 /// Represents a reference to a qubit.
 #[repr(transparent)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct QubitRef(u64);
 
 impl fmt::Display for QubitRef {
@@ -0,0 +1,175 @@
+use ipc_channel::ipc::IpcSharedMemory;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cell::Cell,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// Immutable binary string stored in a shared memory segment.
+///
+/// When an `ArbData` containing these is sent to another plugin or to the
+/// host, only a handle to the shared memory segment is transferred; the
+/// receiver maps the same memory instead of receiving a copy. This makes it
+/// suitable for large payloads such as state vectors or lookup tables.
+///
+/// The data is copied into the shared memory segment once, when the object
+/// is constructed. After that, cloning, sending, and reading the object does
+/// not copy the data.
+///
+/// When serialized to a human-readable format (such as the YAML of a
+/// reproduction file), the contents are written out as a regular byte
+/// string instead. The same goes for binary formats while
+/// `with_inline_serialization()` is active.
+#[derive(Clone)]
+pub struct SharedBytes {
+    /// The shared memory segment, or `None` for an empty string; ipc-channel
+    /// cannot map empty segments.
+    shm: Option<IpcSharedMemory>,
+}
+
+impl SharedBytes {
+    /// Copies the given bytes into a new shared memory segment.
+    pub fn from_bytes(bytes: &[u8]) -> SharedBytes {
+        SharedBytes {
+            shm: if bytes.is_empty() {
+                None
+            } else {
+                Some(IpcSharedMemory::from_bytes(bytes))
+            },
+        }
+    }
+}
+
+thread_local! {
+    /// Whether `SharedBytes` objects are currently to be serialized inline,
+    /// see `with_inline_serialization()`.
+    static INLINE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs the given closure such that any `SharedBytes` objects that are
+/// (de)serialized by it are written out as regular byte strings, even for
+/// binary formats.
+///
+/// Serializing a shared memory handle only makes sense when the result is
+/// sent over an IPC channel. This must be used when the result ends up
+/// somewhere else, such as a file.
+pub fn with_inline_serialization<T>(f: impl FnOnce() -> T) -> T {
+    let previous = INLINE.with(|inline| inline.replace(true));
+    let result = f();
+    INLINE.with(|inline| inline.set(previous));
+    result
+}
+
+/// Returns whether `SharedBytes` objects are to be serialized inline.
+fn inline(human_readable: bool) -> bool {
+    human_readable || INLINE.with(Cell::get)
+}
+
+impl Deref for SharedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.shm {
+            Some(shm) => shm,
+            None => &[],
+        }
+    }
+}
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<&[u8]> for SharedBytes {
+    fn from(bytes: &[u8]) -> SharedBytes {
+        SharedBytes::from_bytes(bytes)
+    }
+}
+
+impl From<Vec<u8>> for SharedBytes {
+    fn from(bytes: Vec<u8>) -> SharedBytes {
+        SharedBytes::from_bytes(&bytes)
+    }
+}
+
+impl PartialEq for SharedBytes {
+    fn eq(&self, other: &SharedBytes) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl Eq for SharedBytes {}
+
+impl Hash for SharedBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state);
+    }
+}
+
+impl fmt::Debug for SharedBytes {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SharedBytes")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl Serialize for SharedBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if inline(serializer.is_human_readable()) {
+            self[..].serialize(serializer)
+        } else {
+            self.shm.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SharedBytes, D::Error> {
+        if inline(deserializer.is_human_readable()) {
+            Vec::<u8>::deserialize(deserializer).map(SharedBytes::from)
+        } else {
+            Option::<IpcSharedMemory>::deserialize(deserializer).map(|shm| SharedBytes { shm })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ipc_channel::ipc;
+
+    #[test]
+    fn transfer() {
+        let data: Vec<u8> = (0..=255).cycle().take(1 << 20).collect();
+        let shared = SharedBytes::from(data.clone());
+        assert_eq!(&shared[..], &data[..]);
+        assert_eq!(format!("{:?}", shared), "SharedBytes { len: 1048576 }");
+
+        let (tx, rx) = ipc::channel().unwrap();
+        tx.send(shared.clone()).unwrap();
+        let received: SharedBytes = rx.recv().unwrap();
+        assert_eq!(received, shared);
+
+        let empty = SharedBytes::from_bytes(&[]);
+        tx.send(empty.clone()).unwrap();
+        assert!(rx.recv().unwrap().is_empty());
+
+        let json = serde_json::to_string(&SharedBytes::from_bytes(b"abc")).unwrap();
+        assert_eq!(json, "[97,98,99]");
+        assert_eq!(
+            serde_json::from_str::<SharedBytes>(&json).unwrap(),
+            SharedBytes::from_bytes(b"abc")
+        );
+
+        let cbor = with_inline_serialization(|| serde_cbor::to_vec(&shared).unwrap());
+        assert!(cbor.len() > 1 << 20);
+        let decoded: SharedBytes =
+            with_inline_serialization(|| serde_cbor::from_slice(&cbor).unwrap());
+        assert_eq!(decoded, shared);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::host::debugger::{Breakpoint, DebuggerInterface};
+use std::fmt;
+
+/// Configuration for the interactive gatestream debugger.
+///
+/// When specified, the simulator inserts a debugger operator just upstream
+/// of the plugin being debugged, which pauses before forwarding gatestream
+/// requests to it. See the `host::debugger` module for more information.
+pub struct DebuggerConfiguration {
+    /// Instance name of the plugin being debugged. This cannot be the
+    /// frontend, since it does not receive gatestream requests.
+    pub plugin: String,
+
+    /// The initial breakpoints.
+    pub breakpoints: Vec<Breakpoint>,
+
+    /// Whether to pause before the first request, regardless of the
+    /// breakpoints.
+    pub pause_at_start: bool,
+
+    /// The user interface that is called when the simulation pauses.
+    pub interface: Box<dyn DebuggerInterface>,
+}
+
+impl fmt::Debug for DebuggerConfiguration {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DebuggerConfiguration")
+            .field("plugin", &self.plugin)
+            .field("breakpoints", &self.breakpoints)
+            .field("pause_at_start", &self.pause_at_start)
+            .field("interface", &"...".to_string())
+            .finish()
+    }
+}
+
+impl DebuggerConfiguration {
+    /// Creates a new debugger configuration for the plugin with the given
+    /// instance name.
+    ///
+    /// By default, there are no breakpoints and the simulation pauses before
+    /// the first request.
+    pub fn new(
+        plugin: impl Into<String>,
+        interface: impl DebuggerInterface + 'static,
+    ) -> DebuggerConfiguration {
+        DebuggerConfiguration {
+            plugin: plugin.into(),
+            breakpoints: vec![],
+            pause_at_start: true,
+            interface: Box::new(interface),
+        }
+    }
+
+    /// Adds a breakpoint, builder style.
+    pub fn with_breakpoint(mut self, breakpoint: Breakpoint) -> DebuggerConfiguration {
+        self.breakpoints.push(breakpoint);
+        self
+    }
+
+    /// Sets whether to pause before the first request, builder style.
+    pub fn with_pause_at_start(mut self, pause_at_start: bool) -> DebuggerConfiguration {
+        self.pause_at_start = pause_at_start;
+        self
+    }
+}
@@ -0,0 +1,334 @@
+use crate::common::error::{inv_arg, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    env::{split_paths, var_os},
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Selects the script files that an interpreter command applies to.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum InterpreterMatch {
+    /// Matches scripts with the given file extension, specified without the
+    /// period.
+    Extension(String),
+
+    /// Matches scripts with a shebang line that runs the given program. Only
+    /// the filename of the program is compared, and `/usr/bin/env <program>`
+    /// is treated as `<program>`.
+    Shebang(String),
+}
+
+impl InterpreterMatch {
+    /// Returns whether this matches the given script file. `shebang` is the
+    /// program named by the shebang line of the script, if any.
+    fn matches(&self, script: &Path, shebang: Option<&str>) -> bool {
+        match self {
+            InterpreterMatch::Extension(ext) => {
+                script.extension().is_some_and(|x| x == ext.as_str())
+            }
+            InterpreterMatch::Shebang(program) => shebang == Some(program.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for InterpreterMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterpreterMatch::Extension(ext) => write!(f, ".{}", ext),
+            InterpreterMatch::Shebang(program) => write!(f, "#!{}", program),
+        }
+    }
+}
+
+impl FromStr for InterpreterMatch {
+    type Err = Error;
+
+    /// Parses an interpreter match from `.<extension>` or `#!<program>`.
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with('.') && s.len() > 1 {
+            Ok(InterpreterMatch::Extension(s[1..].to_string()))
+        } else if s.starts_with("#!") && s.len() > 2 {
+            Ok(InterpreterMatch::Shebang(s[2..].to_string()))
+        } else {
+            inv_arg(format!(
+                "invalid interpreter match '{}', expected .<extension> or #!<program>",
+                s
+            ))
+        }
+    }
+}
+
+/// An interpreter command line. The script filename is passed after the
+/// arguments, followed by the simulator endpoint like for any other plugin.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InterpreterCommand {
+    /// The interpreter program. If this does not contain a slash, it is
+    /// searched for in the system $PATH.
+    pub program: PathBuf,
+
+    /// The arguments passed to the interpreter before the script filename.
+    pub args: Vec<String>,
+}
+
+impl InterpreterCommand {
+    /// Constructs a new interpreter command.
+    pub fn new<T>(program: impl Into<PathBuf>, args: impl IntoIterator<Item = T>) -> Self
+    where
+        T: Into<String>,
+    {
+        InterpreterCommand {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the path to the interpreter program, looking it up in the
+    /// system $PATH if it does not contain a slash.
+    pub fn resolve_program(&self) -> Result<PathBuf> {
+        if self.program.to_string_lossy().contains('/') {
+            if self.program.exists() {
+                return Ok(self.program.clone());
+            }
+        } else if let Some(sys_path) = var_os("PATH") {
+            for base in split_paths(&sys_path) {
+                let program = base.join(&self.program);
+                if program.exists() {
+                    return Ok(program);
+                }
+            }
+        }
+        inv_arg(format!(
+            "could not find interpreter '{}'",
+            self.program.to_string_lossy()
+        ))
+    }
+}
+
+impl fmt::Display for InterpreterCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program.to_string_lossy())?;
+        for arg in self.args.iter() {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InterpreterCommand {
+    type Err = Error;
+
+    /// Parses an interpreter command line. The program and its arguments are
+    /// separated by whitespace; quoting is not supported.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut words = s.split_whitespace();
+        match words.next() {
+            Some(program) => Ok(InterpreterCommand::new(program, words)),
+            None => inv_arg("interpreter command must not be empty"),
+        }
+    }
+}
+
+/// An entry of an `InterpreterRegistry`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InterpreterEntry {
+    /// The script files this entry applies to.
+    pub matcher: InterpreterMatch,
+
+    /// The command used to run them.
+    pub command: InterpreterCommand,
+}
+
+impl fmt::Display for InterpreterEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.matcher, self.command)
+    }
+}
+
+impl FromStr for InterpreterEntry {
+    type Err = Error;
+
+    /// Parses an interpreter registry entry of the form `<match>=<command>`,
+    /// for instance `.jl=julia --project=/opt/env` or
+    /// `#!python3=/opt/venv/bin/python`.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut splitter = s.splitn(2, '=');
+        let matcher = splitter.next().unwrap();
+        match splitter.next() {
+            Some(command) => Ok(InterpreterEntry {
+                matcher: matcher.parse()?,
+                command: command.parse()?,
+            }),
+            None => inv_arg(format!(
+                "invalid interpreter '{}', expected <match>=<command>",
+                s
+            )),
+        }
+    }
+}
+
+/// Registry of the interpreter commands used to run script plugins.
+///
+/// By default, a script with extension `<ext>` is run by the plugin
+/// executable named `dqcsfe<ext>`, `dqcsop<ext>`, or `dqcsbe<ext>`. Scripts
+/// matched by an entry in this registry are run by the registered command
+/// instead. Extension matches take precedence over shebang matches.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct InterpreterRegistry {
+    entries: Vec<InterpreterEntry>,
+}
+
+impl InterpreterRegistry {
+    /// Constructs an empty registry.
+    pub fn new() -> InterpreterRegistry {
+        InterpreterRegistry::default()
+    }
+
+    /// Registers an interpreter command, replacing any previous command for
+    /// the same match.
+    pub fn register(&mut self, entry: InterpreterEntry) {
+        self.entries.retain(|x| x.matcher != entry.matcher);
+        self.entries.push(entry);
+    }
+
+    /// Registers an interpreter command, builder style.
+    pub fn with(mut self, matcher: InterpreterMatch, command: InterpreterCommand) -> Self {
+        self.register(InterpreterEntry { matcher, command });
+        self
+    }
+
+    /// Returns whether no interpreters have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the registered entries.
+    pub fn entries(&self) -> &[InterpreterEntry] {
+        &self.entries
+    }
+
+    /// Returns the interpreter command registered for the given script file,
+    /// if any. The first line of the script is read if the registry contains
+    /// shebang matches.
+    pub fn lookup(&self, script: impl AsRef<Path>) -> Option<&InterpreterCommand> {
+        let script = script.as_ref();
+        let is_shebang = |entry: &&InterpreterEntry| match entry.matcher {
+            InterpreterMatch::Extension(_) => false,
+            InterpreterMatch::Shebang(_) => true,
+        };
+        if let Some(entry) = self
+            .entries
+            .iter()
+            .filter(|entry| !is_shebang(entry))
+            .find(|entry| entry.matcher.matches(script, None))
+        {
+            return Some(&entry.command);
+        }
+        if !self.entries.iter().any(|entry| is_shebang(&entry)) {
+            return None;
+        }
+        let shebang = read_shebang(script)?;
+        self.entries
+            .iter()
+            .filter(is_shebang)
+            .find(|entry| entry.matcher.matches(script, Some(&shebang)))
+            .map(|entry| &entry.command)
+    }
+}
+
+/// Returns the filename of the program named by the shebang line of the
+/// given file, looking through `/usr/bin/env`.
+fn read_shebang(script: &Path) -> Option<String> {
+    let mut line = vec![];
+    BufReader::new(File::open(script).ok()?)
+        .take(1024)
+        .read_until(b'\n', &mut line)
+        .ok()?;
+    let line = String::from_utf8_lossy(&line);
+    if !line.starts_with("#!") {
+        return None;
+    }
+    let mut words = line[2..].split_whitespace();
+    let mut program = Path::new(words.next()?).file_name()?.to_string_lossy();
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-'))?.into();
+    }
+    Some(program.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn parse() {
+        let entry: InterpreterEntry = ".jl=julia --project=/opt/env".parse().unwrap();
+        assert_eq!(
+            entry,
+            InterpreterEntry {
+                matcher: InterpreterMatch::Extension("jl".to_string()),
+                command: InterpreterCommand::new("julia", vec!["--project=/opt/env"]),
+            }
+        );
+        assert_eq!(entry.to_string(), ".jl=julia --project=/opt/env");
+
+        let entry: InterpreterEntry = "#!python3=/opt/venv/bin/python".parse().unwrap();
+        assert_eq!(
+            entry.matcher,
+            InterpreterMatch::Shebang("python3".to_string())
+        );
+        assert_eq!(entry.command.program, PathBuf::from("/opt/venv/bin/python"));
+        assert!(entry.command.args.is_empty());
+
+        assert_eq!(
+            "jl=julia".parse::<InterpreterEntry>().unwrap_err().to_string(),
+            "Invalid argument: invalid interpreter match 'jl', expected .<extension> or #!<program>"
+        );
+        assert_eq!(
+            ".jl= ".parse::<InterpreterEntry>().unwrap_err().to_string(),
+            "Invalid argument: interpreter command must not be empty"
+        );
+        assert_eq!(
+            ".jl".parse::<InterpreterEntry>().unwrap_err().to_string(),
+            "Invalid argument: invalid interpreter '.jl', expected <match>=<command>"
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let dir = std::env::temp_dir().join(format!("dqcsim-interp-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let shebang = dir.join("plugin");
+        fs::write(&shebang, "#!/usr/bin/env -S python3 -u\nprint()\n").unwrap();
+        let julia = dir.join("plugin.jl");
+        fs::write(&julia, "#!/usr/bin/python3\n").unwrap();
+
+        let registry = InterpreterRegistry::new()
+            .with(
+                "#!python3".parse().unwrap(),
+                "/opt/venv/bin/python".parse().unwrap(),
+            )
+            .with(".jl".parse().unwrap(), "julia".parse().unwrap())
+            .with(".jl".parse().unwrap(), "julia --project".parse().unwrap());
+        let shebang_command = registry.lookup(&shebang).cloned();
+        let julia_command = registry.lookup(&julia).cloned();
+        let missing_command = registry.lookup(dir.join("missing")).cloned();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(registry.entries().len(), 2);
+        assert_eq!(
+            shebang_command.unwrap().program,
+            PathBuf::from("/opt/venv/bin/python")
+        );
+        assert_eq!(
+            julia_command,
+            Some(InterpreterCommand::new("julia", vec!["--project"]))
+        );
+        assert_eq!(missing_command, None);
+        assert_eq!(InterpreterRegistry::new().lookup(&julia), None);
+    }
+}
@@ -12,10 +12,16 @@ pub use seed::Seed;
 mod timeout;
 pub use timeout::Timeout;
 
+mod interpreter;
+pub use interpreter::{
+    InterpreterCommand, InterpreterEntry, InterpreterMatch, InterpreterRegistry,
+};
+
 mod plugin;
 pub use plugin::{
     log::PluginLogConfiguration,
     process::{
+        discover_plugins, plugin_search_path, user_plugin_dir, DesugarOptions, DiscoveredPlugin,
         PluginProcessConfiguration, PluginProcessFunctionalConfiguration,
         PluginProcessNonfunctionalConfiguration, PluginProcessSpecification,
     },
@@ -26,5 +32,8 @@ pub use plugin::{
 mod reproduction_path_style;
 pub use reproduction_path_style::ReproductionPathStyle;
 
+mod debugger;
+pub use debugger::DebuggerConfiguration;
+
 mod simulator;
 pub use simulator::SimulatorConfiguration;
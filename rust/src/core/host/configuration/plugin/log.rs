@@ -1,5 +1,5 @@
 use crate::{
-    common::log::{tee_file::TeeFileConfiguration, LoglevelFilter},
+    common::log::{module_filter::ModuleFilter, tee_file::TeeFileConfiguration, LoglevelFilter},
     host::configuration::PluginProcessConfiguration,
 };
 use serde::{Deserialize, Serialize};
@@ -13,6 +13,10 @@ pub struct PluginLogConfiguration {
     /// Specifies the verbosity of the messages sent to DQCsim.
     pub verbosity: LoglevelFilter,
 
+    /// Overrides the verbosity for messages from specific modules.
+    #[serde(default)]
+    pub modules: ModuleFilter,
+
     /// Specifies the tee file configurations for this plugin.
     pub tee_files: Vec<TeeFileConfiguration>,
 }
@@ -24,9 +28,17 @@ impl PluginLogConfiguration {
         PluginLogConfiguration {
             name: name.into(),
             verbosity,
+            modules: ModuleFilter::default(),
             tee_files: vec![],
         }
     }
+
+    /// Overrides the verbosity for messages from specific modules, builder
+    /// style.
+    pub fn with_modules(mut self, modules: ModuleFilter) -> PluginLogConfiguration {
+        self.modules = modules;
+        self
+    }
 }
 
 impl From<&PluginProcessConfiguration> for PluginLogConfiguration {
@@ -34,6 +46,7 @@ impl From<&PluginProcessConfiguration> for PluginLogConfiguration {
         PluginLogConfiguration {
             name: cfg.name.clone(),
             verbosity: cfg.nonfunctional.verbosity,
+            modules: cfg.nonfunctional.modules.clone(),
             tee_files: cfg.nonfunctional.tee_files.clone(),
         }
     }
@@ -1,25 +1,32 @@
 use crate::{
     common::{
         error::{inv_arg, oe_err, Result},
-        log::{tee_file::TeeFileConfiguration, Loglevel, LoglevelFilter},
+        log::{
+            module_filter::ModuleFilter, tee_file::TeeFileConfiguration, Loglevel, LoglevelFilter,
+        },
         types::{ArbCmd, PluginType},
     },
     host::{
         configuration::{
-            env_mod::EnvMod, plugin::log::PluginLogConfiguration,
-            stream_capture_mode::StreamCaptureMode, timeout::Timeout, PluginConfiguration,
-            ReproductionPathStyle,
+            env_mod::EnvMod,
+            interpreter::{InterpreterEntry, InterpreterRegistry},
+            plugin::log::PluginLogConfiguration,
+            stream_capture_mode::StreamCaptureMode,
+            timeout::Timeout,
+            PluginConfiguration, ReproductionPathStyle,
         },
         plugin::{process::PluginProcess, Plugin},
         reproduction::PluginReproduction,
     },
 };
+use is_executable::IsExecutable;
 use serde::{Deserialize, Serialize};
 use std::{
     env::{current_exe, split_paths, var_os},
     ffi::OsString,
     path::PathBuf,
 };
+use strum::IntoEnumIterator;
 
 /// Plugin specification, consisting of the executable filename for the plugin
 /// and an optional script filename for it to execute for when the executable
@@ -34,6 +41,12 @@ pub struct PluginProcessSpecification {
     /// is a native plugin.
     pub script: Option<PathBuf>,
 
+    /// Additional arguments passed to the executable before the script
+    /// filename, for instance the arguments of an interpreter command from an
+    /// `InterpreterRegistry`. Set using `with_args()`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+
     /// Plugin type.
     pub typ: PluginType,
 }
@@ -51,28 +64,60 @@ impl PluginProcessSpecification {
         PluginProcessSpecification {
             executable: executable.into(),
             script: script.map(std::convert::Into::into),
+            args: vec![],
             typ: typ.into(),
         }
     }
 
+    /// Sets the arguments passed to the executable before the script
+    /// filename.
+    pub fn with_args<T>(mut self, args: impl IntoIterator<Item = T>) -> PluginProcessSpecification
+    where
+        T: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the arguments passed to the executable before the script
+    /// filename.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
     /// Constructs a plugin specification from a "sugared" specification.
     ///
     /// The specification can take the following forms:
     ///
     /// - a valid path to a plugin executable with no file extension;
     /// - the basename of a plugin executable with no file extension with
-    ///   implicit "dqcsfe"/"dqcsop"/"dqcsbe" prefix, searched for in A) the
-    ///   working directory, B) the binary directory, and C) the system $PATH;
+    ///   implicit "dqcsfe"/"dqcsop"/"dqcsbe" prefix, searched for in the
+    ///   working directory and the directories returned by
+    ///   `plugin_search_path()`;
     /// - a valid path to a script file with a file extension. In this case,
     ///   the above rule is run for a plugin named by the file extension of the
     ///   script file. For instance, if "test.py" is specified for the frontend,
     ///   this will look for an executable named "dqcsfepy".
     ///
     /// Failure to find the plugin executable or script file results in an
-    /// error being returned.
+    /// error being returned, which lists the directories that were searched.
     pub fn from_sugar(
         specification: impl Into<PathBuf>,
         typ: PluginType,
+    ) -> Result<PluginProcessSpecification> {
+        PluginProcessSpecification::from_sugar_with(specification, typ, &DesugarOptions::default())
+    }
+
+    /// Like `from_sugar()`, but with the given options: the directories in
+    /// `options.plugin_path` are searched for sugared plugin names before the
+    /// default search path, i.e. right after the working directory, and
+    /// existing files matched by `options.interpreters`, by file extension or
+    /// shebang line, are run as scripts by the registered interpreter command
+    /// instead of a "dqcsfe"/"dqcsop"/"dqcsbe" plugin executable.
+    pub fn from_sugar_with(
+        specification: impl Into<PathBuf>,
+        typ: PluginType,
+        options: &DesugarOptions,
     ) -> Result<PluginProcessSpecification> {
         // Generate the default specification. This default assumes that the
         // specification is a valid path to an executable. We'll fix the
@@ -82,13 +127,20 @@ impl PluginProcessSpecification {
         let mut specification = PluginProcessSpecification {
             executable: specification,
             script: None,
+            args: vec![],
             typ,
         };
 
         // Handle the simple cases, where the specification is a path to an
         // existing file.
         if specification.executable.exists() {
-            if specification.executable.extension().is_some() {
+            if let Some(command) = options.interpreters.lookup(&specification.executable) {
+                // The file is a script with a registered interpreter.
+                specification.script = Some(specification.executable);
+                specification.executable = command.resolve_program()?;
+                specification.args = command.args.clone();
+                return Ok(specification);
+            } else if specification.executable.extension().is_some() {
                 // The file that we assumed to be the executable is actually a
                 // script file. Set the executable to just the file extension;
                 // we desugar that later.
@@ -126,12 +178,7 @@ impl PluginProcessSpecification {
         // The executable does not exist (or is just a file extension and
         // should always be treated as sugar). Before we look for the plugin
         // elsewhere, add the appropriate prefix.
-        let mut prefix: OsString = match typ {
-            PluginType::Frontend => "dqcsfe",
-            PluginType::Operator => "dqcsop",
-            PluginType::Backend => "dqcsbe",
-        }
-        .into();
+        let mut prefix: OsString = typ.executable_prefix().into();
         prefix.push(specification.executable.as_os_str());
         specification.executable = prefix.into();
 
@@ -141,40 +188,193 @@ impl PluginProcessSpecification {
             return Ok(specification);
         }
 
-        // Look for the file in the directory where DQCsim resides.
-        if let Ok(dqcsim_dir) = current_exe() {
-            let mut exec = dqcsim_dir
-                .parent()
-                .ok_or_else(oe_err("Could not determine path to DQCsim binary."))?
-                .to_path_buf();
-            exec.push(&specification.executable);
+        // Look for the file in the remaining search path directories.
+        let search_path = plugin_search_path(&options.plugin_path)?;
+        for base in search_path.iter() {
+            let exec = base.join(&specification.executable);
             if exec.exists() {
                 specification.executable = exec;
                 return Ok(specification);
             }
         }
 
-        // Okay, still not found. Try the system path then.
-        if let Some(sys_path) = var_os("PATH") {
-            for base in split_paths(&sys_path) {
-                let mut exec = base.clone();
-                exec.push(&specification.executable);
-                if exec.exists() {
-                    specification.executable = exec;
-                    return Ok(specification);
-                }
-            }
-        }
-
         inv_arg(format!(
             "could not find plugin executable '{}', needed for plugin \
-             specification '{}'",
+             specification '{}'; searched the working directory{}",
             specification.executable.to_string_lossy(),
             sugared.to_string_lossy(),
+            search_path
+                .iter()
+                .map(|dir| format!(", '{}'", dir.to_string_lossy()))
+                .collect::<String>()
         ))
     }
 }
 
+/// Options for desugaring plugin specifications with
+/// `PluginProcessSpecification::from_sugar_with()`.
+#[derive(Debug, Clone, Default)]
+pub struct DesugarOptions {
+    /// Additional directories to search for plugin executables, in order of
+    /// precedence. These take precedence over the default search path.
+    pub plugin_path: Vec<PathBuf>,
+
+    /// The interpreter commands used to run script plugins, overriding the
+    /// "dqcsfe"/"dqcsop"/"dqcsbe" plugin executables named after the script
+    /// extension.
+    pub interpreters: InterpreterRegistry,
+}
+
+impl DesugarOptions {
+    /// Adds a directory to search for plugin executables.
+    pub fn with_plugin_path(mut self, dir: impl Into<PathBuf>) -> DesugarOptions {
+        self.plugin_path.push(dir.into());
+        self
+    }
+
+    /// Registers an interpreter command for script plugins.
+    pub fn with_interpreter(mut self, entry: InterpreterEntry) -> DesugarOptions {
+        self.interpreters.register(entry);
+        self
+    }
+}
+
+/// Returns the per-user plugin directory, `$XDG_DATA_HOME/dqcsim/plugins`,
+/// defaulting to `~/.local/share/dqcsim/plugins`, if the home directory is
+/// known.
+pub fn user_plugin_dir() -> Option<PathBuf> {
+    var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            var_os("HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|dir| PathBuf::from(dir).join(".local").join("share"))
+        })
+        .map(|dir| dir.join("dqcsim").join("plugins"))
+}
+
+/// Returns the directories that sugared plugin specifications are searched
+/// for in after the working directory, in order of precedence:
+///
+///  - the given directories, for instance from `--plugin-path` or
+///    `DesugarOptions::plugin_path`;
+///  - the directories in the `DQCSIM_PLUGIN_PATH` environment variable,
+///    which uses the same syntax as `$PATH`;
+///  - the per-user plugin directory returned by `user_plugin_dir()`;
+///  - the directory where DQCsim resides;
+///  - the directories in the system `$PATH`.
+pub fn plugin_search_path(plugin_path: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    search_path(plugin_path, var_os("DQCSIM_PLUGIN_PATH"))
+}
+
+/// Implements `plugin_search_path()`, given the value of the
+/// `DQCSIM_PLUGIN_PATH` environment variable.
+fn search_path(plugin_path: &[PathBuf], env_path: Option<OsString>) -> Result<Vec<PathBuf>> {
+    let mut dirs = plugin_path.to_vec();
+    if let Some(env_path) = env_path {
+        dirs.extend(split_paths(&env_path).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    dirs.extend(user_plugin_dir());
+    if let Ok(dqcsim_dir) = current_exe() {
+        dirs.push(
+            dqcsim_dir
+                .parent()
+                .ok_or_else(oe_err("Could not determine path to DQCsim binary."))?
+                .to_path_buf(),
+        );
+    }
+    if let Some(sys_path) = var_os("PATH") {
+        dirs.extend(split_paths(&sys_path));
+    }
+    Ok(dirs)
+}
+
+/// A plugin executable found by [`discover_plugins()`].
+///
+/// [`discover_plugins()`]: ./fn.discover_plugins.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredPlugin {
+    /// The type of the plugin, derived from the executable name prefix.
+    pub typ: PluginType,
+
+    /// The sugared name of the plugin, i.e. the executable filename without
+    /// the "dqcsfe"/"dqcsop"/"dqcsbe" prefix. Scripts with this name as their
+    /// file extension are executed by this plugin.
+    pub name: String,
+
+    /// The path to the plugin executable.
+    pub executable: PathBuf,
+
+    /// Whether a plugin with the same type and name was found earlier in the
+    /// search order, in which case `PluginProcessSpecification::from_sugar()`
+    /// never selects this one.
+    pub shadowed: bool,
+}
+
+/// Lists all plugin executables that
+/// `PluginProcessSpecification::from_sugar_with()` can find for sugared
+/// plugin names given the same `plugin_path`, in the order in which they are
+/// searched for: the working directory followed by `plugin_search_path()`.
+/// Plugins are sorted by type and name within each directory.
+pub fn discover_plugins(plugin_path: &[PathBuf]) -> Result<Vec<DiscoveredPlugin>> {
+    let mut dirs = vec![PathBuf::from(".")];
+    dirs.extend(plugin_search_path(plugin_path)?);
+    Ok(discover_plugins_in(dirs))
+}
+
+/// Lists the plugin executables in the given directories, in order of
+/// precedence.
+fn discover_plugins_in(dirs: impl IntoIterator<Item = PathBuf>) -> Vec<DiscoveredPlugin> {
+    let mut plugins: Vec<DiscoveredPlugin> = vec![];
+    let mut visited: Vec<PathBuf> = vec![];
+    for dir in dirs {
+        // The same directory may appear more than once, for instance when
+        // DQCsim is installed in a directory in $PATH.
+        let canonical = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if visited.contains(&canonical) {
+            continue;
+        }
+        visited.push(canonical);
+
+        // Directories that don't exist or can't be read are silently
+        // skipped, just like from_sugar() would.
+        let entries = match dir.read_dir() {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let mut found = vec![];
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let typ = match PluginType::iter()
+                .find(|typ| filename.starts_with(typ.executable_prefix()))
+            {
+                Some(typ) => typ,
+                None => continue,
+            };
+            let name = filename[typ.executable_prefix().len()..].to_string();
+            let executable = entry.path();
+            if name.is_empty() || !executable.is_file() || !executable.is_executable() {
+                continue;
+            }
+            found.push((typ, name, executable));
+        }
+        found.sort_by(|a, b| (a.0 as u8, &a.1).cmp(&(b.0 as u8, &b.1)));
+        for (typ, name, executable) in found {
+            let shadowed = plugins
+                .iter()
+                .any(|plugin| plugin.typ == typ && plugin.name == name);
+            plugins.push(DiscoveredPlugin {
+                typ,
+                name,
+                executable,
+                shadowed,
+            });
+        }
+    }
+    plugins
+}
+
 /// Structure describing the functional configuration of a plugin, i.e. the
 /// parameters that affect a plugin's behavior.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -206,6 +406,10 @@ pub struct PluginProcessNonfunctionalConfiguration {
     /// Specifies the verbosity of the messages sent to DQCsim.
     pub verbosity: LoglevelFilter,
 
+    /// Overrides the verbosity for messages from specific modules.
+    #[serde(default)]
+    pub modules: ModuleFilter,
+
     /// Specifies the tee file configuration for this plugin.
     pub tee_files: Vec<TeeFileConfiguration>,
 
@@ -228,6 +432,7 @@ impl Default for PluginProcessNonfunctionalConfiguration {
     fn default() -> PluginProcessNonfunctionalConfiguration {
         PluginProcessNonfunctionalConfiguration {
             verbosity: LoglevelFilter::Trace,
+            modules: ModuleFilter::default(),
             tee_files: vec![],
             stdout_mode: StreamCaptureMode::Capture(Loglevel::Info),
             stderr_mode: StreamCaptureMode::Capture(Loglevel::Info),
@@ -299,6 +504,7 @@ impl PluginConfiguration for PluginProcessConfiguration {
             name: self.name.clone(),
             executable: path_style.convert_path(&self.specification.executable)?,
             script: path_style.convert_path_option(&self.specification.script)?,
+            args: self.specification.args.clone(),
             functional: PluginProcessFunctionalConfiguration {
                 init: self.functional.init.clone(),
                 env: self.functional.env.clone(),
@@ -311,6 +517,7 @@ impl PluginConfiguration for PluginProcessConfiguration {
         if self.nonfunctional.verbosity > max_verbosity {
             self.nonfunctional.verbosity = max_verbosity;
         }
+        self.nonfunctional.modules.limit(max_verbosity);
     }
 
     fn set_default_name(&mut self, default_name: String) {
@@ -319,3 +526,83 @@ impl PluginConfiguration for PluginProcessConfiguration {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env::join_paths, fs};
+
+    #[test]
+    fn discover() {
+        let base = std::env::temp_dir().join(format!("dqcsim-discover-{}", std::process::id()));
+        let first = base.join("first");
+        let second = base.join("second");
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        let exe = current_exe().unwrap();
+        fs::copy(&exe, first.join("dqcsbequantumsim")).unwrap();
+        fs::copy(&exe, first.join("dqcsfepy")).unwrap();
+        fs::copy(&exe, second.join("dqcsbequantumsim")).unwrap();
+        fs::copy(&exe, second.join("dqcsopfoo")).unwrap();
+        fs::write(second.join("dqcsopbar"), "not executable").unwrap();
+        fs::copy(&exe, second.join("notaplugin")).unwrap();
+
+        let plugins = discover_plugins_in(vec![first.clone(), second.clone(), first.clone()]);
+        fs::remove_dir_all(&base).unwrap();
+
+        let summary: Vec<_> = plugins
+            .iter()
+            .map(|plugin| {
+                (
+                    plugin.typ,
+                    plugin.name.as_str(),
+                    plugin.executable.parent().unwrap() == first,
+                    plugin.shadowed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (PluginType::Frontend, "py", true, false),
+                (PluginType::Backend, "quantumsim", true, false),
+                (PluginType::Operator, "foo", false, false),
+                (PluginType::Backend, "quantumsim", false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn sugar_search_path() {
+        let base = std::env::temp_dir().join(format!("dqcsim-search-{}", std::process::id()));
+        let explicit = base.join("explicit");
+        let from_env = base.join("env");
+        fs::create_dir_all(&explicit).unwrap();
+        fs::write(explicit.join("dqcsbesearchtest"), "").unwrap();
+
+        let err = PluginProcessSpecification::from_sugar("searchtest", PluginType::Backend)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(
+            "Invalid argument: could not find plugin executable 'dqcsbesearchtest', \
+             needed for plugin specification 'searchtest'; searched the working directory, '"
+        ));
+
+        let spec = PluginProcessSpecification::from_sugar_with(
+            "searchtest",
+            PluginType::Backend,
+            &DesugarOptions::default()
+                .with_plugin_path(base.join("missing"))
+                .with_plugin_path(&explicit),
+        )
+        .unwrap();
+        assert_eq!(spec.executable, explicit.join("dqcsbesearchtest"));
+        fs::remove_dir_all(&base).unwrap();
+
+        // Empty entries in $DQCSIM_PLUGIN_PATH are ignored.
+        let env_path = join_paths(&[from_env.clone(), PathBuf::new(), base.clone()]).unwrap();
+        let dirs = search_path(std::slice::from_ref(&explicit), Some(env_path)).unwrap();
+        assert_eq!(dirs[..3], [explicit, from_env, base]);
+        assert_eq!(dirs[3..], search_path(&[], None).unwrap()[..]);
+    }
+}
@@ -119,6 +119,7 @@ impl PluginConfiguration for PluginThreadConfiguration {
         if self.log_configuration.verbosity > max_verbosity {
             self.log_configuration.verbosity = max_verbosity;
         }
+        self.log_configuration.modules.limit(max_verbosity);
     }
 
     fn set_default_name(&mut self, default_name: String) {
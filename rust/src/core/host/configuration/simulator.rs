@@ -1,11 +1,18 @@
 use crate::{
     common::{
-        error::{inv_arg, Result},
-        log::{callback::LogCallback, tee_file::TeeFileConfiguration, LoglevelFilter},
+        error::{inv_arg, oe_inv_arg, Result},
+        log::{callback::LogCallback, tee_file::TeeFileConfiguration, LogFormat, LoglevelFilter},
         types::PluginType,
     },
-    host::configuration::{PluginConfiguration, ReproductionPathStyle, Seed},
+    host::{
+        configuration::{
+            DebuggerConfiguration, PluginConfiguration, PluginLogConfiguration,
+            PluginThreadConfiguration, ReproductionPathStyle, Seed,
+        },
+        debugger::{self, DEBUGGER_NAME},
+    },
 };
+use std::path::PathBuf;
 
 /// The complete configuration for a DQCsim run.
 #[derive(Debug)]
@@ -16,6 +23,9 @@ pub struct SimulatorConfiguration {
     /// The verbosity for logging messages to stderr.
     pub stderr_level: LoglevelFilter,
 
+    /// The format for logging messages to stderr.
+    pub stderr_format: LogFormat,
+
     /// Logs messages to the specified file in addition to stderr. level sets
     /// the minimum importance for a message to be logged to this file.
     pub tee_files: Vec<TeeFileConfiguration>,
@@ -31,6 +41,18 @@ pub struct SimulatorConfiguration {
 
     /// The path style used when writing the reproduction file.
     pub reproduction_path_style: Option<ReproductionPathStyle>,
+
+    /// Records all gatestream messages crossing the plugin boundaries to the
+    /// specified file, if specified.
+    pub gatestream_trace: Option<PathBuf>,
+
+    /// Inserts an interactive debugger upstream of one of the plugins, if
+    /// specified.
+    pub debugger: Option<DebuggerConfiguration>,
+
+    /// Whether to log a summary of the performance profile of each plugin
+    /// at the end of the simulation.
+    pub profile: bool,
 }
 
 impl SimulatorConfiguration {
@@ -46,6 +68,12 @@ impl SimulatorConfiguration {
         self
     }
 
+    /// Sets the stderr log format.
+    pub fn with_stderr_format(mut self, format: LogFormat) -> SimulatorConfiguration {
+        self.stderr_format = format;
+        self
+    }
+
     /// Adds a tee file.
     pub fn with_tee_file(mut self, tee: impl Into<TeeFileConfiguration>) -> SimulatorConfiguration {
         self.tee_files.push(tee.into());
@@ -88,6 +116,26 @@ impl SimulatorConfiguration {
         self
     }
 
+    /// Enables recording all gatestream messages crossing the plugin
+    /// boundaries to the specified trace file.
+    pub fn with_gatestream_trace(mut self, path: impl Into<PathBuf>) -> SimulatorConfiguration {
+        self.gatestream_trace = Some(path.into());
+        self
+    }
+
+    /// Enables the interactive debugger.
+    pub fn with_debugger(mut self, debugger: DebuggerConfiguration) -> SimulatorConfiguration {
+        self.debugger = Some(debugger);
+        self
+    }
+
+    /// Enables logging a summary of the performance profile of each plugin
+    /// at the end of the simulation.
+    pub fn with_profiling(mut self) -> SimulatorConfiguration {
+        self.profile = true;
+        self
+    }
+
     /// Disables all logging methods by setting `stderr_level` and
     /// `dqcsim_level` to LoglevelFilter::Off, `tee_files` to an empty vector
     /// and `log_callback` to none.
@@ -188,6 +236,47 @@ impl SimulatorConfiguration {
 
         Ok(())
     }
+
+    /// Inserts the debugger operator just upstream of the plugin being
+    /// debugged, if a debugger is configured.
+    ///
+    /// This must be called after `check_plugin_list()`, such that the plugin
+    /// names are known. The debugger operator is named `debugger`.
+    pub fn insert_debugger(&mut self) -> Result<()> {
+        let debugger = match self.debugger.take() {
+            Some(debugger) => debugger,
+            None => return Ok(()),
+        };
+        let index = self
+            .plugins
+            .iter()
+            .position(|plugin| plugin.get_name() == debugger.plugin)
+            .ok_or_else(oe_inv_arg(format!("plugin {} not found", debugger.plugin)))?;
+        if index == 0 {
+            inv_arg(
+                "the frontend cannot be debugged, since it does not receive gatestream requests",
+            )?;
+        }
+        if self
+            .plugins
+            .iter()
+            .any(|plugin| plugin.get_name() == DEBUGGER_NAME)
+        {
+            inv_arg(format!(
+                "cannot insert debugger, plugin name '{}' is already in use",
+                DEBUGGER_NAME
+            ))?;
+        }
+        self.plugins.insert(
+            index,
+            PluginThreadConfiguration::new(
+                debugger::definition(debugger),
+                PluginLogConfiguration::new(DEBUGGER_NAME, self.dqcsim_level),
+            )
+            .into(),
+        );
+        Ok(())
+    }
 }
 
 impl Default for SimulatorConfiguration {
@@ -199,11 +288,15 @@ impl Default for SimulatorConfiguration {
         SimulatorConfiguration {
             seed: Seed::default(),
             stderr_level: LoglevelFilter::Info,
+            stderr_format: LogFormat::default(),
             tee_files: vec![],
             log_callback: None,
             dqcsim_level: LoglevelFilter::Trace,
             plugins: vec![],
             reproduction_path_style: Some(ReproductionPathStyle::Keep),
+            gatestream_trace: None,
+            debugger: None,
+            profile: false,
         }
     }
 }
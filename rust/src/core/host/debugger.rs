@@ -0,0 +1,598 @@
+//! Interactive gatestream debugger.
+//!
+//! The debugger is a special operator that the simulator inserts into the
+//! pipeline just upstream of the plugin being debugged, when a
+//! `DebuggerConfiguration` is specified. Before forwarding a gatestream
+//! request to that plugin, it checks whether the simulation should pause,
+//! either because the user is stepping through the requests or because a
+//! breakpoint matches. If so, the request is described by a `DebugStop` and
+//! passed to the user-provided `DebuggerInterface`, which decides how to
+//! proceed.
+//!
+//! While paused, the interface can send `ArbCmd`s to the debugged plugin to
+//! inspect its state. These are handled by the `host_arb()` callback of the
+//! plugin, as if they were sent by the host.
+//!
+//! The debugger operator is hidden from the plugin lookup functions of the
+//! `Simulation`, so it does not shift the indices of the other plugins.
+
+use crate::{
+    common::{
+        error::{err, inv_arg, oe_inv_arg, Error, Result},
+        types::{
+            ArbCmd, ArbData, Cycle, Cycles, Gate, GateType, PluginMetadata, PluginType,
+            QubitMeasurementValue, QubitRef,
+        },
+    },
+    host::configuration::DebuggerConfiguration,
+    plugin::{definition::PluginDefinition, state::PluginState},
+};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+/// The instance name of the debugger operator.
+pub const DEBUGGER_NAME: &str = "debugger";
+
+/// Returns the name used to refer to the type of the given gate, i.e. the
+/// name of custom gates or one of `unitary`, `measurement`, `prep`, or
+/// `pauli_measurement`.
+fn gate_type_name(gate: &Gate) -> &str {
+    match gate.get_type() {
+        GateType::Unitary => "unitary",
+        GateType::Measurement => "measurement",
+        GateType::Prep => "prep",
+        GateType::PauliMeasurement => "pauli_measurement",
+        GateType::Custom(name) => name,
+    }
+}
+
+/// Formats a list of qubits for display.
+fn format_qubits(qubits: &[QubitRef]) -> String {
+    if qubits.is_empty() {
+        "-".to_string()
+    } else {
+        qubits
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A condition for pausing the simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breakpoint {
+    /// Pauses before gates of the given type. This is either the name of a
+    /// custom gate, or one of `unitary`, `measurement`, `prep`, or
+    /// `pauli_measurement`.
+    Gate(String),
+
+    /// Pauses before any request that operates on the given qubit.
+    Qubit(QubitRef),
+
+    /// Pauses before the first request issued at or after the given cycle.
+    Cycle(Cycles),
+}
+
+impl Breakpoint {
+    /// Returns whether this breakpoint matches the given request, issued at
+    /// the given cycle. `previous` is the cycle at which the previous request
+    /// was issued, if any.
+    pub fn matches(&self, request: &DebugRequest, previous: Option<Cycle>, cycle: Cycle) -> bool {
+        match self {
+            Breakpoint::Gate(name) => match request {
+                DebugRequest::Gate(gate) => gate_type_name(gate) == name,
+                _ => false,
+            },
+            Breakpoint::Qubit(qubit) => request.qubits().contains(qubit),
+            Breakpoint::Cycle(at) => {
+                let at = *at as i64;
+                let cycle: i64 = cycle.into();
+                cycle >= at && previous.is_none_or(|previous| Into::<i64>::into(previous) < at)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Gate(name) => write!(f, "gate:{}", name),
+            Breakpoint::Qubit(qubit) => write!(f, "qubit:{}", qubit),
+            Breakpoint::Cycle(cycle) => write!(f, "cycle:{}", cycle),
+        }
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = Error;
+
+    /// Parses a breakpoint from its string representation, being
+    /// `gate:<type>`, `qubit:<index>`, or `cycle:<cycle>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap().trim().to_lowercase();
+        let value = parts.next().map(str::trim).unwrap_or_default();
+        if value.is_empty() {
+            return inv_arg(format!(
+                "invalid breakpoint {}, expected gate:<type>, qubit:<index>, or cycle:<cycle>",
+                s
+            ));
+        }
+        match &kind[..] {
+            "gate" => Ok(Breakpoint::Gate(value.to_string())),
+            "qubit" => value
+                .parse::<u64>()
+                .ok()
+                .and_then(QubitRef::from_foreign)
+                .map(Breakpoint::Qubit)
+                .ok_or_else(oe_inv_arg(format!("{} is not a valid qubit index", value))),
+            "cycle" => value
+                .parse::<Cycles>()
+                .map(Breakpoint::Cycle)
+                .or_else(|_| inv_arg(format!("{} is not a valid cycle", value))),
+            _ => inv_arg(format!(
+                "invalid breakpoint {}, expected gate:<type>, qubit:<index>, or cycle:<cycle>",
+                s
+            )),
+        }
+    }
+}
+
+/// A gatestream request that is about to be forwarded to the debugged
+/// plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugRequest {
+    /// Allocation of the given qubits with the given commands.
+    Allocate(Vec<QubitRef>, Vec<ArbCmd>),
+
+    /// Deallocation of the given qubits.
+    Free(Vec<QubitRef>),
+
+    /// Execution of a gate.
+    Gate(Gate),
+
+    /// Reset of the given qubits.
+    Reset(Vec<QubitRef>),
+
+    /// Advancement of the simulation time by the given number of cycles.
+    Advance(Cycles),
+
+    /// An `ArbCmd` from upstream.
+    Arb(ArbCmd),
+}
+
+impl DebugRequest {
+    /// Returns a short name for the kind of request.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DebugRequest::Allocate(..) => "allocate",
+            DebugRequest::Free(_) => "free",
+            DebugRequest::Gate(_) => "gate",
+            DebugRequest::Reset(_) => "reset",
+            DebugRequest::Advance(_) => "advance",
+            DebugRequest::Arb(_) => "arb",
+        }
+    }
+
+    /// Returns the qubits that the request operates on, without duplicates.
+    /// For gates, this includes the qubit that the gate is conditioned on.
+    pub fn qubits(&self) -> Vec<QubitRef> {
+        match self {
+            DebugRequest::Allocate(qubits, _)
+            | DebugRequest::Free(qubits)
+            | DebugRequest::Reset(qubits) => qubits.clone(),
+            DebugRequest::Gate(gate) => {
+                let mut qubits = vec![];
+                for qubit in gate
+                    .get_targets()
+                    .iter()
+                    .chain(gate.get_controls())
+                    .chain(gate.get_measures())
+                    .chain(gate.get_condition().map(|condition| &condition.qubit))
+                {
+                    if !qubits.contains(qubit) {
+                        qubits.push(*qubit);
+                    }
+                }
+                qubits
+            }
+            DebugRequest::Advance(_) | DebugRequest::Arb(_) => vec![],
+        }
+    }
+}
+
+/// Describes the request that the simulation paused before.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugStop {
+    /// Instance name of the debugged plugin.
+    pub plugin: String,
+
+    /// The cycle at which the request is issued.
+    pub cycle: Cycle,
+
+    /// The request that is about to be forwarded.
+    pub request: DebugRequest,
+
+    /// The measurement registers of the qubits that the request operates on,
+    /// or `None` for qubits that have not been measured yet. This is empty
+    /// for allocations.
+    pub measurements: Vec<(QubitRef, Option<QubitMeasurementValue>)>,
+
+    /// The indices of the breakpoints that caused the pause. This is empty
+    /// if the simulation paused because the user was stepping.
+    pub breakpoints: Vec<usize>,
+}
+
+impl fmt::Display for DebugStop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Paused before {} to {} at cycle {}",
+            self.request.kind(),
+            self.plugin,
+            self.cycle
+        )?;
+        if !self.breakpoints.is_empty() {
+            write!(
+                f,
+                " (breakpoint {})",
+                self.breakpoints
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        writeln!(f)?;
+        match &self.request {
+            DebugRequest::Allocate(qubits, cmds) => {
+                writeln!(f, "  qubits:    {}", format_qubits(qubits))?;
+                for cmd in cmds {
+                    writeln!(f, "  command:   {}", cmd)?;
+                }
+            }
+            DebugRequest::Free(qubits) | DebugRequest::Reset(qubits) => {
+                writeln!(f, "  qubits:    {}", format_qubits(qubits))?;
+            }
+            DebugRequest::Gate(gate) => {
+                writeln!(f, "  gate:      {}", gate_type_name(gate))?;
+                writeln!(f, "  targets:   {}", format_qubits(gate.get_targets()))?;
+                writeln!(f, "  controls:  {}", format_qubits(gate.get_controls()))?;
+                writeln!(f, "  measures:  {}", format_qubits(gate.get_measures()))?;
+                if let Some(condition) = gate.get_condition() {
+                    writeln!(
+                        f,
+                        "  condition: qubit {} is {}",
+                        condition.qubit, condition.value
+                    )?;
+                }
+                if let Some(matrix) = gate.get_matrix() {
+                    writeln!(f, "  matrix:")?;
+                    for line in matrix.to_string().lines() {
+                        writeln!(f, "    {}", line.trim_end())?;
+                    }
+                }
+                if gate.data != ArbData::default() {
+                    writeln!(f, "  data:      {}", gate.data)?;
+                }
+            }
+            DebugRequest::Advance(cycles) => {
+                writeln!(f, "  cycles:    {}", cycles)?;
+            }
+            DebugRequest::Arb(cmd) => {
+                writeln!(f, "  command:   {}", cmd)?;
+            }
+        }
+        if !self.measurements.is_empty() {
+            writeln!(
+                f,
+                "  registers: {}",
+                self.measurements
+                    .iter()
+                    .map(|(qubit, value)| match value {
+                        Some(value) => format!("{}={}", qubit, value),
+                        None => format!("{}=-", qubit),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The way the simulation proceeds after a pause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugAction {
+    /// Forwards the request and pauses again before the next one.
+    Step,
+
+    /// Forwards the request and continues until a breakpoint matches.
+    Continue,
+
+    /// Fails the request, aborting the simulation.
+    Abort,
+}
+
+/// Gives a `DebuggerInterface` access to the debugger while paused.
+pub struct DebugControl<'a, 'b> {
+    state: &'a mut PluginState<'b>,
+    breakpoints: &'a mut Vec<Breakpoint>,
+}
+
+impl<'a, 'b> DebugControl<'a, 'b> {
+    /// Sends an `ArbCmd` to the debugged plugin, to be handled by its
+    /// `host_arb()` callback.
+    pub fn arb(&mut self, cmd: ArbCmd) -> Result<ArbData> {
+        self.state.host_arb_downstream(cmd)
+    }
+
+    /// Returns the current breakpoints.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        self.breakpoints
+    }
+
+    /// Adds a breakpoint, returning its index.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    /// Removes the breakpoint with the given index. The indices of the
+    /// subsequent breakpoints shift down by one.
+    pub fn remove_breakpoint(&mut self, index: usize) -> Result<Breakpoint> {
+        if index < self.breakpoints.len() {
+            Ok(self.breakpoints.remove(index))
+        } else {
+            inv_arg(format!("there is no breakpoint {}", index))
+        }
+    }
+}
+
+/// User interface for the debugger.
+pub trait DebuggerInterface: Send {
+    /// Called when the simulation pauses before forwarding a request to the
+    /// debugged plugin. The interface may inspect the simulation and modify
+    /// the breakpoints through `control`, and returns how to proceed.
+    /// Returning an error aborts the simulation, like `DebugAction::Abort`.
+    fn pause(&mut self, stop: &DebugStop, control: &mut DebugControl) -> Result<DebugAction>;
+}
+
+/// The state of the debugger operator.
+struct Session {
+    plugin: String,
+    breakpoints: Vec<Breakpoint>,
+    stepping: bool,
+    previous_cycle: Option<Cycle>,
+    interface: Box<dyn DebuggerInterface>,
+}
+
+impl Session {
+    /// Pauses before the given request if we're stepping or a breakpoint
+    /// matches it.
+    fn check(&mut self, state: &mut PluginState, request: &DebugRequest) -> Result<()> {
+        let cycle = state.get_cycle()?;
+        let previous = self.previous_cycle.replace(cycle);
+        let breakpoints: Vec<usize> = self
+            .breakpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, breakpoint)| breakpoint.matches(request, previous, cycle))
+            .map(|(index, _)| index)
+            .collect();
+        if !self.stepping && breakpoints.is_empty() {
+            return Ok(());
+        }
+
+        // Qubits that are being allocated don't exist downstream yet, so we
+        // can't query their measurement registers.
+        let measurements = match request {
+            DebugRequest::Allocate(..) => vec![],
+            _ => request
+                .qubits()
+                .into_iter()
+                .map(|qubit| {
+                    (
+                        qubit,
+                        state
+                            .get_measurement(qubit)
+                            .ok()
+                            .map(|measurement| measurement.value),
+                    )
+                })
+                .collect(),
+        };
+
+        let stop = DebugStop {
+            plugin: self.plugin.clone(),
+            cycle,
+            request: request.clone(),
+            measurements,
+            breakpoints,
+        };
+        let mut control = DebugControl {
+            state,
+            breakpoints: &mut self.breakpoints,
+        };
+        match self.interface.pause(&stop, &mut control)? {
+            DebugAction::Step => self.stepping = true,
+            DebugAction::Continue => self.stepping = false,
+            DebugAction::Abort => err("simulation aborted by the debugger")?,
+        }
+        Ok(())
+    }
+}
+
+/// Constructs the definition of the debugger operator.
+pub(crate) fn definition(configuration: DebuggerConfiguration) -> PluginDefinition {
+    let session = Arc::new(Mutex::new(Session {
+        plugin: configuration.plugin,
+        breakpoints: configuration.breakpoints,
+        stepping: configuration.pause_at_start,
+        previous_cycle: None,
+        interface: configuration.interface,
+    }));
+
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("DQCsim debugger", "TU Delft QCE", env!("CARGO_PKG_VERSION")),
+    );
+
+    let s = session.clone();
+    definition.allocate = Box::new(move |state, qubits, cmds| {
+        let request = DebugRequest::Allocate(qubits, cmds);
+        s.lock().unwrap().check(state, &request)?;
+        if let DebugRequest::Allocate(qubits, cmds) = request {
+            state.allocate(qubits.len(), cmds)?;
+        }
+        Ok(())
+    });
+
+    let s = session.clone();
+    definition.free = Box::new(move |state, qubits| {
+        let request = DebugRequest::Free(qubits);
+        s.lock().unwrap().check(state, &request)?;
+        if let DebugRequest::Free(qubits) = request {
+            state.free(qubits)?;
+        }
+        Ok(())
+    });
+
+    let s = session.clone();
+    definition.gate = Box::new(move |state, gate| {
+        let request = DebugRequest::Gate(gate);
+        s.lock().unwrap().check(state, &request)?;
+        if let DebugRequest::Gate(gate) = request {
+            state.gate(gate)?;
+        }
+        Ok(vec![])
+    });
+
+    let s = session.clone();
+    definition.reset = Box::new(move |state, qubits| {
+        let request = DebugRequest::Reset(qubits);
+        s.lock().unwrap().check(state, &request)?;
+        if let DebugRequest::Reset(qubits) = request {
+            state.reset_qubits(qubits)?;
+        }
+        Ok(())
+    });
+
+    let s = session.clone();
+    definition.advance = Box::new(move |state, cycles| {
+        s.lock()
+            .unwrap()
+            .check(state, &DebugRequest::Advance(cycles))?;
+        state.advance(cycles).map(|_| ())
+    });
+
+    definition.upstream_arb = Box::new(move |state, cmd| {
+        let request = DebugRequest::Arb(cmd);
+        session.lock().unwrap().check(state, &request)?;
+        if let DebugRequest::Arb(cmd) = request {
+            state.arb(cmd)
+        } else {
+            unreachable!()
+        }
+    });
+
+    definition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{Matrix, QubitRefGenerator};
+
+    #[test]
+    fn parse_breakpoint() {
+        assert_eq!(
+            "gate:measurement".parse::<Breakpoint>().unwrap(),
+            Breakpoint::Gate("measurement".to_string())
+        );
+        assert_eq!(
+            "Qubit: 3".parse::<Breakpoint>().unwrap(),
+            Breakpoint::Qubit(QubitRef::from_foreign(3).unwrap())
+        );
+        assert_eq!(
+            "cycle:10".parse::<Breakpoint>().unwrap(),
+            Breakpoint::Cycle(10)
+        );
+        assert_eq!(
+            "cycle:10".parse::<Breakpoint>().unwrap().to_string(),
+            "cycle:10"
+        );
+        assert_eq!(
+            "qubit:0".parse::<Breakpoint>().unwrap_err().to_string(),
+            "Invalid argument: 0 is not a valid qubit index"
+        );
+        assert_eq!(
+            "cycle:-1".parse::<Breakpoint>().unwrap_err().to_string(),
+            "Invalid argument: -1 is not a valid cycle"
+        );
+        assert_eq!(
+            "gate".parse::<Breakpoint>().unwrap_err().to_string(),
+            "Invalid argument: invalid breakpoint gate, expected gate:<type>, qubit:<index>, or cycle:<cycle>"
+        );
+        assert_eq!(
+            "line:3".parse::<Breakpoint>().unwrap_err().to_string(),
+            "Invalid argument: invalid breakpoint line:3, expected gate:<type>, qubit:<index>, or cycle:<cycle>"
+        );
+    }
+
+    #[test]
+    fn breakpoint_matches() {
+        let qubits = QubitRefGenerator::new().allocate(3);
+        let gate = DebugRequest::Gate(
+            Gate::new_unitary(vec![qubits[0]], vec![qubits[1]], Matrix::new_identity(2)).unwrap(),
+        );
+        let advance = DebugRequest::Advance(3);
+        let zero = Cycle::t_zero();
+
+        assert!(Breakpoint::Gate("unitary".into()).matches(&gate, None, zero));
+        assert!(!Breakpoint::Gate("prep".into()).matches(&gate, None, zero));
+        assert!(!Breakpoint::Gate("unitary".into()).matches(&advance, None, zero));
+
+        assert!(Breakpoint::Qubit(qubits[1]).matches(&gate, None, zero));
+        assert!(!Breakpoint::Qubit(qubits[2]).matches(&gate, None, zero));
+
+        assert!(Breakpoint::Cycle(0).matches(&advance, None, zero));
+        assert!(!Breakpoint::Cycle(0).matches(&advance, Some(zero), zero));
+        assert!(!Breakpoint::Cycle(5).matches(&advance, Some(zero), zero.advance(3)));
+        assert!(Breakpoint::Cycle(5).matches(&advance, Some(zero.advance(3)), zero.advance(6)));
+        assert!(!Breakpoint::Cycle(5).matches(&advance, Some(zero.advance(6)), zero.advance(9)));
+    }
+
+    #[test]
+    fn display_stop() {
+        let qubits = QubitRefGenerator::new().allocate(2);
+        let stop = DebugStop {
+            plugin: "back".to_string(),
+            cycle: Cycle::t_zero().advance(2),
+            request: DebugRequest::Gate(
+                Gate::new_measurement(qubits.clone(), Matrix::new_identity(2)).unwrap(),
+            ),
+            measurements: vec![
+                (qubits[0], Some(QubitMeasurementValue::One)),
+                (qubits[1], None),
+            ],
+            breakpoints: vec![0, 2],
+        };
+        assert_eq!(
+            stop.to_string(),
+            "Paused before gate to back at cycle 2 (breakpoint 0, 2)\n  \
+             gate:      measurement\n  \
+             targets:   -\n  \
+             controls:  -\n  \
+             measures:  1, 2\n  \
+             matrix:\n     \
+             1.000+0.000i   0.000+0.000i\n     \
+             0.000+0.000i   1.000+0.000i\n  \
+             registers: 1=1, 2=-\n"
+        );
+    }
+}
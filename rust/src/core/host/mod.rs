@@ -2,6 +2,7 @@
 
 pub mod accelerator;
 pub mod configuration;
+pub mod debugger;
 pub mod plugin;
 pub mod reproduction;
 pub mod simulation;
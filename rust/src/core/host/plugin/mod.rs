@@ -6,16 +6,19 @@ pub mod thread;
 
 use crate::{
     common::{
+        channel::UpstreamChannel,
         error::{err, Result},
         log::thread::LogThread,
         protocol::{
             PluginAcceptUpstreamRequest, PluginInitializeRequest, PluginInitializeResponse,
             PluginToSimulator, PluginUserInitializeRequest, SimulatorToPlugin,
         },
-        types::{ArbCmd, ArbData, PluginType},
+        trace::thread::TraceThread,
+        types::{ArbCmd, ArbData, ArbInterfaces, PluginProfile, PluginType},
     },
     host::configuration::PluginLogConfiguration,
 };
+use ipc_channel::ipc::IpcOneShotServer;
 use std::fmt::Debug;
 
 #[macro_export]
@@ -76,20 +79,68 @@ impl dyn Plugin {
         logger: &LogThread,
         downstream: &Option<String>,
         seed: u64,
+    ) -> Result<PluginInitializeResponse> {
+        self.initialize_with_downstream_interfaces(
+            logger,
+            None,
+            downstream,
+            &ArbInterfaces::new(),
+            seed,
+        )
+    }
+
+    /// Sends an `PluginInitializeRequest` to this plugin, passing along the
+    /// `ArbCmd` interfaces understood by the downstream plugin such that the
+    /// plugin can reject unsupported commands before sending them. If
+    /// `tracer` is specified, the plugin is instructed to record its
+    /// gatestream messages through it.
+    pub(crate) fn initialize_with_downstream_interfaces(
+        &mut self,
+        logger: &LogThread,
+        tracer: Option<&TraceThread>,
+        downstream: &Option<String>,
+        downstream_interfaces: &ArbInterfaces,
+        seed: u64,
     ) -> Result<PluginInitializeResponse> {
         checked_rpc!(
             self,
             PluginInitializeRequest {
                 downstream: downstream.clone(),
+                downstream_interfaces: downstream_interfaces.clone(),
                 plugin_type: self.plugin_type(),
                 seed,
                 log_configuration: self.log_configuration(),
                 log_channel: logger.get_ipc_sender(),
+                trace_channel: tracer.map(TraceThread::get_ipc_sender),
             },
             expect Initialized
         )
     }
 
+    /// Spawns the plugin and performs the initialization handshake without
+    /// connecting it to any other plugins or calling its `initialize()`
+    /// callback, in order to query its metadata and supported interfaces.
+    /// The plugin should be dropped afterwards.
+    ///
+    /// Frontends and operators connect to their downstream plugin as part of
+    /// the handshake, by sending it their channel pair. They are given the
+    /// address of a one-shot server that is never accepted: connecting only
+    /// queues the channel pair in the server's socket, which is discarded
+    /// when the server is dropped at the end of this function. The plugin
+    /// does not send anything else downstream until the simulation starts,
+    /// which never happens for a probed plugin.
+    pub fn probe(&mut self, logger: &LogThread) -> Result<PluginInitializeResponse> {
+        self.spawn(logger)?;
+
+        let (_server, address) = IpcOneShotServer::<UpstreamChannel>::new()?;
+        let downstream = if self.plugin_type() == PluginType::Backend {
+            None
+        } else {
+            Some(address)
+        };
+        self.initialize(logger, &downstream, 0)
+    }
+
     /// Requests that the plugin waits for the upstream plugin to connect and
     /// establishes the connection.
     pub fn accept_upstream(&mut self) -> Result<()> {
@@ -115,4 +166,13 @@ impl dyn Plugin {
             expect ArbResponse
         )
     }
+
+    /// Requests the performance profile of this plugin.
+    pub fn profile(&mut self) -> Result<PluginProfile> {
+        checked_rpc!(
+            self,
+            SimulatorToPlugin::ProfileRequest,
+            expect ProfileResponse
+        )
+    }
 }
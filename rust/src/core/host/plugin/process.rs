@@ -4,7 +4,7 @@
 use crate::{
     common::{
         channel::SimulatorChannel,
-        error::{err, inv_op, ErrorKind, Result},
+        error::{err, inv_op, Result},
         log::{stdio::proxy_stdio, thread::LogThread},
         protocol::{PluginToSimulator, SimulatorToPlugin},
         types::{ArbCmd, PluginType},
@@ -19,7 +19,25 @@ use crate::{
 };
 use ipc_channel::ipc;
 use is_executable::IsExecutable;
-use std::{process, sync, thread, time};
+use std::{process, thread, time};
+
+/// Runs the given accept function in a separate thread, such that we can
+/// stop waiting for it when the timeout expires. The result is passed
+/// through a channel rather than a condition variable, such that a
+/// connection that is accepted before we start waiting is not missed.
+fn accept_within<T: Send + 'static>(
+    accept: impl FnOnce() -> Result<T> + Send + 'static,
+    timeout: time::Duration,
+) -> Result<T> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    thread::spawn(move || {
+        let _ = tx.send(accept());
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => err("plugin did not connect within specified timeout"),
+    }
+}
 
 /// A Plugin running in a child process.
 ///
@@ -76,6 +94,9 @@ impl Plugin for PluginProcess {
         // Construct the child process
         let mut command = process::Command::new(exe_path);
 
+        // Interpreter arguments
+        command.args(self.configuration.specification.args());
+
         // Script
         if let Some(script) = &self.configuration.specification.script {
             command.arg(script);
@@ -133,41 +154,11 @@ impl Plugin for PluginProcess {
                 self.channel = Some(channel);
             }
             Timeout::Duration(timeout) => {
-                #[cfg_attr(feature = "cargo-clippy", allow(clippy::mutex_atomic))]
-                let pair = sync::Arc::new((sync::Mutex::new(false), sync::Condvar::new()));
-                let pair2 = pair.clone();
-                let handle: thread::JoinHandle<Result<SimulatorChannel>> =
-                    thread::spawn(move || {
-                        {
-                            let &(ref lock, _) = &*pair2;
-                            let mut started = lock.lock().expect("Unable to aquire lock");
-                            *started = true;
-                        }
-                        let (_, channel) = server.accept()?;
-                        {
-                            let &(_, ref cvar) = &*pair2;
-                            cvar.notify_one();
-                        }
-                        Ok(channel)
-                    });
-                let &(ref lock, ref cvar) = &*pair;
-                let (started, wait_result) = cvar
-                    .wait_timeout(
-                        lock.lock()
-                            .expect("Plugin IPC connection start lock poisoned"),
-                        timeout,
-                    )
-                    .expect("Plugin IPC connection start lock poisoned");
-                if *started && !wait_result.timed_out() {
-                    self.channel = handle
-                        .join()
-                        .map_err(|_| {
-                            ErrorKind::Other("Plugin IPC connection thread failed".to_string())
-                        })?
-                        .ok();
-                } else {
-                    err("plugin did not connect within specified timeout")?
-                }
+                let channel = accept_within(
+                    move || Ok(server.accept().map(|(_, channel)| channel)?),
+                    timeout,
+                )?;
+                self.channel = Some(channel);
             }
         }
 
@@ -266,3 +257,38 @@ impl Drop for PluginProcess {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_within_timeout() {
+        // Accepting before we start waiting must not be missed.
+        assert_eq!(
+            accept_within(|| Ok(42), time::Duration::from_secs(10)).unwrap(),
+            42
+        );
+        assert_eq!(
+            accept_within(
+                || -> Result<()> { err("boom") },
+                time::Duration::from_secs(10)
+            )
+            .unwrap_err()
+            .to_string(),
+            "boom"
+        );
+        assert_eq!(
+            accept_within(
+                || {
+                    thread::sleep(time::Duration::from_secs(1));
+                    Ok(())
+                },
+                time::Duration::from_millis(10)
+            )
+            .unwrap_err()
+            .to_string(),
+            "plugin did not connect within specified timeout"
+        );
+    }
+}
@@ -3,7 +3,7 @@
 use crate::{
     common::{
         error::{err, inv_arg, oe_inv_arg, Result},
-        log::{tee_file::TeeFileConfiguration, LoglevelFilter},
+        log::{module_filter::ModuleFilter, tee_file::TeeFileConfiguration, LoglevelFilter},
         types::PluginType,
         util::friendly_enumerate,
     },
@@ -29,6 +29,11 @@ pub struct PluginReproduction {
     /// is a native plugin.
     pub script: Option<PathBuf>,
 
+    /// Additional arguments passed to the executable before the script
+    /// filename.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
     /// The functional configuration of the plugin, i.e. the options
     /// configuring how the plugin behaves (besides the specification).
     #[serde(flatten)]
@@ -52,6 +57,10 @@ pub struct PluginModification {
     /// `None`, the value of DQCsim's `--plugin_level` option should be used.
     pub verbosity: Option<LoglevelFilter>,
 
+    /// Overrides the verbosity for messages from specific modules, in
+    /// addition to any existing overrides.
+    pub modules: ModuleFilter,
+
     /// Specifies the tee file configuration for this plugin.
     pub tee_files: Vec<TeeFileConfiguration>,
 
@@ -83,6 +92,7 @@ impl PluginModification {
                 if let Some(verbosity) = self.verbosity {
                     plugin_config.nonfunctional.verbosity = verbosity;
                 }
+                plugin_config.nonfunctional.modules.extend(&self.modules);
                 plugin_config
                     .nonfunctional
                     .tee_files
@@ -202,7 +212,8 @@ impl Reproduction {
                     &x.executable,
                     x.script.clone(),
                     PluginType::Operator,
-                ),
+                )
+                .with_args(x.args.clone()),
                 functional: x.functional.clone(),
                 nonfunctional: PluginProcessNonfunctionalConfiguration::default(),
             })
@@ -233,6 +244,117 @@ impl Reproduction {
         Ok(self.host_calls.clone())
     }
 
+    /// Returns the index of the plugin with the given name.
+    fn plugin_index(&self, name: &str) -> Result<usize> {
+        self.plugins
+            .iter()
+            .position(|x| x.name == name)
+            .ok_or_else(oe_inv_arg(format!(
+                "There is no plugin named {}. The available plugins are {}.",
+                name,
+                friendly_enumerate(self.plugins.iter().map(|x| &x.name[..]), Some("or"))
+            )))
+    }
+
+    /// Returns the type of the plugin with the given name, which follows from
+    /// its position in the pipeline.
+    pub fn plugin_type(&self, name: &str) -> Result<PluginType> {
+        let index = self.plugin_index(name)?;
+        Ok(if index == 0 {
+            PluginType::Frontend
+        } else if index == self.plugins.len() - 1 {
+            PluginType::Backend
+        } else {
+            PluginType::Operator
+        })
+    }
+
+    /// Replaces the executable, script, and arguments of the plugin with the
+    /// given name, keeping its functional configuration. The plugin type of
+    /// the specification is ignored; it follows from the position of the
+    /// plugin in the pipeline.
+    pub fn replace_plugin(
+        &mut self,
+        name: &str,
+        specification: PluginProcessSpecification,
+    ) -> Result<()> {
+        let index = self.plugin_index(name)?;
+        let plugin = &mut self.plugins[index];
+        plugin.args = specification.args().to_vec();
+        plugin.executable = specification.executable;
+        plugin.script = specification.script;
+        Ok(())
+    }
+
+    /// Converts the executable, script, and working directory paths of all
+    /// plugins to the given style. Relative paths are interpreted relative to
+    /// the current working directory, as they are when the file is
+    /// reproduced, so the referenced files must exist for anything but
+    /// `ReproductionPathStyle::Keep`.
+    pub fn convert_paths(&mut self, style: ReproductionPathStyle) -> Result<()> {
+        let convert = |path: &Path| {
+            style
+                .convert_path(path)
+                .or_else(|e| inv_arg(format!("cannot convert path {}: {}", path.display(), e)))
+        };
+        for plugin in self.plugins.iter_mut() {
+            plugin.executable = convert(&plugin.executable)?;
+            if let Some(script) = &plugin.script {
+                plugin.script = Some(convert(script)?);
+            }
+            plugin.functional.work = convert(&plugin.functional.work)?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether this reproduction structure can be reproduced from the
+    /// current working directory, returning a description of every problem
+    /// found. That is, whether there are at least two plugins, whether the
+    /// files and directories referenced by the plugins exist, and whether the
+    /// arb host calls refer to existing plugins.
+    pub fn check(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.plugins.len() < 2 {
+            problems.push("less than two plugins specified".to_string());
+        }
+        for plugin in self.plugins.iter() {
+            if !plugin.executable.is_file() {
+                problems.push(format!(
+                    "executable {} of plugin {} does not exist",
+                    plugin.executable.display(),
+                    plugin.name
+                ));
+            }
+            if let Some(script) = &plugin.script {
+                if !script.is_file() {
+                    problems.push(format!(
+                        "script {} of plugin {} does not exist",
+                        script.display(),
+                        plugin.name
+                    ));
+                }
+            }
+            if !plugin.functional.work.is_dir() {
+                problems.push(format!(
+                    "working directory {} of plugin {} does not exist",
+                    plugin.functional.work.display(),
+                    plugin.name
+                ));
+            }
+        }
+        for (index, host_call) in self.host_calls.iter().enumerate() {
+            if let HostCall::Arb(name, _) = host_call {
+                if !self.plugins.iter().any(|x| &x.name == name) {
+                    problems.push(format!(
+                        "host call {} refers to nonexistent plugin {}",
+                        index, name
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
     /// Constructs a reproduction structure from a file.
     pub fn from_file(file: impl AsRef<Path>) -> Result<Reproduction> {
         Ok(serde_yaml::from_reader(&mut std::fs::File::open(
@@ -246,3 +368,111 @@ impl Reproduction {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn reproduction() -> Reproduction {
+        let plugin = |name: &str, executable: &str| PluginReproduction {
+            name: name.to_string(),
+            executable: executable.into(),
+            script: None,
+            args: vec![],
+            functional: PluginProcessFunctionalConfiguration::default(),
+        };
+        Reproduction {
+            seed: 33,
+            plugins: vec![plugin("front", "Cargo.toml"), plugin("back", "missing")],
+            host_calls: vec![
+                HostCall::Start(Default::default()),
+                HostCall::from_str("arb:op:a.b").unwrap(),
+            ],
+            hostname: "host".to_string(),
+            username: "user".to_string(),
+            workdir: ".".into(),
+        }
+    }
+
+    #[test]
+    fn replace_plugin() {
+        let mut reproduction = reproduction();
+        reproduction.plugins[1].functional.work = "/tmp".into();
+        reproduction
+            .replace_plugin(
+                "back",
+                PluginProcessSpecification::new("python3", Some("be.py"), PluginType::Frontend)
+                    .with_args(vec!["-u"]),
+            )
+            .unwrap();
+        let plugin = &reproduction.plugins[1];
+        assert_eq!(plugin.executable, PathBuf::from("python3"));
+        assert_eq!(plugin.script, Some(PathBuf::from("be.py")));
+        assert_eq!(plugin.args, vec!["-u".to_string()]);
+        assert_eq!(plugin.functional.work, PathBuf::from("/tmp"));
+
+        let specification =
+            PluginProcessSpecification::new("x", None::<PathBuf>, PluginType::Operator);
+        assert_eq!(
+            reproduction
+                .replace_plugin("op", specification)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: There is no plugin named op. The available plugins are front or back."
+        );
+    }
+
+    #[test]
+    fn plugin_type() {
+        let reproduction = reproduction();
+        assert_eq!(
+            reproduction.plugin_type("front").unwrap(),
+            PluginType::Frontend
+        );
+        assert_eq!(
+            reproduction.plugin_type("back").unwrap(),
+            PluginType::Backend
+        );
+        assert!(reproduction.plugin_type("op").is_err());
+    }
+
+    #[test]
+    fn convert_paths() {
+        let mut reproduction = reproduction();
+        reproduction.plugins.truncate(1);
+        reproduction
+            .convert_paths(ReproductionPathStyle::Absolute)
+            .unwrap();
+        assert_eq!(
+            reproduction.plugins[0].executable,
+            std::env::current_dir().unwrap().join("Cargo.toml")
+        );
+        reproduction
+            .convert_paths(ReproductionPathStyle::Relative)
+            .unwrap();
+        assert_eq!(
+            reproduction.plugins[0].executable,
+            PathBuf::from("Cargo.toml")
+        );
+        assert_eq!(reproduction.plugins[0].functional.work, PathBuf::from("."));
+
+        let mut reproduction = self::reproduction();
+        assert!(reproduction
+            .convert_paths(ReproductionPathStyle::Absolute)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid argument: cannot convert path missing: "));
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(
+            reproduction().check(),
+            vec![
+                "executable missing of plugin back does not exist".to_string(),
+                "host call 1 refers to nonexistent plugin op".to_string(),
+            ]
+        );
+    }
+}
@@ -4,10 +4,11 @@
 use crate::{
     checked_rpc,
     common::{
-        error::{err, inv_arg, inv_op, Result},
+        error::{err, inv_arg, inv_op, oe_inv_arg, Result},
         log::thread::LogThread,
         protocol::{FrontendRunRequest, PluginToSimulator},
-        types::{ArbCmd, ArbData, PluginMetadata},
+        trace::thread::TraceThread,
+        types::{ArbCmd, ArbData, ArbInterfaces, PluginMetadata, PluginProfile, PluginType},
     },
     debug, error, fatal,
     host::{
@@ -32,6 +33,8 @@ pub type Pipeline = Vec<Box<dyn Plugin>>;
 struct InitializedPlugin {
     pub plugin: Box<dyn Plugin>,
     pub metadata: PluginMetadata,
+    pub interfaces: ArbInterfaces,
+    pub hidden: bool,
 }
 
 /// Tracks the state of the simulated accelerator.
@@ -138,10 +141,22 @@ pub struct Simulation {
 impl Simulation {
     /// Constructs a Simulation from a collection of PluginInstance and a random seed.
     pub fn new(
+        pipeline: Pipeline,
+        seed: Seed,
+        reproduction_log: Option<Reproduction>,
+        logger: &LogThread,
+    ) -> Result<Simulation> {
+        Simulation::new_with_tracer(pipeline, seed, reproduction_log, logger, None)
+    }
+
+    /// Constructs a Simulation like `new()`, instructing the plugins to
+    /// record their gatestream messages through `tracer` if specified.
+    pub fn new_with_tracer(
         mut pipeline: Pipeline,
         seed: Seed,
         reproduction_log: Option<Reproduction>,
         logger: &LogThread,
+        tracer: Option<&TraceThread>,
     ) -> Result<Simulation> {
         trace!("Constructing Simulation");
         if pipeline.len() < 2 {
@@ -163,12 +178,33 @@ impl Simulation {
 
         // Initialize the plugins.
         let mut downstream = None;
+        let mut downstream_interfaces = ArbInterfaces::new();
         let mut metadata = vec![];
         let mut rng = ChaChaRng::seed_from_u64(seed.value);
         for plugin in pipeline.iter_mut().rev() {
-            let res = plugin.initialize(logger, &downstream, rng.next_u64())?;
+            let res = plugin.initialize_with_downstream_interfaces(
+                logger,
+                tracer,
+                &downstream,
+                &downstream_interfaces,
+                rng.next_u64(),
+            )?;
             downstream = res.upstream;
-            metadata.push(res.metadata);
+
+            // Operators forward the commands they don't handle themselves, so
+            // their upstream plugin may send anything understood by either.
+            downstream_interfaces = match plugin.plugin_type() {
+                PluginType::Operator => res.interfaces.union(&downstream_interfaces),
+                _ => res.interfaces.clone(),
+            };
+
+            // Fail fast if the plugin advertised its supported ArbCmds and
+            // the user is trying to initialize it with something else.
+            for cmd in plugin.init_cmds() {
+                Simulation::check_arb(&plugin.name(), &res.interfaces, &cmd)?;
+            }
+
+            metadata.push((res.metadata, res.interfaces));
         }
 
         // Tell downstream plugins to wait for a connection from upstream
@@ -187,7 +223,12 @@ impl Simulation {
         let pipeline: Vec<_> = pipeline
             .into_iter()
             .zip(metadata.into_iter().rev())
-            .map(|(plugin, metadata)| InitializedPlugin { plugin, metadata })
+            .map(|(plugin, (metadata, interfaces))| InitializedPlugin {
+                plugin,
+                metadata,
+                interfaces,
+                hidden: false,
+            })
             .collect();
 
         for (i, p) in pipeline.iter().enumerate() {
@@ -208,6 +249,31 @@ impl Simulation {
         })
     }
 
+    /// Checks that the given `ArbCmd` is supported by a plugin with the given
+    /// instance name and interface registry, and that its payload matches the
+    /// registered schema.
+    fn check_arb(name: &str, interfaces: &ArbInterfaces, cmd: &ArbCmd) -> Result<()> {
+        if interfaces.accepts(cmd) {
+            interfaces.validate(cmd)
+        } else {
+            inv_arg(format!(
+                "plugin {} does not support ArbCmd {}.{}",
+                name,
+                cmd.interface_identifier(),
+                cmd.operation_identifier()
+            ))
+        }
+    }
+
+    /// Hides the plugin with the given instance name from the plugin lookup
+    /// functions. This is used for operators inserted by DQCsim itself, such
+    /// as the debugger, so they don't shift the indices of the other plugins.
+    pub(crate) fn hide_plugin(&mut self, name: impl AsRef<str>) -> Result<()> {
+        let index = self.find_plugin(name)?;
+        self.pipeline[index].hidden = true;
+        Ok(())
+    }
+
     /// Drains the plugin pipeline so their drop() implementations get called.
     pub fn drop_plugins(&mut self) {
         trace!("Implicit yield() prior to dropping plugins...");
@@ -301,20 +367,33 @@ impl Simulation {
     /// all pending asynchronous calls are flushed and executed *before* the
     /// `ArbCmd`.
     pub fn arb(&mut self, name: impl AsRef<str>, cmd: impl Into<ArbCmd>) -> Result<ArbData> {
+        let index = self.find_plugin(name)?;
+        self.arb_at(index, cmd.into())
+    }
+
+    /// Returns the pipeline index of the plugin with the given instance name.
+    /// Hidden plugins are not found.
+    fn find_plugin(&self, name: impl AsRef<str>) -> Result<usize> {
         let name = name.as_ref();
-        for (i, p) in self.pipeline.iter().enumerate() {
-            if p.plugin.name() == name {
-                return self.arb_idx(i as isize, cmd);
-            }
-        }
-        inv_arg(format!("plugin {} not found", name))
+        self.pipeline
+            .iter()
+            .position(|p| !p.hidden && p.plugin.name() == name)
+            .ok_or_else(oe_inv_arg(format!("plugin {} not found", name)))
     }
 
     /// Checks plugin index range and allows using negative numbers for
-    /// Pythonic indexation.
+    /// Pythonic indexation. Hidden plugins are skipped; the returned value is
+    /// the index into the pipeline.
     fn convert_plugin_index(&self, index: isize) -> Result<usize> {
+        let visible: Vec<usize> = self
+            .pipeline
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.hidden)
+            .map(|(i, _)| i)
+            .collect();
         let mut conv_index = index;
-        let n_plugins = self.pipeline.len();
+        let n_plugins = visible.len();
         if conv_index < 0 {
             conv_index += n_plugins as isize;
             if conv_index < 0 {
@@ -325,7 +404,7 @@ impl Simulation {
         if conv_index >= n_plugins {
             inv_arg(format!("index {} out of range", index))?
         }
-        Ok(conv_index)
+        Ok(visible[conv_index])
     }
 
     /// Sends an `ArbCmd` message to one of the plugins, referenced by index.
@@ -340,11 +419,22 @@ impl Simulation {
     /// `ArbCmd`s are executed immediately after yielding to the simulator, so
     /// all pending asynchronous calls are flushed and executed *before* the
     /// `ArbCmd`.
+    ///
+    /// If the plugin advertised the `ArbCmd` interfaces it supports, commands
+    /// outside of those and commands with a payload that does not match the
+    /// advertised schema are rejected without being sent.
     pub fn arb_idx(&mut self, index: isize, cmd: impl Into<ArbCmd>) -> Result<ArbData> {
         let index = self.convert_plugin_index(index)?;
+        self.arb_at(index, cmd.into())
+    }
 
-        // Perform the actual call.
-        let cmd = cmd.into();
+    /// Sends an `ArbCmd` message to the plugin at the given pipeline index.
+    fn arb_at(&mut self, index: usize, cmd: ArbCmd) -> Result<ArbData> {
+        Simulation::check_arb(
+            &self.pipeline[index].plugin.name(),
+            &self.pipeline[index].interfaces,
+            &cmd,
+        )?;
         self.record_host_call(HostCall::Arb(
             self.pipeline[index].plugin.name(),
             cmd.clone(),
@@ -356,13 +446,7 @@ impl Simulation {
     /// Returns a reference to the metadata object belonging to the plugin
     /// referenced by instance name.
     pub fn get_metadata(&self, name: impl AsRef<str>) -> Result<&PluginMetadata> {
-        let name = name.as_ref();
-        for (i, p) in self.pipeline.iter().enumerate() {
-            if p.plugin.name() == name {
-                return self.get_metadata_idx(i as isize);
-            }
-        }
-        inv_arg(format!("plugin {} not found", name))
+        Ok(&self.pipeline[self.find_plugin(name)?].metadata)
     }
 
     /// Returns a reference to the metadata object belonging to the plugin
@@ -371,10 +455,69 @@ impl Simulation {
         Ok(&self.pipeline[self.convert_plugin_index(index)?].metadata)
     }
 
-    /// Writes a the reproduction log to a file.
-    pub fn write_reproduction_file(&self, filename: impl AsRef<Path>) -> Result<()> {
+    /// Returns a reference to the `ArbCmd` interfaces and operations
+    /// supported by the plugin referenced by instance name.
+    ///
+    /// If the plugin did not register any interfaces, the returned registry
+    /// is empty. Such plugins accept any `ArbCmd`.
+    pub fn get_interfaces(&self, name: impl AsRef<str>) -> Result<&ArbInterfaces> {
+        Ok(&self.pipeline[self.find_plugin(name)?].interfaces)
+    }
+
+    /// Returns a reference to the `ArbCmd` interfaces and operations
+    /// supported by the plugin referenced by index.
+    pub fn get_interfaces_idx(&self, index: isize) -> Result<&ArbInterfaces> {
+        Ok(&self.pipeline[self.convert_plugin_index(index)?].interfaces)
+    }
+
+    /// Returns the performance profile of the plugin referenced by instance
+    /// name, i.e. the time it spent in each of its callbacks, waiting for
+    /// downstream, and serializing IPC messages.
+    ///
+    /// Like `arb()`, this yields to the simulator first, so the profile
+    /// includes all pending asynchronous calls. It is not recorded in the
+    /// reproduction log.
+    pub fn get_profile(&mut self, name: impl AsRef<str>) -> Result<PluginProfile> {
+        let index = self.find_plugin(name)?;
+        self.profile_at(index)
+    }
+
+    /// Returns the performance profile of the plugin referenced by index.
+    pub fn get_profile_idx(&mut self, index: isize) -> Result<PluginProfile> {
+        let index = self.convert_plugin_index(index)?;
+        self.profile_at(index)
+    }
+
+    /// Returns the performance profile of the plugin at the given pipeline
+    /// index.
+    fn profile_at(&mut self, index: usize) -> Result<PluginProfile> {
+        self.internal_yield()?;
+        self.pipeline[index].plugin.profile()
+    }
+
+    /// Logs a summary of the performance profiles of all plugins.
+    pub fn log_profiles(&mut self) {
+        if let Err(e) = self.internal_yield() {
+            error!("Implicit yield to frontend failed: {}", e.to_string());
+        }
+        for p in self.pipeline.iter_mut() {
+            let name = p.plugin.name();
+            match p.plugin.profile() {
+                Ok(profile) => {
+                    info!("Performance profile for {}:", name);
+                    for line in profile.to_string().lines() {
+                        info!("  {}", line);
+                    }
+                }
+                Err(e) => error!("Failed to retrieve profile for {}: {}", name, e),
+            }
+        }
+    }
+
+    /// Returns the reproduction log.
+    pub fn get_reproduction_log(&self) -> Result<&Reproduction> {
         if let Some(log) = &self.reproduction_log {
-            log.to_file(filename)
+            Ok(log)
         } else {
             inv_op(
                 "cannot output reproduction file; \
@@ -382,6 +525,11 @@ impl Simulation {
             )
         }
     }
+
+    /// Writes a the reproduction log to a file.
+    pub fn write_reproduction_file(&self, filename: impl AsRef<Path>) -> Result<()> {
+        self.get_reproduction_log()?.to_file(filename)
+    }
 }
 
 impl Accelerator for Simulation {
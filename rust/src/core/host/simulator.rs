@@ -1,9 +1,12 @@
 //! Simulator driver: wraps a `Simulation` and a `LogThread`.
 
 use crate::{
-    common::{error::Result, log::thread::LogThread},
+    common::{
+        error::Result, log::thread::LogThread, trace::thread::TraceThread, types::ArbInterfaces,
+    },
     host::{
         configuration::{PluginConfiguration, SimulatorConfiguration},
+        debugger::DEBUGGER_NAME,
         plugin::Plugin,
         reproduction::Reproduction,
         simulation::Simulation,
@@ -22,18 +25,27 @@ use crate::{
 /// through the public [`simulation field`].
 ///
 /// When the Simulator gets dropped it will ensure the [`Simulation`] gets
-/// dropped before the [`LogThread`].
+/// dropped before the [`LogThread`]. If gatestream tracing is enabled, the
+/// Simulator also owns the [`TraceThread`] that writes the trace file.
 ///
 /// [`SimulatorConfiguration`]: ../configuration/struct.SimulatorConfiguration.html
 /// [`Simulation`]: ../simulation/struct.Simulation.html
 /// [`simulation field`]: ./struct.Simulator.html#structfield.simulation
 /// [`Pipeline`]: ../simulation/struct.Pipeline.html
 /// [`LogThread`]: ../log/thread/struct.LogThread.html
+/// [`TraceThread`]: ../../common/trace/thread/struct.TraceThread.html
 #[derive(Debug)]
 pub struct Simulator {
+    /// TraceThread used by this Simulator for gatestream tracing, if enabled.
+    trace_thread: Option<TraceThread>,
+
     /// LogThread used by this Simulator for logging.
     log_thread: LogThread,
 
+    /// Whether to log the performance profiles of the plugins before they
+    /// are dropped.
+    profile: bool,
+
     /// The Simulation driven by this Simulator.
     pub simulation: Simulation,
 }
@@ -54,11 +66,18 @@ impl Simulator {
             .reproduction_path_style
             .map(|_| Reproduction::new_logger(&configuration));
 
+        // Insert the debugger operator, if any. This is done after
+        // constructing the reproduction logger, such that the reproduction
+        // file describes the pipeline without the debugger.
+        let debugger = configuration.debugger.is_some();
+        configuration.insert_debugger()?;
+
         // Spawn log thread.
-        let log_thread = LogThread::spawn(
+        let log_thread = LogThread::spawn_with_format(
             "dqcsim",
             configuration.dqcsim_level,
             configuration.stderr_level,
+            configuration.stderr_format,
             configuration.log_callback,
             configuration.tee_files,
         )?;
@@ -75,6 +94,19 @@ impl Simulator {
             None => None,
         };
 
+        // Spawn trace thread.
+        let trace_thread = match &configuration.gatestream_trace {
+            Some(path) => Some(TraceThread::spawn(
+                path,
+                configuration
+                    .plugins
+                    .iter()
+                    .map(|plugin| plugin.get_name())
+                    .collect(),
+            )?),
+            None => None,
+        };
+
         // Construct plugin pipeline.
         let pipeline: Vec<Box<dyn Plugin>> = configuration
             .plugins
@@ -83,22 +115,57 @@ impl Simulator {
             .collect();
 
         // Construct simulation.
-        let simulation = Simulation::new(pipeline, configuration.seed, reproduction, &log_thread)?;
+        let mut simulation = Simulation::new_with_tracer(
+            pipeline,
+            configuration.seed,
+            reproduction,
+            &log_thread,
+            trace_thread.as_ref(),
+        )?;
+
+        // Hide the debugger from the plugin lookup functions, such that the
+        // indices of the user's plugins are the same as without it.
+        if debugger {
+            simulation.hide_plugin(DEBUGGER_NAME)?;
+        }
 
         Ok(Simulator {
+            trace_thread,
             log_thread,
+            profile: configuration.profile,
             simulation,
         })
     }
+
+    /// Returns the `ArbCmd` interfaces and operations supported by the
+    /// plugin with the given instance name, as advertised by the plugin
+    /// during initialization.
+    ///
+    /// Plugins that did not register any interfaces return an empty
+    /// registry. Such plugins accept any `ArbCmd`.
+    pub fn supported_interfaces(&self, plugin: impl AsRef<str>) -> Result<&ArbInterfaces> {
+        self.simulation.get_interfaces(plugin)
+    }
 }
 
 impl Drop for Simulator {
     fn drop(&mut self) {
         trace!("Dropping Simulator");
 
+        // Log the performance profiles while the plugins are still alive.
+        if self.profile {
+            self.simulation.log_profiles();
+        }
+
         // Drain the simulation pipeline to drop the Plugin instances before
         // dropping the log thread.
         self.simulation.drop_plugins();
+
+        // Wait for the trace thread to finish writing the trace file, while
+        // the log thread is still around to report trace file errors.
+        if let Some(trace_thread) = self.trace_thread.take() {
+            drop(trace_thread);
+        }
     }
 }
 
@@ -133,7 +200,7 @@ mod tests {
         assert!(simulator.is_ok());
         let simulator = simulator.unwrap();
         assert_eq!(
-            format!("{:?}", simulator).find("imulator { log_thread"),
+            format!("{:?}", simulator).find("imulator { trace_thread: None, log_thread"),
             Some(1)
         );
 
@@ -27,7 +27,11 @@ use crate::{
     trace,
 };
 use ipc_channel::ipc::{IpcOneShotServer, IpcReceiverSet, IpcSelectionResult, IpcSender};
-use std::collections::{HashMap, VecDeque};
+use std::{
+    cell::Cell,
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
 
 /// Incoming enum used to map incoming requests in the IpcReceiverSet used in
 /// the Connection wrapper.
@@ -100,6 +104,16 @@ pub struct Connection {
 
     /// Optional Downstream sender. Is None for Backend plugins.
     downstream: Option<IpcSender<GatestreamDown>>,
+
+    /// Total time spent serializing and sending outgoing messages and
+    /// deserializing incoming messages.
+    ipc_time: Cell<Duration>,
+
+    /// Total time spent blocked waiting for incoming messages.
+    wait_time: Duration,
+
+    /// The part of `wait_time` spent waiting for downstream responses.
+    downstream_wait_time: Duration,
 }
 
 impl Connection {
@@ -157,6 +171,9 @@ impl Connection {
             downstream: None,
             pending_upstream: None,
             upstream: None,
+            ipc_time: Cell::new(Duration::default()),
+            wait_time: Duration::default(),
+            downstream_wait_time: Duration::default(),
         })
     }
 
@@ -245,30 +262,39 @@ impl Connection {
     /// error when the channel is closed, does not exist or when sending
     /// failed.
     pub fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let start = Instant::now();
         match message {
             OutgoingMessage::Simulator(response) => self.response.send(response)?,
             OutgoingMessage::Downstream(request) => self.downstream_ref()?.send(request)?,
             OutgoingMessage::Upstream(response) => self.upstream_ref()?.send(response)?,
         }
+        self.ipc_time.set(self.ipc_time.get() + start.elapsed());
         Ok(())
     }
 
     /// Buffer incoming messages.
     /// If there are connected channels make sure at least one additional
-    /// message is pending in the buffer.
-    fn buffer_incoming(&mut self) -> Result<()> {
+    /// message is pending in the buffer. Returns the time spent blocked
+    /// waiting for messages.
+    fn buffer_incoming(&mut self) -> Result<Duration> {
         let mut received_any = false;
+        let mut waited = Duration::default();
         while !received_any && !self.incoming_map.is_empty() {
             // Store incoming message in the buffer.
-            for event in self.incoming.select()? {
+            let start = Instant::now();
+            let events = self.incoming.select()?;
+            waited += start.elapsed();
+            for event in events {
                 match event {
                     IpcSelectionResult::MessageReceived(id, msg) => {
                         if let Some(incoming) = self.incoming_map.get(&id) {
+                            let start = Instant::now();
                             self.incoming_buffer.push_back(match incoming {
                                 Incoming::Simulator => IncomingMessage::Simulator(msg.to()?),
                                 Incoming::Upstream => IncomingMessage::Upstream(msg.to()?),
                                 Incoming::Downstream => IncomingMessage::Downstream(msg.to()?),
                             });
+                            self.ipc_time.set(self.ipc_time.get() + start.elapsed());
                             received_any = true;
                         }
                     }
@@ -281,7 +307,25 @@ impl Connection {
                 }
             }
         }
-        Ok(())
+        self.wait_time += waited;
+        Ok(waited)
+    }
+
+    /// Returns the total time spent serializing and sending outgoing
+    /// messages and deserializing incoming messages.
+    pub fn ipc_time(&self) -> Duration {
+        self.ipc_time.get()
+    }
+
+    /// Returns the total time spent blocked waiting for incoming messages.
+    pub fn wait_time(&self) -> Duration {
+        self.wait_time
+    }
+
+    /// Returns the total time spent blocked waiting for responses from the
+    /// downstream plugin.
+    pub fn downstream_wait_time(&self) -> Duration {
+        self.downstream_wait_time
     }
 
     /// Fetch next request from either the Simulator request channel or the
@@ -311,7 +355,8 @@ impl Connection {
             Ok(Some(self.incoming_buffer.remove(idx).unwrap()))
         } else {
             // Buffer incoming messages.
-            self.buffer_incoming()?;
+            let waited = self.buffer_incoming()?;
+            self.downstream_wait_time += waited;
             // If there are no connected channels return None.
             if self.incoming_map.is_empty() {
                 Ok(None)
@@ -5,7 +5,8 @@ use crate::{
     common::{
         error::{inv_op, Result},
         types::{
-            ArbCmd, ArbData, Gate, PluginMetadata, PluginType, QubitMeasurementResult, QubitRef,
+            ArbCmd, ArbData, ArbInterfaces, Gate, Matrix, PluginMetadata, PluginType,
+            QubitMeasurementResult, QubitRef,
         },
     },
     plugin::state::PluginState,
@@ -26,6 +27,9 @@ pub struct PluginDefinition {
     /// Name, author, and version of the plugin.
     metadata: PluginMetadata,
 
+    /// The `ArbCmd` interfaces and operations supported by the plugin.
+    interfaces: ArbInterfaces,
+
     /// Initialization callback.
     pub initialize: Box<dyn Fn(&mut PluginState, Vec<ArbCmd>) -> Result<()> + Send + 'static>,
 
@@ -46,6 +50,9 @@ pub struct PluginDefinition {
     pub gate:
         Box<dyn Fn(&mut PluginState, Gate) -> Result<Vec<QubitMeasurementResult>> + Send + 'static>,
 
+    /// Qubit reset callback for operators and backends.
+    pub reset: Box<dyn Fn(&mut PluginState, Vec<QubitRef>) -> Result<()> + Send + 'static>,
+
     /// Measurement modification callback for operators.
     pub modify_measurement: Box<
         dyn Fn(&mut PluginState, QubitMeasurementResult) -> Result<Vec<QubitMeasurementResult>>
@@ -86,12 +93,14 @@ impl PluginDefinition {
             PluginType::Frontend => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                interfaces: ArbInterfaces::new(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("run() is not implemented")),
                 allocate: Box::new(|_, _, _| inv_op("frontend.allocate() called")),
                 free: Box::new(|_, _| inv_op("frontend.free() called")),
                 gate: Box::new(|_, _| inv_op("frontend.gate() called")),
+                reset: Box::new(|_, _| inv_op("frontend.reset() called")),
                 modify_measurement: Box::new(|_, _| inv_op("frontend.modify_measurement() called")),
                 advance: Box::new(|_, _| inv_op("frontend.advance() called")),
                 upstream_arb: Box::new(|_, _| inv_op("frontend.upstream_arb() called")),
@@ -100,6 +109,7 @@ impl PluginDefinition {
             PluginType::Operator => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                interfaces: ArbInterfaces::new(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("operator.run() called")),
@@ -108,6 +118,7 @@ impl PluginDefinition {
                 }),
                 free: Box::new(|state, qubits| state.free(qubits)),
                 gate: Box::new(|state, gate| state.gate(gate).map(|_| vec![])),
+                reset: Box::new(|state, qubits| state.reset_qubits(qubits)),
                 modify_measurement: Box::new(|_, measurement| Ok(vec![measurement])),
                 advance: Box::new(|state, cycles| state.advance(cycles).map(|_| ())),
                 upstream_arb: Box::new(|state, cmd| state.arb(cmd)),
@@ -116,12 +127,17 @@ impl PluginDefinition {
             PluginType::Backend => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                interfaces: ArbInterfaces::new(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("backend.run() called")),
                 allocate: Box::new(|_, _, _| Ok(())),
                 free: Box::new(|_, _| Ok(())),
                 gate: Box::new(|_, _| inv_op("gate() is not implemented")),
+                reset: Box::new(|state, qubits| {
+                    let gate = Gate::new_prep(qubits, Matrix::new_identity(2))?;
+                    state.execute_gate(gate).map(|_| ())
+                }),
                 modify_measurement: Box::new(|_, _| inv_op("backend.modify_measurement() called")),
                 advance: Box::new(|_, _| Ok(())),
                 upstream_arb: Box::new(|_, _| Ok(ArbData::default())),
@@ -144,6 +160,37 @@ impl PluginDefinition {
     pub fn get_metadata(&self) -> &PluginMetadata {
         &self.metadata
     }
+
+    /// Returns the `ArbCmd` interfaces and operations supported by the
+    /// plugin.
+    pub fn get_interfaces(&self) -> &ArbInterfaces {
+        &self.interfaces
+    }
+
+    /// Registers an `ArbCmd` operation supported by the `host_arb()` and/or
+    /// `upstream_arb()` callbacks, optionally with a JSON schema for its
+    /// payload.
+    ///
+    /// The registered operations are advertised to the host and to the
+    /// upstream plugin during initialization. If at least one operation is
+    /// registered, initialization commands and `ArbCmd`s from the host or
+    /// from upstream that the plugin does not support are rejected by both
+    /// the sender and the plugin, instead of being silently ignored.
+    /// Operators also accept the operations supported downstream of them.
+    ///
+    /// The payload of commands for operations with a schema is validated
+    /// both by the sender before sending them and by the plugin before passing
+    /// them to the `initialize()`, `host_arb()`, and `upstream_arb()`
+    /// callbacks. See `ArbSchema` for the supported subset of JSON Schema.
+    pub fn register_arb_operation(
+        &mut self,
+        interface_identifier: impl Into<String>,
+        operation_identifier: impl Into<String>,
+        schema: Option<String>,
+    ) -> Result<()> {
+        self.interfaces
+            .register(interface_identifier, operation_identifier, schema)
+    }
 }
 
 #[cfg(test)]
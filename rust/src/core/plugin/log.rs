@@ -23,9 +23,10 @@ pub fn setup_logging(
     log_channel: IpcSender<LogRecord>,
 ) -> Result<()> {
     let mut loggers = Vec::with_capacity(1 + configuration.tee_files.len());
-    loggers.push(LogProxy::boxed(
+    loggers.push(LogProxy::boxed_with_modules(
         configuration.name.as_str(),
         configuration.verbosity,
+        configuration.modules.clone(),
         log_channel,
     ) as Box<dyn Log>);
     let tee_files: Result<Vec<_>> = configuration
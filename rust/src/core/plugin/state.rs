@@ -8,10 +8,11 @@ use crate::{
             PipelinedGatestreamDown, PluginInitializeRequest, PluginInitializeResponse,
             PluginToSimulator, SimulatorToPlugin,
         },
+        trace::{TraceMessage, Tracer},
         types::{
-            ArbCmd, ArbData, Cycle, Cycles, Gate, PluginType, QubitMeasurementResult,
-            QubitMeasurementValue, QubitRef, QubitRefGenerator, SequenceNumber,
-            SequenceNumberGenerator,
+            ArbCmd, ArbData, ArbInterfaces, Cycle, Cycles, Gate, PluginProfile, PluginType,
+            QubitMeasurementResult, QubitMeasurementValue, QubitRef, QubitRefGenerator,
+            SequenceNumber, SequenceNumberGenerator,
         },
         util::friendly_enumerate,
     },
@@ -28,7 +29,14 @@ use rand_chacha::{
     rand_core::{RngCore, SeedableRng},
     ChaChaRng,
 };
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// The maximum number of pipelined requests that are coalesced into a single
+/// `GatestreamDown::PipelinedBatch` message before it is sent downstream.
+const MAX_BATCH_SIZE: usize = 1024;
 
 /// Deterministic random number generator used for plugins.
 ///
@@ -173,6 +181,11 @@ pub struct PluginState<'a> {
     /// `CompletedUpTo` message.
     upstream_completed_up_to: SequenceNumber,
 
+    /// The measurement register values that we've most recently sent upstream
+    /// as a backend, used to evaluate conditional gates. Qubits that are not
+    /// in this map have an undefined measurement register.
+    upstream_measurement_register: HashMap<QubitRef, QubitMeasurementValue>,
+
     /// Downstream sequence number generator.
     downstream_sequence_tx: SequenceNumberGenerator,
 
@@ -205,6 +218,38 @@ pub struct PluginState<'a> {
     /// we sent downstream.
     downstream_expected_measurements: VecDeque<(SequenceNumber, HashSet<QubitRef>)>,
 
+    /// Qubit resets we sent downstream, queued until their sequence number
+    /// is acknowledged. The measurement data of the qubits is cleared at that
+    /// point, in order with respect to the measurement results.
+    downstream_pending_resets: VecDeque<(SequenceNumber, Vec<QubitRef>)>,
+
+    /// Pipelined requests that have not been sent downstream yet. Requests
+    /// are coalesced here until the next synchronization point, at which
+    /// point they are sent as a single `PipelinedBatch` message.
+    downstream_batch: Vec<(SequenceNumber, PipelinedGatestreamDown)>,
+
+    /// Responses to upstream that are being collected while handling a
+    /// `PipelinedBatch` message, to be sent as a single `Batch` message. When
+    /// `None`, responses are sent immediately.
+    upstream_batch: Option<Vec<GatestreamUp>>,
+
+    /// Gatestream tracer, used to record all messages sent to and received
+    /// from downstream when gatestream tracing is enabled.
+    tracer: Option<Tracer>,
+
+    /// The `ArbCmd` interfaces understood by the downstream plugin, or `None`
+    /// for backends. Used to reject unsupported `ArbCmd`s before sending them
+    /// downstream, and by operators to accept the `ArbCmd`s that they
+    /// forward downstream.
+    downstream_interfaces: Option<ArbInterfaces>,
+
+    /// Timing statistics for the user callbacks.
+    profile: PluginProfile,
+
+    /// Total time recorded in `profile` so far, used to exclude the time
+    /// spent in nested callbacks from the enclosing callback.
+    profiled_time: Duration,
+
     /// Aborted flag indicates if the plugin received the aborted signal.
     aborted: bool,
 }
@@ -215,6 +260,15 @@ impl<'a> PluginState<'a> {
         let typ = self.definition.get_type();
         let seed = req.seed;
 
+        // Setup gatestream tracing. Only the upstream side of each boundary
+        // records messages, so backends have nothing to do here.
+        if typ != PluginType::Backend {
+            let name = &req.log_configuration.name;
+            self.tracer = req
+                .trace_channel
+                .map(|sender| Tracer::new(name.clone(), sender));
+        }
+
         // Setup logging.
         setup_logging(&req.log_configuration, req.log_channel)?;
 
@@ -237,6 +291,7 @@ impl<'a> PluginState<'a> {
         if typ != PluginType::Backend {
             self.connection
                 .connect_downstream(req.downstream.unwrap())?;
+            self.downstream_interfaces = Some(req.downstream_interfaces);
         }
 
         // If we're not a frontend, initialize an upstream server.
@@ -251,6 +306,7 @@ impl<'a> PluginState<'a> {
         Ok(PluginInitializeResponse {
             upstream,
             metadata: self.definition.get_metadata().clone(),
+            interfaces: self.definition.get_interfaces().clone(),
         })
     }
 
@@ -271,7 +327,7 @@ impl<'a> PluginState<'a> {
         self.synchronize_downstream()?;
 
         // Call the user's finalize function.
-        (self.definition.drop)(self)?;
+        self.profile_callback("drop", |state| (state.definition.drop)(state))?;
 
         // Finalization should not send any more requests downstream, but just
         // in case:
@@ -281,6 +337,38 @@ impl<'a> PluginState<'a> {
         Ok(())
     }
 
+    /// Calls a user callback through `f`, recording the time spent in it
+    /// under the given callback name.
+    ///
+    /// The time spent blocked on other plugins or the host, the IPC overhead,
+    /// and the time spent in nested callbacks (for instance
+    /// `modify_measurement()` called while a `gate()` callback waits for a
+    /// measurement) are not included.
+    fn profile_callback<T>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        let excluded = |state: &Self| {
+            state.connection.wait_time() + state.connection.ipc_time() + state.profiled_time
+        };
+        let excluded_before = excluded(self);
+        let start = Instant::now();
+        let result = f(self);
+        let elapsed = start.elapsed();
+        let time = elapsed
+            .checked_sub(excluded(self) - excluded_before)
+            .unwrap_or_default();
+        self.profile.record(name, time);
+        self.profiled_time += time;
+        result
+    }
+
+    /// Returns the performance profile of this plugin so far.
+    fn get_profile(&self) -> PluginProfile {
+        PluginProfile {
+            downstream_wait: self.connection.downstream_wait_time(),
+            ipc: self.connection.ipc_time(),
+            ..self.profile.clone()
+        }
+    }
+
     /// Handles a run request while we're NOT blocked inside the run()
     /// callback.
     fn handle_run(&mut self, req: FrontendRunRequest) -> Result<FrontendRunResponse> {
@@ -300,7 +388,8 @@ impl<'a> PluginState<'a> {
         // If start is set, call the run() callback.
         let return_value = if let Some(args) = req.start {
             self.inside_run = true;
-            let return_value = (self.definition.run)(self, args);
+            let return_value =
+                self.profile_callback("run", |state| (state.definition.run)(state, args));
             self.inside_run = false;
             Some(return_value?)
         } else {
@@ -355,12 +444,11 @@ impl<'a> PluginState<'a> {
             // If we're an operator, propagate the measurement upstream using
             // the `modify_measurement()` callback.
             if self.definition.get_type() == PluginType::Operator {
-                let measurements = (self.definition.modify_measurement)(self, measurement)?;
+                let measurements = self.profile_callback("modify_measurement", |state| {
+                    (state.definition.modify_measurement)(state, measurement)
+                })?;
                 for measurement in measurements {
-                    self.connection
-                        .send(OutgoingMessage::Upstream(GatestreamUp::Measured(
-                            measurement,
-                        )))?;
+                    self.send_upstream(GatestreamUp::Measured(measurement))?;
                 }
             }
         } else {
@@ -372,6 +460,54 @@ impl<'a> PluginState<'a> {
         Ok(())
     }
 
+    /// Evaluates the classical condition of a gate received from upstream.
+    ///
+    /// Backends evaluate the condition against the measurement results they
+    /// previously sent upstream, returning the gate without its condition if
+    /// the condition is satisfied, or `None` if the gate is to be skipped.
+    /// Operators return the gate as-is, such that the condition is passed on
+    /// downstream by their gate() callback.
+    fn evaluate_condition(&self, gate: Gate) -> Option<Gate> {
+        if self.definition.get_type() != PluginType::Backend {
+            return Some(gate);
+        }
+        if let Some(condition) = gate.get_condition() {
+            let value = self
+                .upstream_measurement_register
+                .get(&condition.qubit)
+                .cloned()
+                .unwrap_or(QubitMeasurementValue::Undefined);
+            if !condition.is_satisfied_by(value) {
+                trace!(
+                    "Skipping conditional gate; measurement register of qubit {} is {}, not {}",
+                    condition.qubit,
+                    value,
+                    condition.value
+                );
+                return None;
+            }
+            return Some(gate.without_condition());
+        }
+        Some(gate)
+    }
+
+    /// Clears the measurement data of the qubits reset by the resets that
+    /// were acknowledged by the given downstream sequence number.
+    fn handle_resets_up_to(&mut self, sequence: SequenceNumber) {
+        while let Some((reset, _)) = self.downstream_pending_resets.front() {
+            if !sequence.acknowledges(*reset) {
+                break;
+            }
+            let (_, qubits) = self.downstream_pending_resets.pop_front().unwrap();
+            for qubit in qubits {
+                if let Some(data) = self.downstream_qubit_data.get_mut(&qubit) {
+                    trace!("Clearing measurement data for qubit {}...", qubit);
+                    data.measurement.take();
+                }
+            }
+        }
+    }
+
     /// Verifies that the queued measurement results correspond with the
     /// `measures` vectors in the respective gates that we sent, and saves the
     /// downstream sequence number. We may also need to forward the
@@ -400,18 +536,24 @@ impl<'a> PluginState<'a> {
             // measurement has not been handled yet.
             let mut ok = false;
 
-            // Note that we're using the above two flags to keep Ferris happy;
-            // we can't use self within the if let due to the mutable borrow.
+            // The sequence number of the current gate.
+            let mut gate_sequence = SequenceNumber::none();
+
+            // Note that we're using the above flags to keep Ferris happy; we
+            // can't use self within the if let due to the mutable borrow.
             if let Some(expected) = self.downstream_expected_measurements.front_mut() {
                 if sequence.acknowledges(expected.0) && expected.1.remove(&measurement.qubit) {
                     ok = true;
                     pop = expected.1.is_empty();
+                    gate_sequence = expected.0;
                 }
             }
 
             // Do what we just determined we need to do.
             if ok {
-                // Handle the received measurement.
+                // Apply any resets that precede the gate, then handle the
+                // received measurement.
+                self.handle_resets_up_to(gate_sequence.preceding());
                 self.handle_measurement(measurement)?;
 
                 // Clean up/move on to the next gate if we received everything
@@ -460,13 +602,10 @@ impl<'a> PluginState<'a> {
             // "undefined" of course) to work around the downstream plugin's
             // bugs. We also move on to the next gate (note the pop_front in
             // the iterator).
-            for qubit in self
-                .downstream_expected_measurements
-                .pop_front()
-                .unwrap()
-                .1
-                .drain()
-            {
+            let (gate_sequence, mut qubits) =
+                self.downstream_expected_measurements.pop_front().unwrap();
+            self.handle_resets_up_to(gate_sequence.preceding());
+            for qubit in qubits.drain() {
                 if self.downstream_qubit_data.contains_key(&qubit) {
                     warn!(
                         "missing measurement data for qubit {}, setting to undefined; bug in downstream plugin!",
@@ -486,6 +625,9 @@ impl<'a> PluginState<'a> {
             }
         }
 
+        // Apply the remaining acknowledged resets.
+        self.handle_resets_up_to(sequence);
+
         self.check_completed_up_to()?;
 
         Ok(())
@@ -505,10 +647,7 @@ impl<'a> PluginState<'a> {
             if acknowledged {
                 let (_, _, postponed_measurements) = self.upstream_postponed.pop_front().unwrap();
                 for postponed_measurement in postponed_measurements {
-                    self.connection
-                        .send(OutgoingMessage::Upstream(GatestreamUp::Measured(
-                            postponed_measurement,
-                        )))?;
+                    self.send_upstream(GatestreamUp::Measured(postponed_measurement))?;
                 }
             } else {
                 break;
@@ -530,17 +669,87 @@ impl<'a> PluginState<'a> {
         // acknowledge.
         if completed_up_to.after(self.upstream_completed_up_to) {
             trace!("We've completed up to {}", completed_up_to);
-            self.connection
-                .send(OutgoingMessage::Upstream(GatestreamUp::CompletedUpTo(
-                    completed_up_to,
-                )))?;
+            self.send_upstream(GatestreamUp::CompletedUpTo(completed_up_to))?;
             self.upstream_completed_up_to = completed_up_to;
         }
         Ok(())
     }
 
+    /// Sends a message to the downstream plugin, recording it first if
+    /// gatestream tracing is enabled.
+    fn send_downstream(&mut self, message: GatestreamDown) -> Result<()> {
+        if let Some(tracer) = &self.tracer {
+            tracer.record(
+                self.downstream_cycle_tx,
+                TraceMessage::Down(message.clone()),
+            )?;
+        }
+        self.connection.send(OutgoingMessage::Downstream(message))
+    }
+
+    /// Queues a pipelined request for the downstream plugin, recording it
+    /// first if gatestream tracing is enabled. The request is sent when the
+    /// queue is flushed.
+    fn send_pipelined(
+        &mut self,
+        sequence: SequenceNumber,
+        message: PipelinedGatestreamDown,
+    ) -> Result<()> {
+        if let Some(tracer) = &self.tracer {
+            tracer.record(
+                self.downstream_cycle_tx,
+                TraceMessage::Down(GatestreamDown::Pipelined(sequence, message.clone())),
+            )?;
+        }
+        self.downstream_batch.push((sequence, message));
+        if self.downstream_batch.len() >= MAX_BATCH_SIZE {
+            self.flush_downstream()?;
+        }
+        Ok(())
+    }
+
+    /// Sends the queued pipelined requests downstream, as a single
+    /// `PipelinedBatch` message if there is more than one.
+    fn flush_downstream(&mut self) -> Result<()> {
+        let message = match self.downstream_batch.len() {
+            0 => return Ok(()),
+            1 => {
+                let (sequence, message) = self.downstream_batch.pop().unwrap();
+                GatestreamDown::Pipelined(sequence, message)
+            }
+            _ => GatestreamDown::PipelinedBatch(self.downstream_batch.drain(..).collect()),
+        };
+        self.connection.send(OutgoingMessage::Downstream(message))
+    }
+
+    /// Sends a response to the upstream plugin, or adds it to the batch of
+    /// responses if we're handling a `PipelinedBatch` message.
+    fn send_upstream(&mut self, message: GatestreamUp) -> Result<()> {
+        if let Some(batch) = &mut self.upstream_batch {
+            batch.push(message);
+            Ok(())
+        } else {
+            self.connection.send(OutgoingMessage::Upstream(message))
+        }
+    }
+
+    /// Records a message received from the downstream plugin if gatestream
+    /// tracing is enabled.
+    fn trace_upstream(&self, message: &GatestreamUp) -> Result<()> {
+        if let Some(tracer) = &self.tracer {
+            tracer.record(self.downstream_cycle_rx, TraceMessage::Up(message.clone()))?;
+        }
+        Ok(())
+    }
+
     /// Handle an incoming upstream message from the downstream plugin.
     fn handle_downstream_message(&mut self, message: GatestreamUp) -> Result<()> {
+        if let GatestreamUp::Batch(messages) = message {
+            return messages
+                .into_iter()
+                .try_for_each(|message| self.handle_downstream_message(message));
+        }
+        self.trace_upstream(&message)?;
         if let Some(ref mut rng) = self.rng {
             rng.select(2);
         }
@@ -578,6 +787,133 @@ impl<'a> PluginState<'a> {
         Ok(())
     }
 
+    /// Calls the user-defined gate() callback for a gate received from
+    /// upstream with the given sequence number, and checks the returned
+    /// measurements against the `measures` set of the gate. The measurements
+    /// are added to `queued_measurements`.
+    fn handle_gate(
+        &mut self,
+        sequence: SequenceNumber,
+        gate: Gate,
+        queued_measurements: &mut Vec<QubitMeasurementResult>,
+    ) -> Result<()> {
+        let mut measures: HashSet<_> = gate.get_measures().iter().cloned().collect();
+        let measurements =
+            self.profile_callback("gate", |state| (state.definition.gate)(state, gate))?;
+        for measurement in measurements {
+            if measures.remove(&measurement.qubit) {
+                queued_measurements.push(measurement);
+            } else {
+                err(format!(
+                    "user-defined gate() function returned multiple measurements for qubit {}",
+                    measurement.qubit
+                ))?;
+            }
+        }
+        if !measures.is_empty() {
+            if self.definition.get_type() == PluginType::Operator {
+                // These measurement results are postponed until we receive
+                // (and maybe modify) them from downstream.
+                trace!(
+                    "Postponing measurement results for {} until downstream {}",
+                    sequence,
+                    self.downstream_sequence_tx.get_previous()
+                );
+            } else {
+                // Backends cannot postpone.
+                err(format!(
+                    "user-defined gate() function failed to return measurement for qubits {}",
+                    friendly_enumerate(measures.into_iter(), Some("or"))
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a pipelined request from the upstream plugin, up to but not
+    /// including sending the `CompletedUpTo` message that may result from
+    /// it.
+    fn handle_pipelined(
+        &mut self,
+        sequence: SequenceNumber,
+        message: PipelinedGatestreamDown,
+    ) -> Result<()> {
+        if let Some(ref mut rng) = self.rng {
+            rng.select(1)
+        }
+        self.synchronized_to_rpcs = true;
+
+        trace!("Received request {} from upstream", sequence);
+
+        let mut queued_measurements = vec![];
+
+        let response = match message {
+            PipelinedGatestreamDown::Allocate(num_qubits, commands) => {
+                let qubits = self.upstream_qubit_ref_generator.allocate(num_qubits);
+                self.profile_callback("allocate", |state| {
+                    (state.definition.allocate)(state, qubits, commands)
+                })
+            }
+            PipelinedGatestreamDown::Free(qubits) => {
+                for qubit in qubits.iter() {
+                    self.upstream_measurement_register.remove(qubit);
+                }
+                self.upstream_qubit_ref_generator.free(qubits.clone());
+                self.profile_callback("free", |state| (state.definition.free)(state, qubits))
+            }
+            PipelinedGatestreamDown::Reset(qubits) => {
+                for qubit in qubits.iter() {
+                    self.upstream_measurement_register.remove(qubit);
+                }
+                self.profile_callback("reset", |state| (state.definition.reset)(state, qubits))
+            }
+            PipelinedGatestreamDown::Gate(gate) => match self.evaluate_condition(gate) {
+                None => Ok(()),
+                Some(gate) => self.handle_gate(sequence, gate, &mut queued_measurements),
+            },
+            PipelinedGatestreamDown::Advance(cycles) => self
+                .send_upstream(GatestreamUp::Advanced(cycles))
+                .and_then(|_| {
+                    self.profile_callback("advance", |state| {
+                        (state.definition.advance)(state, cycles)
+                    })
+                }),
+        };
+
+        // Propagate errors.
+        if let Err(e) = response {
+            let e = e.to_string();
+            error!("{}", e);
+            self.send_upstream(GatestreamUp::Failure(sequence, e))?;
+        }
+
+        // Save that we've completed the downstream handling of the
+        // upstream requests stream up to this point.
+        self.upstream_issued_up_to = sequence;
+        trace!("We've just finished issuing {}", sequence);
+
+        // Operators need to wait for any downstream requests made
+        // by user code to be acknowledged before forwarding the
+        // acknowledgement upstream.
+        if self.definition.get_type() == PluginType::Operator {
+            let back_sequence = self.downstream_sequence_tx.get_previous();
+            self.upstream_postponed
+                .push_back((back_sequence, sequence, queued_measurements));
+            trace!(
+                "Downstream needs to complete up to {} to ack {}",
+                back_sequence,
+                sequence
+            );
+        } else {
+            for measurement in queued_measurements {
+                self.upstream_measurement_register
+                    .insert(measurement.qubit, measurement.value);
+                self.send_upstream(GatestreamUp::Measured(measurement))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Handles any incoming message.
     ///
     /// The returned boolean indicates whether the message was an abort,
@@ -612,7 +948,15 @@ impl<'a> PluginState<'a> {
                             }
                         },
                         SimulatorToPlugin::UserInitialize(req) => {
-                            match (self.definition.initialize)(self, req.init_cmds) {
+                            match req
+                                .init_cmds
+                                .iter()
+                                .try_for_each(|cmd| self.definition.get_interfaces().check(cmd))
+                                .and_then(|_| {
+                                    self.profile_callback("initialize", |state| {
+                                        (state.definition.initialize)(state, req.init_cmds)
+                                    })
+                                }) {
                                 Ok(_) => PluginToSimulator::Success,
                                 Err(e) => {
                                     let e = e.to_string();
@@ -640,8 +984,15 @@ impl<'a> PluginState<'a> {
                                 PluginToSimulator::Failure(e)
                             }
                         },
+                        SimulatorToPlugin::ProfileRequest => {
+                            PluginToSimulator::ProfileResponse(self.get_profile())
+                        }
                         SimulatorToPlugin::ArbRequest(req) => {
-                            match (self.definition.host_arb)(self, req) {
+                            match self.definition.get_interfaces().check(&req).and_then(|_| {
+                                self.profile_callback("host_arb", |state| {
+                                    (state.definition.host_arb)(state, req)
+                                })
+                            }) {
                                 Ok(x) => PluginToSimulator::ArbResponse(x),
                                 Err(e) => {
                                     let e = e.to_string();
@@ -663,119 +1014,75 @@ impl<'a> PluginState<'a> {
                     self.connection.send(response)?;
                 }
                 IncomingMessage::Upstream(GatestreamDown::Pipelined(sequence, message)) => {
-                    if let Some(ref mut rng) = self.rng {
-                        rng.select(1)
-                    }
-                    self.synchronized_to_rpcs = true;
-
-                    trace!("Received request {} from upstream", sequence);
-
-                    let mut queued_measurements = vec![];
-
-                    let response = match message {
-                        PipelinedGatestreamDown::Allocate(num_qubits, commands) => {
-                            let qubits = self.upstream_qubit_ref_generator.allocate(num_qubits);
-                            (self.definition.allocate)(self, qubits, commands)
-                        }
-                        PipelinedGatestreamDown::Free(qubits) => {
-                            self.upstream_qubit_ref_generator.free(qubits.clone());
-                            (self.definition.free)(self, qubits)
-                        }
-                        PipelinedGatestreamDown::Gate(gate) => {
-                            let mut measures: HashSet<_> =
-                                gate.get_measures().iter().cloned().collect();
-                            (self.definition.gate)(self, gate).and_then(|measurements| {
-                            for measurement in measurements {
-                                if measures.remove(&measurement.qubit) {
-                                    queued_measurements.push(measurement);
-                                } else {
-                                    err(format!(
-                                        "user-defined gate() function returned multiple measurements for qubit {}",
-                                        measurement.qubit
-                                    ))?;
-                                }
-                            }
-                            if !measures.is_empty() {
-                                if self.definition.get_type() == PluginType::Operator {
-                                    // These measurement results are postponed
-                                    // until we receive (and maybe modify) them
-                                    // from downstream.
-                                    trace!("Postponing measurement results for {} until downstream {}",
-                                        sequence, self.downstream_sequence_tx.get_previous());
-                                } else {
-                                    // Backends cannot postpone.
-                                    err(format!(
-                                        "user-defined gate() function failed to return measurement for qubits {}",
-                                        friendly_enumerate(measures.into_iter(), Some("or"))
-                                    ))?;
-                                }
-                            }
-                            Ok(())
-                        })
-                        }
-                        PipelinedGatestreamDown::Advance(cycles) => self
-                            .connection
-                            .send(OutgoingMessage::Upstream(GatestreamUp::Advanced(cycles)))
-                            .and_then(|_| (self.definition.advance)(self, cycles)),
-                    };
-
-                    // Propagate errors.
-                    if let Err(e) = response {
-                        let e = e.to_string();
-                        error!("{}", e);
-                        self.connection
-                            .send(OutgoingMessage::Upstream(GatestreamUp::Failure(
-                                sequence, e,
-                            )))?;
-                    }
-
-                    // Save that we've completed the downstream handling of the
-                    // upstream requests stream up to this point.
-                    self.upstream_issued_up_to = sequence;
-                    trace!("We've just finished issuing {}", sequence);
-
-                    // Operators need to wait for any downstream requests made
-                    // by user code to be acknowledged before forwarding the
-                    // acknowledgement upstream.
-                    if self.definition.get_type() == PluginType::Operator {
-                        let back_sequence = self.downstream_sequence_tx.get_previous();
-                        self.upstream_postponed.push_back((
-                            back_sequence,
-                            sequence,
-                            queued_measurements,
-                        ));
-                        trace!(
-                            "Downstream needs to complete up to {} to ack {}",
-                            back_sequence,
-                            sequence
-                        );
-                    } else {
-                        for measurement in queued_measurements {
-                            self.connection.send(OutgoingMessage::Upstream(
-                                GatestreamUp::Measured(measurement),
-                            ))?;
-                        }
-                    }
+                    self.handle_pipelined(sequence, message)?;
 
                     // Changing upstream_issued_up_to and/or upstream_postponed
                     // means we may be ready to send the next CompletedUpTo
                     // message.
                     self.check_completed_up_to()?;
                 }
+                IncomingMessage::Upstream(GatestreamDown::PipelinedBatch(messages)) => {
+                    // Collect the responses to the requests in the batch, such
+                    // that they can be sent upstream as a single message,
+                    // acknowledging the whole batch at once.
+                    self.upstream_batch = Some(vec![]);
+                    let result = messages
+                        .into_iter()
+                        .try_for_each(|(sequence, message)| {
+                            self.handle_pipelined(sequence, message)
+                        })
+                        .and_then(|_| self.check_completed_up_to());
+                    let mut responses = self.upstream_batch.take().unwrap();
+                    match responses.len() {
+                        0 => (),
+                        1 => self
+                            .connection
+                            .send(OutgoingMessage::Upstream(responses.pop().unwrap()))?,
+                        _ => self
+                            .connection
+                            .send(OutgoingMessage::Upstream(GatestreamUp::Batch(responses)))?,
+                    }
+                    result?;
+                }
                 IncomingMessage::Upstream(GatestreamDown::ArbRequest(cmd)) => {
                     if let Some(ref mut rng) = self.rng {
                         rng.select(1)
                     }
                     self.synchronized_to_rpcs = true;
 
-                    let response = match (self.definition.upstream_arb)(self, cmd) {
+                    let response = match self.check_upstream_arb(&cmd).and_then(|_| {
+                        self.profile_callback("upstream_arb", |state| {
+                            (state.definition.upstream_arb)(state, cmd)
+                        })
+                    }) {
                         Ok(r) => GatestreamUp::ArbSuccess(r),
                         Err(e) => GatestreamUp::ArbFailure(e.to_string()),
                     };
                     self.connection.send(OutgoingMessage::Upstream(response))?;
                 }
+                IncomingMessage::Upstream(GatestreamDown::HostArbRequest(cmd)) => {
+                    if let Some(ref mut rng) = self.rng {
+                        rng.select(0)
+                    }
+                    self.synchronized_to_rpcs = true;
+
+                    let response =
+                        match self.definition.get_interfaces().check(&cmd).and_then(|_| {
+                            self.profile_callback("host_arb", |state| {
+                                (state.definition.host_arb)(state, cmd)
+                            })
+                        }) {
+                            Ok(r) => GatestreamUp::ArbSuccess(r),
+                            Err(e) => GatestreamUp::ArbFailure(e.to_string()),
+                        };
+                    self.connection.send(OutgoingMessage::Upstream(response))?;
+                }
                 IncomingMessage::Downstream(message) => self.handle_downstream_message(message)?,
             }
+
+            // Don't hold on to requests made by callbacks; the downstream
+            // plugin may be needed to complete the message we just handled.
+            self.flush_downstream()?;
         }
 
         Ok(self.aborted)
@@ -811,7 +1118,9 @@ impl<'a> PluginState<'a> {
             .map(RandomNumberGenerator::get_selected)
             .unwrap_or(0);
         trace!("Syncing up to {}", num);
-        let result = self._synchronize_downstream_up_to(num);
+        let result = self
+            .flush_downstream()
+            .and_then(|_| self._synchronize_downstream_up_to(num));
         trace!("Synced up to {}", num);
         if let Some(ref mut rng) = self.rng {
             rng.select(rng_index);
@@ -866,9 +1175,17 @@ impl<'a> PluginState<'a> {
             upstream_issued_up_to: SequenceNumber::none(),
             upstream_postponed: VecDeque::new(),
             upstream_completed_up_to: SequenceNumber::none(),
+            upstream_measurement_register: HashMap::new(),
             downstream_qubit_data: HashMap::new(),
             downstream_measurement_queue: VecDeque::new(),
             downstream_expected_measurements: VecDeque::new(),
+            downstream_pending_resets: VecDeque::new(),
+            downstream_batch: vec![],
+            upstream_batch: None,
+            tracer: None,
+            downstream_interfaces: None,
+            profile: PluginProfile::default(),
+            profiled_time: Duration::default(),
             aborted: false,
         };
 
@@ -985,11 +1302,11 @@ impl<'a> PluginState<'a> {
         }
 
         // Send the allocate message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Allocate(num_qubits, commands),
-            )))?;
+        let sequence = self.downstream_sequence_tx.get_next();
+        self.send_pipelined(
+            sequence,
+            PipelinedGatestreamDown::Allocate(num_qubits, commands),
+        )?;
 
         // Return the references to the qubits.
         Ok(qubits)
@@ -1008,11 +1325,8 @@ impl<'a> PluginState<'a> {
         self.check_qubits_live(qubits.iter())?;
 
         // Send the free message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Free(qubits.clone()),
-            )))?;
+        let sequence = self.downstream_sequence_tx.get_next();
+        self.send_pipelined(sequence, PipelinedGatestreamDown::Free(qubits.clone()))?;
 
         // Kill our classical storage for the qubits.
         for qubit in qubits.iter() {
@@ -1027,6 +1341,13 @@ impl<'a> PluginState<'a> {
 
     /// Tells the downstream plugin to execute a gate.
     ///
+    /// If the gate has a classical condition, it is evaluated by the backend
+    /// against the most recent measurement result for the condition qubit
+    /// that precedes the gate in the gatestream, so there is no need to wait
+    /// for the measurement result using `get_measurement()` first. Note that
+    /// operators that modify measurement results should take care of this
+    /// themselves, as the backend only knows the unmodified results.
+    ///
     /// Backend plugins are not allowed to call this. Doing so will result in
     /// an `Err` return value.
     pub fn gate(&mut self, gate: Gate) -> Result<()> {
@@ -1038,17 +1359,14 @@ impl<'a> PluginState<'a> {
         self.check_qubits_live(gate.get_targets())?;
         self.check_qubits_live(gate.get_controls())?;
         self.check_qubits_live(gate.get_measures())?;
+        self.check_qubits_live(gate.get_condition().map(|condition| &condition.qubit))?;
 
         // Store which qubits we're expecting to be measured.
         let measures: HashSet<_> = gate.get_measures().iter().cloned().collect();
 
         // Send the gate message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Gate(gate),
-            )))?;
-        let sequence = self.downstream_sequence_tx.get_previous();
+        let sequence = self.downstream_sequence_tx.get_next();
+        self.send_pipelined(sequence, PipelinedGatestreamDown::Gate(gate))?;
 
         // Update the last-mutation sequence number for the measured qubits.
         for measure in measures.iter() {
@@ -1067,6 +1385,50 @@ impl<'a> PluginState<'a> {
         Ok(())
     }
 
+    /// Resets the given downstream qubits to the |0> state.
+    ///
+    /// Besides resetting the quantum state, this clears the measurement
+    /// registers of the qubits in all plugins along the way. Once the reset
+    /// completes, `get_measurement()`, `get_cycles_since_measure()`, and
+    /// `get_cycles_between_measures()` behave as if the qubits were never
+    /// measured, just like they would for a newly allocated qubit. This allows
+    /// qubits to be reused without freeing and reallocating them.
+    ///
+    /// Backend plugins are not allowed to call this. Doing so will result in
+    /// an `Err` return value.
+    pub fn reset_qubits(&mut self, qubits: Vec<QubitRef>) -> Result<()> {
+        if self.definition.get_type() == PluginType::Backend {
+            return inv_op("reset_qubits() is not available for backends")?;
+        } else if !self.synchronized_to_rpcs {
+            return inv_op("reset_qubits() cannot be called while handling a gatestream response")?;
+        }
+        self.check_qubits_live(qubits.iter())?;
+
+        // Send the reset message.
+        let sequence = self.downstream_sequence_tx.get_next();
+        self.send_pipelined(sequence, PipelinedGatestreamDown::Reset(qubits.clone()))?;
+
+        // Update the last-mutation sequence number for the qubits, and clear
+        // their measurement data once the reset is acknowledged.
+        for qubit in qubits.iter() {
+            self.downstream_qubit_data
+                .get_mut(qubit)
+                .unwrap()
+                .last_mutation = sequence;
+        }
+        self.downstream_pending_resets.push_back((sequence, qubits));
+
+        Ok(())
+    }
+
+    /// Calls the user-defined gate() callback of this plugin directly.
+    ///
+    /// This is used to implement the default reset() callback for backends in
+    /// terms of a prep gate.
+    pub(crate) fn execute_gate(&mut self, gate: Gate) -> Result<Vec<QubitMeasurementResult>> {
+        self.profile_callback("gate", |state| (state.definition.gate)(state, gate))
+    }
+
     /// Returns the latest measurement of the given downstream qubit.
     ///
     /// Backend plugins are not allowed to call this. Doing so will result in
@@ -1193,16 +1555,15 @@ impl<'a> PluginState<'a> {
             return inv_op("advance() cannot be called while handling a gatestream response")?;
         }
 
+        // Send the advance message. Advancing marks the boundary of a batch of
+        // requests, so the queued requests are sent along with it.
+        let sequence = self.downstream_sequence_tx.get_next();
+        self.send_pipelined(sequence, PipelinedGatestreamDown::Advance(cycles))?;
+        self.flush_downstream()?;
+
         // Advance our local counter.
         self.downstream_cycle_tx = self.downstream_cycle_tx.advance(cycles);
 
-        // Send the advance message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Advance(cycles),
-            )))?;
-
         // Return the current simulation time.
         Ok(self.downstream_cycle_tx)
     }
@@ -1220,29 +1581,98 @@ impl<'a> PluginState<'a> {
         Ok(self.downstream_cycle_tx)
     }
 
-    /// Sends an arbitrary command downstream.
+    /// Sends the requests queued by `allocate()`, `free()`, `gate()`, and
+    /// `reset_qubits()` downstream.
+    ///
+    /// Such requests are queued until the next synchronization point, for
+    /// instance a measurement query, an `arb()`, or an `advance()`, such that
+    /// many of them can be sent in a single message. Calling this function is
+    /// never required, but it allows the downstream plugin to start on the
+    /// queued requests earlier, which may be useful before a long-running
+    /// classical computation.
     ///
     /// Backend plugins are not allowed to call this. Doing so will result in
     /// an `Err` return value.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.definition.get_type() == PluginType::Backend {
+            return inv_op("flush() is not available for backends")?;
+        }
+        self.flush_downstream()
+    }
+
+    /// Sends an arbitrary command downstream.
+    ///
+    /// Backend plugins are not allowed to call this. Doing so will result in
+    /// an `Err` return value. If the downstream plugins advertised the
+    /// `ArbCmd` interfaces they support, commands outside of those and
+    /// commands with a payload that does not match the advertised schema are
+    /// rejected without being sent.
     pub fn arb(&mut self, cmd: ArbCmd) -> Result<ArbData> {
         if self.definition.get_type() == PluginType::Backend {
             return inv_op("arb() is not available for backends")?;
         } else if !self.synchronized_to_rpcs {
             return inv_op("arb() cannot be called while handling a gatestream response")?;
         }
+        if let Some(interfaces) = &self.downstream_interfaces {
+            if !interfaces.accepts(&cmd) {
+                inv_arg(format!(
+                    "downstream plugin does not support ArbCmd {}.{}",
+                    cmd.interface_identifier(),
+                    cmd.operation_identifier()
+                ))?;
+            }
+            interfaces.validate(&cmd)?;
+        }
+
+        self.arb_downstream(GatestreamDown::ArbRequest(cmd))
+    }
+
+    /// Checks an `ArbCmd` received from upstream before it is passed to the
+    /// `upstream_arb()` callback. Operators also accept the commands
+    /// understood by their downstream plugin, which they may forward.
+    fn check_upstream_arb(&self, cmd: &ArbCmd) -> Result<()> {
+        let interfaces = self.definition.get_interfaces();
+        if interfaces.accepts(cmd) {
+            interfaces.validate(cmd)
+        } else if let Some(downstream) = &self.downstream_interfaces {
+            downstream.check(cmd)
+        } else {
+            interfaces.check(cmd)
+        }
+    }
+
+    /// Sends an arbitrary command to the `host_arb()` callback of the
+    /// downstream plugin. This is used by the debugger operator to inspect
+    /// the plugin it is debugging.
+    pub(crate) fn host_arb_downstream(&mut self, cmd: ArbCmd) -> Result<ArbData> {
+        if self.definition.get_type() == PluginType::Backend {
+            return inv_op("host_arb_downstream() is not available for backends")?;
+        } else if !self.synchronized_to_rpcs {
+            return inv_op(
+                "host_arb_downstream() cannot be called while handling a gatestream response",
+            )?;
+        }
+        self.arb_downstream(GatestreamDown::HostArbRequest(cmd))
+    }
 
+    /// Sends an `ArbRequest` or `HostArbRequest` downstream and waits for the
+    /// response.
+    fn arb_downstream(&mut self, request: GatestreamDown) -> Result<ArbData> {
         // ArbCmds are synchronous in nature, because they return data
         // immediately. Therefore we must first wait for all pipelined
         // requests to complete.
         self.synchronize_downstream()?;
 
         // Send the command.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::ArbRequest(cmd)))?;
+        self.send_downstream(request)?;
 
         // The next downstream response must either be ArbFailure for an error
         // or ArbSuccess for success. Any other message is a protocol error.
-        match self.connection.next_downstream_request()? {
+        let response = self.connection.next_downstream_request()?;
+        if let Some(IncomingMessage::Downstream(message)) = &response {
+            self.trace_upstream(message)?;
+        }
+        match response {
             Some(IncomingMessage::Downstream(GatestreamUp::ArbSuccess(x))) => Ok(x),
             Some(IncomingMessage::Downstream(GatestreamUp::ArbFailure(e))) => err(e),
             Some(IncomingMessage::Downstream(_)) => {
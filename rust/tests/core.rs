@@ -1,17 +1,23 @@
 use dqcsim::{
     common::{
         error::err,
-        log::{thread::LogThread, LoglevelFilter},
+        log::{
+            callback::LogCallback, module_filter::ModuleFilter, thread::LogThread, LogRecord,
+            LoglevelFilter,
+        },
+        trace::{TraceDirection, TraceFilter, TraceHeader, TraceReader, TraceSummary},
         types::{
-            ArbCmd, ArbData, Gate, Matrix, PluginMetadata, PluginType, QubitMeasurementResult,
-            QubitMeasurementValue, QubitRef,
+            ArbCmd, ArbData, Gate, GateType, Matrix, PluginMetadata, PluginProfile, PluginType,
+            QubitMeasurementResult, QubitMeasurementValue, QubitRef,
         },
     },
     host::{
         accelerator::Accelerator,
         configuration::{
-            PluginLogConfiguration, PluginThreadConfiguration, Seed, SimulatorConfiguration,
+            DebuggerConfiguration, PluginLogConfiguration, PluginThreadConfiguration, Seed,
+            SimulatorConfiguration,
         },
+        debugger::{Breakpoint, DebugAction, DebugControl, DebugStop, DebuggerInterface},
         plugin::Plugin,
         simulation::Simulation,
         simulator::Simulator,
@@ -22,6 +28,7 @@ use num_complex::Complex64;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 pub fn fe_op_be() -> (PluginDefinition, PluginDefinition, PluginDefinition) {
@@ -470,6 +477,847 @@ fn simulation_init_cmds() {
     assert!(simulator.is_ok());
 }
 
+#[test]
+// Tests that the ArbCmd interfaces registered by a plugin are advertised to
+// the host, and that unsupported commands are rejected up front.
+fn simulation_arb_interfaces() {
+    let definition = || {
+        let mut definition =
+            PluginDefinition::new(PluginType::Frontend, PluginMetadata::new("", "", ""));
+        definition.register_arb_operation("a", "b", None).unwrap();
+        definition
+            .register_arb_operation("a", "c", Some(r#"{"type": "object"}"#.to_string()))
+            .unwrap();
+        definition.host_arb = Box::new(|_, cmd| {
+            assert_eq!(cmd.interface_identifier(), "a");
+            Ok(ArbData::default())
+        });
+        definition
+    };
+
+    let configuration = |init_cmd| {
+        SimulatorConfiguration::default()
+            .without_reproduction()
+            .without_logging()
+            .with_plugin(thread_config_type(PluginType::Backend))
+            .with_plugin(
+                PluginThreadConfiguration::new(
+                    definition(),
+                    PluginLogConfiguration::new("front", LoglevelFilter::Off),
+                )
+                .with_init_cmd(init_cmd),
+            )
+    };
+
+    let mut simulator =
+        Simulator::new(configuration(ArbCmd::new("a", "b", ArbData::default()))).unwrap();
+    let interfaces = simulator.supported_interfaces("front").unwrap();
+    assert_eq!(interfaces.get_interfaces().collect::<Vec<_>>(), vec!["a"]);
+    assert_eq!(
+        interfaces.get_schema("a", "c").unwrap().as_str(),
+        r#"{"type": "object"}"#
+    );
+    assert!(simulator
+        .simulation
+        .get_interfaces_idx(-1)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        simulator
+            .supported_interfaces("asdf")
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: plugin asdf not found"
+    );
+
+    assert!(simulator
+        .simulation
+        .arb("front", ArbCmd::new("a", "c", ArbData::default()))
+        .is_ok());
+    assert_eq!(
+        simulator
+            .simulation
+            .arb("front", ArbCmd::new("x", "c", ArbData::default()))
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: plugin front does not support ArbCmd x.c"
+    );
+    assert_eq!(
+        simulator
+            .simulation
+            .arb(
+                "front",
+                ArbCmd::new("a", "c", ArbData::from_json("[1]", vec![]).unwrap())
+            )
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: invalid payload for ArbCmd a.c: $: expected object, found array"
+    );
+
+    let simulator = Simulator::new(configuration(ArbCmd::new("a", "d", ArbData::default())));
+    assert_eq!(
+        simulator.unwrap_err().to_string(),
+        "Invalid argument: plugin front does not support ArbCmd a.d"
+    );
+
+    let simulator = Simulator::new(configuration(ArbCmd::new(
+        "a",
+        "c",
+        ArbData::from_json("3", vec![]).unwrap(),
+    )));
+    assert_eq!(
+        simulator.unwrap_err().to_string(),
+        "Invalid argument: invalid payload for ArbCmd a.c: $: expected object, found integer"
+    );
+}
+
+#[test]
+// Tests that plugins validate ArbCmds received from upstream against their
+// own schemas.
+fn upstream_arb_schema() {
+    let (mut frontend, _, mut backend) = fe_op_be();
+
+    backend
+        .register_arb_operation(
+            "a",
+            "b",
+            Some(r#"{"properties": {"shots": {"type": "integer"}}}"#.to_string()),
+        )
+        .unwrap();
+
+    frontend.run = Box::new(|state, _| {
+        assert!(state
+            .arb(ArbCmd::new(
+                "a",
+                "b",
+                ArbData::from_json(r#"{"shots": 3}"#, vec![]).unwrap()
+            ))
+            .is_ok());
+        assert_eq!(
+            state
+                .arb(ArbCmd::new(
+                    "a",
+                    "b",
+                    ArbData::from_json(r#"{"shots": "3"}"#, vec![]).unwrap()
+                ))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: invalid payload for ArbCmd a.b: $.shots: expected integer, found string"
+        );
+        Ok(ArbData::default())
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+}
+
+#[test]
+// Tests that ArbCmds are checked against the interfaces of the downstream
+// plugins before they are sent, and that operators accept the commands that
+// they forward downstream.
+fn downstream_arb_interfaces() {
+    let (mut frontend, mut operator, mut backend) = fe_op_be();
+
+    operator.register_arb_operation("x", "y", None).unwrap();
+    operator.upstream_arb = Box::new(|state, cmd| {
+        if cmd.interface_identifier() == "x" {
+            Ok(ArbData::default())
+        } else {
+            state.arb(cmd)
+        }
+    });
+
+    backend
+        .register_arb_operation(
+            "a",
+            "b",
+            Some(r#"{"properties": {"shots": {"type": "integer"}}}"#.to_string()),
+        )
+        .unwrap();
+
+    frontend.run = Box::new(|state, _| {
+        assert!(state.arb(ArbCmd::new("x", "y", ArbData::default())).is_ok());
+        assert!(state
+            .arb(ArbCmd::new(
+                "a",
+                "b",
+                ArbData::from_json(r#"{"shots": 3}"#, vec![]).unwrap()
+            ))
+            .is_ok());
+        assert_eq!(
+            state
+                .arb(ArbCmd::new("a", "c", ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: downstream plugin does not support ArbCmd a.c"
+        );
+        assert_eq!(
+            state
+                .arb(ArbCmd::new(
+                    "a",
+                    "b",
+                    ArbData::from_json(r#"{"shots": "3"}"#, vec![]).unwrap()
+                ))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: invalid payload for ArbCmd a.b: $.shots: expected integer, found string"
+        );
+        Ok(ArbData::default())
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+}
+
+#[test]
+// Tests that shared memory arguments survive the trip between plugins and
+// back to the host.
+fn shared_arb_data() {
+    let (mut frontend, _, mut backend) = fe_op_be();
+
+    let payload = || (0..=255).cycle().take(1 << 20).collect::<Vec<u8>>();
+
+    backend.upstream_arb = Box::new(move |_, cmd| {
+        assert_eq!(&cmd.data().get_shared()[0][..], &payload()[..]);
+        Ok(ArbData::default().with_shared(payload()))
+    });
+
+    frontend.run = Box::new(move |state, _| {
+        let data = ArbData::default().with_shared(payload());
+        let response = state.arb(ArbCmd::new("a", "b", data)).unwrap();
+        assert_eq!(response.get_shared().len(), 1);
+        assert_eq!(&response.get_shared()[0][..], &payload()[..]);
+        Ok(response.with_shared(vec![]))
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    let result = simulator.simulation.wait().unwrap();
+    assert_eq!(result.get_shared().len(), 2);
+    assert_eq!(&result.get_shared()[0][..], &payload()[..]);
+    assert!(result.get_shared()[1].is_empty());
+}
+
+#[test]
+// Tests that module directives override the plugin verbosity.
+fn plugin_module_filter() {
+    mod chatty {
+        pub fn log() {
+            dqcsim::trace!("chatty trace");
+        }
+    }
+    mod quiet {
+        pub fn log() {
+            dqcsim::info!("quiet info");
+            dqcsim::error!("quiet error");
+        }
+    }
+
+    let mut definition = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+    );
+    definition.run = Box::new(|_, _| {
+        dqcsim::info!("default info");
+        dqcsim::debug!("default debug");
+        chatty::log();
+        quiet::log();
+        Ok(ArbData::default())
+    });
+
+    let records = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let records_clone = records.clone();
+    let callback = LogCallback::new(
+        Box::new(move |record: &LogRecord| {
+            if record.logger() == "front" {
+                records_clone
+                    .lock()
+                    .unwrap()
+                    .push(record.payload().to_string());
+            }
+        }),
+        LoglevelFilter::Trace,
+    );
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_log_callback(callback)
+        .with_plugin(PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("front", LoglevelFilter::Info).with_modules(
+                ModuleFilter::new()
+                    .with(
+                        module_path!().to_string() + "::chatty",
+                        LoglevelFilter::Trace,
+                    )
+                    .with(
+                        module_path!().to_string() + "::quiet",
+                        LoglevelFilter::Error,
+                    ),
+            ),
+        ))
+        .with_plugin(thread_config_type(PluginType::Backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    drop(simulator);
+
+    assert_eq!(
+        *records.lock().unwrap(),
+        vec!["default info", "chatty trace", "quiet error"]
+    );
+}
+
+#[test]
+// Records the gatestream messages of a simple run.
+fn gatestream_trace() {
+    let mut frontend = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+    );
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(2, vec![])?;
+        state.gate(Gate::new_measurement(
+            vec![qubits[0]],
+            Matrix::new_identity(2),
+        )?)?;
+        state.get_measurement(qubits[0])?;
+        state.advance(5)?;
+        state.free(qubits)?;
+        Ok(ArbData::default())
+    });
+
+    let operator = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("operator", "dqcsim", "0.1.0"),
+    );
+
+    let mut backend = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+    );
+    backend.gate = Box::new(|_, gate| {
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|q| {
+                QubitMeasurementResult::new(*q, QubitMeasurementValue::One, ArbData::default())
+            })
+            .collect())
+    });
+
+    let path = std::env::temp_dir().join(format!("dqcsim-core-{}.trace", std::process::id()));
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_gatestream_trace(&path)
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("front", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            operator,
+            PluginLogConfiguration::new("op", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            backend,
+            PluginLogConfiguration::new("back", LoglevelFilter::Off),
+        ));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    drop(simulator);
+
+    let reader = TraceReader::open(&path).unwrap();
+    assert_eq!(reader.header().plugins, vec!["front", "op", "back"]);
+    let records: Vec<_> = reader
+        .collect::<dqcsim::common::error::Result<_>>()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let kinds = |filter: TraceFilter| {
+        records
+            .iter()
+            .filter(|r| filter.matches(r))
+            .map(|r| r.message.kind())
+            .collect::<Vec<_>>()
+    };
+    for plugin in &["front", "op"] {
+        assert_eq!(
+            kinds(
+                TraceFilter::new()
+                    .with_plugin(*plugin)
+                    .with_direction(TraceDirection::Down)
+            ),
+            vec!["allocate", "gate", "advance", "free"]
+        );
+        let up = kinds(
+            TraceFilter::new()
+                .with_plugin(*plugin)
+                .with_direction(TraceDirection::Up),
+        );
+        assert!(up.contains(&"measured"));
+        assert!(up.contains(&"advanced"));
+        assert!(up.contains(&"completed"));
+    }
+    assert!(kinds(TraceFilter::new().with_plugin("back")).is_empty());
+    assert_eq!(
+        kinds(
+            TraceFilter::new()
+                .with_plugin("front")
+                .with_direction(TraceDirection::Down)
+                .with_cycles(Some(5), None)
+        ),
+        vec!["free"]
+    );
+
+    let mut summary = TraceSummary::new(TraceHeader::new(vec![
+        "front".into(),
+        "op".into(),
+        "back".into(),
+    ]));
+    records.iter().for_each(|r| summary.add(r));
+    let front = &summary.boundaries["front"];
+    assert_eq!(front.sent_up_to, front.completed_up_to);
+    assert!(summary
+        .to_string()
+        .contains("op -> back:\n  messages: 4 down, "));
+}
+
+#[test]
+// Sends many gates through an operator, which should be coalesced into
+// batches and acknowledged per batch.
+fn gatestream_batching() {
+    let (mut frontend, operator, mut backend) = fe_op_be();
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(1, vec![])?;
+        for _ in 0..3000 {
+            state.gate(Gate::new_unitary(
+                vec![qubits[0]],
+                vec![],
+                Matrix::new_identity(2),
+            )?)?;
+        }
+        state.flush()?;
+        state.gate(Gate::new_measurement(
+            vec![qubits[0]],
+            Matrix::new_identity(2),
+        )?)?;
+        let value = state.get_measurement(qubits[0])?.value;
+        assert_eq!(value, QubitMeasurementValue::One);
+        state.free(qubits)?;
+        Ok(ArbData::default())
+    });
+
+    let gates = Arc::new(Mutex::new(0));
+    let backend_gates = gates.clone();
+    backend.gate = Box::new(move |_, gate| {
+        *backend_gates.lock().unwrap() += 1;
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|q| {
+                QubitMeasurementResult::new(*q, QubitMeasurementValue::One, ArbData::default())
+            })
+            .collect())
+    });
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_path_buf();
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_gatestream_trace(&path)
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("front", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            operator,
+            PluginLogConfiguration::new("op", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            backend,
+            PluginLogConfiguration::new("back", LoglevelFilter::Off),
+        ));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    drop(simulator);
+    assert_eq!(*gates.lock().unwrap(), 3001);
+
+    let records: Vec<_> = TraceReader::open(&path)
+        .unwrap()
+        .collect::<dqcsim::common::error::Result<_>>()
+        .unwrap();
+    drop(file);
+    let count = |plugin: &str, kind: &str| {
+        records
+            .iter()
+            .filter(|r| TraceFilter::new().with_plugin(plugin).matches(r))
+            .filter(|r| r.message.kind() == kind)
+            .count()
+    };
+
+    // Traces contain the individual messages, regardless of batching.
+    for plugin in &["front", "op"] {
+        assert_eq!(count(plugin, "gate"), 3001);
+        assert_eq!(count(plugin, "measured"), 1);
+    }
+
+    // The gates are acknowledged per batch rather than one by one.
+    assert!(count("front", "completed") < 10);
+    assert!(count("op", "completed") < 10);
+}
+
+/// Debugger interface that records the stops and responds with a fixed
+/// sequence of actions.
+struct ScriptedDebugger {
+    stops: Arc<Mutex<Vec<DebugStop>>>,
+    arb_results: Arc<Mutex<Vec<ArbData>>>,
+}
+
+impl DebuggerInterface for ScriptedDebugger {
+    fn pause(
+        &mut self,
+        stop: &DebugStop,
+        control: &mut DebugControl,
+    ) -> dqcsim::common::error::Result<DebugAction> {
+        let mut stops = self.stops.lock().unwrap();
+        stops.push(stop.clone());
+        Ok(match stops.len() {
+            1 => DebugAction::Step,
+            2 => {
+                self.arb_results
+                    .lock()
+                    .unwrap()
+                    .push(control.arb(ArbCmd::new("test", "state", ArbData::default()))?);
+                control.add_breakpoint(Breakpoint::Cycle(5));
+                DebugAction::Continue
+            }
+            3 => DebugAction::Continue,
+            _ => DebugAction::Abort,
+        })
+    }
+}
+
+#[test]
+fn debugger() {
+    let mut frontend = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+    );
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(2, vec![])?;
+        state.gate(Gate::new_unitary(
+            vec![qubits[0]],
+            vec![],
+            Matrix::new_identity(2),
+        )?)?;
+        state.gate(Gate::new_measurement(
+            vec![qubits[0]],
+            Matrix::new_identity(2),
+        )?)?;
+        state.advance(5)?;
+        state.free(qubits)?;
+        state.get_cycle()?;
+        state.arb(ArbCmd::new("test", "sync", ArbData::default()))?;
+        Ok(ArbData::default())
+    });
+
+    let mut backend = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+    );
+    backend.gate = Box::new(|_, gate| {
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|q| {
+                QubitMeasurementResult::new(*q, QubitMeasurementValue::One, ArbData::default())
+            })
+            .collect())
+    });
+    backend.upstream_arb = Box::new(|_, _| Ok(ArbData::from_args(vec![b"upstream".to_vec()])));
+    backend.host_arb = Box::new(|_, _| Ok(ArbData::from_args(vec![b"host".to_vec()])));
+
+    let stops = Arc::new(Mutex::new(vec![]));
+    let arb_results = Arc::new(Mutex::new(vec![]));
+    let debugger = DebuggerConfiguration::new(
+        "back",
+        ScriptedDebugger {
+            stops: stops.clone(),
+            arb_results: arb_results.clone(),
+        },
+    )
+    .with_breakpoint(Breakpoint::Gate("measurement".to_string()));
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_debugger(debugger)
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("front", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            backend,
+            PluginLogConfiguration::new("back", LoglevelFilter::Off),
+        ));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+
+    // The debugger operator does not shift the indices of the plugins.
+    assert_eq!(
+        simulator.simulation.get_metadata_idx(1).unwrap().get_name(),
+        "backend"
+    );
+    assert!(simulator.simulation.get_metadata_idx(2).is_err());
+    assert!(simulator.simulation.get_metadata("debugger").is_err());
+
+    simulator.simulation.start(ArbData::default()).unwrap();
+    assert!(simulator
+        .simulation
+        .wait()
+        .unwrap_err()
+        .to_string()
+        .contains("simulation aborted by the debugger"));
+    drop(simulator);
+
+    let stops = stops.lock().unwrap();
+    let kinds: Vec<_> = stops.iter().map(|stop| stop.request.kind()).collect();
+    assert_eq!(kinds, vec!["allocate", "gate", "gate", "free"]);
+    assert!(stops.iter().all(|stop| stop.plugin == "back"));
+    assert!(stops[0].breakpoints.is_empty());
+    assert!(stops[1].breakpoints.is_empty());
+    assert_eq!(stops[2].breakpoints, vec![0]);
+    assert_eq!(stops[3].breakpoints, vec![1]);
+    assert_eq!(Into::<i64>::into(stops[3].cycle), 5);
+    assert_eq!(
+        stops[3]
+            .measurements
+            .iter()
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>(),
+        vec![Some(QubitMeasurementValue::One), None]
+    );
+    assert_eq!(
+        *arb_results.lock().unwrap(),
+        vec![ArbData::from_args(vec![b"host".to_vec()])]
+    );
+}
+
+#[test]
+fn debugger_bad_plugin() {
+    let configuration = |plugin: &str| {
+        let (frontend, _, backend) = fe_op_be();
+        SimulatorConfiguration::default()
+            .without_reproduction()
+            .without_logging()
+            .with_debugger(DebuggerConfiguration::new(
+                plugin,
+                ScriptedDebugger {
+                    stops: Arc::new(Mutex::new(vec![])),
+                    arb_results: Arc::new(Mutex::new(vec![])),
+                },
+            ))
+            .with_plugin(PluginThreadConfiguration::new(
+                frontend,
+                PluginLogConfiguration::new("front", LoglevelFilter::Off),
+            ))
+            .with_plugin(PluginThreadConfiguration::new(
+                backend,
+                PluginLogConfiguration::new("back", LoglevelFilter::Off),
+            ))
+    };
+    assert_eq!(
+        Simulator::new(configuration("front"))
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: the frontend cannot be debugged, since it does not receive gatestream requests"
+    );
+    assert_eq!(
+        Simulator::new(configuration("nope"))
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: plugin nope not found"
+    );
+}
+
+#[test]
+// Profiles the callbacks of a simple run.
+fn profile() {
+    let mut frontend = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+    );
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(2, vec![])?;
+        for _ in 0..3 {
+            state.gate(Gate::new_unitary(
+                vec![qubits[1]],
+                vec![],
+                Matrix::new_identity(2),
+            )?)?;
+        }
+        state.gate(Gate::new_measurement(
+            vec![qubits[0]],
+            Matrix::new_identity(2),
+        )?)?;
+        state.get_measurement(qubits[0])?;
+        state.advance(5)?;
+        Ok(ArbData::default())
+    });
+
+    let operator = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("operator", "dqcsim", "0.1.0"),
+    );
+
+    let mut backend = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+    );
+    backend.gate = Box::new(|_, gate| {
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|q| {
+                QubitMeasurementResult::new(*q, QubitMeasurementValue::One, ArbData::default())
+            })
+            .collect())
+    });
+
+    let records = Arc::new(Mutex::new(vec![]));
+    let records_clone = records.clone();
+    let callback = LogCallback::new(
+        Box::new(move |record: &LogRecord| {
+            if record.logger() == "dqcsim" {
+                records_clone
+                    .lock()
+                    .unwrap()
+                    .push(record.payload().to_string());
+            }
+        }),
+        LoglevelFilter::Info,
+    );
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_log_callback(callback)
+        .with_dqcsim_level(LoglevelFilter::Info)
+        .with_profiling()
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("front", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            operator,
+            PluginLogConfiguration::new("op", LoglevelFilter::Off),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            backend,
+            PluginLogConfiguration::new("back", LoglevelFilter::Off),
+        ));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+
+    let calls = |profile: &PluginProfile| -> HashMap<String, u64> {
+        profile
+            .callbacks
+            .iter()
+            .map(|(name, callback)| (name.clone(), callback.calls))
+            .collect()
+    };
+
+    let front = simulator.simulation.get_profile("front").unwrap();
+    assert_eq!(calls(&front)["run"], 1);
+    assert_eq!(calls(&front).get("gate"), None);
+
+    let op = simulator.simulation.get_profile_idx(1).unwrap();
+    assert_eq!(calls(&op)["allocate"], 1);
+    assert_eq!(calls(&op)["gate"], 4);
+    assert_eq!(calls(&op)["modify_measurement"], 1);
+    assert_eq!(calls(&op)["advance"], 1);
+
+    let back = simulator.simulation.get_profile_idx(-1).unwrap();
+    assert_eq!(calls(&back)["allocate"], 1);
+    assert_eq!(calls(&back)["gate"], 4);
+    assert_eq!(calls(&back)["advance"], 1);
+    assert_eq!(back.downstream_wait, Duration::default());
+
+    assert_eq!(
+        simulator
+            .simulation
+            .get_profile("nope")
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument: plugin nope not found"
+    );
+
+    drop(simulator);
+
+    let records = records.lock().unwrap();
+    for name in &["front", "op", "back"] {
+        assert!(records
+            .iter()
+            .any(|record| record == &format!("Performance profile for {}:", name)));
+    }
+    assert!(records.iter().any(|record| record.starts_with("  gate ")));
+    assert!(records
+        .iter()
+        .any(|record| record.starts_with("  IPC overhead ")));
+}
+
 #[test]
 // Attempt recv outside of run callbacks.
 fn simulation_bad_recv() {
@@ -508,6 +1356,46 @@ fn simulation_bad_recv() {
     assert!(simulator.is_ok());
 }
 
+#[test]
+// Tests that plugins can be probed for their metadata and interfaces without
+// running their initialize() callback, for all plugin types.
+fn plugin_probe() {
+    let (frontend, operator, backend) = fe_op_be();
+    let mut configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging();
+    for mut definition in [frontend, operator, backend] {
+        definition.register_arb_operation("a", "b", None).unwrap();
+        definition.initialize = Box::new(|_, _| panic!("initialize() called while probing"));
+        configuration = configuration.with_plugin(PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        ));
+    }
+
+    let log_thread = LogThread::spawn(
+        "dqcsim",
+        configuration.dqcsim_level,
+        configuration.stderr_level,
+        configuration.log_callback,
+        configuration.tee_files,
+    )
+    .unwrap();
+
+    for plugin in configuration.plugins {
+        let mut plugin = plugin.instantiate();
+        let response = plugin.probe(&log_thread).unwrap();
+        drop(plugin);
+        assert_eq!(response.metadata.get_author(), "dqcsim");
+        assert!(response
+            .interfaces
+            .accepts(&ArbCmd::new("a", "b", ArbData::default())));
+        assert!(!response
+            .interfaces
+            .accepts(&ArbCmd::new("a", "c", ArbData::default())));
+    }
+}
+
 #[test]
 #[allow(clippy::redundant_closure)]
 fn bad_simulation_pipeline_too_short() {
@@ -1324,3 +2212,220 @@ fn plugin_thread_panic() {
     let wait = simulator.simulation.wait();
     assert!(wait.is_err());
 }
+
+#[test]
+// This tests that conditional gates are evaluated by the backend without
+// synchronizing the frontend.
+fn conditional_gates() {
+    let (mut frontend, operator, mut backend) = fe_op_be();
+
+    frontend.run = Box::new(|state, _| {
+        let q = state.allocate(2, vec![]).unwrap();
+        let x = vec![
+            Complex64::new(0.0, 0.0),
+            Complex64::new(1.0, 0.0),
+            Complex64::new(1.0, 0.0),
+            Complex64::new(0.0, 0.0),
+        ];
+        let z = vec![
+            Complex64::new(1.0, 0.0),
+            Complex64::new(0.0, 0.0),
+            Complex64::new(0.0, 0.0),
+            Complex64::new(-1.0, 0.0),
+        ];
+
+        // Conditional gates cannot measure.
+        assert_eq!(
+            Gate::new_measurement(vec![q[0]], z.clone())
+                .unwrap()
+                .with_condition(q[1], true)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: conditional gates cannot measure qubits"
+        );
+
+        // Qubit 1 has not been measured yet, so its register is undefined.
+        let gate = Gate::new_unitary(vec![q[0]], vec![], x.clone()).unwrap();
+        state
+            .gate(
+                gate.clone()
+                    .with_condition(q[1], QubitMeasurementValue::Undefined)
+                    .unwrap(),
+            )
+            .unwrap();
+        state
+            .gate(Gate::new_measurement(vec![q[1]], z).unwrap())
+            .unwrap();
+        state
+            .gate(gate.clone().with_condition(q[1], true).unwrap())
+            .unwrap();
+        state
+            .gate(gate.with_condition(q[1], false).unwrap())
+            .unwrap();
+        Ok(ArbData::default())
+    });
+
+    let executed = Arc::new(Mutex::new(vec![]));
+    let executed_gate = Arc::clone(&executed);
+    backend.gate = Box::new(move |_, gate| {
+        executed_gate.lock().unwrap().push(gate.clone());
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|&qubit| QubitMeasurementResult::new(qubit, true, ArbData::default()))
+            .collect())
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    drop(simulator);
+
+    let executed = executed.lock().unwrap();
+    assert_eq!(executed.len(), 3);
+    assert_eq!(executed[0].get_condition(), None);
+    assert_eq!(
+        executed[0].get_targets(),
+        &[QubitRef::from_foreign(1).unwrap()]
+    );
+    assert_eq!(
+        executed[1].get_measures(),
+        &[QubitRef::from_foreign(2).unwrap()]
+    );
+    assert_eq!(executed[2].get_condition(), None);
+    assert_eq!(
+        executed[2].get_targets(),
+        &[QubitRef::from_foreign(1).unwrap()]
+    );
+}
+
+#[test]
+// This tests that resets clear the measurement registers and timers
+// consistently in all plugins.
+fn reset_qubits() {
+    let (mut frontend, operator, mut backend) = fe_op_be();
+
+    frontend.run = Box::new(|state, _| {
+        let q = state.allocate(1, vec![]).unwrap();
+        let basis = Matrix::new_identity(2);
+        let x = vec![
+            Complex64::new(0.0, 0.0),
+            Complex64::new(1.0, 0.0),
+            Complex64::new(1.0, 0.0),
+            Complex64::new(0.0, 0.0),
+        ];
+
+        state
+            .gate(Gate::new_measurement(q.clone(), basis.clone()).unwrap())
+            .unwrap();
+        state.get_measurement(q[0]).unwrap();
+        state.advance(5).unwrap();
+        assert_eq!(state.get_cycles_since_measure(q[0]).unwrap(), 5);
+
+        // Measure, reset, and measure again without synchronizing in between.
+        state
+            .gate(Gate::new_measurement(q.clone(), basis.clone()).unwrap())
+            .unwrap();
+        state.reset_qubits(q.clone()).unwrap();
+        assert_eq!(
+            state.get_measurement(q[0]).unwrap_err().to_string(),
+            "Invalid argument: qubit 1 has not been measured yet"
+        );
+        assert_eq!(
+            state
+                .get_cycles_since_measure(q[0])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 1 has not been measured yet"
+        );
+
+        // The backend's measurement register is cleared as well.
+        state
+            .gate(
+                Gate::new_unitary(q.clone(), vec![], x)
+                    .unwrap()
+                    .with_condition(q[0], QubitMeasurementValue::Undefined)
+                    .unwrap(),
+            )
+            .unwrap();
+
+        state
+            .gate(Gate::new_measurement(q.clone(), basis).unwrap())
+            .unwrap();
+        assert_eq!(
+            state.get_measurement(q[0]).unwrap().value,
+            QubitMeasurementValue::One
+        );
+        assert_eq!(
+            state
+                .get_cycles_between_measures(q[0])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 1 has only been measured once"
+        );
+
+        assert_eq!(
+            state
+                .reset_qubits(vec![QubitRef::from_foreign(2).unwrap()])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 2 is not allocated"
+        );
+        Ok(ArbData::default())
+    });
+
+    let executed = Arc::new(Mutex::new(vec![]));
+    let executed_gate = Arc::clone(&executed);
+    backend.gate = Box::new(move |_, gate| {
+        executed_gate.lock().unwrap().push(gate.get_type().clone());
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|&qubit| QubitMeasurementResult::new(qubit, true, ArbData::default()))
+            .collect())
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    drop(simulator);
+
+    assert_eq!(
+        *executed.lock().unwrap(),
+        vec![
+            GateType::Measurement,
+            GateType::Measurement,
+            GateType::Prep,
+            GateType::Unitary,
+            GateType::Measurement,
+        ]
+    );
+}